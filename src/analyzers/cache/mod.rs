@@ -0,0 +1,453 @@
+//! Persistent per-file analysis cache
+//!
+//! This module stores the result of analyzing each file under
+//! `.zsei/metadata/analysis_cache`, so unchanged files are not parsed again
+//! on the next run. An entry is only reused when the file's content hash, the
+//! version of the analyzer that produced it and the inputs of its own import
+//! resolution all match.
+//!
+//! Those inputs are recorded per entry: the build manifests in the
+//! directories above the file and above each file it depends on, the names of
+//! the files next to it, the files its dependencies resolved to and the
+//! configured search paths. Editing a manifest only invalidates the entries
+//! in its scope, and adding a file only invalidates the entries of the files
+//! in the same directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::analyzers::common::FileReport;
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+
+/// Build manifests the analyzers resolve imports against
+const MANIFEST_NAMES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "package.json",
+    "go.mod",
+    "go.work",
+    "compile_commands.json",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "settings.gradle",
+    "settings.gradle.kts",
+];
+
+/// Check if a file is a build manifest that affects import resolution
+///
+/// Besides the fixed names, this covers `tsconfig.json`, `jsconfig.json` and
+/// the `tsconfig.*.json` files they extend.
+fn is_manifest(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+
+    MANIFEST_NAMES.contains(&name)
+        || name == "jsconfig.json"
+        || (name.starts_with("tsconfig") && name.ends_with(".json"))
+}
+
+/// Inputs the import resolution of one file depended on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ResolutionInputs {
+    /// Hash of the configured include and additional project paths
+    config_hash: String,
+
+    /// Manifests in scope of the file and its dependencies, with the MD5 hash
+    /// of their content
+    manifests: Vec<(PathBuf, String)>,
+
+    /// Hash of the names of the files next to the file, which imports may
+    /// resolve to once they exist
+    siblings_hash: String,
+}
+
+/// A cached analysis of one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Path of the analyzed file
+    path: PathBuf,
+
+    /// MD5 hash of the file content when it was analyzed
+    content_hash: String,
+
+    /// Version of the analyzer that produced the entry
+    analyzer_version: String,
+
+    /// Files the file's dependencies resolved to
+    #[serde(default)]
+    dependency_paths: Vec<PathBuf>,
+
+    /// Inputs of the file's import resolution
+    #[serde(default)]
+    resolution: Option<ResolutionInputs>,
+
+    /// Analysis, dependencies and symbols of the file
+    report: FileReport,
+}
+
+/// On-disk cache of file analyses
+#[derive(Debug, Clone)]
+pub struct AnalysisCache {
+    /// Directory holding one entry file per analyzed file
+    dir: PathBuf,
+
+    /// Roots manifest scopes end at: the project root and the additional
+    /// project paths
+    roots: Vec<PathBuf>,
+
+    /// Hash of the configured include and additional project paths
+    config_hash: String,
+}
+
+impl AnalysisCache {
+    /// Create a cache in the project's metadata directory
+    pub fn new(config: &Config) -> Result<Self> {
+        let mut context = md5::Context::new();
+        for path in config
+            .analysis
+            .include_paths
+            .iter()
+            .chain(&config.additional_project_paths)
+        {
+            context.consume(path.to_string_lossy().as_bytes());
+            context.consume(b"\n");
+        }
+
+        let mut roots = vec![config.project_root().to_path_buf()];
+        roots.extend(config.additional_project_paths.iter().cloned());
+
+        Ok(Self {
+            dir: config.metadata_path()?.join("analysis_cache"),
+            roots,
+            config_hash: format!("{:x}", context.compute()),
+        })
+    }
+
+    /// Version string identifying an analyzer's output format
+    ///
+    /// Combines the crate version with the analyzer's own version, so
+    /// upgrading ZSEI or changing an analyzer invalidates its entries.
+    pub fn analyzer_version(analyzer: &dyn LanguageAnalyzer) -> String {
+        format!(
+            "{}/{}/{}",
+            env!("CARGO_PKG_VERSION"),
+            analyzer.language_name(),
+            analyzer.analyzer_version()
+        )
+    }
+
+    /// Get the cached report for a file if its content, analyzer version and
+    /// resolution inputs are unchanged
+    ///
+    /// Entries written by another analyzer version are removed.
+    pub fn get(
+        &self,
+        path: &Path,
+        content_hash: &str,
+        analyzer_version: &str,
+    ) -> Option<FileReport> {
+        let entry_path = self.entry_path(path);
+        let content = fs::read_to_string(&entry_path).ok()?;
+
+        let entry: CacheEntry = match serde_json::from_str(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!(
+                    "Discarding unreadable cache entry for {}: {}",
+                    path.display(),
+                    e
+                );
+                let _ = fs::remove_file(&entry_path);
+                return None;
+            }
+        };
+
+        if entry.analyzer_version != analyzer_version {
+            debug!(
+                "Discarding cache entry for {} from analyzer {}",
+                path.display(),
+                entry.analyzer_version
+            );
+            let _ = fs::remove_file(&entry_path);
+            return None;
+        }
+
+        if entry.path != path || entry.content_hash != content_hash {
+            return None;
+        }
+
+        // A deleted dependency target changes what the imports resolve to
+        if !entry.dependency_paths.iter().all(|target| target.is_file()) {
+            return None;
+        }
+
+        let resolution = self.resolution_inputs(path, &entry.dependency_paths);
+        if entry.resolution.as_ref() != Some(&resolution) {
+            return None;
+        }
+
+        Some(entry.report)
+    }
+
+    /// Store the report for a file
+    pub fn put(
+        &self,
+        path: &Path,
+        content_hash: &str,
+        analyzer_version: &str,
+        report: &FileReport,
+    ) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to create analysis cache directory: {}", e))
+        })?;
+
+        let dependency_paths: Vec<PathBuf> = report
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.target.clone())
+            .filter(|target| target != path && target.is_file())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let resolution = self.resolution_inputs(path, &dependency_paths);
+
+        let entry = CacheEntry {
+            path: path.to_path_buf(),
+            content_hash: content_hash.to_string(),
+            analyzer_version: analyzer_version.to_string(),
+            dependency_paths,
+            resolution: Some(resolution),
+            report: report.clone(),
+        };
+
+        let content = serde_json::to_string(&entry)
+            .map_err(|e| ZseiError::Analyzer(format!("Failed to serialize cache entry: {}", e)))?;
+
+        // Write to a temporary file first so concurrent readers never see a
+        // partially written entry
+        let entry_path = self.entry_path(path);
+        let temp_path = entry_path.with_extension("json.tmp");
+        fs::write(&temp_path, content)
+            .and_then(|_| fs::rename(&temp_path, &entry_path))
+            .map_err(|e| ZseiError::Analyzer(format!("Failed to write cache entry: {}", e)))
+    }
+
+    /// Remove the entry for a file
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        let entry_path = self.entry_path(path);
+        if entry_path.exists() {
+            fs::remove_file(&entry_path)
+                .map_err(|e| ZseiError::Analyzer(format!("Failed to remove cache entry: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) -> Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).map_err(|e| {
+                ZseiError::Analyzer(format!("Failed to clear analysis cache: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Path of the entry file for an analyzed file
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let key = md5::compute(path.to_string_lossy().as_bytes());
+        self.dir.join(format!("{:x}.json", key))
+    }
+
+    /// Collect the resolution inputs of a file with the given dependencies
+    fn resolution_inputs(&self, path: &Path, dependency_paths: &[PathBuf]) -> ResolutionInputs {
+        let mut dirs = BTreeSet::new();
+        for file in std::iter::once(path).chain(dependency_paths.iter().map(PathBuf::as_path)) {
+            dirs.extend(self.scope_dirs(file));
+        }
+
+        let mut manifests = Vec::new();
+        for dir in &dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| is_manifest(path) && path.is_file())
+                .collect();
+            found.sort();
+
+            for manifest in found {
+                // An unreadable manifest hashes like an empty one
+                let content = fs::read(&manifest).unwrap_or_default();
+                manifests.push((manifest, format!("{:x}", md5::compute(content))));
+            }
+        }
+
+        ResolutionInputs {
+            config_hash: self.config_hash.clone(),
+            manifests,
+            siblings_hash: Self::siblings_hash(path),
+        }
+    }
+
+    /// Directories whose manifests are in scope of a file: its ancestors up
+    /// to the root containing it, or only its own directory outside the roots
+    fn scope_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        let Some(parent) = path.parent() else {
+            return Vec::new();
+        };
+
+        match self.roots.iter().find(|root| path.starts_with(root)) {
+            Some(root) => parent
+                .ancestors()
+                .take_while(|dir| dir.starts_with(root))
+                .map(Path::to_path_buf)
+                .collect(),
+            None => vec![parent.to_path_buf()],
+        }
+    }
+
+    /// Hash the names of the visible entries in a file's directory
+    fn siblings_hash(path: &Path) -> String {
+        let mut names: Vec<String> = path
+            .parent()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        names.sort();
+
+        format!("{:x}", md5::compute(names.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::common::{
+        CodeMetrics, Dependency, DependencyType, FileAnalysis, SymbolGraph,
+    };
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Report of an empty analysis of a file
+    fn report(path: &Path) -> FileReport {
+        FileReport {
+            analysis: FileAnalysis {
+                path: path.to_path_buf(),
+                language: "Rust".to_string(),
+                content: None,
+                functions: Vec::new(),
+                classes: Vec::new(),
+                variables: Vec::new(),
+                imports: Vec::new(),
+                metrics: CodeMetrics {
+                    loc: 0,
+                    comment_lines: 0,
+                    function_count: 0,
+                    class_count: 0,
+                    import_count: 0,
+                    variable_count: 0,
+                    complexity: 0,
+                    maintainability_index: 100.0,
+                },
+            },
+            dependencies: Vec::new(),
+            symbols: SymbolGraph::default(),
+        }
+    }
+
+    #[test]
+    fn entries_depend_only_on_their_own_resolution_inputs() {
+        let root = scratch_dir("resolution");
+        fs::create_dir_all(root.join("app/src")).unwrap();
+        fs::create_dir_all(root.join("other/src")).unwrap();
+        fs::write(
+            root.join("app/Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[dependencies]\nutil = { path = \"../util\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("other/Cargo.toml"),
+            "[package]\nname = \"other\"\n",
+        )
+        .unwrap();
+        let lib = root.join("app/src/lib.rs");
+        let util = root.join("app/src/util.rs");
+        fs::write(&lib, "pub mod util;\n").unwrap();
+        fs::write(&util, "pub fn helper() {}\n").unwrap();
+
+        let mut config = Config::default();
+        config.set_project_root(root.clone());
+        let cache = AnalysisCache::new(&config).unwrap();
+
+        let mut lib_report = report(&lib);
+        lib_report.dependencies.push(Dependency {
+            source: lib.clone(),
+            target: util.clone(),
+            dependency_type: DependencyType::Import,
+            line: Some(1),
+            info: None,
+        });
+        cache.put(&lib, "content", "v1", &lib_report).unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_some());
+        assert!(cache.get(&lib, "edited", "v1").is_none());
+
+        // Files and manifests outside the entry's scope do not matter
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/notes.md"), "# Notes\n").unwrap();
+        fs::write(
+            root.join("other/Cargo.toml"),
+            "[package]\nname = \"other\"\nversion = \"0.2.0\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("other/src/lib.rs"), "pub fn other() {}\n").unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_some());
+
+        // Editing the manifest of the file's crate invalidates the entry
+        fs::write(
+            root.join("app/Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[dependencies]\nutil = { path = \"../shared\" }\n",
+        )
+        .unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_none());
+
+        // So does adding a file next to it that imports may now resolve to
+        cache.put(&lib, "content", "v1", &lib_report).unwrap();
+        fs::write(root.join("app/src/extra.rs"), "pub fn extra() {}\n").unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_none());
+
+        // And deleting a file it depends on
+        cache.put(&lib, "content", "v1", &lib_report).unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_some());
+        fs::remove_file(&util).unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_none());
+
+        // Clearing removes every entry
+        cache.put(&lib, "content", "v1", &report(&lib)).unwrap();
+        cache.clear().unwrap();
+        assert!(cache.get(&lib, "content", "v1").is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
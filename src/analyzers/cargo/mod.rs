@@ -0,0 +1,784 @@
+//! Cargo workspace model
+//!
+//! This module reads `Cargo.toml` and `Cargo.lock` files to describe the
+//! Cargo workspaces of a project: their member crates, the features each
+//! crate declares, and the dependencies between crates, with external
+//! dependencies pinned to the versions recorded in the lock file.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::utils::fs::normalize_path;
+
+/// Crates shipped with the Rust toolchain
+pub const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// A Cargo workspace
+///
+/// A package that is not part of a workspace is treated as a workspace with
+/// a single member, as Cargo does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoWorkspace {
+    /// Workspace root directory
+    pub root: PathBuf,
+
+    /// Member crates
+    pub members: Vec<CargoCrate>,
+
+    /// Packages recorded in `Cargo.lock`
+    pub locked_packages: Vec<LockedPackage>,
+}
+
+/// A member crate of a Cargo workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoCrate {
+    /// Package name
+    pub name: String,
+
+    /// Library name as used in paths (`-` replaced by `_`)
+    pub lib_name: String,
+
+    /// Package version
+    pub version: Option<String>,
+
+    /// Path of the package's `Cargo.toml`
+    pub manifest_path: PathBuf,
+
+    /// Package directory
+    pub root_dir: PathBuf,
+
+    /// Features and the features or dependencies they enable
+    pub features: BTreeMap<String, Vec<String>>,
+
+    /// Declared dependencies
+    pub dependencies: Vec<CrateDependency>,
+}
+
+/// A dependency declared in a `Cargo.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrateDependency {
+    /// Name the dependency is imported as (`-` replaced by `_`)
+    pub name: String,
+
+    /// Package name, which differs from `name` for renamed dependencies
+    pub package: String,
+
+    /// Version requirement
+    pub version_req: Option<String>,
+
+    /// Version resolved in `Cargo.lock`
+    pub version: Option<String>,
+
+    /// Dependency section
+    pub kind: CrateDependencyKind,
+
+    /// Whether the dependency is optional
+    pub optional: bool,
+
+    /// Features enabled on the dependency
+    pub features: Vec<String>,
+
+    /// Local path, for path dependencies
+    pub path: Option<PathBuf>,
+}
+
+/// Section a dependency is declared in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum CrateDependencyKind {
+    /// `[dependencies]`
+    Normal,
+
+    /// `[dev-dependencies]`
+    Dev,
+
+    /// `[build-dependencies]`
+    Build,
+}
+
+/// A package entry of `Cargo.lock`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedPackage {
+    /// Package name
+    pub name: String,
+
+    /// Locked version
+    pub version: String,
+
+    /// Package source, `None` for workspace members and path dependencies
+    pub source: Option<String>,
+
+    /// Dependencies as written in the lock file (`name` or `name version`)
+    pub dependencies: Vec<String>,
+}
+
+/// Reference to an external crate, attached to imports of that crate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalCrate {
+    /// Package name
+    pub name: String,
+
+    /// Locked version, or the version requirement if the crate is not locked.
+    /// `None` for toolchain crates such as `std`.
+    pub version: Option<String>,
+}
+
+impl CargoWorkspace {
+    /// Load the workspace rooted at a directory
+    ///
+    /// The directory must contain a `Cargo.toml` with a `[workspace]` or a
+    /// `[package]` table.
+    pub fn load(root: &Path) -> Option<Self> {
+        let root = normalize_path(root);
+        let manifest = read_manifest(&root.join("Cargo.toml"))?;
+        let workspace_table = manifest.get("workspace");
+
+        let member_dirs = match workspace_members(&root) {
+            Some(members) => members,
+            None if manifest.get("package").is_some() => vec![root.clone()],
+            None => return None,
+        };
+
+        let locked_packages = read_lock_file(&root.join("Cargo.lock"));
+
+        let members = member_dirs
+            .iter()
+            .filter_map(|dir| CargoCrate::load(dir, workspace_table, &root))
+            .map(|mut member| {
+                for dependency in &mut member.dependencies {
+                    dependency.version =
+                        locked_version(&locked_packages, &member.name, &dependency.package);
+                }
+                member
+            })
+            .collect();
+
+        Some(Self {
+            root,
+            members,
+            locked_packages,
+        })
+    }
+
+    /// Discover the workspaces containing a set of Rust source files
+    ///
+    /// Each file is mapped to its nearest package and then to the workspace
+    /// that package belongs to. Manifests above `project_root` are ignored.
+    pub fn discover(files: &[PathBuf], project_root: &Path) -> Vec<Self> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        let mut package_roots: HashMap<PathBuf, Option<PathBuf>> = HashMap::new();
+
+        for file in files {
+            let Some(dir) = file.parent() else {
+                continue;
+            };
+            let package_root = package_roots
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| find_package_root(dir, project_root))
+                .clone();
+
+            if let Some(package_root) = package_root {
+                let root = find_workspace_root(&package_root, project_root);
+                if !roots.contains(&root) {
+                    roots.push(root);
+                }
+            }
+        }
+
+        roots.sort();
+        let workspaces: Vec<Self> = roots.iter().filter_map(|root| Self::load(root)).collect();
+
+        debug!("Found {} Cargo workspaces", workspaces.len());
+        workspaces
+    }
+
+    /// Find the member crate containing a file
+    pub fn crate_for_file(&self, path: &Path) -> Option<&CargoCrate> {
+        self.members
+            .iter()
+            .filter(|member| path.starts_with(&member.root_dir))
+            .max_by_key(|member| member.root_dir.components().count())
+    }
+
+    /// Find a member crate by package name
+    pub fn member(&self, name: &str) -> Option<&CargoCrate> {
+        self.members.iter().find(|member| member.name == name)
+    }
+
+    /// Find a member crate by the name it is referred to in paths
+    pub fn member_by_lib_name(&self, lib_name: &str) -> Option<&CargoCrate> {
+        self.members
+            .iter()
+            .find(|member| member.lib_name == lib_name)
+    }
+}
+
+impl CargoCrate {
+    /// Load a package, resolving `workspace = true` inheritance against the
+    /// workspace table of the root manifest
+    fn load(
+        root_dir: &Path,
+        workspace_table: Option<&toml::Value>,
+        workspace_root: &Path,
+    ) -> Option<Self> {
+        let manifest_path = root_dir.join("Cargo.toml");
+        let manifest = read_manifest(&manifest_path)?;
+        let package = manifest.get("package")?;
+        let name = package.get("name")?.as_str()?.to_string();
+
+        let lib_name = manifest
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(&name)
+            .replace('-', "_");
+
+        let version = match package.get("version") {
+            Some(toml::Value::String(version)) => Some(version.clone()),
+            Some(value) if is_inherited(value) => workspace_table
+                .and_then(|w| w.get("package"))
+                .and_then(|p| p.get("version"))
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            _ => None,
+        };
+
+        let features = manifest
+            .get("features")
+            .and_then(|f| f.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .map(|(feature, enables)| (feature.clone(), string_array(Some(enables))))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let workspace_dependencies = workspace_table
+            .and_then(|w| w.get("dependencies"))
+            .and_then(|d| d.as_table());
+
+        let mut dependencies = Vec::new();
+        let mut tables = vec![&manifest];
+        if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+            tables.extend(targets.values());
+        }
+
+        for table in tables {
+            for (section, kind) in [
+                ("dependencies", CrateDependencyKind::Normal),
+                ("dev-dependencies", CrateDependencyKind::Dev),
+                ("build-dependencies", CrateDependencyKind::Build),
+            ] {
+                let Some(entries) = table.get(section).and_then(|d| d.as_table()) else {
+                    continue;
+                };
+                for (key, spec) in entries {
+                    dependencies.push(parse_dependency(
+                        key,
+                        spec,
+                        kind,
+                        root_dir,
+                        workspace_dependencies,
+                        workspace_root,
+                    ));
+                }
+            }
+        }
+
+        Some(Self {
+            name,
+            lib_name,
+            version,
+            manifest_path: normalize_path(&manifest_path),
+            root_dir: normalize_path(root_dir),
+            features,
+            dependencies,
+        })
+    }
+
+    /// Find a dependency by the name it is imported as
+    pub fn dependency(&self, name: &str) -> Option<&CrateDependency> {
+        self.dependencies.iter().find(|d| d.name == name)
+    }
+}
+
+/// Parse a single dependency entry
+fn parse_dependency(
+    key: &str,
+    spec: &toml::Value,
+    kind: CrateDependencyKind,
+    crate_dir: &Path,
+    workspace_dependencies: Option<&toml::value::Table>,
+    workspace_root: &Path,
+) -> CrateDependency {
+    // `foo = { workspace = true, features = [...] }` takes the rest from the workspace
+    let inherited = spec
+        .get("workspace")
+        .and_then(|w| w.as_bool())
+        .unwrap_or(false);
+    let (base, base_dir) = match workspace_dependencies.and_then(|deps| deps.get(key)) {
+        Some(base) if inherited => (base, workspace_root),
+        _ => (spec, crate_dir),
+    };
+
+    let lookup = |field: &str| spec.get(field).or_else(|| base.get(field));
+
+    let package = lookup("package")
+        .and_then(|p| p.as_str())
+        .unwrap_or(key)
+        .to_string();
+
+    let version_req = match base {
+        toml::Value::String(version) => Some(version.clone()),
+        _ => lookup("version").and_then(|v| v.as_str()).map(String::from),
+    };
+
+    let mut features = string_array(base.get("features"));
+    if inherited {
+        for feature in string_array(spec.get("features")) {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+    }
+
+    CrateDependency {
+        name: key.replace('-', "_"),
+        package,
+        version_req,
+        version: None,
+        kind,
+        optional: lookup("optional")
+            .and_then(|o| o.as_bool())
+            .unwrap_or(false),
+        features,
+        path: base
+            .get("path")
+            .and_then(|p| p.as_str())
+            .map(|p| normalize_path(&base_dir.join(p))),
+    }
+}
+
+/// Check whether a manifest value is `{ workspace = true }`
+fn is_inherited(value: &toml::Value) -> bool {
+    value
+        .get("workspace")
+        .and_then(|w| w.as_bool())
+        .unwrap_or(false)
+}
+
+/// Collect a TOML array of strings
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read the packages of a `Cargo.lock`
+///
+/// A missing or unreadable lock file yields no packages.
+fn read_lock_file(path: &Path) -> Vec<LockedPackage> {
+    let Some(lock) = read_manifest(path) else {
+        return Vec::new();
+    };
+
+    lock.get("package")
+        .and_then(|p| p.as_array())
+        .map(|packages| {
+            packages
+                .iter()
+                .filter_map(|package| {
+                    Some(LockedPackage {
+                        name: package.get("name")?.as_str()?.to_string(),
+                        version: package.get("version")?.as_str()?.to_string(),
+                        source: package
+                            .get("source")
+                            .and_then(|s| s.as_str())
+                            .map(String::from),
+                        dependencies: string_array(package.get("dependencies")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Find the locked version of a package as depended on by another package
+///
+/// When several versions of a package are locked, the lock file names the
+/// version in the dependent's entry (`"name version"`).
+fn locked_version(locked: &[LockedPackage], dependent: &str, package: &str) -> Option<String> {
+    let versions: Vec<&LockedPackage> = locked.iter().filter(|p| p.name == package).collect();
+
+    match versions.as_slice() {
+        [] => None,
+        [only] => Some(only.version.clone()),
+        _ => locked
+            .iter()
+            .filter(|p| p.name == dependent)
+            .flat_map(|p| p.dependencies.iter())
+            .find_map(|entry| {
+                let mut parts = entry.split_whitespace();
+                if parts.next() == Some(package) {
+                    parts.next().map(String::from)
+                } else {
+                    None
+                }
+            }),
+    }
+}
+
+/// Read and parse a TOML file such as `Cargo.toml` or `Cargo.lock`
+pub fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            debug!("Ignoring {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Find the member package directories of the workspace rooted at a directory
+///
+/// Returns `None` if the directory's `Cargo.toml` has no `[workspace]` table.
+pub fn workspace_members(workspace_root: &Path) -> Option<Vec<PathBuf>> {
+    let manifest = read_manifest(&workspace_root.join("Cargo.toml"))?;
+    let workspace = manifest.get("workspace")?;
+
+    let excluded: Vec<PathBuf> = string_array(workspace.get("exclude"))
+        .iter()
+        .map(|p| normalize_path(&workspace_root.join(p)))
+        .collect();
+
+    let mut members = Vec::new();
+
+    // The workspace root may itself be a package
+    if manifest.get("package").is_some() {
+        members.push(normalize_path(workspace_root));
+    }
+
+    for pattern in string_array(workspace.get("members")) {
+        let full_pattern = workspace_root.join(&pattern);
+        let Ok(paths) = glob::glob(&full_pattern.to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            let path = normalize_path(&path);
+            if path.join("Cargo.toml").is_file()
+                && !excluded.contains(&path)
+                && !members.contains(&path)
+            {
+                members.push(path);
+            }
+        }
+    }
+
+    Some(members)
+}
+
+/// Find the nearest directory at or above `dir` whose `Cargo.toml` declares a package
+pub fn find_package_root(dir: &Path, project_root: &Path) -> Option<PathBuf> {
+    let mut current = Some(dir);
+
+    while let Some(dir) = current {
+        let manifest_path = dir.join("Cargo.toml");
+        if manifest_path.is_file()
+            && read_manifest(&manifest_path).map_or(false, |m| m.get("package").is_some())
+        {
+            return Some(normalize_path(dir));
+        }
+        if dir == project_root {
+            break;
+        }
+        current = dir.parent();
+    }
+
+    None
+}
+
+/// Find the root of the workspace a package belongs to
+///
+/// This is the nearest ancestor (or the package itself) whose `[workspace]`
+/// lists the package as a member; a package outside any workspace is its
+/// own root.
+pub fn find_workspace_root(package_root: &Path, project_root: &Path) -> PathBuf {
+    let package_root = normalize_path(package_root);
+    let mut current = Some(package_root.as_path());
+
+    while let Some(dir) = current {
+        if let Some(members) = workspace_members(dir) {
+            if members.contains(&package_root) {
+                return normalize_path(dir);
+            }
+        }
+        if dir == project_root {
+            break;
+        }
+        current = dir.parent();
+    }
+
+    package_root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::common::{CodeGraph, Dependency, DependencyType};
+    use crate::analyzers::rust::resolver::{flatten_use_tree, ModuleResolver};
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-cargo-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Workspace with a library and a binary member, a path dependency
+    /// outside the workspace and a lock file
+    fn two_member_workspace(name: &str) -> PathBuf {
+        let root = scratch_dir(name);
+        write(
+            &root.join("Cargo.toml"),
+            r#"[workspace]
+members = ["core", "cli"]
+
+[workspace.package]
+version = "0.2.0"
+
+[workspace.dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#,
+        );
+        write(
+            &root.join("core/Cargo.toml"),
+            r#"[package]
+name = "demo-core"
+version.workspace = true
+
+[features]
+default = ["json"]
+json = ["dep:serde_json"]
+
+[dependencies]
+serde = { workspace = true, features = ["rc"] }
+serde_json = { version = "1", optional = true }
+"#,
+        );
+        write(&root.join("core/src/lib.rs"), "pub mod model;\n");
+        write(&root.join("core/src/model.rs"), "pub struct Model;\n");
+        write(
+            &root.join("cli/Cargo.toml"),
+            r#"[package]
+name = "demo-cli"
+version = "0.1.0"
+
+[dependencies]
+demo-core = "0.2"
+vendored = { path = "../vendor" }
+anyhow = "1.0"
+
+[dev-dependencies]
+tempfile = "3"
+"#,
+        );
+        write(
+            &root.join("cli/src/main.rs"),
+            "use anyhow::Result;\nuse demo_core::model::Model;\n\nfn main() {}\n",
+        );
+        write(
+            &root.join("vendor/Cargo.toml"),
+            "[package]\nname = \"vendored\"\nversion = \"0.1.0\"\n",
+        );
+        write(
+            &root.join("Cargo.lock"),
+            r#"version = 3
+
+[[package]]
+name = "anyhow"
+version = "1.0.86"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "demo-cli"
+version = "0.1.0"
+dependencies = ["anyhow", "demo-core", "tempfile", "vendored"]
+
+[[package]]
+name = "demo-core"
+version = "0.2.0"
+dependencies = ["serde", "serde_json"]
+
+[[package]]
+name = "serde"
+version = "1.0.203"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde_json"
+version = "1.0.117"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "vendored"
+version = "0.1.0"
+"#,
+        );
+        root
+    }
+
+    #[test]
+    fn workspace_members_features_and_locked_dependencies() {
+        let root = two_member_workspace("model");
+        let workspace = CargoWorkspace::load(&root).unwrap();
+
+        let members: Vec<&str> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(members, vec!["demo-core", "demo-cli"]);
+        assert_eq!(workspace.locked_packages.len(), 6);
+
+        let core = workspace.member("demo-core").unwrap();
+        assert_eq!(core.lib_name, "demo_core");
+        assert_eq!(core.version.as_deref(), Some("0.2.0"));
+        assert_eq!(core.root_dir, root.join("core"));
+        assert_eq!(
+            core.features,
+            BTreeMap::from([
+                ("default".to_string(), vec!["json".to_string()]),
+                ("json".to_string(), vec!["dep:serde_json".to_string()]),
+            ])
+        );
+
+        // Inherited dependencies merge their features with the workspace's
+        let serde = core.dependency("serde").unwrap();
+        assert_eq!(serde.version_req.as_deref(), Some("1.0"));
+        assert_eq!(serde.version.as_deref(), Some("1.0.203"));
+        assert_eq!(serde.features, vec!["derive", "rc"]);
+        let serde_json = core.dependency("serde_json").unwrap();
+        assert!(serde_json.optional);
+        assert_eq!(serde_json.version.as_deref(), Some("1.0.117"));
+
+        let cli = workspace.member_by_lib_name("demo_cli").unwrap();
+        let vendored = cli.dependency("vendored").unwrap();
+        assert_eq!(vendored.path, Some(root.join("vendor")));
+        assert_eq!(vendored.kind, CrateDependencyKind::Normal);
+        let tempfile = cli.dependency("tempfile").unwrap();
+        assert_eq!(tempfile.kind, CrateDependencyKind::Dev);
+        assert_eq!(tempfile.version, None);
+        assert_eq!(tempfile.version_req.as_deref(), Some("3"));
+
+        assert_eq!(
+            workspace
+                .crate_for_file(&root.join("cli/src/main.rs"))
+                .map(|member| member.name.as_str()),
+            Some("demo-cli")
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn graph_gets_crate_nodes_and_dependency_edges() {
+        let root = two_member_workspace("graph");
+        let workspace = CargoWorkspace::load(&root).unwrap();
+
+        let lib = root.join("core/src/lib.rs");
+        let mut graph = CodeGraph::new(&[Dependency {
+            source: lib.clone(),
+            target: root.join("core/src/model.rs"),
+            dependency_type: DependencyType::Import,
+            line: Some(0),
+            info: None,
+        }]);
+        graph.add_cargo_workspaces(&[workspace]);
+
+        let core_manifest = root.join("core/Cargo.toml");
+        let core = &graph.nodes[&core_manifest];
+        assert_eq!(core.node_type, "Crate");
+        assert_eq!(core.label, "demo-core");
+        assert_eq!(core.properties["version"], "0.2.0");
+        assert_eq!(core.properties["features"], "default,json");
+        assert_eq!(graph.nodes[&lib].properties["crate"], "demo-core");
+
+        // External crates are keyed by their locked version, or the
+        // requirement when they are not locked
+        for id in ["anyhow@1.0.86", "serde@1.0.203", "tempfile@3"] {
+            assert_eq!(graph.nodes[&PathBuf::from(id)].node_type, "ExternalCrate");
+        }
+
+        let cli_manifest = root.join("cli/Cargo.toml");
+        let mut targets: Vec<PathBuf> = graph
+            .get_outgoing_dependencies(&cli_manifest)
+            .iter()
+            .map(|edge| {
+                assert_eq!(edge.edge_type, DependencyType::CrateDependency);
+                edge.target.clone()
+            })
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                core_manifest.clone(),
+                PathBuf::from("anyhow@1.0.86"),
+                PathBuf::from("tempfile@3"),
+                PathBuf::from("vendored@0.1.0"),
+            ]
+        );
+        assert_eq!(graph.get_incoming_dependencies(&core_manifest).len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn external_crates_carry_locked_versions() {
+        let root = two_member_workspace("external");
+        let main = root.join("cli/src/main.rs");
+        let resolver = ModuleResolver::new(&root);
+        let external = |path: &str| {
+            let use_path = flatten_use_tree(path).remove(0);
+            resolver.external_crate(&use_path, &main)
+        };
+
+        assert_eq!(
+            external("anyhow::Result"),
+            Some(ExternalCrate {
+                name: "anyhow".to_string(),
+                version: Some("1.0.86".to_string()),
+            })
+        );
+        assert_eq!(
+            external("std::fs"),
+            Some(ExternalCrate {
+                name: "std".to_string(),
+                version: None,
+            })
+        );
+
+        // Workspace members and path dependencies are part of the project,
+        // even where the path does not resolve
+        assert_eq!(external("demo_core::model::Model"), None);
+        assert_eq!(external("demo_core::missing::Thing"), None);
+        assert_eq!(external("vendored::Thing"), None);
+        assert_eq!(external("crate::Thing"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -6,8 +6,153 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use crate::analyzers::cargo::{CargoCrate, CargoWorkspace, ExternalCrate};
 use crate::core::project::ProjectStructure;
 
+/// Iterate over all nodes of a syntax tree in pre-order
+///
+/// Shared by the tree-sitter based analyzers to find items that are not
+/// conveniently reachable through queries.
+pub fn iter_tree<'a>(node: tree_sitter::Node<'a>) -> impl Iterator<Item = tree_sitter::Node<'a>> {
+    struct NodeIterator<'a> {
+        stack: Vec<tree_sitter::Node<'a>>,
+    }
+
+    impl<'a> Iterator for NodeIterator<'a> {
+        type Item = tree_sitter::Node<'a>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let node = self.stack.pop()?;
+
+            // Add all children to stack in reverse order
+            for i in (0..node.child_count()).rev() {
+                if let Some(child) = node.child(i) {
+                    self.stack.push(child);
+                }
+            }
+
+            Some(node)
+        }
+    }
+
+    NodeIterator { stack: vec![node] }
+}
+
+/// Get the source text covered by a node
+pub fn node_text<'a>(node: tree_sitter::Node<'_>, content: &'a str) -> &'a str {
+    &content[node.byte_range()]
+}
+
+/// Count occurrences of branch-creating patterns in a code block
+///
+/// Returns a cyclomatic complexity estimate, starting at the base complexity of 1.
+pub fn count_branch_points(body: &str, patterns: &[&str]) -> usize {
+    1 + patterns
+        .iter()
+        .map(|pattern| body.matches(pattern).count())
+        .sum::<usize>()
+}
+
+/// Estimate cognitive complexity of a code block
+///
+/// Each line containing one of the given control flow patterns adds one plus
+/// its nesting depth, where nesting is derived from leading indentation.
+pub fn indentation_cognitive_complexity(body: &str, patterns: &[&str]) -> usize {
+    let base_indent = body
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut complexity = 0;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if patterns.iter().any(|p| trimmed.starts_with(p)) {
+            let indent = (line.len() - trimmed.len()).saturating_sub(base_indent);
+            complexity += 1 + indent / 4;
+        }
+    }
+
+    complexity
+}
+
+/// Calculate maintainability index on a 0-100 scale
+pub fn maintainability_index(loc: usize, comment_lines: usize, complexity: usize) -> f64 {
+    if loc == 0 {
+        return 100.0;
+    }
+
+    // Halstead volume approximation based on code size
+    let halstead_volume = (loc as f64) * 3.0;
+
+    let comment_ratio = comment_lines as f64 / loc as f64;
+
+    // MI = 171 - 5.2 * ln(V) - 0.23 * G - 16.2 * ln(LOC) + 50 * sin(sqrt(2.4 * CM))
+    let mi =
+        171.0 - 5.2 * halstead_volume.ln() - 0.23 * complexity as f64 - 16.2 * (loc as f64).ln()
+            + 50.0 * (2.4 * comment_ratio).sqrt().sin();
+
+    (mi * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+/// Calculate class cohesion from method bodies and properties
+///
+/// Uses the Henderson-Sellers lack of cohesion (LCOM*) over the properties a
+/// method accesses through `receiver` (e.g. `self.`), and returns `1 - LCOM*`
+/// so that 1.0 means every method touches every property. An empty receiver
+/// matches bare property names, for languages with implicit member access.
+pub fn class_cohesion(methods: &[Function], properties: &[Variable], receiver: &str) -> f64 {
+    let method_count = methods.len();
+    if method_count <= 1 || properties.is_empty() {
+        return 1.0;
+    }
+
+    let mut total_accesses = 0usize;
+
+    for property in properties {
+        let needle = format!("{}{}", receiver, property.name);
+
+        total_accesses += methods
+            .iter()
+            .filter(|m| {
+                m.body
+                    .as_deref()
+                    .map_or(false, |body| contains_identifier(body, &needle))
+            })
+            .count();
+    }
+
+    let mean_accesses = total_accesses as f64 / properties.len() as f64;
+    let lcom = (mean_accesses - method_count as f64) / (1.0 - method_count as f64);
+
+    (1.0 - lcom).clamp(0.0, 1.0)
+}
+
+/// Check whether `haystack` contains `needle` as a whole identifier, i.e. not
+/// preceded or followed by an identifier character
+fn contains_identifier(haystack: &str, needle: &str) -> bool {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    haystack.match_indices(needle).any(|(i, _)| {
+        let preceded = haystack[..i]
+            .chars()
+            .next_back()
+            .map_or(false, is_identifier_char);
+        let followed = haystack[i + needle.len()..]
+            .chars()
+            .next()
+            .map_or(false, is_identifier_char);
+
+        !preceded && !followed
+    })
+}
+
 /// Analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
@@ -22,6 +167,18 @@ pub struct AnalysisResult {
 
     /// Project structure
     pub project_structure: Option<ProjectStructure>,
+
+    /// Cargo workspaces containing the analyzed Rust files
+    #[serde(default)]
+    pub cargo_workspaces: Vec<CargoWorkspace>,
+
+    /// Symbol-level call graph
+    #[serde(default)]
+    pub symbol_graph: SymbolGraph,
+
+    /// MD5 hash of each analyzed file's content when it was analyzed
+    #[serde(skip)]
+    pub content_hashes: HashMap<PathBuf, String>,
 }
 
 impl AnalysisResult {
@@ -80,6 +237,24 @@ impl AnalysisResult {
             .collect()
     }
 
+    /// Get the Cargo crate a file belongs to
+    pub fn get_crate_for_file(&self, path: &Path) -> Option<&CargoCrate> {
+        self.cargo_workspaces
+            .iter()
+            .filter_map(|workspace| workspace.crate_for_file(path))
+            .max_by_key(|member| member.root_dir.components().count())
+    }
+
+    /// Get the symbols calling a function or method, by fully qualified path
+    pub fn callers_of(&self, path: &str) -> Vec<&Symbol> {
+        self.symbol_graph.callers_of(path)
+    }
+
+    /// Get the symbols called by a function or method, by fully qualified path
+    pub fn callees_of(&self, path: &str) -> Vec<&Symbol> {
+        self.symbol_graph.callees_of(path)
+    }
+
     /// Get all files in the analysis
     pub fn get_all_files(&self) -> HashSet<&PathBuf> {
         let mut files = HashSet::new();
@@ -97,6 +272,19 @@ impl AnalysisResult {
     }
 }
 
+/// Everything extracted from a single file in one analysis pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileReport {
+    /// File analysis
+    pub analysis: FileAnalysis,
+
+    /// Dependencies of the file
+    pub dependencies: Vec<Dependency>,
+
+    /// Symbols defined in the file and the calls they make
+    pub symbols: SymbolGraph,
+}
+
 /// File analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileAnalysis {
@@ -230,11 +418,19 @@ pub struct Import {
     /// Import name
     pub name: Option<String>,
 
+    /// Name the import is bound to when renamed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
     /// Line number
     pub line: usize,
 
     /// Whether it's a relative import
     pub is_relative: bool,
+
+    /// External crate the import refers to (Rust only)
+    #[serde(default)]
+    pub external_crate: Option<ExternalCrate>,
 }
 
 /// Code metrics
@@ -339,6 +535,9 @@ pub enum DependencyType {
 
     /// Type usage
     TypeUsage,
+
+    /// Dependency between Cargo crates
+    CrateDependency,
 }
 
 /// Code graph
@@ -435,6 +634,99 @@ impl CodeGraph {
         Self { nodes, edges }
     }
 
+    /// Add the crates of Cargo workspaces to the graph
+    ///
+    /// Member crates become `Crate` nodes keyed by their manifest path, and
+    /// each resolved external dependency becomes an `ExternalCrate` node keyed
+    /// by `name@version`. Dependencies are added as `CrateDependency` edges,
+    /// and file nodes get a `crate` property naming their crate.
+    pub fn add_cargo_workspaces(&mut self, workspaces: &[CargoWorkspace]) {
+        for workspace in workspaces {
+            for member in &workspace.members {
+                let mut properties = HashMap::new();
+                properties.insert(
+                    "workspace".to_string(),
+                    workspace.root.display().to_string(),
+                );
+                if let Some(version) = &member.version {
+                    properties.insert("version".to_string(), version.clone());
+                }
+                if !member.features.is_empty() {
+                    let features: Vec<&str> = member.features.keys().map(String::as_str).collect();
+                    properties.insert("features".to_string(), features.join(","));
+                }
+
+                self.nodes.insert(
+                    member.manifest_path.clone(),
+                    GraphNode {
+                        id: member.manifest_path.clone(),
+                        label: member.name.clone(),
+                        node_type: "Crate".to_string(),
+                        properties,
+                    },
+                );
+
+                for dependency in &member.dependencies {
+                    let target = match workspace.member(&dependency.package) {
+                        Some(target) => target.manifest_path.clone(),
+                        None => {
+                            let version = dependency
+                                .version
+                                .as_deref()
+                                .or(dependency.version_req.as_deref())
+                                .unwrap_or("*");
+                            let id = PathBuf::from(format!("{}@{}", dependency.package, version));
+
+                            let mut properties = HashMap::new();
+                            properties.insert("version".to_string(), version.to_string());
+                            if let Some(path) = &dependency.path {
+                                properties.insert("path".to_string(), path.display().to_string());
+                            }
+
+                            self.nodes.entry(id.clone()).or_insert_with(|| GraphNode {
+                                id: id.clone(),
+                                label: dependency.package.clone(),
+                                node_type: "ExternalCrate".to_string(),
+                                properties,
+                            });
+                            id
+                        }
+                    };
+
+                    let mut properties = HashMap::new();
+                    properties.insert("kind".to_string(), format!("{:?}", dependency.kind));
+                    properties.insert("optional".to_string(), dependency.optional.to_string());
+                    if let Some(version_req) = &dependency.version_req {
+                        properties.insert("version_req".to_string(), version_req.clone());
+                    }
+                    if !dependency.features.is_empty() {
+                        properties.insert("features".to_string(), dependency.features.join(","));
+                    }
+
+                    self.edges.push(GraphEdge {
+                        source: member.manifest_path.clone(),
+                        target,
+                        label: format!("{:?}", DependencyType::CrateDependency),
+                        edge_type: DependencyType::CrateDependency,
+                        weight: 1.0,
+                        properties,
+                    });
+                }
+            }
+
+            // Tag file nodes with their crate
+            for node in self.nodes.values_mut() {
+                if node.node_type != "File" {
+                    continue;
+                }
+                if let Some(member) = workspace.crate_for_file(&node.id) {
+                    node.properties
+                        .insert("crate".to_string(), member.name.clone());
+                }
+            }
+        }
+    }
+
     /// Get outgoing dependencies for a file
     pub fn get_outgoing_dependencies(&self, file: &Path) -> Vec<&GraphEdge> {
         self.edges.iter().filter(|e| e.source == file).collect()
@@ -483,3 +775,129 @@ impl CodeGraph {
         None
     }
 }
+
+/// Symbol-level call graph
+///
+/// Nodes are functions, methods and types keyed by their fully qualified
+/// path (e.g. `zsei::analyzers::Analyzer::new`); edges go from caller to
+/// callee.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolGraph {
+    /// Symbols by fully qualified path
+    pub symbols: HashMap<String, Symbol>,
+
+    /// Call edges
+    pub calls: Vec<CallEdge>,
+}
+
+/// A symbol in the call graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    /// Fully qualified path
+    pub path: String,
+
+    /// Unqualified name
+    pub name: String,
+
+    /// Symbol kind
+    pub kind: SymbolKind,
+
+    /// Defining file
+    pub file: PathBuf,
+
+    /// Line of the definition
+    pub line: usize,
+}
+
+/// Symbol kind
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// Free function
+    Function,
+
+    /// Method or associated function
+    Method,
+
+    /// Struct, enum, trait or other type
+    Type,
+}
+
+/// A call from one symbol to another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct CallEdge {
+    /// Fully qualified path of the calling function
+    pub caller: String,
+
+    /// Fully qualified path of the called function
+    pub callee: String,
+
+    /// File containing the call
+    pub file: PathBuf,
+
+    /// Line of the call
+    pub line: usize,
+}
+
+impl SymbolGraph {
+    /// Add a symbol, keeping the first definition of a path
+    pub fn add_symbol(&mut self, symbol: Symbol) {
+        self.symbols.entry(symbol.path.clone()).or_insert(symbol);
+    }
+
+    /// Add a call edge
+    pub fn add_call(&mut self, call: CallEdge) {
+        self.calls.push(call);
+    }
+
+    /// Merge another graph into this one
+    pub fn merge(&mut self, other: SymbolGraph) {
+        for (_, symbol) in other.symbols {
+            self.add_symbol(symbol);
+        }
+        self.calls.extend(other.calls);
+    }
+
+    /// Drop calls whose callee is not a known symbol and duplicate calls
+    ///
+    /// Analyzers record calls before all files are seen, so calls into the
+    /// standard library or external crates are only removed here.
+    pub fn retain_resolved_calls(&mut self) {
+        let mut seen = HashSet::new();
+        let symbols = &self.symbols;
+        self.calls
+            .retain(|call| symbols.contains_key(&call.callee) && seen.insert(call.clone()));
+    }
+
+    /// Get the symbols calling a symbol
+    pub fn callers_of(&self, path: &str) -> Vec<&Symbol> {
+        let mut callers: Vec<&Symbol> = Vec::new();
+        for call in self.calls.iter().filter(|c| c.callee == path) {
+            if let Some(symbol) = self.symbols.get(&call.caller) {
+                if !callers.iter().any(|s| s.path == symbol.path) {
+                    callers.push(symbol);
+                }
+            }
+        }
+        callers
+    }
+
+    /// Get the symbols called by a symbol
+    pub fn callees_of(&self, path: &str) -> Vec<&Symbol> {
+        let mut callees: Vec<&Symbol> = Vec::new();
+        for call in self.calls.iter().filter(|c| c.caller == path) {
+            if let Some(symbol) = self.symbols.get(&call.callee) {
+                if !callees.iter().any(|s| s.path == symbol.path) {
+                    callees.push(symbol);
+                }
+            }
+        }
+        callees
+    }
+
+    /// Get the symbols defined in a file
+    pub fn symbols_in_file(&self, file: &Path) -> Vec<&Symbol> {
+        let mut symbols: Vec<&Symbol> = self.symbols.values().filter(|s| s.file == file).collect();
+        symbols.sort_by_key(|s| s.line);
+        symbols
+    }
+}
@@ -0,0 +1,1222 @@
+//! C and C++ code analyzer
+//!
+//! This module provides an analyzer for C and C++ code, extracting
+//! functions, classes and structs with their inheritance, namespaces,
+//! global variables and `#include` directives using tree-sitter. Includes
+//! are resolved against the configured include paths and, when present,
+//! the include flags recorded in `compile_commands.json`.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::analyzers::common::{
+    class_cohesion, count_branch_points, indentation_cognitive_complexity, iter_tree,
+    maintainability_index, node_text, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType,
+    FileAnalysis, FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
+};
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+use crate::utils::fs::normalize_path;
+
+/// Header file extensions, in the order they are tried for pairing
+const HEADER_EXTENSIONS: &[&str] = &["h", "hpp", "hh", "hxx"];
+
+/// Implementation file extensions
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cpp", "cc", "cxx"];
+
+/// Patterns that create branches in C and C++ code
+const BRANCH_PATTERNS: &[&str] = &[
+    "if (", "if(", "for (", "for(", "while (", "while(", "case ", "catch", "&&", "||", "?",
+    "return", "break", "continue", "goto ",
+];
+
+/// Statements that add to cognitive complexity in C and C++ code
+const COGNITIVE_PATTERNS: &[&str] = &[
+    "if (", "if(", "} else", "else", "for (", "for(", "while", "switch", "case ", "try", "catch",
+    "goto ",
+];
+
+/// C/C++ analyzer implementation providing structural analysis and include
+/// resolution for C and C++ source and header files using tree-sitter.
+pub struct CppAnalyzer {
+    /// Configuration options
+    config: Arc<Config>,
+
+    /// Include directories from `compile_commands.json`, loaded on first use;
+    /// `Some(None)` when the project has none
+    compile_database: Mutex<Option<Option<Arc<CompileDatabase>>>>,
+}
+
+/// Include directories extracted from a compilation database
+#[derive(Debug, Clone, Default)]
+struct CompileDatabase {
+    /// Include directories per translation unit
+    per_file: HashMap<PathBuf, Vec<PathBuf>>,
+
+    /// Union of all include directories, used for headers
+    all: Vec<PathBuf>,
+}
+
+/// An entry of `compile_commands.json`
+#[derive(Debug, Deserialize)]
+struct CompileCommand {
+    /// Working directory of the compilation
+    directory: PathBuf,
+
+    /// Main translation unit source
+    file: PathBuf,
+
+    /// Compiler invocation as a single shell string
+    #[serde(default)]
+    command: Option<String>,
+
+    /// Compiler invocation as a list of arguments
+    #[serde(default)]
+    arguments: Option<Vec<String>>,
+}
+
+/// An `#include` directive as written in the source
+#[derive(Debug, Clone)]
+struct IncludeSite {
+    /// Included path without delimiters
+    path: String,
+
+    /// Whether the include uses angle brackets
+    system: bool,
+
+    /// Line number
+    line: usize,
+}
+
+/// Scope information while walking declarations
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    /// Enclosing namespaces, outermost first
+    namespaces: Vec<String>,
+
+    /// Whether the scope is inside an anonymous namespace
+    anonymous: bool,
+}
+
+impl Scope {
+    /// Qualify a name with the enclosing namespaces
+    fn qualify(&self, name: &str) -> String {
+        if self.namespaces.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self.namespaces.join("::"), name)
+        }
+    }
+}
+
+impl CompileDatabase {
+    /// Load `compile_commands.json` from the project root or its `build` directory
+    fn load(project_root: &Path) -> Option<Self> {
+        let path = [
+            project_root.join("compile_commands.json"),
+            project_root.join("build").join("compile_commands.json"),
+        ]
+        .into_iter()
+        .find(|p| p.is_file())?;
+
+        let content = fs::read_to_string(&path).ok()?;
+        let commands: Vec<CompileCommand> = match serde_json::from_str(&content) {
+            Ok(commands) => commands,
+            Err(e) => {
+                debug!("Ignoring {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        let mut database = CompileDatabase::default();
+
+        for command in commands {
+            let arguments = match (command.arguments, command.command) {
+                (Some(arguments), _) => arguments,
+                (None, Some(command)) => command.split_whitespace().map(String::from).collect(),
+                (None, None) => continue,
+            };
+
+            let include_dirs: Vec<PathBuf> = include_flags(&arguments)
+                .into_iter()
+                .map(|dir| normalize_path(&command.directory.join(dir)))
+                .collect();
+
+            for dir in &include_dirs {
+                if !database.all.contains(dir) {
+                    database.all.push(dir.clone());
+                }
+            }
+
+            let file = normalize_path(&command.directory.join(&command.file));
+            database.per_file.insert(file, include_dirs);
+        }
+
+        debug!(
+            "Loaded {} compile commands from {}",
+            database.per_file.len(),
+            path.display()
+        );
+
+        Some(database)
+    }
+}
+
+/// Extract include directories from compiler arguments
+///
+/// Handles `-I<dir>`, `-I <dir>`, `-isystem <dir>` and `-iquote <dir>`.
+fn include_flags(arguments: &[String]) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut iter = arguments.iter();
+
+    while let Some(argument) = iter.next() {
+        let argument = argument.trim_matches('"');
+        match argument {
+            "-I" | "-isystem" | "-iquote" | "-idirafter" => {
+                if let Some(dir) = iter.next() {
+                    dirs.push(dir.trim_matches('"').to_string());
+                }
+            }
+            _ => {
+                if let Some(dir) = argument.strip_prefix("-I") {
+                    dirs.push(dir.to_string());
+                }
+            }
+        }
+    }
+
+    dirs
+}
+
+impl CppAnalyzer {
+    /// Create a new C/C++ analyzer with the given configuration.
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            compile_database: Mutex::new(None),
+        }
+    }
+
+    /// Get the tree-sitter grammar and display name for a file
+    ///
+    /// `.c` files use the C grammar; headers use the C++ grammar, which also
+    /// accepts C declarations.
+    fn language_for(&self, path: &Path) -> (Language, &'static str) {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("c") => (tree_sitter_c::LANGUAGE.into(), "C"),
+            Some("h") => (tree_sitter_cpp::LANGUAGE.into(), "C"),
+            _ => (tree_sitter_cpp::LANGUAGE.into(), "C++"),
+        }
+    }
+
+    /// Parse file content into a syntax tree
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let (language, _) = self.language_for(path);
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language)
+            .expect("Failed to set C/C++ language");
+
+        parser
+            .parse(content, None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Read a file into a string
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Build a file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> FileAnalysis {
+        let root = tree.root_node();
+        let (_, language) = self.language_for(path);
+
+        let mut functions = Vec::new();
+        let mut classes = Vec::new();
+        let mut out_of_line_methods = Vec::new();
+        let mut variables = Vec::new();
+
+        self.collect_declarations(
+            root,
+            &Scope::default(),
+            content,
+            &mut functions,
+            &mut classes,
+            &mut out_of_line_methods,
+            &mut variables,
+        );
+
+        // Attach out-of-line member definitions (`void Foo::bar() {}`) to
+        // their class, creating entries for classes declared in a header
+        for (class_name, method) in out_of_line_methods {
+            match classes
+                .iter_mut()
+                .find(|c: &&mut Class| c.name == class_name)
+            {
+                Some(class) => {
+                    // Replace the in-class declaration with the definition
+                    class
+                        .methods
+                        .retain(|m| m.name != method.name || m.body.is_some());
+                    class.methods.push(method);
+                }
+                None => classes.push(Class {
+                    name: class_name,
+                    start_line: method.start_line,
+                    end_line: method.end_line,
+                    methods: vec![method],
+                    properties: Vec::new(),
+                    base_classes: Vec::new(),
+                    is_public: true,
+                    metrics: ClassMetrics {
+                        loc: 0,
+                        method_count: 0,
+                        property_count: 0,
+                        inheritance_depth: 0,
+                        cohesion: 1.0,
+                    },
+                }),
+            }
+        }
+
+        for class in &mut classes {
+            if class.metrics.loc == 0 {
+                class.start_line = class
+                    .methods
+                    .iter()
+                    .map(|m| m.start_line)
+                    .min()
+                    .unwrap_or(0);
+                class.end_line = class.methods.iter().map(|m| m.end_line).max().unwrap_or(0);
+                class.metrics.loc = class
+                    .methods
+                    .iter()
+                    .map(|m| m.end_line - m.start_line + 1)
+                    .sum();
+            }
+            class.metrics.method_count = class.methods.len();
+            // Members are mostly accessed without `this->`, which bare names
+            // also match
+            class.metrics.cohesion = class_cohesion(&class.methods, &class.properties, "");
+        }
+
+        let imports: Vec<Import> = self
+            .extract_include_sites(root, content)
+            .into_iter()
+            .map(|site| Import {
+                is_relative: !site.system,
+                external_crate: None,
+                path: site.path,
+                name: None,
+                alias: None,
+                line: site.line,
+            })
+            .collect();
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .map(str::trim_start)
+            .filter(|l| l.starts_with("//") || l.starts_with("/*") || l.starts_with('*'))
+            .count();
+
+        let complexity = functions
+            .iter()
+            .chain(classes.iter().flat_map(|c| c.methods.iter()))
+            .map(|f| f.metrics.complexity)
+            .sum();
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index: maintainability_index(loc, comment_lines, complexity),
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} classes, {} includes, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        FileAnalysis {
+            path: path.to_path_buf(),
+            language: language.to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        }
+    }
+
+    /// Collect declarations from a translation unit or namespace body
+    #[allow(clippy::too_many_arguments)]
+    fn collect_declarations(
+        &self,
+        container: Node<'_>,
+        scope: &Scope,
+        content: &str,
+        functions: &mut Vec<Function>,
+        classes: &mut Vec<Class>,
+        out_of_line_methods: &mut Vec<(String, Function)>,
+        variables: &mut Vec<Variable>,
+    ) {
+        let mut cursor = container.walk();
+        for node in container.named_children(&mut cursor) {
+            // Look through `template <...>` and `extern "C" { ... }` wrappers
+            let node = match node.kind() {
+                "template_declaration" => match last_named_child(node) {
+                    Some(inner) => inner,
+                    None => continue,
+                },
+                "linkage_specification" => {
+                    if let Some(body) = node.child_by_field_name("body") {
+                        if body.kind() == "declaration_list" {
+                            self.collect_declarations(
+                                body,
+                                scope,
+                                content,
+                                functions,
+                                classes,
+                                out_of_line_methods,
+                                variables,
+                            );
+                            continue;
+                        }
+                        body
+                    } else {
+                        continue;
+                    }
+                }
+                _ => node,
+            };
+
+            match node.kind() {
+                "namespace_definition" => {
+                    let mut inner = scope.clone();
+                    match node.child_by_field_name("name") {
+                        Some(name) => inner.namespaces.extend(
+                            node_text(name, content)
+                                .split("::")
+                                .map(|s| s.trim().to_string()),
+                        ),
+                        None => inner.anonymous = true,
+                    }
+
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.collect_declarations(
+                            body,
+                            &inner,
+                            content,
+                            functions,
+                            classes,
+                            out_of_line_methods,
+                            variables,
+                        );
+                    }
+                }
+                "function_definition" => {
+                    let Some(mut function) = self.build_function(node, content) else {
+                        continue;
+                    };
+
+                    // `Class::method` definitions belong to the class
+                    if let Some((class_name, method_name)) = function.name.rsplit_once("::") {
+                        let class_name = scope.qualify(class_name);
+                        function.name = method_name.to_string();
+                        function.is_public = true;
+                        out_of_line_methods.push((class_name, function));
+                        continue;
+                    }
+
+                    function.name = scope.qualify(&function.name);
+                    function.is_public =
+                        !scope.anonymous && !has_storage_class(node, "static", content);
+                    functions.push(function);
+                }
+                "class_specifier" | "struct_specifier" | "union_specifier" => {
+                    if let Some(class) = self.build_class(node, scope, content) {
+                        classes.push(class);
+                    }
+                }
+                "declaration" => {
+                    // `struct Foo { ... };` and `class Foo {...} foo;` declare a type
+                    if let Some(type_node) = node.child_by_field_name("type") {
+                        if matches!(
+                            type_node.kind(),
+                            "class_specifier" | "struct_specifier" | "union_specifier"
+                        ) {
+                            if let Some(class) = self.build_class(type_node, scope, content) {
+                                classes.push(class);
+                            }
+                        }
+                    }
+
+                    variables.extend(self.declaration_variables(node, scope, content));
+                }
+                "type_definition" => {
+                    // `typedef struct { ... } Name;`
+                    let Some(type_node) = node.child_by_field_name("type") else {
+                        continue;
+                    };
+                    if !matches!(type_node.kind(), "struct_specifier" | "union_specifier") {
+                        continue;
+                    }
+                    if let Some(mut class) = self.build_class(type_node, scope, content) {
+                        if let Some(alias) = node.child_by_field_name("declarator") {
+                            if class.name.is_empty() {
+                                class.name = scope.qualify(node_text(alias, content));
+                            }
+                        }
+                        if !class.name.is_empty() {
+                            classes.push(class);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Build a function from a `function_definition` or a method declaration
+    ///
+    /// The name keeps any `Class::` qualification from the declarator.
+    fn build_function(&self, node: Node<'_>, content: &str) -> Option<Function> {
+        let declarator = function_declarator(node.child_by_field_name("declarator")?)?;
+        let name = node_text(declarator.child_by_field_name("declarator")?, content).to_string();
+
+        let parameters = declarator
+            .child_by_field_name("parameters")
+            .map(|p| self.extract_parameters(p, content))
+            .unwrap_or_default();
+
+        let return_type = node
+            .child_by_field_name("type")
+            .map(|t| node_text(t, content).to_string());
+
+        let body_node = node.child_by_field_name("body");
+        let body = body_node.map(|b| node_text(b, content).to_string());
+
+        let signature_end = body_node.map_or(node.end_byte(), |b| b.start_byte());
+        let signature = content[node.start_byte()..signature_end]
+            .trim_end()
+            .trim_end_matches(';')
+            .to_string();
+
+        let body_text = body.as_deref().unwrap_or("");
+        let metrics = FunctionMetrics {
+            loc: body_text.lines().count(),
+            complexity: count_branch_points(body_text, BRANCH_PATTERNS),
+            parameter_count: parameters.len(),
+            cognitive_complexity: indentation_cognitive_complexity(body_text, COGNITIVE_PATTERNS),
+        };
+
+        Some(Function {
+            name,
+            signature,
+            start_line: node.start_position().row,
+            end_line: node.end_position().row,
+            body,
+            return_type,
+            parameters,
+            is_public: false,
+            metrics,
+        })
+    }
+
+    /// Extract parameters from a `parameter_list` node
+    fn extract_parameters(&self, params_node: Node<'_>, content: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let (name, param_type, default_value) = match child.kind() {
+                "parameter_declaration" | "optional_parameter_declaration" => {
+                    let param_type = child
+                        .child_by_field_name("type")
+                        .map(|t| node_text(t, content).to_string());
+
+                    // `void f(void)` has no parameters
+                    if child.child_by_field_name("declarator").is_none()
+                        && param_type.as_deref() == Some("void")
+                    {
+                        continue;
+                    }
+
+                    let name = child
+                        .child_by_field_name("declarator")
+                        .map(|d| declarator_name(d, content))
+                        .unwrap_or_default();
+                    let default_value = child
+                        .child_by_field_name("default_value")
+                        .map(|v| node_text(v, content).to_string());
+                    (name, param_type, default_value)
+                }
+                "variadic_parameter_declaration" | "variadic_parameter" => {
+                    ("...".to_string(), None, None)
+                }
+                _ => continue,
+            };
+
+            parameters.push(Parameter {
+                name,
+                param_type,
+                default_value,
+                position: parameters.len(),
+            });
+        }
+
+        parameters
+    }
+
+    /// Build a class from a class, struct or union specifier with a body
+    fn build_class(&self, node: Node<'_>, scope: &Scope, content: &str) -> Option<Class> {
+        // Forward declarations have no body
+        let body = node.child_by_field_name("body")?;
+
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| scope.qualify(node_text(n, content)))
+            .unwrap_or_default();
+
+        let mut base_classes = Vec::new();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() != "base_class_clause" {
+                continue;
+            }
+            let mut base_cursor = child.walk();
+            for base in child.named_children(&mut base_cursor) {
+                if matches!(
+                    base.kind(),
+                    "type_identifier" | "qualified_identifier" | "template_type"
+                ) {
+                    base_classes.push(node_text(base, content).to_string());
+                }
+            }
+        }
+
+        // Members of a `class` are private by default
+        let mut public = node.kind() != "class_specifier";
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+
+        let mut member_cursor = body.walk();
+        for member in body.named_children(&mut member_cursor) {
+            let member = if member.kind() == "template_declaration" {
+                match last_named_child(member) {
+                    Some(inner) => inner,
+                    None => continue,
+                }
+            } else {
+                member
+            };
+
+            match member.kind() {
+                "access_specifier" => {
+                    public = node_text(member, content).trim_end_matches(':').trim() == "public";
+                }
+                "function_definition" => {
+                    if let Some(mut method) = self.build_function(member, content) {
+                        method.is_public = public;
+                        methods.push(method);
+                    }
+                }
+                "field_declaration" | "declaration" => {
+                    let declarator = member.child_by_field_name("declarator");
+
+                    // Method declarations are field declarations with a function declarator
+                    if declarator.and_then(function_declarator).is_some() {
+                        if let Some(mut method) = self.build_function(member, content) {
+                            method.is_public = public;
+                            methods.push(method);
+                        }
+                        continue;
+                    }
+
+                    let var_type = member
+                        .child_by_field_name("type")
+                        .map(|t| node_text(t, content).to_string());
+                    let init_value = member
+                        .child_by_field_name("default_value")
+                        .map(|v| node_text(v, content).to_string());
+
+                    let mut declarator_cursor = member.walk();
+                    for declarator in
+                        member.children_by_field_name("declarator", &mut declarator_cursor)
+                    {
+                        properties.push(Variable {
+                            name: declarator_name(declarator, content),
+                            var_type: var_type.clone(),
+                            line: member.start_position().row,
+                            is_public: public,
+                            init_value: init_value.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let start_line = node.start_position().row;
+        let end_line = node.end_position().row;
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            inheritance_depth: usize::from(!base_classes.is_empty()),
+            cohesion: 1.0,
+        };
+
+        Some(Class {
+            is_public: !scope.anonymous,
+            name,
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            metrics,
+        })
+    }
+
+    /// Extract global variables from a declaration, skipping function prototypes
+    fn declaration_variables(
+        &self,
+        declaration: Node<'_>,
+        scope: &Scope,
+        content: &str,
+    ) -> Vec<Variable> {
+        let var_type = declaration
+            .child_by_field_name("type")
+            .map(|t| node_text(t, content).to_string());
+        let is_public = !scope.anonymous
+            && !has_storage_class(declaration, "static", content)
+            && !has_storage_class(declaration, "extern", content);
+
+        let mut variables = Vec::new();
+        let mut cursor = declaration.walk();
+
+        for declarator in declaration.children_by_field_name("declarator", &mut cursor) {
+            if function_declarator(declarator).is_some() {
+                continue;
+            }
+
+            let init_value = (declarator.kind() == "init_declarator")
+                .then(|| declarator.child_by_field_name("value"))
+                .flatten()
+                .map(|v| node_text(v, content).to_string());
+
+            variables.push(Variable {
+                name: scope.qualify(&declarator_name(declarator, content)),
+                var_type: var_type.clone(),
+                line: declaration.start_position().row,
+                is_public,
+                init_value,
+            });
+        }
+
+        variables
+    }
+
+    /// Extract all `#include` directives
+    fn extract_include_sites(&self, root: Node<'_>, content: &str) -> Vec<IncludeSite> {
+        iter_tree(root)
+            .filter(|n| n.kind() == "preproc_include")
+            .filter_map(|include| {
+                let path_node = include.child_by_field_name("path")?;
+                let raw = node_text(path_node, content);
+                Some(IncludeSite {
+                    system: path_node.kind() == "system_lib_string",
+                    path: raw
+                        .trim_matches(|c| c == '"' || c == '<' || c == '>')
+                        .to_string(),
+                    line: include.start_position().row,
+                })
+            })
+            .collect()
+    }
+
+    /// Include directories for a file, in search order
+    ///
+    /// Configured include paths come first, followed by the directories from
+    /// `compile_commands.json` for the file (or all of them for headers and
+    /// files without an entry).
+    fn include_dirs(&self, source_path: &Path, project_root: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self
+            .config
+            .analysis
+            .include_paths
+            .iter()
+            .map(|dir| normalize_path(&project_root.join(dir)))
+            .collect();
+
+        let database = self
+            .compile_database
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| CompileDatabase::load(project_root).map(Arc::new))
+            .clone();
+
+        if let Some(database) = database {
+            let from_database = database
+                .per_file
+                .get(&normalize_path(source_path))
+                .unwrap_or(&database.all);
+            for dir in from_database {
+                if !dirs.contains(dir) {
+                    dirs.push(dir.clone());
+                }
+            }
+        }
+
+        // Conventional layouts when nothing is configured
+        if dirs.is_empty() {
+            dirs.push(project_root.join("include"));
+            dirs.push(project_root.join("src"));
+        }
+
+        dirs
+    }
+
+    /// Resolve an include directive to a file
+    ///
+    /// Quoted includes are looked up next to the including file first.
+    /// System headers that are not found in an include directory resolve to
+    /// `None`.
+    fn resolve_include(
+        &self,
+        site: &IncludeSite,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Option<PathBuf> {
+        if !site.system {
+            if let Some(dir) = source_path.parent() {
+                let candidate = normalize_path(&dir.join(&site.path));
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+
+        self.include_dirs(source_path, project_root)
+            .into_iter()
+            .map(|dir| normalize_path(&dir.join(&site.path)))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Extract dependencies from a parsed syntax tree
+    fn extract_dependencies_from_tree(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Vec<Dependency> {
+        let root = tree.root_node();
+        let mut dependencies = Vec::new();
+        let mut resolved_includes = Vec::new();
+
+        for site in self.extract_include_sites(root, content) {
+            let Some(target) = self.resolve_include(&site, source_path, project_root) else {
+                debug!(
+                    "Could not resolve include '{}' in {}",
+                    site.path,
+                    source_path.display()
+                );
+                continue;
+            };
+
+            dependencies.push(Dependency {
+                source: source_path.to_path_buf(),
+                target: target.clone(),
+                dependency_type: DependencyType::Import,
+                line: Some(site.line),
+                info: Some(format!("Include: {}", site.path)),
+            });
+
+            resolved_includes.push((site.line, target));
+        }
+
+        // Implementation files implement the header with the same stem
+        if is_source_file(source_path) {
+            let stem = source_path.file_stem();
+            let header = resolved_includes
+                .iter()
+                .find(|(_, target)| target.file_stem() == stem && is_header_file(target))
+                .map(|(line, target)| (Some(*line), target.clone()))
+                .or_else(|| {
+                    HEADER_EXTENSIONS
+                        .iter()
+                        .map(|ext| source_path.with_extension(ext))
+                        .find(|candidate| candidate.is_file())
+                        .map(|candidate| (None, candidate))
+                });
+
+            if let Some((line, header)) = header {
+                dependencies.push(Dependency {
+                    source: source_path.to_path_buf(),
+                    target: header.clone(),
+                    dependency_type: DependencyType::Implementation,
+                    line,
+                    info: Some(format!("Implements: {}", header.display())),
+                });
+            }
+        }
+
+        // Inheritance from classes declared in included headers
+        for node in iter_tree(root) {
+            if !matches!(node.kind(), "class_specifier" | "struct_specifier") {
+                continue;
+            }
+            let Some(class) = self.build_class(node, &Scope::default(), content) else {
+                continue;
+            };
+
+            for base in &class.base_classes {
+                let base_name = base.split('<').next().unwrap_or(base);
+                let base_name = base_name.rsplit("::").next().unwrap_or(base_name).trim();
+
+                if let Some((_, target)) = resolved_includes
+                    .iter()
+                    .find(|(_, target)| declares_class(target, base_name))
+                {
+                    dependencies.push(Dependency {
+                        source: source_path.to_path_buf(),
+                        target: target.clone(),
+                        dependency_type: DependencyType::Inheritance,
+                        line: Some(class.start_line),
+                        info: Some(format!("Inheritance: {} extends {}", class.name, base)),
+                    });
+                }
+            }
+        }
+
+        dependencies
+    }
+}
+
+/// Find the `function_declarator` inside a (possibly pointer or reference) declarator
+fn function_declarator(declarator: Node<'_>) -> Option<Node<'_>> {
+    let mut current = declarator;
+    loop {
+        match current.kind() {
+            "function_declarator" => return Some(current),
+            "pointer_declarator" | "reference_declarator" | "attributed_declarator" => {
+                current = current
+                    .child_by_field_name("declarator")
+                    .or_else(|| last_named_child(current))?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Get the declared identifier from a declarator, stripping pointers,
+/// references, array sizes and initializers
+fn declarator_name(declarator: Node<'_>, content: &str) -> String {
+    let mut current = declarator;
+    while let Some(inner) = current.child_by_field_name("declarator") {
+        current = inner;
+    }
+    if matches!(
+        current.kind(),
+        "pointer_declarator" | "reference_declarator"
+    ) {
+        if let Some(inner) = last_named_child(current) {
+            current = inner;
+        }
+    }
+    node_text(current, content).to_string()
+}
+
+/// Get the last named child of a node
+fn last_named_child(node: Node<'_>) -> Option<Node<'_>> {
+    node.named_child(node.named_child_count().checked_sub(1)?)
+}
+
+/// Check whether a declaration has the given storage class specifier
+fn has_storage_class(node: Node<'_>, storage_class: &str, content: &str) -> bool {
+    let mut cursor = node.walk();
+    let result = node.named_children(&mut cursor).any(|child| {
+        child.kind() == "storage_class_specifier" && node_text(child, content) == storage_class
+    });
+    result
+}
+
+/// Check whether a path is a C/C++ header
+fn is_header_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map_or(false, |e| HEADER_EXTENSIONS.contains(&e))
+}
+
+/// Check whether a path is a C/C++ implementation file
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map_or(false, |e| SOURCE_EXTENSIONS.contains(&e))
+}
+
+/// Check whether a file declares a class or struct with the given name
+fn declares_class(path: &Path, class_name: &str) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+
+    ["class ", "struct "].iter().any(|keyword| {
+        let needle = format!("{}{}", keyword, class_name);
+        content.match_indices(&needle).any(|(index, _)| {
+            content[index + needle.len()..]
+                .chars()
+                .next()
+                .map_or(true, |c| !c.is_alphanumeric() && c != '_')
+        })
+    })
+}
+
+#[async_trait]
+impl LanguageAnalyzer for CppAnalyzer {
+    fn language_name(&self) -> &'static str {
+        "C/C++"
+    }
+
+    fn supported_extensions(&self) -> &[&'static str] {
+        &["c", "h", "cpp", "cc", "cxx", "hpp", "hh", "hxx"]
+    }
+
+    async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
+        info!("Analyzing C/C++ file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        Ok(self.analyze_tree(path, &tree, &content))
+    }
+
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!(
+            "Extracting dependencies from C/C++ file: {}",
+            path.display()
+        );
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(dependencies)
+    }
+
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing C/C++ file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content),
+            dependencies,
+            symbols: SymbolGraph::default(),
+        })
+    }
+
+    fn reset_caches(&self) {
+        if let Ok(mut database) = self.compile_database.lock() {
+            *database = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-cpp-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn analyzer(root: &Path) -> CppAnalyzer {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        CppAnalyzer::new(Arc::new(config))
+    }
+
+    #[test]
+    fn include_flags_cover_joined_and_separate_forms() {
+        let arguments: Vec<String> = [
+            "c++",
+            "-Iinclude",
+            "-I",
+            "gen",
+            "-isystem",
+            "\"third party\"",
+            "-iquote",
+            "quoted",
+            "-DNDEBUG",
+            "-c",
+            "main.cpp",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+
+        assert_eq!(
+            include_flags(&arguments),
+            vec!["include", "gen", "third party", "quoted"]
+        );
+    }
+
+    #[test]
+    fn includes_resolve_through_compile_commands() {
+        let root = scratch_dir("includes");
+        write(
+            &root.join("build/compile_commands.json"),
+            r#"[{
+                "directory": "BUILD",
+                "file": "../src/main.cpp",
+                "command": "c++ -I../include -isystem ../third_party -c ../src/main.cpp"
+            }]"#
+            .replace("BUILD", &root.join("build").to_string_lossy())
+            .as_str(),
+        );
+        write(&root.join("include/app/config.h"), "#pragma once\n");
+        write(&root.join("third_party/lib.h"), "#pragma once\n");
+        write(&root.join("src/local.h"), "#pragma once\n");
+        let content =
+            "#include \"local.h\"\n#include <app/config.h>\n#include <lib.h>\n#include <vector>\n";
+        let path = root.join("src/main.cpp");
+        write(&path, content);
+
+        let analyzer = analyzer(&root);
+        let tree = analyzer.parse(&path, content).unwrap();
+        let dependencies = analyzer.extract_dependencies_from_tree(&tree, content, &path, &root);
+
+        let targets: Vec<PathBuf> = dependencies.iter().map(|d| d.target.clone()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                root.join("src/local.h"),
+                root.join("include/app/config.h"),
+                root.join("third_party/lib.h"),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn sources_implement_and_inherit_from_included_headers() {
+        let root = scratch_dir("implementation");
+        write(
+            &root.join("shape.h"),
+            "class Shape {\npublic:\n    virtual ~Shape();\n};\n",
+        );
+        let content = "#include \"shape.h\"\n\nclass Circle : public Shape {};\n";
+        let path = root.join("shape.cpp");
+        write(&path, content);
+
+        let analyzer = analyzer(&root);
+        let tree = analyzer.parse(&path, content).unwrap();
+        let dependencies = analyzer.extract_dependencies_from_tree(&tree, content, &path, &root);
+
+        let kinds: Vec<(DependencyType, PathBuf)> = dependencies
+            .iter()
+            .map(|d| (d.dependency_type.clone(), d.target.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DependencyType::Import, root.join("shape.h")),
+                (DependencyType::Implementation, root.join("shape.h")),
+                (DependencyType::Inheritance, root.join("shape.h")),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn classes_are_qualified_and_count_implicit_member_access() {
+        let content = "\
+namespace geo {
+class Shape {
+public:
+    double area() const { return width * height; }
+    void scale(double f) { width *= f; height *= f; }
+private:
+    double width;
+    double height;
+};
+}
+
+struct Square : public geo::Shape {};
+";
+        let path = Path::new("shape.hpp");
+        let analyzer = analyzer(Path::new("."));
+        let tree = analyzer.parse(path, content).unwrap();
+        let analysis = analyzer.analyze_tree(path, &tree, content);
+
+        let shape = analysis
+            .classes
+            .iter()
+            .find(|c| c.name == "geo::Shape")
+            .unwrap();
+        assert_eq!(shape.methods.len(), 2);
+        assert!(shape.methods.iter().all(|m| m.is_public));
+        assert!(shape.properties.iter().all(|p| !p.is_public));
+        assert_eq!(shape.metrics.cohesion, 1.0);
+
+        let square = analysis
+            .classes
+            .iter()
+            .find(|c| c.name == "Square")
+            .unwrap();
+        assert_eq!(square.base_classes, vec!["geo::Shape"]);
+        assert_eq!(square.metrics.inheritance_depth, 1);
+    }
+}
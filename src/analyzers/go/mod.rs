@@ -0,0 +1,1004 @@
+//! Go code analyzer
+//!
+//! This module provides an analyzer for Go code, extracting functions,
+//! methods, structs, interfaces, package-level variables and imports using
+//! tree-sitter. Methods are grouped under a class entry for their receiver
+//! type. Imports inside the current module are resolved to the files of the
+//! imported package using `go.mod`.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::analyzers::common::{
+    class_cohesion, count_branch_points, indentation_cognitive_complexity, iter_tree,
+    maintainability_index, node_text, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType,
+    FileAnalysis, FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
+};
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+use crate::utils::fs::normalize_path;
+
+/// Patterns that create branches in Go code
+const BRANCH_PATTERNS: &[&str] = &[
+    "if ", "for ", "case ", "select ", "&&", "||", "return", "break", "continue", "goto ",
+];
+
+/// Statements that add to cognitive complexity in Go code
+const COGNITIVE_PATTERNS: &[&str] = &[
+    "if ", "} else", "for ", "switch ", "select ", "case ", "defer ", "go ",
+];
+
+/// Go analyzer implementation providing structural analysis and
+/// package-aware import resolution for Go source files using tree-sitter.
+pub struct GoAnalyzer {
+    /// Configuration options
+    config: Arc<Config>,
+
+    /// Nearest `go.mod` per directory
+    module_cache: Mutex<HashMap<PathBuf, Option<Arc<GoModule>>>>,
+}
+
+/// A Go module as described by its `go.mod` file
+#[derive(Debug, Clone)]
+struct GoModule {
+    /// Directory containing `go.mod`
+    root: PathBuf,
+
+    /// Module path from the `module` directive
+    path: String,
+
+    /// `replace` directives pointing at local directories
+    replacements: Vec<(String, PathBuf)>,
+}
+
+/// An import spec as written in the source
+#[derive(Debug, Clone)]
+struct ImportSite {
+    /// Import path
+    path: String,
+
+    /// Explicit package name (`_`, `.` or an alias)
+    alias: Option<String>,
+
+    /// Line number
+    line: usize,
+}
+
+impl ImportSite {
+    /// Name the imported package is referred to by in this file
+    ///
+    /// Without an alias this is the last import path element, which matches
+    /// the package name by convention.
+    fn binding(&self) -> String {
+        self.alias.clone().unwrap_or_else(|| {
+            self.path
+                .rsplit('/')
+                .next()
+                .unwrap_or(&self.path)
+                .to_string()
+        })
+    }
+}
+
+impl GoModule {
+    /// Parse a `go.mod` file
+    fn load(go_mod: &Path) -> Result<Self> {
+        let content = fs::read_to_string(go_mod).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read {}: {}", go_mod.display(), e))
+        })?;
+
+        let root = go_mod.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut path = None;
+        let mut replacements = Vec::new();
+        let mut in_replace_block = false;
+
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if in_replace_block {
+                if line == ")" {
+                    in_replace_block = false;
+                } else if let Some(replacement) = parse_replacement(line, &root) {
+                    replacements.push(replacement);
+                }
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix("module ") {
+                path = Some(rest.trim().trim_matches('"').to_string());
+            } else if line == "replace (" {
+                in_replace_block = true;
+            } else if let Some(rest) = line.strip_prefix("replace ") {
+                if let Some(replacement) = parse_replacement(rest, &root) {
+                    replacements.push(replacement);
+                }
+            }
+        }
+
+        let path = path.ok_or_else(|| {
+            ZseiError::Analyzer(format!("No module directive in {}", go_mod.display()))
+        })?;
+
+        Ok(Self {
+            root,
+            path,
+            replacements,
+        })
+    }
+
+    /// Map an import path to a package directory, if it belongs to this
+    /// module or to a locally replaced module
+    fn package_dir(&self, import_path: &str) -> Option<PathBuf> {
+        let candidates = std::iter::once((self.path.as_str(), &self.root)).chain(
+            self.replacements
+                .iter()
+                .map(|(module, dir)| (module.as_str(), dir)),
+        );
+
+        for (module_path, module_root) in candidates {
+            if import_path == module_path {
+                return Some(module_root.clone());
+            }
+            if let Some(rest) = import_path
+                .strip_prefix(module_path)
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                return Some(module_root.join(rest));
+            }
+        }
+
+        None
+    }
+}
+
+/// Parse `old [version] => new [version]`, keeping only local replacements
+fn parse_replacement(directive: &str, module_root: &Path) -> Option<(String, PathBuf)> {
+    let (old, new) = directive.split_once("=>")?;
+    let old_path = old.split_whitespace().next()?;
+    let new_path = new.split_whitespace().next()?;
+
+    if new_path.starts_with('.') || new_path.starts_with('/') {
+        Some((
+            old_path.to_string(),
+            normalize_path(&module_root.join(new_path)),
+        ))
+    } else {
+        None
+    }
+}
+
+impl GoAnalyzer {
+    /// Create a new Go analyzer with the given configuration.
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            module_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Parse file content into a syntax tree
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .expect("Failed to set Go language");
+
+        parser
+            .parse(content, None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Read a file into a string
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Build a file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> FileAnalysis {
+        let root = tree.root_node();
+
+        let mut functions = Vec::new();
+        let mut classes: Vec<Class> = Vec::new();
+        let mut methods: Vec<(String, String, Function)> = Vec::new();
+        let mut variables = Vec::new();
+
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            match node.kind() {
+                "function_declaration" => {
+                    functions.push(self.build_function(node, content));
+                }
+                "method_declaration" => {
+                    if let Some((receiver_type, receiver_name)) = self.receiver(node, content) {
+                        methods.push((
+                            receiver_type,
+                            receiver_name,
+                            self.build_function(node, content),
+                        ));
+                    }
+                }
+                "type_declaration" => {
+                    let mut spec_cursor = node.walk();
+                    for spec in node.named_children(&mut spec_cursor) {
+                        if let Some(class) = self.build_type(spec, content) {
+                            classes.push(class);
+                        }
+                    }
+                }
+                "var_declaration" | "const_declaration" => {
+                    variables.extend(self.declaration_variables(node, content));
+                }
+                _ => {}
+            }
+        }
+
+        // Attach methods to their receiver types, creating entries for types
+        // declared in another file of the package
+        let mut receivers: HashMap<String, String> = HashMap::new();
+        for (receiver_type, receiver_name, method) in methods {
+            let index = match classes.iter().position(|c| c.name == receiver_type) {
+                Some(index) => index,
+                None => {
+                    classes.push(Class {
+                        is_public: is_exported(&receiver_type),
+                        name: receiver_type.clone(),
+                        start_line: method.start_line,
+                        end_line: method.end_line,
+                        methods: Vec::new(),
+                        properties: Vec::new(),
+                        base_classes: Vec::new(),
+                        metrics: ClassMetrics {
+                            loc: 0,
+                            method_count: 0,
+                            property_count: 0,
+                            inheritance_depth: 0,
+                            cohesion: 1.0,
+                        },
+                    });
+                    classes.len() - 1
+                }
+            };
+
+            receivers.entry(receiver_type).or_insert(receiver_name);
+            classes[index].methods.push(method);
+        }
+
+        for class in &mut classes {
+            let Some(receiver_name) = receivers.get(&class.name) else {
+                continue;
+            };
+
+            // Classes assembled from methods only span their methods
+            if class.metrics.loc == 0 {
+                class.start_line = class
+                    .methods
+                    .iter()
+                    .map(|m| m.start_line)
+                    .min()
+                    .unwrap_or(0);
+                class.end_line = class.methods.iter().map(|m| m.end_line).max().unwrap_or(0);
+                class.metrics.loc = class
+                    .methods
+                    .iter()
+                    .map(|m| m.end_line - m.start_line + 1)
+                    .sum();
+            }
+
+            class.metrics.method_count = class.methods.len();
+            class.metrics.cohesion = class_cohesion(
+                &class.methods,
+                &class.properties,
+                &format!("{}.", receiver_name),
+            );
+        }
+
+        let imports: Vec<Import> = self
+            .extract_import_sites(root, content)
+            .into_iter()
+            .map(|site| Import {
+                // Go has no relative imports in module mode
+                is_relative: site.path.starts_with('.'),
+                external_crate: None,
+                path: site.path,
+                name: None,
+                alias: site.alias,
+                line: site.line,
+            })
+            .collect();
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .map(str::trim_start)
+            .filter(|l| l.starts_with("//") || l.starts_with("/*") || l.starts_with('*'))
+            .count();
+
+        let complexity = functions
+            .iter()
+            .chain(classes.iter().flat_map(|c| c.methods.iter()))
+            .map(|f| f.metrics.complexity)
+            .sum();
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index: maintainability_index(loc, comment_lines, complexity),
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} types, {} imports, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        FileAnalysis {
+            path: path.to_path_buf(),
+            language: "Go".to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        }
+    }
+
+    /// Build a function from a `function_declaration` or `method_declaration`
+    fn build_function(&self, node: Node<'_>, content: &str) -> Function {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_default();
+
+        let parameters = node
+            .child_by_field_name("parameters")
+            .map(|p| self.extract_parameters(p, content))
+            .unwrap_or_default();
+
+        let return_type = node
+            .child_by_field_name("result")
+            .map(|r| node_text(r, content).to_string());
+
+        let body_node = node.child_by_field_name("body");
+        let body = body_node.map(|b| node_text(b, content).to_string());
+
+        let signature_end = body_node.map_or(node.end_byte(), |b| b.start_byte());
+        let signature = content[node.start_byte()..signature_end]
+            .trim_end()
+            .to_string();
+
+        let body_text = body.as_deref().unwrap_or("");
+        let metrics = FunctionMetrics {
+            loc: body_text.lines().count(),
+            complexity: count_branch_points(body_text, BRANCH_PATTERNS),
+            parameter_count: parameters.len(),
+            cognitive_complexity: indentation_cognitive_complexity(body_text, COGNITIVE_PATTERNS),
+        };
+
+        Function {
+            is_public: is_exported(&name),
+            name,
+            signature,
+            start_line: node.start_position().row,
+            end_line: node.end_position().row,
+            body,
+            return_type,
+            parameters,
+            metrics,
+        }
+    }
+
+    /// Extract parameters from a `parameter_list` node
+    ///
+    /// Grouped parameters such as `a, b int` produce one entry per name.
+    fn extract_parameters(&self, params_node: Node<'_>, content: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let variadic = child.kind() == "variadic_parameter_declaration";
+            if child.kind() != "parameter_declaration" && !variadic {
+                continue;
+            }
+
+            let param_type = child.child_by_field_name("type").map(|t| {
+                let type_text = node_text(t, content);
+                if variadic {
+                    format!("...{}", type_text)
+                } else {
+                    type_text.to_string()
+                }
+            });
+
+            let mut name_cursor = child.walk();
+            let names: Vec<String> = child
+                .children_by_field_name("name", &mut name_cursor)
+                .map(|n| node_text(n, content).to_string())
+                .collect();
+
+            // Unnamed parameters only have a type
+            if names.is_empty() {
+                parameters.push(Parameter {
+                    name: "_".to_string(),
+                    param_type,
+                    default_value: None,
+                    position: parameters.len(),
+                });
+                continue;
+            }
+
+            for name in names {
+                parameters.push(Parameter {
+                    name,
+                    param_type: param_type.clone(),
+                    default_value: None,
+                    position: parameters.len(),
+                });
+            }
+        }
+
+        parameters
+    }
+
+    /// Get the receiver type name and receiver variable of a method
+    fn receiver(&self, method: Node<'_>, content: &str) -> Option<(String, String)> {
+        let receiver = method.child_by_field_name("receiver")?;
+        let declaration = receiver.named_child(0)?;
+
+        let type_text = node_text(declaration.child_by_field_name("type")?, content);
+        let type_name = type_text
+            .trim_start_matches('*')
+            .split('[')
+            .next()
+            .unwrap_or(type_text)
+            .trim()
+            .to_string();
+
+        let receiver_name = declaration
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_else(|| "_".to_string());
+
+        Some((type_name, receiver_name))
+    }
+
+    /// Build a class entry from a `type_spec` declaring a struct or interface
+    fn build_type(&self, spec: Node<'_>, content: &str) -> Option<Class> {
+        if spec.kind() != "type_spec" {
+            return None;
+        }
+
+        let name = node_text(spec.child_by_field_name("name")?, content).to_string();
+        let type_node = spec.child_by_field_name("type")?;
+
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+        let mut base_classes = Vec::new();
+
+        match type_node.kind() {
+            "struct_type" => {
+                for field in iter_tree(type_node).filter(|n| n.kind() == "field_declaration") {
+                    let field_type = field
+                        .child_by_field_name("type")
+                        .map(|t| node_text(t, content).to_string());
+
+                    let mut name_cursor = field.walk();
+                    let names: Vec<String> = field
+                        .children_by_field_name("name", &mut name_cursor)
+                        .map(|n| node_text(n, content).to_string())
+                        .collect();
+
+                    // Embedded fields are Go's composition in place of inheritance
+                    if names.is_empty() {
+                        if let Some(embedded) = field_type {
+                            base_classes.push(embedded.trim_start_matches('*').to_string());
+                        }
+                        continue;
+                    }
+
+                    for field_name in names {
+                        properties.push(Variable {
+                            is_public: is_exported(&field_name),
+                            name: field_name,
+                            var_type: field_type.clone(),
+                            line: field.start_position().row,
+                            init_value: None,
+                        });
+                    }
+                }
+            }
+            "interface_type" => {
+                let mut member_cursor = type_node.walk();
+                for member in type_node.named_children(&mut member_cursor) {
+                    match member.kind() {
+                        "method_elem" | "method_spec" => {
+                            methods.push(self.build_function(member, content));
+                        }
+                        // Embedded interfaces and type constraints
+                        "type_elem" | "constraint_elem" | "interface_type_name" => {
+                            base_classes.push(node_text(member, content).to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => return None,
+        }
+
+        let start_line = spec.start_position().row;
+        let end_line = spec.end_position().row;
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            inheritance_depth: usize::from(!base_classes.is_empty()),
+            cohesion: 1.0,
+        };
+
+        Some(Class {
+            is_public: is_exported(&name),
+            name,
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            metrics,
+        })
+    }
+
+    /// Extract variables and constants from a `var` or `const` declaration
+    fn declaration_variables(&self, declaration: Node<'_>, content: &str) -> Vec<Variable> {
+        let mut variables = Vec::new();
+
+        for spec in
+            iter_tree(declaration).filter(|n| n.kind() == "var_spec" || n.kind() == "const_spec")
+        {
+            let var_type = spec
+                .child_by_field_name("type")
+                .map(|t| node_text(t, content).to_string());
+
+            let values: Vec<String> = spec
+                .child_by_field_name("value")
+                .map(|list| {
+                    let mut value_cursor = list.walk();
+                    let values = list
+                        .named_children(&mut value_cursor)
+                        .map(|v| node_text(v, content).to_string())
+                        .collect();
+                    values
+                })
+                .unwrap_or_default();
+
+            let mut name_cursor = spec.walk();
+            let names: Vec<Node<'_>> = spec
+                .children_by_field_name("name", &mut name_cursor)
+                .collect();
+
+            for (i, name_node) in names.into_iter().enumerate() {
+                let name = node_text(name_node, content).to_string();
+                if name == "_" {
+                    continue;
+                }
+
+                variables.push(Variable {
+                    is_public: is_exported(&name),
+                    name,
+                    var_type: var_type.clone(),
+                    line: spec.start_position().row,
+                    init_value: values.get(i).cloned(),
+                });
+            }
+        }
+
+        variables
+    }
+
+    /// Extract all import specs
+    fn extract_import_sites(&self, root: Node<'_>, content: &str) -> Vec<ImportSite> {
+        iter_tree(root)
+            .filter(|n| n.kind() == "import_spec")
+            .filter_map(|spec| {
+                let path_node = spec.child_by_field_name("path")?;
+                Some(ImportSite {
+                    path: node_text(path_node, content)
+                        .trim_matches(|c| c == '"' || c == '`')
+                        .to_string(),
+                    alias: spec
+                        .child_by_field_name("name")
+                        .map(|n| node_text(n, content).to_string()),
+                    line: spec.start_position().row,
+                })
+            })
+            .collect()
+    }
+
+    /// Find the module containing a directory
+    fn module_for(&self, dir: &Path, project_root: &Path) -> Option<Arc<GoModule>> {
+        if let Some(cached) = self.module_cache.lock().ok()?.get(dir) {
+            return cached.clone();
+        }
+
+        let mut current = Some(dir);
+        let mut found = None;
+
+        while let Some(candidate_dir) = current {
+            let go_mod = candidate_dir.join("go.mod");
+            if go_mod.is_file() {
+                match GoModule::load(&go_mod) {
+                    Ok(module) => found = Some(Arc::new(module)),
+                    Err(e) => debug!("Ignoring {}: {}", go_mod.display(), e),
+                }
+                break;
+            }
+
+            if candidate_dir == project_root {
+                break;
+            }
+
+            current = candidate_dir
+                .parent()
+                .filter(|parent| parent.starts_with(project_root));
+        }
+
+        if let Ok(mut cache) = self.module_cache.lock() {
+            cache.insert(dir.to_path_buf(), found.clone());
+        }
+
+        found
+    }
+
+    /// Resolve an import path to the source files of the imported package
+    ///
+    /// Packages outside the current module (standard library, third-party
+    /// modules) resolve to an empty list unless a `replace` directive points
+    /// them at a local directory. Test files are only included for imports
+    /// made from test files.
+    fn resolve_package_files(
+        &self,
+        import_path: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Vec<PathBuf> {
+        let Some(source_dir) = source_path.parent() else {
+            return Vec::new();
+        };
+        let Some(module) = self.module_for(source_dir, project_root) else {
+            return Vec::new();
+        };
+        let Some(package_dir) = module.package_dir(import_path) else {
+            return Vec::new();
+        };
+
+        let from_test = source_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map_or(false, |n| n.ends_with("_test.go"));
+
+        let Ok(entries) = fs::read_dir(&package_dir) else {
+            return Vec::new();
+        };
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    return false;
+                };
+                path.is_file()
+                    && name.ends_with(".go")
+                    && (from_test || !name.ends_with("_test.go"))
+                    && path.as_path() != source_path
+            })
+            .collect();
+
+        files.sort();
+        files
+    }
+
+    /// Extract dependencies from a parsed syntax tree
+    fn extract_dependencies_from_tree(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Vec<Dependency> {
+        let root = tree.root_node();
+        let mut dependencies = Vec::new();
+
+        // Package names bound by imports, used to resolve embedded types
+        let mut bindings: HashMap<String, Vec<PathBuf>> = HashMap::new();
+
+        for site in self.extract_import_sites(root, content) {
+            let files = self.resolve_package_files(&site.path, source_path, project_root);
+            if files.is_empty() {
+                debug!(
+                    "Could not resolve import '{}' in {}",
+                    site.path,
+                    source_path.display()
+                );
+                continue;
+            }
+
+            for target in &files {
+                dependencies.push(Dependency {
+                    source: source_path.to_path_buf(),
+                    target: target.clone(),
+                    dependency_type: DependencyType::Import,
+                    line: Some(site.line),
+                    info: Some(format!("Import: {}", site.path)),
+                });
+            }
+
+            bindings.insert(site.binding(), files);
+        }
+
+        // Embedding of types from imported packages
+        for node in iter_tree(root).filter(|n| n.kind() == "type_spec") {
+            let Some(class) = self.build_type(node, content) else {
+                continue;
+            };
+
+            for base in &class.base_classes {
+                let Some((package, type_name)) = base.split_once('.') else {
+                    continue;
+                };
+                let Some(files) = bindings.get(package) else {
+                    continue;
+                };
+
+                let type_name = type_name.split('[').next().unwrap_or(type_name);
+                if let Some(target) = files.iter().find(|file| declares_type(file, type_name)) {
+                    dependencies.push(Dependency {
+                        source: source_path.to_path_buf(),
+                        target: target.clone(),
+                        dependency_type: DependencyType::Inheritance,
+                        line: Some(class.start_line),
+                        info: Some(format!("Embedding: {} embeds {}", class.name, base)),
+                    });
+                }
+            }
+        }
+
+        dependencies
+    }
+}
+
+/// Go visibility: identifiers starting with an upper-case letter are exported
+fn is_exported(name: &str) -> bool {
+    name.chars().next().map_or(false, char::is_uppercase)
+}
+
+/// Check whether a Go file declares a type with the given name
+fn declares_type(path: &Path, type_name: &str) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+
+    let needle = format!("{} ", type_name);
+    let mut in_type_block = false;
+
+    for line in content.lines() {
+        if in_type_block {
+            if line.trim() == ")" {
+                in_type_block = false;
+            } else if line.trim_start().starts_with(&needle) {
+                return true;
+            }
+        } else if line.trim_end() == "type (" {
+            in_type_block = true;
+        } else if let Some(rest) = line.strip_prefix("type ") {
+            if rest.trim_start().starts_with(&needle) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[async_trait]
+impl LanguageAnalyzer for GoAnalyzer {
+    fn language_name(&self) -> &'static str {
+        "Go"
+    }
+
+    fn supported_extensions(&self) -> &[&'static str] {
+        &["go"]
+    }
+
+    async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
+        info!("Analyzing Go file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        Ok(self.analyze_tree(path, &tree, &content))
+    }
+
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!("Extracting dependencies from Go file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(dependencies)
+    }
+
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing Go file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content),
+            dependencies,
+            symbols: SymbolGraph::default(),
+        })
+    }
+
+    fn reset_caches(&self) {
+        if let Ok(mut cache) = self.module_cache.lock() {
+            cache.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-go-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn go_mod_maps_module_and_local_replacements() {
+        let root = scratch_dir("go-mod");
+        write(
+            &root.join("go.mod"),
+            "module example.com/app // the app\n\n\
+             go 1.22\n\n\
+             replace example.com/single => ./single\n\
+             replace (\n\
+             \texample.com/lib v1.0.0 => ../lib\n\
+             \texample.com/remote => example.com/fork v1.2.0\n\
+             )\n",
+        );
+
+        let module = GoModule::load(&root.join("go.mod")).unwrap();
+        assert_eq!(module.path, "example.com/app");
+        assert_eq!(
+            module.package_dir("example.com/app/internal/store"),
+            Some(root.join("internal/store"))
+        );
+        assert_eq!(module.package_dir("example.com/app"), Some(root.clone()));
+        assert_eq!(
+            module.package_dir("example.com/single/util"),
+            Some(root.join("single/util"))
+        );
+        assert_eq!(
+            module.package_dir("example.com/lib"),
+            Some(normalize_path(&root.join("../lib")))
+        );
+        assert_eq!(module.package_dir("example.com/remote"), None);
+        assert_eq!(module.package_dir("example.com/application"), None);
+        assert_eq!(module.package_dir("fmt"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn methods_attach_to_receiver_and_embedding_resolves_across_packages() {
+        let root = scratch_dir("embedding");
+        write(&root.join("go.mod"), "module example.com/app\n");
+        write(
+            &root.join("base/base.go"),
+            "package base\n\ntype Model struct {\n\tID int\n}\n",
+        );
+        write(&root.join("base/base_test.go"), "package base\n");
+        let content = "package user
+
+import (
+\tb \"example.com/app/base\"
+\t\"fmt\"
+)
+
+type User struct {
+\t*b.Model
+\tName string
+}
+
+func (u *User) Greet() string {
+\treturn fmt.Sprintf(\"hi %s\", u.Name)
+}
+";
+        let path = root.join("user/user.go");
+        write(&path, content);
+
+        let mut config = Config::default();
+        config.set_project_root(root.clone());
+        let analyzer = GoAnalyzer::new(Arc::new(config));
+        let tree = analyzer.parse(&path, content).unwrap();
+
+        let analysis = analyzer.analyze_tree(&path, &tree, content);
+        let user = &analysis.classes[0];
+        assert_eq!(user.name, "User");
+        assert_eq!(user.base_classes, vec!["b.Model"]);
+        assert_eq!(user.methods.len(), 1);
+        assert_eq!(user.methods[0].name, "Greet");
+
+        let imports: Vec<(&str, Option<&str>)> = analysis
+            .imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![("example.com/app/base", Some("b")), ("fmt", None)]
+        );
+
+        let dependencies = analyzer.extract_dependencies_from_tree(&tree, content, &path, &root);
+        let targets: Vec<(&Path, &DependencyType)> = dependencies
+            .iter()
+            .map(|d| (d.target.as_path(), &d.dependency_type))
+            .collect();
+        let base = root.join("base/base.go");
+        assert_eq!(
+            targets,
+            vec![
+                (base.as_path(), &DependencyType::Import),
+                (base.as_path(), &DependencyType::Inheritance),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,1325 @@
+//! Java and Kotlin code analyzer
+//!
+//! This module provides an analyzer for JVM languages, extracting classes,
+//! interfaces, enums and objects with their base types, methods and
+//! properties using tree-sitter. Imports are resolved to source files using
+//! the package declarations of files under Maven/Gradle `src/main/*` source
+//! roots, and inheritance depth is computed across the whole project.
+
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::analyzers::common::{
+    class_cohesion, count_branch_points, indentation_cognitive_complexity, iter_tree,
+    maintainability_index, node_text, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType,
+    FileAnalysis, FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
+};
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+
+/// Patterns that create branches in Java and Kotlin code
+const BRANCH_PATTERNS: &[&str] = &[
+    "if (", "if(", "for (", "for(", "while (", "while(", "case ", "catch", "&&", "||", "?:", "->",
+    "return", "break", "continue",
+];
+
+/// Statements that add to cognitive complexity in Java and Kotlin code
+const COGNITIVE_PATTERNS: &[&str] = &[
+    "if (", "if(", "} else", "else", "for (", "for(", "while", "switch", "when", "case ", "try",
+    "} catch", "catch",
+];
+
+/// Class-like declaration node kinds for both grammars
+const CLASS_KINDS: &[&str] = &[
+    "class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+    "annotation_type_declaration",
+    "object_declaration",
+];
+
+/// Java/Kotlin analyzer implementation providing structural analysis and
+/// package-based import resolution for JVM source files using tree-sitter.
+pub struct JvmAnalyzer {
+    /// Configuration options
+    config: Arc<Config>,
+
+    /// Source files per package, built on first use
+    package_index: Mutex<Option<Arc<PackageIndex>>>,
+}
+
+/// Source files of the project grouped by declared package
+#[derive(Debug, Default)]
+struct PackageIndex {
+    /// Files per package name
+    packages: HashMap<String, Vec<PathBuf>>,
+}
+
+/// The JVM language of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JvmLanguage {
+    /// Java source
+    Java,
+
+    /// Kotlin source or script
+    Kotlin,
+}
+
+/// A base type of a class with the kind of relationship
+#[derive(Debug, Clone)]
+struct BaseType {
+    /// Base type as written, including type arguments
+    name: String,
+
+    /// Inheritance for superclasses, implementation for interfaces
+    dependency_type: DependencyType,
+
+    /// Line number
+    line: usize,
+}
+
+/// Resolution context of a source file: its package, imports and classes
+#[derive(Debug, Clone, Default)]
+struct FileContext {
+    /// Declared package
+    package: String,
+
+    /// Import paths, including wildcards such as `a.b.*`
+    imports: Vec<String>,
+
+    /// Base types per class name (nested classes as `Outer.Inner`)
+    classes: HashMap<String, Vec<String>>,
+}
+
+impl JvmLanguage {
+    /// Determine the language from a file extension
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("kt") | Some("kts") => JvmLanguage::Kotlin,
+            _ => JvmLanguage::Java,
+        }
+    }
+
+    /// Display name of the language
+    fn name(self) -> &'static str {
+        match self {
+            JvmLanguage::Java => "Java",
+            JvmLanguage::Kotlin => "Kotlin",
+        }
+    }
+
+    /// Tree-sitter grammar for the language
+    fn grammar(self) -> Language {
+        match self {
+            JvmLanguage::Java => tree_sitter_java::LANGUAGE.into(),
+            JvmLanguage::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
+        }
+    }
+}
+
+impl PackageIndex {
+    /// Index the files under the Maven/Gradle source roots of the given
+    /// directories
+    ///
+    /// Files under `src/main/<language>` directories are preferred; when a
+    /// project has no such layout all Java and Kotlin files are indexed.
+    fn build(roots: &[PathBuf]) -> Self {
+        let mut layout_files = Vec::new();
+        let mut other_files = Vec::new();
+
+        for root in roots.iter().filter(|r| r.is_dir()) {
+            for entry in ignore::WalkBuilder::new(root).build().flatten() {
+                let path = entry.path();
+                let is_jvm_file = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map_or(false, |e| matches!(e, "java" | "kt"));
+                if !is_jvm_file {
+                    continue;
+                }
+
+                if is_under_main_source_root(path) {
+                    layout_files.push(path.to_path_buf());
+                } else {
+                    other_files.push(path.to_path_buf());
+                }
+            }
+        }
+
+        let files = if layout_files.is_empty() {
+            other_files
+        } else {
+            layout_files
+        };
+
+        let mut index = PackageIndex::default();
+        for file in files {
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            index
+                .packages
+                .entry(package_of(&content))
+                .or_default()
+                .push(file);
+        }
+
+        debug!("Indexed {} JVM packages", index.packages.len());
+        index
+    }
+
+    /// Find the file of a class in a package
+    fn class_file(&self, package: &str, class_name: &str) -> Option<&PathBuf> {
+        let files = self.packages.get(package)?;
+
+        files
+            .iter()
+            .find(|f| f.file_stem().and_then(|s| s.to_str()) == Some(class_name))
+            .or_else(|| files.iter().find(|f| declares_class(f, class_name)))
+    }
+
+    /// Resolve a fully qualified name to the file defining its outermost class
+    ///
+    /// Tries every split point, so `a.b.C`, `a.b.C.Inner` and static imports
+    /// such as `a.b.C.method` all resolve to the file of `a.b.C`.
+    fn resolve_qualified(&self, qualified: &str) -> Option<&PathBuf> {
+        let segments: Vec<&str> = qualified.split('.').collect();
+
+        for split in (1..segments.len()).rev() {
+            let package = segments[..split].join(".");
+            if let Some(file) = self.class_file(&package, segments[split]) {
+                return Some(file);
+            }
+        }
+
+        // Classes in the default package
+        self.class_file("", segments.first()?)
+    }
+}
+
+impl JvmAnalyzer {
+    /// Create a new Java/Kotlin analyzer with the given configuration.
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            package_index: Mutex::new(None),
+        }
+    }
+
+    /// Get the package index, building it on first use
+    fn package_index(&self) -> Arc<PackageIndex> {
+        let mut index = self.package_index.lock().unwrap_or_else(|e| e.into_inner());
+        index
+            .get_or_insert_with(|| {
+                let mut roots = vec![self.config.project_root().to_path_buf()];
+                roots.extend(self.config.additional_project_paths.iter().cloned());
+                Arc::new(PackageIndex::build(&roots))
+            })
+            .clone()
+    }
+
+    /// Parse file content into a syntax tree
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let language = JvmLanguage::from_path(path);
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language.grammar())
+            .expect("Failed to set Java/Kotlin language");
+
+        parser
+            .parse(content, None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Read a file into a string
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Build a file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> FileAnalysis {
+        let root = tree.root_node();
+        let language = JvmLanguage::from_path(path);
+
+        let mut functions = Vec::new();
+        let mut classes = Vec::new();
+        let mut variables = Vec::new();
+
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            match node.kind() {
+                kind if CLASS_KINDS.contains(&kind) => {
+                    self.collect_classes(node, None, language, content, &mut classes);
+                }
+                // Kotlin top-level functions and properties
+                "function_declaration" => {
+                    functions.push(self.build_function(node, language, false, content));
+                }
+                "property_declaration" => {
+                    variables.extend(self.build_properties(node, language, false, content));
+                }
+                _ => {}
+            }
+        }
+
+        let imports: Vec<Import> = self
+            .extract_imports(root, content)
+            .into_iter()
+            .map(|(path, alias, line)| Import {
+                name: (!path.ends_with('*'))
+                    .then(|| path.rsplit('.').next().unwrap_or(&path).to_string()),
+                alias,
+                path,
+                line,
+                is_relative: false,
+                external_crate: None,
+            })
+            .collect();
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .map(str::trim_start)
+            .filter(|l| l.starts_with("//") || l.starts_with("/*") || l.starts_with('*'))
+            .count();
+
+        let complexity = functions
+            .iter()
+            .chain(classes.iter().flat_map(|c| c.methods.iter()))
+            .map(|f| f.metrics.complexity)
+            .sum();
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index: maintainability_index(loc, comment_lines, complexity),
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} classes, {} imports, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        FileAnalysis {
+            path: path.to_path_buf(),
+            language: language.name().to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        }
+    }
+
+    /// Build a class and its nested classes, named `Outer.Inner`
+    fn collect_classes(
+        &self,
+        node: Node<'_>,
+        outer: Option<&str>,
+        language: JvmLanguage,
+        content: &str,
+        classes: &mut Vec<Class>,
+    ) {
+        let simple_name = declaration_name(node, content).unwrap_or_default();
+        let name = match outer {
+            Some(outer) => format!("{}.{}", outer, simple_name),
+            None => simple_name,
+        };
+
+        let is_interface = is_interface(node, content);
+        let base_classes = self
+            .base_types(node, language, content)
+            .into_iter()
+            .map(|base| base.name)
+            .collect();
+
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+        let mut nested = Vec::new();
+
+        // Kotlin primary constructor `val`/`var` parameters are properties
+        for child in named_children(node) {
+            if child.kind() != "primary_constructor" {
+                continue;
+            }
+            for parameter in iter_tree(child).filter(|n| n.kind() == "class_parameter") {
+                let text = node_text(parameter, content).trim_start();
+                let declares_property = named_children(parameter)
+                    .into_iter()
+                    .any(|c| c.kind() == "binding_pattern_kind")
+                    || text.starts_with("val ")
+                    || text.starts_with("var ")
+                    || text.contains(" val ")
+                    || text.contains(" var ");
+                if !declares_property {
+                    continue;
+                }
+
+                let (parameter_name, parameter_type) = split_kotlin_parameter(text);
+                properties.push(Variable {
+                    is_public: kotlin_is_public(parameter, content),
+                    name: parameter_name,
+                    var_type: parameter_type,
+                    line: parameter.start_position().row,
+                    init_value: None,
+                });
+            }
+        }
+
+        if let Some(body) = class_body(node) {
+            for member in named_children(body) {
+                match member.kind() {
+                    "method_declaration"
+                    | "constructor_declaration"
+                    | "compact_constructor_declaration"
+                    | "function_declaration"
+                    | "secondary_constructor" => {
+                        methods.push(self.build_function(member, language, is_interface, content));
+                    }
+                    "field_declaration" | "constant_declaration" | "property_declaration" => {
+                        properties.extend(self.build_properties(
+                            member,
+                            language,
+                            is_interface,
+                            content,
+                        ));
+                    }
+                    // Java enum constants and bodies
+                    "enum_body_declarations" => {
+                        for inner in named_children(member) {
+                            match inner.kind() {
+                                "method_declaration" | "constructor_declaration" => {
+                                    methods
+                                        .push(self.build_function(inner, language, false, content));
+                                }
+                                "field_declaration" => {
+                                    properties.extend(
+                                        self.build_properties(inner, language, false, content),
+                                    );
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    // Kotlin companion objects contribute static-like members
+                    "companion_object" => {
+                        if let Some(companion_body) = class_body(member) {
+                            for inner in named_children(companion_body) {
+                                match inner.kind() {
+                                    "function_declaration" => methods
+                                        .push(self.build_function(inner, language, false, content)),
+                                    "property_declaration" => properties.extend(
+                                        self.build_properties(inner, language, false, content),
+                                    ),
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    kind if CLASS_KINDS.contains(&kind) => nested.push(member),
+                    _ => {}
+                }
+            }
+        }
+
+        // Java records declare their components as properties
+        if let Some(components) = node.child_by_field_name("parameters") {
+            if node.kind() == "record_declaration" {
+                for parameter in self.extract_parameters(components, language, content) {
+                    properties.push(Variable {
+                        name: parameter.name,
+                        var_type: parameter.param_type,
+                        line: components.start_position().row,
+                        is_public: true,
+                        init_value: None,
+                    });
+                }
+            }
+        }
+
+        let start_line = node.start_position().row;
+        let end_line = node.end_position().row;
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            // Computed across the project in `finalize_analyses`
+            inheritance_depth: 0,
+            cohesion: class_cohesion(&methods, &properties, ""),
+        };
+
+        let is_public = match language {
+            JvmLanguage::Java => modifiers_text(node, content).contains("public"),
+            JvmLanguage::Kotlin => kotlin_is_public(node, content),
+        };
+
+        classes.push(Class {
+            name: name.clone(),
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            is_public,
+            metrics,
+        });
+
+        for nested_node in nested {
+            self.collect_classes(nested_node, Some(&name), language, content, classes);
+        }
+    }
+
+    /// Extract the base types of a class-like declaration
+    fn base_types(&self, node: Node<'_>, language: JvmLanguage, content: &str) -> Vec<BaseType> {
+        let mut bases = Vec::new();
+
+        match language {
+            JvmLanguage::Java => {
+                let interface = node.kind() == "interface_declaration";
+
+                for child in named_children(node) {
+                    let dependency_type = match child.kind() {
+                        "superclass" => DependencyType::Inheritance,
+                        "super_interfaces" => DependencyType::Implementation,
+                        // Interfaces extending interfaces
+                        "extends_interfaces" if interface => DependencyType::Inheritance,
+                        _ => continue,
+                    };
+
+                    for base in iter_tree(child).filter(|n| {
+                        matches!(n.kind(), "type_identifier" | "scoped_type_identifier")
+                            && n.parent().map_or(false, |p| {
+                                !matches!(p.kind(), "scoped_type_identifier" | "type_arguments")
+                            })
+                    }) {
+                        bases.push(BaseType {
+                            name: node_text(base, content).to_string(),
+                            dependency_type: dependency_type.clone(),
+                            line: base.start_position().row,
+                        });
+                    }
+                }
+            }
+            JvmLanguage::Kotlin => {
+                let interface = is_interface(node, content);
+
+                for specifiers in named_children(node)
+                    .into_iter()
+                    .filter(|c| c.kind() == "delegation_specifiers")
+                {
+                    for specifier in named_children(specifiers) {
+                        // `: Base()` calls a superclass constructor; `: Iface` names an interface
+                        let is_constructor_call =
+                            iter_tree(specifier).any(|n| n.kind() == "constructor_invocation");
+
+                        let Some(type_node) =
+                            iter_tree(specifier).find(|n| n.kind() == "user_type")
+                        else {
+                            continue;
+                        };
+
+                        let dependency_type = if is_constructor_call || interface {
+                            DependencyType::Inheritance
+                        } else {
+                            DependencyType::Implementation
+                        };
+
+                        bases.push(BaseType {
+                            name: node_text(type_node, content).to_string(),
+                            dependency_type,
+                            line: type_node.start_position().row,
+                        });
+                    }
+                }
+            }
+        }
+
+        bases
+    }
+
+    /// Build a function from a Java method/constructor or Kotlin function
+    fn build_function(
+        &self,
+        node: Node<'_>,
+        language: JvmLanguage,
+        in_interface: bool,
+        content: &str,
+    ) -> Function {
+        let name = match node.kind() {
+            "secondary_constructor" => "constructor".to_string(),
+            _ => declaration_name(node, content).unwrap_or_else(|| "<anonymous>".to_string()),
+        };
+
+        let parameters = named_children(node)
+            .into_iter()
+            .find(|c| matches!(c.kind(), "formal_parameters" | "function_value_parameters"))
+            .map(|p| self.extract_parameters(p, language, content))
+            .unwrap_or_default();
+
+        let body_node = node.child_by_field_name("body").or_else(|| {
+            named_children(node)
+                .into_iter()
+                .find(|c| matches!(c.kind(), "function_body" | "block" | "constructor_body"))
+        });
+        let body = body_node.map(|b| node_text(b, content).to_string());
+
+        let signature_end = body_node.map_or(node.end_byte(), |b| b.start_byte());
+        let signature = content[node.start_byte()..signature_end]
+            .trim_end()
+            .trim_end_matches(';')
+            .to_string();
+
+        let return_type = match language {
+            JvmLanguage::Java => node
+                .child_by_field_name("type")
+                .map(|t| node_text(t, content).to_string()),
+            JvmLanguage::Kotlin => signature
+                .rsplit_once(')')
+                .and_then(|(_, rest)| rest.trim_start().strip_prefix(':'))
+                .map(|rest| {
+                    rest.split(" where ")
+                        .next()
+                        .unwrap_or(rest)
+                        .trim()
+                        .to_string()
+                })
+                .filter(|rest| !rest.is_empty()),
+        };
+
+        let is_public = match language {
+            JvmLanguage::Java => {
+                let modifiers = modifiers_text(node, content);
+                modifiers.contains("public") || (in_interface && !modifiers.contains("private"))
+            }
+            JvmLanguage::Kotlin => kotlin_is_public(node, content),
+        };
+
+        let body_text = body.as_deref().unwrap_or("");
+        let metrics = FunctionMetrics {
+            loc: body_text.lines().count(),
+            complexity: count_branch_points(body_text, BRANCH_PATTERNS),
+            parameter_count: parameters.len(),
+            cognitive_complexity: indentation_cognitive_complexity(body_text, COGNITIVE_PATTERNS),
+        };
+
+        Function {
+            name,
+            signature,
+            start_line: node.start_position().row,
+            end_line: node.end_position().row,
+            body,
+            return_type,
+            parameters,
+            is_public,
+            metrics,
+        }
+    }
+
+    /// Extract parameters from `formal_parameters` or `function_value_parameters`
+    fn extract_parameters(
+        &self,
+        params_node: Node<'_>,
+        language: JvmLanguage,
+        content: &str,
+    ) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let children: Vec<Node<'_>> = {
+            let mut cursor = params_node.walk();
+            let children = params_node.children(&mut cursor).collect();
+            children
+        };
+
+        for (i, child) in children.iter().enumerate() {
+            let (name, param_type, default_value) = match (language, child.kind()) {
+                (JvmLanguage::Java, "formal_parameter") => (
+                    child
+                        .child_by_field_name("name")
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default(),
+                    child
+                        .child_by_field_name("type")
+                        .map(|t| node_text(t, content).to_string()),
+                    None,
+                ),
+                (JvmLanguage::Java, "spread_parameter") => {
+                    let name = iter_tree(*child)
+                        .find(|n| n.kind() == "variable_declarator")
+                        .and_then(|d| d.child_by_field_name("name"))
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default();
+                    let param_type = named_children(*child)
+                        .into_iter()
+                        .find(|n| n.kind() != "variable_declarator" && n.kind() != "modifiers")
+                        .map(|t| format!("{}...", node_text(t, content)));
+                    (name, param_type, None)
+                }
+                (JvmLanguage::Kotlin, "parameter") => {
+                    let (name, param_type) = split_kotlin_parameter(node_text(*child, content));
+
+                    // Defaults follow the parameter as `= expression`
+                    let default_value = children
+                        .get(i + 1)
+                        .filter(|next| node_text(**next, content) == "=")
+                        .and_then(|_| children.get(i + 2))
+                        .map(|value| node_text(*value, content).to_string());
+
+                    (name, param_type, default_value)
+                }
+                _ => continue,
+            };
+
+            parameters.push(Parameter {
+                name,
+                param_type,
+                default_value,
+                position: parameters.len(),
+            });
+        }
+
+        parameters
+    }
+
+    /// Build properties from a Java field declaration or Kotlin property declaration
+    fn build_properties(
+        &self,
+        node: Node<'_>,
+        language: JvmLanguage,
+        in_interface: bool,
+        content: &str,
+    ) -> Vec<Variable> {
+        match language {
+            JvmLanguage::Java => {
+                let modifiers = modifiers_text(node, content);
+                let is_public = modifiers.contains("public") || in_interface;
+                let var_type = node
+                    .child_by_field_name("type")
+                    .map(|t| node_text(t, content).to_string());
+
+                let mut cursor = node.walk();
+                let declarators: Vec<Node<'_>> = node
+                    .children_by_field_name("declarator", &mut cursor)
+                    .collect();
+
+                declarators
+                    .into_iter()
+                    .filter_map(|declarator| {
+                        Some(Variable {
+                            name: node_text(declarator.child_by_field_name("name")?, content)
+                                .to_string(),
+                            var_type: var_type.clone(),
+                            line: node.start_position().row,
+                            is_public,
+                            init_value: declarator
+                                .child_by_field_name("value")
+                                .map(|v| node_text(v, content).to_string()),
+                        })
+                    })
+                    .collect()
+            }
+            JvmLanguage::Kotlin => {
+                let Some(declaration) =
+                    iter_tree(node).find(|n| n.kind() == "variable_declaration")
+                else {
+                    return Vec::new();
+                };
+                let (name, var_type) = split_kotlin_parameter(node_text(declaration, content));
+
+                let text = node_text(node, content);
+                let init_value = text
+                    .split_once('=')
+                    .map(|(_, value)| value.trim().to_string())
+                    .filter(|value| !value.is_empty());
+
+                vec![Variable {
+                    name,
+                    var_type,
+                    line: node.start_position().row,
+                    is_public: kotlin_is_public(node, content),
+                    init_value,
+                }]
+            }
+        }
+    }
+
+    /// Extract import paths with their Kotlin alias and line number
+    ///
+    /// Wildcard imports keep their trailing `.*`.
+    fn extract_imports(
+        &self,
+        root: Node<'_>,
+        content: &str,
+    ) -> Vec<(String, Option<String>, usize)> {
+        iter_tree(root)
+            .filter(|n| matches!(n.kind(), "import_declaration" | "import_header" | "import"))
+            // The Kotlin grammar's `import` keyword token shares the node kind
+            .filter(|n| n.is_named() && n.named_child_count() > 0)
+            .filter_map(|import| {
+                let text = node_text(import, content)
+                    .trim()
+                    .trim_start_matches("import")
+                    .trim()
+                    .trim_start_matches("static ")
+                    .trim_end_matches(';')
+                    .trim();
+                let (path, alias) = match text.split_once(" as ") {
+                    Some((path, alias)) => (path, Some(alias.trim().to_string())),
+                    None => (text, None),
+                };
+                let path: String = path.chars().filter(|c| !c.is_whitespace()).collect();
+
+                (!path.is_empty()).then(|| (path, alias, import.start_position().row))
+            })
+            .collect()
+    }
+
+    /// Build the resolution context of a file from its analysis
+    fn file_context(&self, analysis: &FileAnalysis) -> FileContext {
+        FileContext {
+            package: analysis
+                .content
+                .as_deref()
+                .map(package_of)
+                .unwrap_or_default(),
+            imports: analysis.imports.iter().map(|i| i.path.clone()).collect(),
+            classes: analysis
+                .classes
+                .iter()
+                .map(|c| (c.name.clone(), c.base_classes.clone()))
+                .collect(),
+        }
+    }
+
+    /// Load the resolution context of a file that was not part of the run
+    fn load_file_context(&self, path: &Path) -> Option<FileContext> {
+        let content = self.read_file(path).ok()?;
+        let tree = self.parse(path, &content).ok()?;
+        Some(self.file_context(&self.analyze_tree(path, &tree, &content)))
+    }
+
+    /// Resolve a type name as used in a file to the file and class defining it
+    ///
+    /// Looks at classes of the same file, explicit imports, the file's own
+    /// package, wildcard imports and finally treats the name as fully
+    /// qualified.
+    fn resolve_type(
+        &self,
+        type_name: &str,
+        source_path: &Path,
+        context: &FileContext,
+    ) -> Option<(PathBuf, String)> {
+        let type_name = strip_type_arguments(type_name);
+        let head = type_name.split('.').next().unwrap_or(type_name);
+        let simple = type_name.rsplit('.').next().unwrap_or(type_name);
+
+        // Same file, including nested classes
+        if let Some(name) = context
+            .classes
+            .keys()
+            .find(|name| *name == type_name || name.ends_with(&format!(".{}", type_name)))
+        {
+            return Some((source_path.to_path_buf(), name.clone()));
+        }
+
+        let index = self.package_index();
+
+        // Explicit import of the head segment (`import a.b.Outer` for `Outer.Inner`)
+        for import in &context.imports {
+            if import.rsplit('.').next() == Some(head) {
+                if let Some(file) = index.resolve_qualified(import) {
+                    return Some((file.clone(), type_name.to_string()));
+                }
+            }
+        }
+
+        // Same package
+        if let Some(file) = index.class_file(&context.package, head) {
+            return Some((file.clone(), type_name.to_string()));
+        }
+
+        // Wildcard imports
+        for import in &context.imports {
+            if let Some(package) = import.strip_suffix(".*") {
+                if let Some(file) = index.class_file(package, head) {
+                    return Some((file.clone(), type_name.to_string()));
+                }
+            }
+        }
+
+        // Fully qualified reference
+        if type_name.contains('.') {
+            if let Some(file) = index.resolve_qualified(type_name) {
+                return Some((file.clone(), simple.to_string()));
+            }
+        }
+
+        None
+    }
+
+    /// Compute the inheritance depth of a class, resolving bases across files
+    fn inheritance_depth(
+        &self,
+        path: &Path,
+        class_name: &str,
+        contexts: &mut HashMap<PathBuf, FileContext>,
+        depths: &mut HashMap<(PathBuf, String), usize>,
+        visiting: &mut HashSet<(PathBuf, String)>,
+    ) -> usize {
+        let key = (path.to_path_buf(), class_name.to_string());
+        if let Some(depth) = depths.get(&key) {
+            return *depth;
+        }
+
+        // Cyclic hierarchies only occur in broken code; stop there
+        if !visiting.insert(key.clone()) {
+            return 0;
+        }
+
+        if !contexts.contains_key(path) {
+            let context = self.load_file_context(path).unwrap_or_default();
+            contexts.insert(path.to_path_buf(), context);
+        }
+        let context = contexts[path].clone();
+
+        // Classes named by suffix when resolved through a qualified reference
+        let bases = context
+            .classes
+            .get(class_name)
+            .or_else(|| {
+                context
+                    .classes
+                    .iter()
+                    .find(|(name, _)| name.ends_with(&format!(".{}", class_name)))
+                    .map(|(_, bases)| bases)
+            })
+            .cloned()
+            .unwrap_or_default();
+
+        let depth = bases
+            .iter()
+            .map(|base| match self.resolve_type(base, path, &context) {
+                Some((base_path, base_name)) => {
+                    1 + self.inheritance_depth(&base_path, &base_name, contexts, depths, visiting)
+                }
+                // Library types count as one level
+                None => 1,
+            })
+            .max()
+            .unwrap_or(0);
+
+        visiting.remove(&key);
+        depths.insert(key, depth);
+        depth
+    }
+
+    /// Extract dependencies from a parsed syntax tree and the analysis built
+    /// from it
+    fn extract_dependencies_from_tree(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+        analysis: &FileAnalysis,
+    ) -> Vec<Dependency> {
+        let root = tree.root_node();
+        let language = JvmLanguage::from_path(source_path);
+        let index = self.package_index();
+        let mut dependencies = Vec::new();
+
+        for (import, _, line) in self.extract_imports(root, content) {
+            let targets: Vec<PathBuf> = match import.strip_suffix(".*") {
+                Some(package) => match index.packages.get(package) {
+                    Some(files) => files.clone(),
+                    // `import a.b.Outer.*` imports nested classes of a class
+                    None => index
+                        .resolve_qualified(package)
+                        .cloned()
+                        .into_iter()
+                        .collect(),
+                },
+                None => index
+                    .resolve_qualified(&import)
+                    .cloned()
+                    .into_iter()
+                    .collect(),
+            };
+
+            if targets.is_empty() {
+                debug!(
+                    "Could not resolve import '{}' in {}",
+                    import,
+                    source_path.display()
+                );
+            }
+
+            for target in targets.into_iter().filter(|t| t != source_path) {
+                dependencies.push(Dependency {
+                    source: source_path.to_path_buf(),
+                    target,
+                    dependency_type: DependencyType::Import,
+                    line: Some(line),
+                    info: Some(format!("Import: {}", import)),
+                });
+            }
+        }
+
+        // Inheritance and implementation across files
+        let context = self.file_context(analysis);
+
+        for node in iter_tree(root).filter(|n| CLASS_KINDS.contains(&n.kind())) {
+            let class_name = declaration_name(node, content).unwrap_or_default();
+
+            for base in self.base_types(node, language, content) {
+                let Some((target, _)) = self.resolve_type(&base.name, source_path, &context) else {
+                    continue;
+                };
+                if target == source_path {
+                    continue;
+                }
+
+                let relation = match base.dependency_type {
+                    DependencyType::Implementation => "implements",
+                    _ => "extends",
+                };
+
+                dependencies.push(Dependency {
+                    source: source_path.to_path_buf(),
+                    target,
+                    dependency_type: base.dependency_type,
+                    line: Some(base.line),
+                    info: Some(format!("{} {} {}", class_name, relation, base.name)),
+                });
+            }
+        }
+
+        dependencies
+    }
+}
+
+/// Collect the named children of a node
+fn named_children(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = node.walk();
+    let children = node.named_children(&mut cursor).collect();
+    children
+}
+
+/// Get the name of a declaration in either grammar
+fn declaration_name(node: Node<'_>, content: &str) -> Option<String> {
+    node.child_by_field_name("name")
+        .or_else(|| {
+            named_children(node).into_iter().find(|c| {
+                matches!(
+                    c.kind(),
+                    "identifier" | "simple_identifier" | "type_identifier"
+                )
+            })
+        })
+        .map(|n| node_text(n, content).to_string())
+}
+
+/// Get the body of a class-like declaration in either grammar
+fn class_body(node: Node<'_>) -> Option<Node<'_>> {
+    node.child_by_field_name("body").or_else(|| {
+        named_children(node).into_iter().find(|c| {
+            matches!(
+                c.kind(),
+                "class_body" | "enum_class_body" | "interface_body" | "enum_body"
+            )
+        })
+    })
+}
+
+/// Check whether a declaration is an interface
+fn is_interface(node: Node<'_>, content: &str) -> bool {
+    if node.kind() == "interface_declaration" {
+        return true;
+    }
+
+    // Kotlin uses `class_declaration` with an `interface` keyword
+    let mut cursor = node.walk();
+    let result = node
+        .children(&mut cursor)
+        .any(|c| !c.is_named() && node_text(c, content) == "interface");
+    result
+}
+
+/// Get the text of a declaration's modifiers, or an empty string
+fn modifiers_text<'a>(node: Node<'_>, content: &'a str) -> &'a str {
+    named_children(node)
+        .into_iter()
+        .find(|c| c.kind() == "modifiers")
+        .map_or("", |m| node_text(m, content))
+}
+
+/// Kotlin visibility: public unless marked private, protected or internal
+fn kotlin_is_public(node: Node<'_>, content: &str) -> bool {
+    let modifiers = modifiers_text(node, content);
+    !(modifiers.contains("private")
+        || modifiers.contains("protected")
+        || modifiers.contains("internal"))
+}
+
+/// Split a Kotlin `name: Type` declaration, ignoring modifiers and `val`/`var`
+fn split_kotlin_parameter(text: &str) -> (String, Option<String>) {
+    let declaration = text.split('=').next().unwrap_or(text);
+    let (name_part, type_part) = match declaration.split_once(':') {
+        Some((name, type_text)) => (name, Some(type_text.trim().to_string())),
+        None => (declaration, None),
+    };
+
+    let name = name_part
+        .split_whitespace()
+        .last()
+        .unwrap_or_default()
+        .to_string();
+
+    (name, type_part.filter(|t| !t.is_empty()))
+}
+
+/// Remove type arguments from a type name (`List<String>` -> `List`)
+fn strip_type_arguments(type_name: &str) -> &str {
+    type_name.split('<').next().unwrap_or(type_name).trim()
+}
+
+/// Read the package declaration of a Java or Kotlin source file
+fn package_of(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("package "))
+        .map(|package| {
+            package
+                .trim_end_matches(';')
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '`')
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check whether a file lies under a Maven/Gradle `src/main/<language>` root
+fn is_under_main_source_root(path: &Path) -> bool {
+    let components: Vec<_> = path.components().map(|c| c.as_os_str()).collect();
+    components
+        .windows(2)
+        .any(|pair| pair[0] == "src" && pair[1] == "main")
+}
+
+/// Check whether a file declares a class, interface or object with the given name
+fn declares_class(path: &Path, class_name: &str) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+
+    ["class ", "interface ", "object ", "enum ", "record "]
+        .iter()
+        .any(|keyword| {
+            let needle = format!("{}{}", keyword, class_name);
+            content.match_indices(&needle).any(|(index, _)| {
+                content[index + needle.len()..]
+                    .chars()
+                    .next()
+                    .map_or(true, |c| !c.is_alphanumeric() && c != '_')
+            })
+        })
+}
+
+#[async_trait]
+impl LanguageAnalyzer for JvmAnalyzer {
+    fn language_name(&self) -> &'static str {
+        "Java/Kotlin"
+    }
+
+    fn supported_extensions(&self) -> &[&'static str] {
+        &["java", "kt", "kts"]
+    }
+
+    async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
+        info!("Analyzing Java/Kotlin file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        Ok(self.analyze_tree(path, &tree, &content))
+    }
+
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!(
+            "Extracting dependencies from Java/Kotlin file: {}",
+            path.display()
+        );
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+        let analysis = self.analyze_tree(path, &tree, &content);
+
+        let dependencies = self.extract_dependencies_from_tree(&tree, &content, path, &analysis);
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(dependencies)
+    }
+
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing Java/Kotlin file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies = self.extract_dependencies_from_tree(&tree, &content, path);
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content),
+            dependencies,
+            symbols: SymbolGraph::default(),
+        })
+    }
+
+    fn finalize_analyses(&self, analyses: &mut [FileAnalysis]) {
+        let mut contexts: HashMap<PathBuf, FileContext> = analyses
+            .iter()
+            .filter(|a| self.is_supported(&a.path))
+            .map(|a| (a.path.clone(), self.file_context(a)))
+            .collect();
+        let mut depths = HashMap::new();
+        let mut visiting = HashSet::new();
+
+        for analysis in analyses.iter_mut().filter(|a| self.is_supported(&a.path)) {
+            for class in &mut analysis.classes {
+                class.metrics.inheritance_depth = self.inheritance_depth(
+                    &analysis.path,
+                    &class.name,
+                    &mut contexts,
+                    &mut depths,
+                    &mut visiting,
+                );
+            }
+        }
+    }
+
+    fn reset_caches(&self) {
+        if let Ok(mut index) = self.package_index.lock() {
+            *index = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-jvm-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn analyzer(root: &Path) -> JvmAnalyzer {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        JvmAnalyzer::new(Arc::new(config))
+    }
+
+    #[test]
+    fn kotlin_imports_keep_name_and_alias_apart() {
+        let content =
+            "package app\n\nimport com.acme.model.Shape as Base\nimport com.acme.util.*\n";
+        let path = Path::new("App.kt");
+        let analyzer = analyzer(Path::new("."));
+        let tree = analyzer.parse(path, content).unwrap();
+        let analysis = analyzer.analyze_tree(path, &tree, content);
+
+        let imports: Vec<(&str, Option<&str>, Option<&str>)> = analysis
+            .imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.name.as_deref(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("com.acme.model.Shape", Some("Shape"), Some("Base")),
+                ("com.acme.util.*", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn packages_resolve_imports_and_inheritance_across_files() {
+        let root = scratch_dir("packages");
+        let sources = root.join("src/main/java/com/acme");
+        write(
+            &sources.join("model/Shape.java"),
+            "package com.acme.model;\n\npublic class Shape {}\n",
+        );
+        write(
+            &sources.join("model/Circle.java"),
+            "package com.acme.model;\n\npublic class Circle extends Shape {}\n",
+        );
+        let content = "package com.acme.app;\n\nimport com.acme.model.Circle;\n\npublic class App extends Circle implements Runnable {}\n";
+        let path = sources.join("app/App.java");
+        write(&path, content);
+
+        let analyzer = analyzer(&root);
+        let tree = analyzer.parse(&path, content).unwrap();
+        let analysis = analyzer.analyze_tree(&path, &tree, content);
+        let dependencies =
+            analyzer.extract_dependencies_from_tree(&tree, content, &path, &analysis);
+
+        let kinds: Vec<(DependencyType, PathBuf)> = dependencies
+            .iter()
+            .map(|d| (d.dependency_type.clone(), d.target.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DependencyType::Import, sources.join("model/Circle.java")),
+                (
+                    DependencyType::Inheritance,
+                    sources.join("model/Circle.java")
+                ),
+            ]
+        );
+
+        // App -> Circle -> Shape, with `Runnable` counting as one library level
+        let mut analyses = vec![analysis];
+        analyzer.finalize_analyses(&mut analyses);
+        assert_eq!(analyses[0].classes[0].metrics.inheritance_depth, 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -4,18 +4,34 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{debug, info};
 
+pub mod cache;
+pub mod cargo;
 pub mod common;
+pub mod cpp;
+pub mod go;
+pub mod jvm;
+pub mod python;
 pub mod rust;
+pub mod symbol_store;
+pub mod typescript;
 
 use crate::core::config::Config;
 use crate::core::project::{Project, ProjectStructure};
 use crate::errors::{Result, ZseiError};
-use common::{AnalysisResult, CodeGraph, Dependency, DependencyType, FileAnalysis};
+use crate::utils::fs::file_md5;
+use cache::AnalysisCache;
+use cargo::CargoWorkspace;
+use common::{
+    AnalysisResult, CodeGraph, Dependency, DependencyType, FileAnalysis, FileReport, SymbolGraph,
+};
+use symbol_store::{FileSymbols, SymbolStore};
 
 /// Progress update
 #[derive(Debug, Clone)]
@@ -42,6 +58,14 @@ pub trait LanguageAnalyzer: Send + Sync {
     /// Get supported file extensions
     fn supported_extensions(&self) -> &[&'static str];
 
+    /// Version of the analyzer's output
+    ///
+    /// Bump this when the analyzer's output changes so that cached analyses
+    /// produced by the previous version are discarded.
+    fn analyzer_version(&self) -> u32 {
+        1
+    }
+
     /// Check if a file is supported
     fn is_supported(&self, path: &Path) -> bool {
         if let Some(ext) = path.extension() {
@@ -57,6 +81,40 @@ pub trait LanguageAnalyzer: Send + Sync {
 
     /// Extract dependencies from a file
     async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>>;
+
+    /// Extract the symbols defined in a file and the calls they make
+    ///
+    /// Calls may name callees defined in other files; unresolved callees are
+    /// dropped once all files are merged. The default extracts nothing.
+    async fn extract_symbols(&self, _path: &Path) -> Result<SymbolGraph> {
+        Ok(SymbolGraph::default())
+    }
+
+    /// Analyze a file, extract its dependencies and symbols in one pass
+    ///
+    /// The default calls the individual methods, which read and parse the
+    /// file once each; analyzers override it to share a single syntax tree.
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        Ok(FileReport {
+            analysis: self.analyze_file(path).await?,
+            dependencies: self.extract_dependencies(path).await?,
+            symbols: self.extract_symbols(path).await?,
+        })
+    }
+
+    /// Post-process the analyses of a run once all files are analyzed
+    ///
+    /// Used for metrics that need the whole project, such as inheritance
+    /// depth across files. Implementations must only modify analyses of files
+    /// they support. The default does nothing.
+    fn finalize_analyses(&self, _analyses: &mut [FileAnalysis]) {}
+
+    /// Forget what was cached about the project's files and manifests
+    ///
+    /// Called before each run, so resolvers see files created, moved or
+    /// deleted since the previous run of a long-lived analyzer. The default
+    /// does nothing.
+    fn reset_caches(&self) {}
 }
 
 /// Main analyzer struct
@@ -65,22 +123,103 @@ pub struct Analyzer {
     config: Arc<Config>,
 
     /// Language analyzers
-    language_analyzers: Vec<Box<dyn LanguageAnalyzer>>,
+    language_analyzers: Vec<Arc<dyn LanguageAnalyzer>>,
+
+    /// Per-file analysis cache
+    cache: Option<Arc<AnalysisCache>>,
+
+    /// Symbols of each file from its last analysis
+    symbol_store: Option<SymbolStore>,
+
+    /// Project whose file states are updated after each run
+    project: Arc<Project>,
 }
 
 impl Analyzer {
     /// Create a new analyzer
-    pub fn new(config: Arc<Config>) -> Self {
-        let mut language_analyzers: Vec<Box<dyn LanguageAnalyzer>> = Vec::new();
+    pub fn new(config: Arc<Config>, project: Arc<Project>) -> Self {
+        let mut language_analyzers: Vec<Arc<dyn LanguageAnalyzer>> = Vec::new();
 
         // Add Rust analyzer
-        language_analyzers.push(Box::new(rust::RustAnalyzer::new(config.clone())));
+        language_analyzers.push(Arc::new(rust::RustAnalyzer::new(config.clone())));
+
+        // Add Python analyzer
+        language_analyzers.push(Arc::new(python::PythonAnalyzer::new(config.clone())));
+
+        // Add TypeScript/JavaScript analyzer
+        language_analyzers.push(Arc::new(typescript::TypeScriptAnalyzer::new(
+            config.clone(),
+        )));
+
+        // Add Go analyzer
+        language_analyzers.push(Arc::new(go::GoAnalyzer::new(config.clone())));
+
+        // Add C/C++ analyzer
+        language_analyzers.push(Arc::new(cpp::CppAnalyzer::new(config.clone())));
+
+        // Add Java/Kotlin analyzer
+        language_analyzers.push(Arc::new(jvm::JvmAnalyzer::new(config.clone())));
 
         // Add more language analyzers here as they are implemented
 
+        let cache = match AnalysisCache::new(&config) {
+            Ok(cache) => Some(Arc::new(cache)),
+            Err(e) => {
+                debug!("Analysis cache disabled: {}", e);
+                None
+            }
+        };
+
+        let symbol_store = match SymbolStore::new(&config) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                debug!("Symbol store disabled: {}", e);
+                None
+            }
+        };
+
         Self {
             config,
             language_analyzers,
+            cache,
+            symbol_store,
+            project,
+        }
+    }
+
+    /// Get the project tracked by the analyzer
+    pub fn project(&self) -> &Arc<Project> {
+        &self.project
+    }
+
+    /// Drop cached analyses of files that no longer exist
+    pub fn evict_cached_files(&self, paths: &[PathBuf]) {
+        if let Some(cache) = &self.cache {
+            for path in paths {
+                if let Err(e) = cache.remove(path) {
+                    debug!(
+                        "Failed to evict cached analysis of {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Remove every cached file analysis, so the next run analyzes all files
+    /// again
+    pub fn clear_cache(&self) -> Result<()> {
+        match &self.cache {
+            Some(cache) => cache.clear(),
+            None => Ok(()),
+        }
+    }
+
+    /// Reset the caches the language analyzers keep between runs
+    pub fn reset_caches(&self) {
+        for analyzer in &self.language_analyzers {
+            analyzer.reset_caches();
         }
     }
 
@@ -94,6 +233,14 @@ impl Analyzer {
         None
     }
 
+    /// Get a shared handle to the language analyzer for a file
+    fn shared_analyzer_for_file(&self, path: &Path) -> Option<Arc<dyn LanguageAnalyzer>> {
+        self.language_analyzers
+            .iter()
+            .find(|analyzer| analyzer.is_supported(path))
+            .cloned()
+    }
+
     /// Analyze files (full analysis)
     pub async fn analyze_full(
         &self,
@@ -102,15 +249,20 @@ impl Analyzer {
     ) -> Result<AnalysisResult> {
         info!("Starting full analysis");
 
-        let project = Project::new(self.config.clone())?;
         let files = self.collect_files_to_analyze(paths, false)?;
 
         debug!("Found {} files to analyze", files.len());
 
-        self.analyze_files(files, progress_tx).await
+        self.analyze_and_record(files, Vec::new(), progress_tx)
+            .await
     }
 
     /// Analyze files (incremental analysis)
+    ///
+    /// Only files whose content changed since they were last analyzed are
+    /// processed. The saved symbols of the unchanged files are merged into
+    /// the symbol graph, so it keeps the calls made from unchanged files.
+    /// Unchanged files without saved symbols are analyzed as context.
     pub async fn analyze_incremental(
         &self,
         paths: &[PathBuf],
@@ -118,25 +270,38 @@ impl Analyzer {
     ) -> Result<AnalysisResult> {
         info!("Starting incremental analysis");
 
-        let project = Project::new(self.config.clone())?;
-        let files = self.collect_files_to_analyze(paths, true)?;
+        let all_files = self.collect_files_to_analyze(paths, false)?;
+        let files = self.project.filter_files_to_analyze(all_files.clone())?;
 
-        debug!("Found {} files to analyze", files.len());
+        let changed: HashSet<&PathBuf> = files.iter().collect();
+        let unchanged: Vec<PathBuf> = all_files
+            .iter()
+            .filter(|path| !changed.contains(path))
+            .cloned()
+            .collect();
+
+        debug!("Found {} changed files to analyze", files.len());
 
-        self.analyze_files(files, progress_tx).await
+        self.analyze_and_record(files, unchanged, progress_tx).await
     }
 
     /// Collect files to analyze
-    fn collect_files_to_analyze(
+    ///
+    /// Files are collected from `paths`, or from the whole project when no
+    /// paths are given. With `incremental`, only files changed since their
+    /// last analysis are kept.
+    pub fn collect_files_to_analyze(
         &self,
         paths: &[PathBuf],
         incremental: bool,
     ) -> Result<Vec<PathBuf>> {
-        let project = Project::new(self.config.clone())?;
-
-        let mut files = if paths.is_empty() {
+        let files = if paths.is_empty() {
             // Get all files in the project
-            project.get_files_to_analyze(incremental)?
+            self.project
+                .get_files_to_analyze(false)?
+                .into_iter()
+                .filter(|path| self.is_supported_file(path))
+                .collect()
         } else {
             // Collect files from the specified paths
             let mut files = Vec::new();
@@ -156,12 +321,46 @@ impl Analyzer {
             files
         };
 
-        // Apply incremental filtering if requested
+        // Apply incremental filtering to the collected files only
         if incremental {
-            files = project.get_files_to_analyze(true)?;
+            self.project.filter_files_to_analyze(files)
+        } else {
+            Ok(files)
         }
+    }
 
-        Ok(files)
+    /// Analyze files and record them as analyzed in the project state
+    ///
+    /// Context files are only used for project-wide results and are not
+    /// recorded.
+    async fn analyze_and_record(
+        &self,
+        files: Vec<PathBuf>,
+        context_files: Vec<PathBuf>,
+        progress_tx: Option<mpsc::Sender<ProgressUpdate>>,
+    ) -> Result<AnalysisResult> {
+        // Resolve against the project as it is now, not as it was when a
+        // previous run filled the caches
+        self.reset_caches();
+
+        let result = self
+            .analyze_files(files, context_files, progress_tx)
+            .await?;
+
+        // Files that failed to analyze are left out, so they are retried
+        self.project
+            .update_analyzed_files(result.file_analyses.iter().map(|analysis| {
+                (
+                    analysis.path.as_path(),
+                    Some(analysis.language.clone()),
+                    result
+                        .content_hashes
+                        .get(&analysis.path)
+                        .map(String::as_str),
+                )
+            }))?;
+
+        Ok(result)
     }
 
     /// Check if a file is supported by any analyzer
@@ -196,62 +395,223 @@ impl Analyzer {
     }
 
     /// Analyze files and build dependency graph
+    ///
+    /// Files are analyzed concurrently by up to `analysis.workers` tasks.
+    /// Results are collected in path order, so the output does not depend on
+    /// scheduling.
+    ///
+    /// Context files only contribute to the symbol graph. Their symbols come
+    /// from the symbol store when they are unchanged since it was saved, and
+    /// otherwise from analyzing them, normally served by the analysis cache.
+    /// The symbols of every analyzed file are saved to the store.
     async fn analyze_files(
         &self,
-        files: Vec<PathBuf>,
+        mut files: Vec<PathBuf>,
+        context_files: Vec<PathBuf>,
         progress_tx: Option<mpsc::Sender<ProgressUpdate>>,
     ) -> Result<AnalysisResult> {
-        let mut file_analyses = Vec::new();
-        let mut dependencies = Vec::new();
+        files.sort();
+        files.dedup();
         let total_files = files.len();
 
-        for (i, path) in files.iter().enumerate() {
-            // Send progress update
-            if let Some(tx) = &progress_tx {
-                let update = ProgressUpdate {
-                    current: i + 1,
-                    total: total_files,
-                    current_item: path.display().to_string(),
-                    message: format!("Analyzing {}", path.display()),
-                };
-
-                if let Err(e) = tx.send(update).await {
-                    debug!("Failed to send progress update: {}", e);
-                }
+        // Saved symbols stand in for unchanged context files
+        let mut stored_symbols = self
+            .symbol_store
+            .as_ref()
+            .map(SymbolStore::load)
+            .unwrap_or_default();
+        let mut stored_context = Vec::new();
+        let mut context_paths = Vec::new();
+        for path in context_files {
+            if files.binary_search(&path).is_ok() {
+                continue;
+            }
+            let unchanged = stored_symbols.get(&path).map_or(false, |stored| {
+                file_md5(&path).map_or(false, |hash| hash == stored.content_hash)
+            });
+            if unchanged {
+                stored_context.push(path);
+            } else {
+                context_paths.push(path);
             }
+        }
+        stored_context.sort();
+        context_paths.sort();
+        context_paths.dedup();
 
-            // Get analyzer for file
-            if let Some(analyzer) = self.get_analyzer_for_file(path) {
-                debug!("Analyzing file: {}", path.display());
+        debug!(
+            "Using saved symbols of {} unchanged files, analyzing {} as context",
+            stored_context.len(),
+            context_paths.len()
+        );
 
-                // Analyze file
-                match analyzer.analyze_file(path).await {
-                    Ok(file_analysis) => {
-                        file_analyses.push(file_analysis);
-                    }
-                    Err(e) => {
-                        debug!("Error analyzing file {}: {}", path.display(), e);
+        let context_files: HashSet<PathBuf> = context_paths.iter().cloned().collect();
+        files.extend(context_paths);
+
+        let mut file_analyses = Vec::new();
+        let mut dependencies = Vec::new();
+        let mut symbol_graph = SymbolGraph::default();
+
+        let workers = self.config.analysis.workers.max(1);
+        let semaphore = Arc::new(Semaphore::new(workers));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let cache_hits = Arc::new(AtomicUsize::new(0));
+        let mut tasks = Vec::with_capacity(total_files);
+
+        debug!("Analyzing {} files with {} workers", total_files, workers);
+
+        for path in files {
+            // Get analyzer for file
+            let Some(analyzer) = self.shared_analyzer_for_file(&path) else {
+                continue;
+            };
+
+            let semaphore = semaphore.clone();
+            let completed = completed.clone();
+            let progress_tx = progress_tx.clone();
+            let cache = self.cache.clone();
+            let cache_hits = cache_hits.clone();
+            let is_context = context_files.contains(&path);
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+
+                // Parsing is CPU-bound, so it runs on a blocking thread while
+                // the permit is held
+                let handle = tokio::runtime::Handle::current();
+                let task_path = path.clone();
+                let analyzed = tokio::task::spawn_blocking(move || {
+                    let path = task_path;
+                    let content_hash = file_md5(&path).ok();
+                    let version = AnalysisCache::analyzer_version(analyzer.as_ref());
+
+                    // Reuse the cached analysis if the file is unchanged
+                    let cached = match (&cache, &content_hash) {
+                        (Some(cache), Some(hash)) => cache.get(&path, hash, &version),
+                        _ => None,
+                    };
+
+                    let report = match cached {
+                        Some(report) => {
+                            debug!("Using cached analysis for {}", path.display());
+                            cache_hits.fetch_add(1, Ordering::SeqCst);
+                            Ok(report)
+                        }
+                        None => {
+                            debug!("Analyzing file: {}", path.display());
+                            let report =
+                                handle.block_on(analyzer.analyze_file_with_dependencies(&path));
+
+                            if let (Some(cache), Some(hash), Ok(report)) =
+                                (&cache, &content_hash, &report)
+                            {
+                                if let Err(e) = cache.put(&path, hash, &version, report) {
+                                    debug!("Failed to cache analysis of {}: {}", path.display(), e);
+                                }
+                            }
+
+                            report
+                        }
+                    };
+
+                    (content_hash, report)
+                })
+                .await;
+
+                let (content_hash, report) = analyzed.unwrap_or_else(|e| {
+                    (
+                        None,
+                        Err(ZseiError::Analyzer(format!("Analysis task failed: {}", e))),
+                    )
+                });
+
+                // Send progress update
+                if let Some(tx) = progress_tx.as_ref().filter(|_| !is_context) {
+                    let update = ProgressUpdate {
+                        current: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                        total: total_files,
+                        current_item: path.display().to_string(),
+                        message: format!("Analyzed {}", path.display()),
+                    };
+
+                    if let Err(e) = tx.send(update).await {
+                        debug!("Failed to send progress update: {}", e);
                     }
                 }
 
-                // Extract dependencies
-                match analyzer.extract_dependencies(path).await {
-                    Ok(file_deps) => {
-                        dependencies.extend(file_deps);
-                    }
-                    Err(e) => {
-                        debug!(
-                            "Error extracting dependencies from {}: {}",
-                            path.display(),
-                            e
+                (path, content_hash, report, is_context)
+            }));
+        }
+
+        // Await in submission order to keep results deterministic
+        let mut content_hashes = HashMap::new();
+        for task in tasks {
+            match task.await {
+                Ok((path, content_hash, Ok(report), is_context)) => {
+                    if let Some(content_hash) = content_hash {
+                        stored_symbols.insert(
+                            path.clone(),
+                            FileSymbols {
+                                content_hash: content_hash.clone(),
+                                symbols: report.symbols.clone(),
+                            },
                         );
+                        if !is_context {
+                            content_hashes.insert(path, content_hash);
+                        }
                     }
+                    symbol_graph.merge(report.symbols);
+                    if !is_context {
+                        file_analyses.push(report.analysis);
+                        dependencies.extend(report.dependencies);
+                    }
+                }
+                Ok((path, _, Err(e), _)) => {
+                    debug!("Error analyzing file {}: {}", path.display(), e);
+                }
+                Err(e) => {
+                    debug!("Analysis task failed: {}", e);
                 }
             }
         }
 
+        debug!(
+            "Reused {} cached file analyses",
+            cache_hits.load(Ordering::SeqCst)
+        );
+
+        for path in &stored_context {
+            if let Some(stored) = stored_symbols.get(path) {
+                symbol_graph.merge(stored.symbols.clone());
+            }
+        }
+
+        if let Some(store) = &self.symbol_store {
+            if let Err(e) = store.save(&stored_symbols) {
+                debug!("Failed to save symbols: {}", e);
+            }
+        }
+
+        // Project-wide post-processing; analyzers load what they need of
+        // other files themselves
+        for analyzer in &self.language_analyzers {
+            analyzer.finalize_analyses(&mut file_analyses);
+        }
+
+        // Model the Cargo workspaces of the analyzed Rust files
+        let rust_files: Vec<PathBuf> = file_analyses
+            .iter()
+            .filter(|a| a.language == "Rust")
+            .map(|a| a.path.clone())
+            .collect();
+        let cargo_workspaces = CargoWorkspace::discover(&rust_files, self.config.project_root());
+
+        // Keep only calls into symbols of the project
+        symbol_graph.retain_resolved_calls();
+
         // Build dependency graph
-        let graph = CodeGraph::new(&dependencies);
+        let mut graph = CodeGraph::new(&dependencies);
+        graph.add_cargo_workspaces(&cargo_workspaces);
 
         // Build analysis result
         let result = AnalysisResult {
@@ -259,8 +619,193 @@ impl Analyzer {
             dependencies,
             graph,
             project_structure: None, // Will be filled later if needed
+            cargo_workspaces,
+            symbol_graph,
+            content_hashes,
         };
 
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zsei-analyzer-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Analyzer for the project at `root`
+    fn analyzer(root: &Path) -> Analyzer {
+        analyzer_with_workers(root, Config::default().analysis.workers)
+    }
+
+    /// Analyzer for the project at `root` running `workers` analysis tasks
+    fn analyzer_with_workers(root: &Path, workers: usize) -> Analyzer {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        config.analysis.workers = workers;
+        let config = Arc::new(config);
+        let project = Arc::new(Project::new(config.clone()).unwrap());
+        Analyzer::new(config, project)
+    }
+
+    /// Get the paths of the symbols calling the symbol named `callee`
+    fn callers(result: &AnalysisResult, callee: &str) -> Vec<String> {
+        let callee = result
+            .symbol_graph
+            .symbols
+            .values()
+            .find(|symbol| symbol.name == callee)
+            .unwrap();
+        result
+            .callers_of(&callee.path)
+            .into_iter()
+            .map(|symbol| symbol.name.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn incremental_runs_keep_calls_from_unchanged_files() {
+        let root = scratch_dir("incremental-calls");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod util;\n\npub fn run() {\n    util::helper();\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/util.rs"), "pub fn helper() {}\n").unwrap();
+
+        let full = analyzer(&root).analyze_full(&[], None).await.unwrap();
+        assert_eq!(callers(&full, "helper"), vec!["run"]);
+
+        // Only the callee changes; the caller comes from the cache
+        fs::write(
+            root.join("src/util.rs"),
+            "pub fn helper() {\n    let _ = 1;\n}\n",
+        )
+        .unwrap();
+
+        let incremental = analyzer(&root)
+            .analyze_incremental(&[], None)
+            .await
+            .unwrap();
+        let analyzed: Vec<&Path> = incremental
+            .file_analyses
+            .iter()
+            .map(|analysis| analysis.path.as_path())
+            .collect();
+        assert_eq!(analyzed, vec![root.join("src/util.rs").as_path()]);
+        assert_eq!(callers(&incremental, "helper"), vec!["run"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn incremental_runs_merge_saved_symbols_without_parsing() {
+        let root = scratch_dir("saved-symbols");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod util;\n\npub fn run() {\n    util::helper();\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/util.rs"), "pub fn helper() {}\n").unwrap();
+
+        let analyzer = analyzer(&root);
+        analyzer.analyze_full(&[], None).await.unwrap();
+
+        // Without cached analyses, only the changed file is parsed
+        analyzer.clear_cache().unwrap();
+        fs::write(
+            root.join("src/util.rs"),
+            "pub fn helper() {\n    let _ = 1;\n}\n",
+        )
+        .unwrap();
+
+        let incremental = analyzer.analyze_incremental(&[], None).await.unwrap();
+        assert_eq!(callers(&incremental, "helper"), vec!["run"]);
+
+        let cached = fs::read_dir(root.join(".zsei/metadata/analysis_cache"))
+            .unwrap()
+            .count();
+        assert_eq!(cached, 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn parallel_and_serial_runs_produce_identical_results() {
+        let root = scratch_dir("parallel-serial");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("scripts")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod util;\npub mod shapes;\n\npub fn run() {\n    util::helper();\n    shapes::area();\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/util.rs"),
+            "pub fn helper() {\n    crate::shapes::area();\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/shapes.rs"),
+            "pub struct Square(f64);\n\nimpl Square {\n    pub fn side(&self) -> f64 {\n        self.0\n    }\n}\n\npub fn area() {}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("scripts/tool.py"),
+            "from helpers import clean\n\n\ndef main():\n    clean()\n",
+        )
+        .unwrap();
+        fs::write(root.join("scripts/helpers.py"), "def clean():\n    pass\n").unwrap();
+
+        let serial = analyzer_with_workers(&root, 1);
+        let serial_result = serial.analyze_full(&[], None).await.unwrap();
+        serial.clear_cache().unwrap();
+
+        let parallel_result = analyzer_with_workers(&root, 8)
+            .analyze_full(&[], None)
+            .await
+            .unwrap();
+
+        assert_eq!(serial_result.file_analyses.len(), 5);
+        assert_eq!(
+            serde_json::to_value(&serial_result.file_analyses).unwrap(),
+            serde_json::to_value(&parallel_result.file_analyses).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&serial_result.dependencies).unwrap(),
+            serde_json::to_value(&parallel_result.dependencies).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&serial_result.symbol_graph).unwrap(),
+            serde_json::to_value(&parallel_result.symbol_graph).unwrap()
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
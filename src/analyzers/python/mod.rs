@@ -0,0 +1,977 @@
+//! Python code analyzer
+//!
+//! This module provides an analyzer for Python code, extracting functions,
+//! classes, module-level variables and imports using tree-sitter, and
+//! resolving absolute and relative imports to files within the project.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info};
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::analyzers::common::{
+    class_cohesion, count_branch_points, indentation_cognitive_complexity, iter_tree,
+    maintainability_index, node_text, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType,
+    FileAnalysis, FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
+};
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+
+/// Patterns that create branches in Python code
+const BRANCH_PATTERNS: &[&str] = &[
+    "if ", "elif ", "for ", "while ", "except", " and ", " or ", "lambda ", "return", "break",
+    "continue",
+];
+
+/// Statements that add to cognitive complexity in Python code
+const COGNITIVE_PATTERNS: &[&str] = &[
+    "if ", "elif ", "else", "for ", "while ", "try", "except", "with ", "match ", "case ",
+];
+
+/// Python analyzer implementation providing structural analysis and
+/// import resolution for Python source files using tree-sitter.
+pub struct PythonAnalyzer {
+    /// Configuration options
+    config: Arc<Config>,
+}
+
+/// An import site as written in the source, before resolution
+#[derive(Debug, Clone)]
+struct ImportSite {
+    /// Module path without leading dots (may be empty for `from . import x`)
+    module: String,
+
+    /// Number of leading dots for relative imports
+    level: usize,
+
+    /// Imported name for `from` imports
+    name: Option<String>,
+
+    /// Alias given with `as`
+    alias: Option<String>,
+
+    /// Name the import is bound to in this module
+    binding: String,
+
+    /// Line number
+    line: usize,
+}
+
+impl ImportSite {
+    /// Convert to the language-neutral import representation
+    fn to_import(&self) -> Import {
+        let path = format!("{}{}", ".".repeat(self.level), self.module);
+
+        Import {
+            path,
+            name: self.name.clone(),
+            alias: self.alias.clone(),
+            line: self.line,
+            is_relative: self.level > 0,
+            external_crate: None,
+        }
+    }
+}
+
+impl PythonAnalyzer {
+    /// Create a new Python analyzer with the given configuration.
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+
+    /// Parse file content into a syntax tree
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .expect("Failed to set Python language");
+
+        parser
+            .parse(content, None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Read a file into a string
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Build a file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> FileAnalysis {
+        let root = tree.root_node();
+
+        let mut functions = Vec::new();
+        let mut classes = Vec::new();
+
+        for node in iter_tree(root) {
+            if !self.is_module_level(node) {
+                continue;
+            }
+
+            match node.kind() {
+                "function_definition" => {
+                    let decorators = self.decorators(node, content);
+                    functions.push(self.build_function(node, content, &decorators));
+                }
+                "class_definition" => classes.push(self.build_class(node, content)),
+                _ => {}
+            }
+        }
+
+        let variables = self.extract_module_variables(root, content);
+        let imports: Vec<Import> = self
+            .extract_import_sites(root, content)
+            .iter()
+            .map(ImportSite::to_import)
+            .collect();
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .filter(|l| l.trim_start().starts_with('#'))
+            .count();
+
+        let complexity = functions
+            .iter()
+            .chain(classes.iter().flat_map(|c| c.methods.iter()))
+            .map(|f| f.metrics.complexity)
+            .sum();
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index: maintainability_index(loc, comment_lines, complexity),
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} classes, {} imports, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        FileAnalysis {
+            path: path.to_path_buf(),
+            language: "Python".to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        }
+    }
+
+    /// Check whether a definition is at module level
+    ///
+    /// Definitions nested in `if`/`try` blocks at module level count as module
+    /// level; anything inside a function or class body does not.
+    fn is_module_level(&self, node: Node<'_>) -> bool {
+        let mut current = node.parent();
+
+        while let Some(parent) = current {
+            match parent.kind() {
+                "function_definition" | "class_definition" | "lambda" => return false,
+                "module" => return true,
+                _ => current = parent.parent(),
+            }
+        }
+
+        false
+    }
+
+    /// Get the decorators applied to a definition
+    fn decorators(&self, definition: Node<'_>, content: &str) -> Vec<String> {
+        let mut decorators = Vec::new();
+
+        if let Some(parent) = definition.parent() {
+            if parent.kind() == "decorated_definition" {
+                let mut cursor = parent.walk();
+                for child in parent.named_children(&mut cursor) {
+                    if child.kind() == "decorator" {
+                        let text = node_text(child, content);
+                        decorators.push(text.trim_start_matches('@').trim().to_string());
+                    }
+                }
+            }
+        }
+
+        decorators
+    }
+
+    /// Build a function from a `function_definition` node
+    fn build_function(&self, node: Node<'_>, content: &str, decorators: &[String]) -> Function {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_default();
+
+        let parameters = node
+            .child_by_field_name("parameters")
+            .map(|p| self.extract_parameters(p, content))
+            .unwrap_or_default();
+
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|n| node_text(n, content).to_string());
+
+        let body_node = node.child_by_field_name("body");
+        let body = body_node.map(|b| node_text(b, content).to_string());
+
+        // Signature is everything up to the body, without the trailing colon
+        let signature_end = body_node.map_or(node.end_byte(), |b| b.start_byte());
+        let signature = content[node.start_byte()..signature_end]
+            .trim_end()
+            .trim_end_matches(':')
+            .to_string();
+
+        let body_text = body.as_deref().unwrap_or("");
+        let metrics = FunctionMetrics {
+            loc: body_text.lines().count(),
+            complexity: count_branch_points(body_text, BRANCH_PATTERNS),
+            parameter_count: parameters.len(),
+            cognitive_complexity: indentation_cognitive_complexity(body_text, COGNITIVE_PATTERNS),
+        };
+
+        let signature = if decorators.is_empty() {
+            signature
+        } else {
+            let mut decorated: Vec<String> = decorators.iter().map(|d| format!("@{}", d)).collect();
+            decorated.push(signature);
+            decorated.join("\n")
+        };
+
+        Function {
+            is_public: is_public_name(&name),
+            name,
+            signature,
+            start_line: node.start_position().row,
+            end_line: node.end_position().row,
+            body,
+            return_type,
+            parameters,
+            metrics,
+        }
+    }
+
+    /// Extract parameters from a `parameters` node
+    ///
+    /// Handles plain, typed, defaulted and splat parameters. Keyword-only and
+    /// positional-only separators are skipped.
+    fn extract_parameters(&self, params_node: Node<'_>, content: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let (name, param_type, default_value) = match child.kind() {
+                "identifier" => (node_text(child, content).to_string(), None, None),
+                "typed_parameter" => {
+                    let name = child
+                        .named_child(0)
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default();
+                    let param_type = child
+                        .child_by_field_name("type")
+                        .map(|n| node_text(n, content).to_string());
+                    (name, param_type, None)
+                }
+                "default_parameter" | "typed_default_parameter" => {
+                    let name = child
+                        .child_by_field_name("name")
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default();
+                    let param_type = child
+                        .child_by_field_name("type")
+                        .map(|n| node_text(n, content).to_string());
+                    let default_value = child
+                        .child_by_field_name("value")
+                        .map(|n| node_text(n, content).to_string());
+                    (name, param_type, default_value)
+                }
+                "list_splat_pattern" | "dictionary_splat_pattern" => {
+                    (node_text(child, content).to_string(), None, None)
+                }
+                _ => continue,
+            };
+
+            if name.is_empty() {
+                continue;
+            }
+
+            parameters.push(Parameter {
+                name,
+                param_type,
+                default_value,
+                position: parameters.len(),
+            });
+        }
+
+        parameters
+    }
+
+    /// Build a class from a `class_definition` node
+    fn build_class(&self, node: Node<'_>, content: &str) -> Class {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_default();
+
+        // Base classes, ignoring keyword arguments such as `metaclass=...`
+        let mut base_classes = Vec::new();
+        if let Some(superclasses) = node.child_by_field_name("superclasses") {
+            let mut cursor = superclasses.walk();
+            for base in superclasses.named_children(&mut cursor) {
+                if base.kind() != "keyword_argument" && base.kind() != "comment" {
+                    base_classes.push(node_text(base, content).to_string());
+                }
+            }
+        }
+
+        let mut methods = Vec::new();
+        let mut properties: Vec<Variable> = Vec::new();
+
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for statement in body.named_children(&mut cursor) {
+                let definition = if statement.kind() == "decorated_definition" {
+                    statement.child_by_field_name("definition")
+                } else {
+                    Some(statement)
+                };
+
+                let Some(definition) = definition else {
+                    continue;
+                };
+
+                match definition.kind() {
+                    "function_definition" => {
+                        let decorators = self.decorators(definition, content);
+                        let method = self.build_function(definition, content, &decorators);
+
+                        if decorators.iter().any(|d| is_property_decorator(d)) {
+                            properties.push(Variable {
+                                name: method.name.clone(),
+                                var_type: method.return_type.clone(),
+                                line: method.start_line,
+                                is_public: method.is_public,
+                                init_value: None,
+                            });
+                        } else {
+                            methods.push(method);
+                        }
+                    }
+                    "expression_statement" => {
+                        for variable in self.assignment_variables(definition, content) {
+                            push_unique(&mut properties, variable);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // Instance attributes assigned through `self.<name> = ...` in any
+            // method, leaving out those of nested classes
+            for child in iter_tree(body) {
+                if child.kind() != "assignment" {
+                    continue;
+                }
+
+                if enclosing_class(child).map(|class| class.id()) != Some(node.id()) {
+                    continue;
+                }
+
+                let Some(left) = child.child_by_field_name("left") else {
+                    continue;
+                };
+
+                if left.kind() != "attribute" {
+                    continue;
+                }
+
+                let object = left.child_by_field_name("object");
+                let attribute = left.child_by_field_name("attribute");
+
+                if let (Some(object), Some(attribute)) = (object, attribute) {
+                    if node_text(object, content) == "self" {
+                        let attr_name = node_text(attribute, content).to_string();
+                        push_unique(
+                            &mut properties,
+                            Variable {
+                                is_public: is_public_name(&attr_name),
+                                name: attr_name,
+                                var_type: child
+                                    .child_by_field_name("type")
+                                    .map(|n| node_text(n, content).to_string()),
+                                line: child.start_position().row,
+                                init_value: child
+                                    .child_by_field_name("right")
+                                    .map(|n| node_text(n, content).to_string()),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        let start_line = node.start_position().row;
+        let end_line = node.end_position().row;
+
+        // Bases are not resolved, so any base class counts as one level
+        let inheritance_depth = usize::from(base_classes.iter().any(|b| is_counted_base(b)));
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            inheritance_depth,
+            cohesion: class_cohesion(&methods, &properties, "self."),
+        };
+
+        Class {
+            is_public: is_public_name(&name),
+            name,
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            metrics,
+        }
+    }
+
+    /// Extract variables from an assignment expression statement
+    fn assignment_variables(&self, statement: Node<'_>, content: &str) -> Vec<Variable> {
+        let mut variables = Vec::new();
+
+        let Some(assignment) = statement.named_child(0) else {
+            return variables;
+        };
+
+        if assignment.kind() != "assignment" {
+            return variables;
+        }
+
+        let Some(left) = assignment.child_by_field_name("left") else {
+            return variables;
+        };
+
+        let var_type = assignment
+            .child_by_field_name("type")
+            .map(|n| node_text(n, content).to_string());
+        let init_value = assignment
+            .child_by_field_name("right")
+            .map(|n| node_text(n, content).to_string());
+
+        // Plain names as well as tuple unpacking (`a, b = ...`)
+        let names: Vec<Node<'_>> = if left.kind() == "identifier" {
+            vec![left]
+        } else if left.kind() == "pattern_list" || left.kind() == "tuple_pattern" {
+            let mut cursor = left.walk();
+            left.named_children(&mut cursor)
+                .filter(|n| n.kind() == "identifier")
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for name_node in names {
+            let name = node_text(name_node, content).to_string();
+            variables.push(Variable {
+                is_public: is_public_name(&name),
+                name,
+                var_type: var_type.clone(),
+                line: assignment.start_position().row,
+                init_value: init_value.clone(),
+            });
+        }
+
+        variables
+    }
+
+    /// Extract module-level variables
+    fn extract_module_variables(&self, root: Node<'_>, content: &str) -> Vec<Variable> {
+        let mut variables = Vec::new();
+
+        for node in iter_tree(root) {
+            if node.kind() == "expression_statement" && self.is_module_level(node) {
+                variables.extend(self.assignment_variables(node, content));
+            }
+        }
+
+        variables
+    }
+
+    /// Extract all import sites in the file
+    fn extract_import_sites(&self, root: Node<'_>, content: &str) -> Vec<ImportSite> {
+        let mut sites = Vec::new();
+
+        for node in iter_tree(root) {
+            match node.kind() {
+                "import_statement" => {
+                    let mut cursor = node.walk();
+                    for name in node.children_by_field_name("name", &mut cursor) {
+                        let (module, alias) = self.dotted_name_and_alias(name, content);
+
+                        // `import a.b` binds `a`, `import a.b as c` binds `c`
+                        let binding = alias.clone().unwrap_or_else(|| {
+                            module.split('.').next().unwrap_or_default().to_string()
+                        });
+
+                        sites.push(ImportSite {
+                            module,
+                            level: 0,
+                            name: None,
+                            alias,
+                            binding,
+                            line: node.start_position().row,
+                        });
+                    }
+                }
+                "import_from_statement" => {
+                    let (module, level) = match node.child_by_field_name("module_name") {
+                        Some(module_node) if module_node.kind() == "relative_import" => {
+                            let text = node_text(module_node, content);
+                            let level = text.chars().take_while(|c| *c == '.').count();
+                            (text[level..].trim().to_string(), level)
+                        }
+                        Some(module_node) => (node_text(module_node, content).to_string(), 0),
+                        None => continue,
+                    };
+
+                    let mut cursor = node.walk();
+                    let names: Vec<Node<'_>> =
+                        node.children_by_field_name("name", &mut cursor).collect();
+
+                    if names.is_empty() {
+                        // Wildcard import
+                        sites.push(ImportSite {
+                            module,
+                            level,
+                            name: Some("*".to_string()),
+                            alias: None,
+                            binding: "*".to_string(),
+                            line: node.start_position().row,
+                        });
+                        continue;
+                    }
+
+                    for name in names {
+                        let (imported, alias) = self.dotted_name_and_alias(name, content);
+                        sites.push(ImportSite {
+                            module: module.clone(),
+                            level,
+                            binding: alias.clone().unwrap_or_else(|| imported.clone()),
+                            alias,
+                            name: Some(imported),
+                            line: node.start_position().row,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        sites
+    }
+
+    /// Split a `dotted_name` or `aliased_import` node into name and alias
+    fn dotted_name_and_alias(&self, node: Node<'_>, content: &str) -> (String, Option<String>) {
+        if node.kind() == "aliased_import" {
+            let name = node
+                .child_by_field_name("name")
+                .map(|n| node_text(n, content).to_string())
+                .unwrap_or_default();
+            let alias = node
+                .child_by_field_name("alias")
+                .map(|n| node_text(n, content).to_string());
+            (name, alias)
+        } else {
+            (node_text(node, content).to_string(), None)
+        }
+    }
+
+    /// Directories against which absolute imports are resolved
+    ///
+    /// This is the directory containing the top-level package of the source
+    /// file, the project root and its `src` directory, and any additional
+    /// project paths.
+    fn search_roots(&self, source_path: &Path, project_root: &Path) -> Vec<PathBuf> {
+        let mut roots = Vec::new();
+
+        // Walk up while we are inside a package
+        if let Some(mut dir) = source_path.parent() {
+            while dir.join("__init__.py").exists() {
+                match dir.parent() {
+                    Some(parent) => dir = parent,
+                    None => break,
+                }
+            }
+            roots.push(dir.to_path_buf());
+        }
+
+        roots.push(project_root.to_path_buf());
+        roots.push(project_root.join("src"));
+
+        for additional in &self.config.additional_project_paths {
+            roots.push(additional.clone());
+            roots.push(additional.join("src"));
+        }
+
+        roots.dedup();
+        roots
+    }
+
+    /// Resolve an import site to the file that defines it
+    ///
+    /// For `from pkg import name`, a submodule `pkg/name.py` is preferred over
+    /// the package itself. Imports that cannot be found under the project
+    /// (standard library, third-party packages) resolve to `None`.
+    fn resolve_import(
+        &self,
+        site: &ImportSite,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Option<PathBuf> {
+        let bases: Vec<PathBuf> = if site.level > 0 {
+            let mut base = source_path.parent()?.to_path_buf();
+            for _ in 1..site.level {
+                base = base.parent()?.to_path_buf();
+            }
+            vec![base]
+        } else {
+            self.search_roots(source_path, project_root)
+        };
+
+        let module_rel: PathBuf = site.module.split('.').filter(|s| !s.is_empty()).collect();
+
+        for base in bases {
+            let module_dir = base.join(&module_rel);
+
+            if let Some(name) = site.name.as_deref().filter(|n| *n != "*") {
+                if let Some(path) = module_file(&module_dir.join(name)) {
+                    return Some(path);
+                }
+            }
+
+            if module_rel.as_os_str().is_empty() {
+                let init = base.join("__init__.py");
+                if init.exists() {
+                    return Some(init);
+                }
+            } else if let Some(path) = module_file(&module_dir) {
+                return Some(path);
+            }
+        }
+
+        None
+    }
+
+    /// Extract dependencies from a parsed syntax tree
+    fn extract_dependencies_from_tree(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Vec<Dependency> {
+        let root = tree.root_node();
+        let mut dependencies = Vec::new();
+
+        // Names bound by imports, used to resolve base classes
+        let mut bindings: HashMap<String, PathBuf> = HashMap::new();
+
+        for site in self.extract_import_sites(root, content) {
+            let Some(target) = self.resolve_import(&site, source_path, project_root) else {
+                debug!(
+                    "Could not resolve import '{}' in {}",
+                    site.to_import().path,
+                    source_path.display()
+                );
+                continue;
+            };
+
+            bindings.insert(site.binding.clone(), target.clone());
+
+            let import = site.to_import();
+            let info = match &site.name {
+                Some(name) => format!("Import: from {} import {}", import.path, name),
+                None => format!("Import: {}", import.path),
+            };
+
+            dependencies.push(Dependency {
+                source: source_path.to_path_buf(),
+                target,
+                dependency_type: DependencyType::Import,
+                line: Some(site.line),
+                info: Some(info),
+            });
+        }
+
+        // Inheritance from imported classes
+        for node in iter_tree(root) {
+            if node.kind() != "class_definition" {
+                continue;
+            }
+
+            let class = self.build_class(node, content);
+            for base in &class.base_classes {
+                let head = base.split('.').next().unwrap_or(base);
+                if let Some(target) = bindings.get(head) {
+                    dependencies.push(Dependency {
+                        source: source_path.to_path_buf(),
+                        target: target.clone(),
+                        dependency_type: DependencyType::Inheritance,
+                        line: Some(class.start_line),
+                        info: Some(format!("Inheritance: {} extends {}", class.name, base)),
+                    });
+                }
+            }
+        }
+
+        dependencies
+    }
+}
+
+/// Find the file for a module path: `<path>.py` or `<path>/__init__.py`
+fn module_file(module_path: &Path) -> Option<PathBuf> {
+    let file = module_path.with_extension("py");
+    if file.is_file() {
+        return Some(file);
+    }
+
+    let stub = module_path.with_extension("pyi");
+    if stub.is_file() {
+        return Some(stub);
+    }
+
+    let package = module_path.join("__init__.py");
+    if package.is_file() {
+        return Some(package);
+    }
+
+    None
+}
+
+/// Get the innermost class definition enclosing a node
+fn enclosing_class(node: Node<'_>) -> Option<Node<'_>> {
+    let mut current = node.parent();
+
+    while let Some(parent) = current {
+        if parent.kind() == "class_definition" {
+            return Some(parent);
+        }
+        current = parent.parent();
+    }
+
+    None
+}
+
+/// Check whether a base class adds to the inheritance depth
+///
+/// `object` and the typing markers `Generic` and `Protocol` do not.
+fn is_counted_base(base: &str) -> bool {
+    base != "object" && !base.starts_with("Generic") && !base.starts_with("Protocol")
+}
+
+/// Python visibility convention: names starting with an underscore are
+/// private, except dunder names such as `__init__`
+fn is_public_name(name: &str) -> bool {
+    !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+}
+
+/// Check whether a decorator turns a method into a property
+fn is_property_decorator(decorator: &str) -> bool {
+    decorator == "property"
+        || decorator == "cached_property"
+        || decorator.ends_with(".cached_property")
+}
+
+/// Add a variable unless one with the same name is already present
+fn push_unique(variables: &mut Vec<Variable>, variable: Variable) {
+    if !variables.iter().any(|v| v.name == variable.name) {
+        variables.push(variable);
+    }
+}
+
+#[async_trait]
+impl LanguageAnalyzer for PythonAnalyzer {
+    fn language_name(&self) -> &'static str {
+        "Python"
+    }
+
+    fn supported_extensions(&self) -> &[&'static str] {
+        &["py", "pyi"]
+    }
+
+    async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
+        info!("Analyzing Python file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        Ok(self.analyze_tree(path, &tree, &content))
+    }
+
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!(
+            "Extracting dependencies from Python file: {}",
+            path.display()
+        );
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(dependencies)
+    }
+
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing Python file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content),
+            dependencies,
+            symbols: SymbolGraph::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-python-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Analyzer resolving imports against `root`
+    fn analyzer(root: &Path) -> PythonAnalyzer {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        PythonAnalyzer::new(Arc::new(config))
+    }
+
+    fn analyze(analyzer: &PythonAnalyzer, path: &Path, content: &str) -> FileAnalysis {
+        let tree = analyzer.parse(path, content).unwrap();
+        analyzer.analyze_tree(path, &tree, content)
+    }
+
+    #[test]
+    fn imports_keep_name_and_alias_apart() {
+        let analyzer = analyzer(Path::new("."));
+        let content = "import os.path\nimport numpy as np\nfrom pkg.models import Model as M\nfrom . import utils\n";
+        let analysis = analyze(&analyzer, Path::new("app.py"), content);
+
+        let imports: Vec<(&str, Option<&str>, Option<&str>)> = analysis
+            .imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.name.as_deref(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("os.path", None, None),
+                ("numpy", None, Some("np")),
+                ("pkg.models", Some("Model"), Some("M")),
+                (".", Some("utils"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_class_attributes_stay_with_the_nested_class() {
+        let analyzer = analyzer(Path::new("."));
+        let content = "\
+class Outer:
+    def __init__(self):
+        self.outer = 1
+
+    class Inner:
+        def __init__(self):
+            self.inner = 2
+";
+        let analysis = analyze(&analyzer, Path::new("nested.py"), content);
+
+        assert_eq!(analysis.classes.len(), 1);
+        let properties: Vec<&str> = analysis.classes[0]
+            .properties
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(properties, vec!["outer"]);
+    }
+
+    #[test]
+    fn relative_imports_resolve_to_project_files() {
+        let root = scratch_dir("relative");
+        fs::create_dir_all(root.join("pkg/sub")).unwrap();
+        fs::write(root.join("pkg/__init__.py"), "").unwrap();
+        fs::write(root.join("pkg/models.py"), "class Model:\n    pass\n").unwrap();
+        fs::write(root.join("pkg/sub/__init__.py"), "").unwrap();
+        fs::write(root.join("pkg/sub/helpers.py"), "def helper():\n    pass\n").unwrap();
+        let views = "\
+from .helpers import helper
+from .. import models
+from ..models import Model
+from . import missing
+";
+        let views_path = root.join("pkg/sub/views.py");
+        fs::write(&views_path, views).unwrap();
+
+        let analyzer = analyzer(&root);
+        let tree = analyzer.parse(&views_path, views).unwrap();
+        let dependencies = analyzer.extract_dependencies_from_tree(&tree, views, &views_path, &root);
+
+        let targets: Vec<(PathBuf, Option<usize>)> = dependencies
+            .iter()
+            .filter(|d| matches!(d.dependency_type, DependencyType::Import))
+            .map(|d| (d.target.clone(), d.line))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                (root.join("pkg/sub/helpers.py"), Some(0)),
+                (root.join("pkg/models.py"), Some(1)),
+                (root.join("pkg/models.py"), Some(2)),
+                // `from . import missing` falls back to the package itself
+                (root.join("pkg/sub/__init__.py"), Some(3)),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -3,17 +3,23 @@
 //! This module provides a comprehensive analyzer for Rust code, extracting
 //! structural information, relationships, and metrics using tree-sitter.
 
+pub mod resolver;
+pub mod symbols;
+
 use async_trait::async_trait;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tracing::{debug, info};
 use tree_sitter::{Parser, Query, QueryCursor, QueryMatch, StreamingIterator, Tree};
 
 use crate::analyzers::common::{
-    CodeMetrics, Dependency, DependencyType, FileAnalysis, Function, FunctionMetrics, Import,
-    Parameter, Variable,
+    class_cohesion, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType, FileAnalysis,
+    FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
 };
+use crate::analyzers::rust::resolver::{flatten_use_tree, ModuleResolver};
+use crate::analyzers::rust::symbols::SymbolExtractor;
 use crate::analyzers::LanguageAnalyzer;
 use crate::core::config::Config;
 use crate::errors::{Result, ZseiError};
@@ -29,6 +35,9 @@ pub struct RustAnalyzer {
 
     /// Collection of pre-compiled queries for Rust code analysis
     queries: RustQueries,
+
+    /// Resolver mapping use paths onto defining files, created on first use
+    resolver: OnceLock<ModuleResolver>,
 }
 
 /// Collection of tree-sitter queries for extracting different
@@ -128,8 +137,8 @@ impl RustAnalyzer {
             &tree_sitter_rust::LANGUAGE.into(),
             r#"
             (impl_item
-              trait: (type_identifier)? @trait_name
-              type: (type_identifier) @type_name
+              trait: (_)? @trait_name
+              type: (_) @type_name
               body: (declaration_list) @body)
             "#,
         )
@@ -174,6 +183,7 @@ impl RustAnalyzer {
             config,
             parser,
             queries,
+            resolver: OnceLock::new(),
         }
     }
 
@@ -193,11 +203,86 @@ impl RustAnalyzer {
         Ok(tree)
     }
 
+    /// Read a source file
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Parse source code with a fresh parser
+    ///
+    /// The shared parser is not used as parsing happens concurrently.
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .expect("Failed to set Rust language");
+
+        parser
+            .parse(content.as_bytes(), None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Build the file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> Result<FileAnalysis> {
+        // Extract code elements
+        let functions = self.extract_functions(tree, content)?;
+        let classes = self.extract_classes(tree, content)?;
+        let imports = self.extract_imports(tree, content, path)?;
+        let variables = self.extract_variables(tree, content)?;
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .filter(|l| l.trim().starts_with("//") || l.trim().starts_with("/*"))
+            .count();
+
+        // Calculate aggregate complexity
+        let complexity = functions.iter().map(|f| f.metrics.complexity).sum();
+
+        // Calculate maintainability index
+        let maintainability_index =
+            self.calculate_maintainability_index(loc, comment_lines, complexity);
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index,
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} types, {} imports, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        Ok(FileAnalysis {
+            path: path.to_path_buf(),
+            language: "Rust".to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        })
+    }
+
     /// Extract functions from the syntax tree
     ///
     /// This queries the syntax tree for function definitions and extracts
     /// comprehensive information about each function including name, signature,
-    /// parameters, and metrics like complexity.
+    /// parameters, and metrics like complexity. Methods of `impl` blocks and
+    /// traits are left to the types they belong to.
     fn extract_functions(&self, tree: &Tree, content: &str) -> Result<Vec<Function>> {
         let mut functions = Vec::new();
         let mut query_cursor = QueryCursor::new();
@@ -232,6 +317,10 @@ impl RustAnalyzer {
             if let (Some(name_node), Some(params_node), Some(body_node)) =
                 (function_name_node, parameters_node, body_node)
             {
+                if Self::is_method(name_node) {
+                    continue;
+                }
+
                 let name = content[name_node.byte_range()].to_string();
                 let params_str = &content[params_node.byte_range()];
                 let signature = format!("fn {}({})", name, params_str);
@@ -349,8 +438,14 @@ impl RustAnalyzer {
     /// Extract imports from the syntax tree
     ///
     /// This queries the syntax tree for import statements (use declarations)
-    /// and extracts information about each import.
-    fn extract_imports(&self, tree: &Tree, content: &str) -> Result<Vec<Import>> {
+    /// and extracts information about each import. Imports from external
+    /// crates are tagged with the crate name and version.
+    fn extract_imports(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+    ) -> Result<Vec<Import>> {
         let mut imports = Vec::new();
         let mut query_cursor = QueryCursor::new();
 
@@ -387,11 +482,17 @@ impl RustAnalyzer {
                 // Extract the module name if possible
                 let name = path.split("::").last().map(String::from);
 
+                let external_crate = flatten_use_tree(&path)
+                    .first()
+                    .and_then(|use_path| self.resolver().external_crate(use_path, source_path));
+
                 imports.push(Import {
                     path,
                     name,
+                    alias: None,
                     line,
                     is_relative,
+                    external_crate,
                 });
             }
         }
@@ -481,6 +582,342 @@ impl RustAnalyzer {
         Ok(variables)
     }
 
+    /// Extract structs, enums and traits as classes
+    ///
+    /// Struct fields and enum variants become properties, methods from impl
+    /// blocks in the same file are attached to their type, and implemented
+    /// traits (or supertraits, for traits) are recorded as base classes.
+    /// Types that are only implemented here, and defined elsewhere, get an
+    /// entry spanning their impl blocks.
+    fn extract_classes(&self, tree: &Tree, content: &str) -> Result<Vec<Class>> {
+        let mut classes: Vec<Class> = Vec::new();
+        let mut impl_only = HashSet::new();
+        let mut query_cursor = QueryCursor::new();
+
+        // Structs and their fields
+        let mut matches = query_cursor.matches(
+            &self.queries.struct_query,
+            tree.root_node(),
+            content.as_bytes(),
+        );
+
+        while let Some(match_) = matches.next() {
+            let Some(name_node) = self.capture(match_, &self.queries.struct_query, "struct_name")
+            else {
+                continue;
+            };
+            let Some(struct_node) = name_node.parent() else {
+                continue;
+            };
+
+            let mut properties = Vec::new();
+            if let Some(body) = struct_node.child_by_field_name("body") {
+                let mut position = 0;
+                for i in 0..body.named_child_count() {
+                    let Some(field) = body.named_child(i) else {
+                        continue;
+                    };
+
+                    match field.kind() {
+                        // Named fields
+                        "field_declaration" => {
+                            if let Some(field_name) = field.child_by_field_name("name") {
+                                properties.push(Variable {
+                                    name: content[field_name.byte_range()].to_string(),
+                                    var_type: field
+                                        .child_by_field_name("type")
+                                        .map(|t| content[t.byte_range()].to_string()),
+                                    line: field.start_position().row,
+                                    is_public: self.is_node_public(field, content),
+                                    init_value: None,
+                                });
+                            }
+                        }
+                        // Tuple struct fields are named by position
+                        "visibility_modifier" | "attribute_item" => {}
+                        _ if body.kind() == "ordered_field_declaration_list" => {
+                            properties.push(Variable {
+                                name: position.to_string(),
+                                var_type: Some(content[field.byte_range()].to_string()),
+                                line: field.start_position().row,
+                                is_public: field
+                                    .prev_named_sibling()
+                                    .map_or(false, |p| p.kind() == "visibility_modifier"),
+                                init_value: None,
+                            });
+                            position += 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            classes.push(self.new_class(struct_node, name_node, properties, Vec::new(), content));
+        }
+
+        // Enums and their variants
+        let mut matches = query_cursor.matches(
+            &self.queries.enum_query,
+            tree.root_node(),
+            content.as_bytes(),
+        );
+
+        while let Some(match_) = matches.next() {
+            let (Some(name_node), Some(body)) = (
+                self.capture(match_, &self.queries.enum_query, "enum_name"),
+                self.capture(match_, &self.queries.enum_query, "body"),
+            ) else {
+                continue;
+            };
+            let Some(enum_node) = name_node.parent() else {
+                continue;
+            };
+            let is_public = self.is_node_public(enum_node, content);
+
+            let mut properties = Vec::new();
+            for i in 0..body.named_child_count() {
+                let Some(variant) = body.named_child(i) else {
+                    continue;
+                };
+                if variant.kind() != "enum_variant" {
+                    continue;
+                }
+                let Some(variant_name) = variant.child_by_field_name("name") else {
+                    continue;
+                };
+
+                properties.push(Variable {
+                    name: content[variant_name.byte_range()].to_string(),
+                    var_type: variant
+                        .child_by_field_name("body")
+                        .map(|b| content[b.byte_range()].to_string()),
+                    line: variant.start_position().row,
+                    is_public,
+                    init_value: variant
+                        .child_by_field_name("value")
+                        .map(|v| content[v.byte_range()].to_string()),
+                });
+            }
+
+            classes.push(self.new_class(enum_node, name_node, properties, Vec::new(), content));
+        }
+
+        // Traits with their declared methods and supertraits
+        let mut matches = query_cursor.matches(
+            &self.queries.trait_query,
+            tree.root_node(),
+            content.as_bytes(),
+        );
+
+        while let Some(match_) = matches.next() {
+            let (Some(name_node), Some(body)) = (
+                self.capture(match_, &self.queries.trait_query, "trait_name"),
+                self.capture(match_, &self.queries.trait_query, "body"),
+            ) else {
+                continue;
+            };
+            let Some(trait_node) = name_node.parent() else {
+                continue;
+            };
+
+            // Trait items share the visibility of the trait
+            let is_public = self.is_node_public(trait_node, content);
+            let methods = self
+                .extract_methods(body, content)
+                .into_iter()
+                .map(|mut method| {
+                    method.is_public = is_public;
+                    method
+                })
+                .collect();
+
+            let mut class = self.new_class(trait_node, name_node, Vec::new(), methods, content);
+
+            if let Some(bounds) = trait_node.child_by_field_name("bounds") {
+                for i in 0..bounds.named_child_count() {
+                    if let Some(bound) = bounds.named_child(i) {
+                        class
+                            .base_classes
+                            .push(content[bound.byte_range()].to_string());
+                    }
+                }
+            }
+
+            classes.push(class);
+        }
+
+        // Impl blocks contribute methods and implemented traits
+        let mut matches = query_cursor.matches(
+            &self.queries.impl_query,
+            tree.root_node(),
+            content.as_bytes(),
+        );
+
+        while let Some(match_) = matches.next() {
+            let (Some(type_node), Some(body)) = (
+                self.capture(match_, &self.queries.impl_query, "type_name"),
+                self.capture(match_, &self.queries.impl_query, "body"),
+            ) else {
+                continue;
+            };
+            let trait_node = self.capture(match_, &self.queries.impl_query, "trait_name");
+            let Some(impl_node) = type_node.parent() else {
+                continue;
+            };
+
+            let type_name = base_type_name(&content[type_node.byte_range()]).to_string();
+
+            let mut methods = self.extract_methods(body, content);
+            if trait_node.is_some() {
+                // Trait impl methods are as visible as the trait
+                for method in &mut methods {
+                    method.is_public = true;
+                }
+            }
+
+            let index = match classes.iter().position(|c| c.name == type_name) {
+                Some(index) => index,
+                None => {
+                    let mut class =
+                        self.new_class(impl_node, type_node, Vec::new(), Vec::new(), content);
+                    class.name = type_name.clone();
+                    impl_only.insert(type_name);
+                    classes.push(class);
+                    classes.len() - 1
+                }
+            };
+
+            let class = &mut classes[index];
+            class.methods.extend(methods);
+
+            if let Some(trait_node) = trait_node {
+                let trait_name = content[trait_node.byte_range()].to_string();
+                if !class.base_classes.contains(&trait_name) {
+                    class.base_classes.push(trait_name);
+                }
+            }
+
+            // Entries for types defined elsewhere span all their impl blocks
+            if impl_only.contains(&class.name) {
+                class.start_line = class.start_line.min(impl_node.start_position().row);
+                class.end_line = class.end_line.max(impl_node.end_position().row);
+            }
+        }
+
+        // Metrics depend on the attached methods and properties
+        for class in &mut classes {
+            class.metrics = ClassMetrics {
+                loc: class.end_line - class.start_line + 1,
+                method_count: class.methods.len(),
+                property_count: class.properties.len(),
+                inheritance_depth: usize::from(!class.base_classes.is_empty()),
+                cohesion: class_cohesion(&class.methods, &class.properties, "self."),
+            };
+        }
+
+        Ok(classes)
+    }
+
+    /// Create a class entry for a type declaration node
+    fn new_class(
+        &self,
+        item_node: tree_sitter::Node<'_>,
+        name_node: tree_sitter::Node<'_>,
+        properties: Vec<Variable>,
+        methods: Vec<Function>,
+        content: &str,
+    ) -> Class {
+        Class {
+            name: content[name_node.byte_range()].to_string(),
+            start_line: item_node.start_position().row,
+            end_line: item_node.end_position().row,
+            methods,
+            properties,
+            base_classes: Vec::new(),
+            is_public: self.is_node_public(item_node, content),
+            metrics: ClassMetrics {
+                loc: 0,
+                method_count: 0,
+                property_count: 0,
+                inheritance_depth: 0,
+                cohesion: 1.0,
+            },
+        }
+    }
+
+    /// Extract the methods declared in an impl or trait body
+    ///
+    /// Trait method signatures without a default body are included without
+    /// a body.
+    fn extract_methods(&self, body: tree_sitter::Node<'_>, content: &str) -> Vec<Function> {
+        let mut methods = Vec::new();
+
+        for i in 0..body.named_child_count() {
+            let Some(item) = body.named_child(i) else {
+                continue;
+            };
+            if item.kind() != "function_item" && item.kind() != "function_signature_item" {
+                continue;
+            }
+
+            let Some(name_node) = item.child_by_field_name("name") else {
+                continue;
+            };
+            let name = content[name_node.byte_range()].to_string();
+
+            let parameters = match item.child_by_field_name("parameters") {
+                Some(params_node) => self
+                    .extract_parameters(params_node, content)
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+
+            let body_node = item.child_by_field_name("body");
+            let body = body_node.map(|b| content[b.byte_range()].to_string());
+            let signature_end = body_node.map_or(item.end_byte(), |b| b.start_byte());
+            let signature = content[item.start_byte()..signature_end]
+                .trim_end()
+                .trim_end_matches(';')
+                .to_string();
+
+            let body_text = body.as_deref().unwrap_or("");
+            let metrics = FunctionMetrics {
+                loc: body_text.lines().count(),
+                complexity: self.calculate_cyclomatic_complexity(body_text),
+                parameter_count: parameters.len(),
+                cognitive_complexity: self.calculate_cognitive_complexity(body_text),
+            };
+
+            methods.push(Function {
+                name,
+                signature,
+                start_line: item.start_position().row,
+                end_line: item.end_position().row,
+                body,
+                return_type: self.extract_return_type(item, content),
+                parameters,
+                is_public: self.is_node_public(item, content),
+                metrics,
+            });
+        }
+
+        methods
+    }
+
+    /// Get the node of a named capture in a query match
+    fn capture<'tree>(
+        &self,
+        match_: &QueryMatch<'_, 'tree>,
+        query: &Query,
+        name: &str,
+    ) -> Option<tree_sitter::Node<'tree>> {
+        match_
+            .captures
+            .iter()
+            .find(|capture| query.capture_names()[capture.index as usize] == name)
+            .map(|capture| capture.node)
+    }
+
     /// Calculate cyclomatic complexity for a code block
     ///
     /// This analyzes code to determine the cyclomatic complexity,
@@ -565,6 +1002,24 @@ impl RustAnalyzer {
         complexity
     }
 
+    /// Check if a function name belongs to a method of an `impl` block or
+    /// trait
+    ///
+    /// Functions nested in a method body are not methods themselves.
+    fn is_method(name_node: tree_sitter::Node<'_>) -> bool {
+        let mut current = name_node.parent().and_then(|function| function.parent());
+
+        while let Some(node) = current {
+            match node.kind() {
+                "impl_item" | "trait_item" => return true,
+                "function_item" => return false,
+                _ => current = node.parent(),
+            }
+        }
+
+        false
+    }
+
     /// Check if a node is public (has a pub modifier)
     ///
     /// This determines whether a declaration (function, struct, etc.)
@@ -635,23 +1090,22 @@ impl RustAnalyzer {
         tree: &Tree,
         content: &str,
         source_path: &Path,
-        project_root: &Path,
     ) -> Result<Vec<Dependency>> {
         let mut dependencies = Vec::new();
 
         // Extract import dependencies
-        let imports = self.extract_imports(tree, content)?;
+        let imports = self.extract_imports(tree, content, source_path)?;
         for import in imports {
-            // Convert import path to file path
-            let target_path = self.resolve_import_path(&import.path, source_path, project_root)?;
-
-            dependencies.push(Dependency {
-                source: source_path.to_path_buf(),
-                target: target_path,
-                dependency_type: DependencyType::Import,
-                line: Some(import.line),
-                info: Some(format!("Import: {}", import.path)),
-            });
+            // Map the use path onto the files defining the imported items
+            for target_path in self.resolve_import_path(&import.path, source_path) {
+                dependencies.push(Dependency {
+                    source: source_path.to_path_buf(),
+                    target: target_path,
+                    dependency_type: DependencyType::Import,
+                    line: Some(import.line),
+                    info: Some(format!("Import: {}", import.path)),
+                });
+            }
         }
 
         // Extract function call dependencies
@@ -683,10 +1137,11 @@ impl RustAnalyzer {
                 let function_name = content[func_node.byte_range()].to_string();
                 let module_name = module_name_node.map(|n| content[n.byte_range()].to_string());
 
-                // If we have a module name, try to resolve it to a file
+                // If we have a module name, try to resolve it to a file.
+                // Calls within the file are tracked by the symbol graph.
                 if let Some(module) = &module_name {
-                    match self.resolve_module_path(module, source_path, project_root) {
-                        Ok(target_path) => {
+                    match self.resolve_module_path(module, &function_name, source_path) {
+                        Some(target_path) => {
                             dependencies.push(Dependency {
                                 source: source_path.to_path_buf(),
                                 target: target_path,
@@ -695,22 +1150,13 @@ impl RustAnalyzer {
                                 info: Some(format!("Function call: {}::{}", module, function_name)),
                             });
                         }
-                        Err(e) => {
+                        None => {
                             debug!(
-                                "Could not resolve module '{}' for function call '{}': {}",
-                                module, function_name, e
+                                "Could not resolve module '{}' for function call '{}'",
+                                module, function_name
                             );
                         }
                     }
-                } else {
-                    // For local function calls, we still record them but don't change the target
-                    dependencies.push(Dependency {
-                        source: source_path.to_path_buf(),
-                        target: source_path.to_path_buf(), // Same file for local calls
-                        dependency_type: DependencyType::FunctionCall,
-                        line: Some(func_node.start_position().row),
-                        info: Some(format!("Local function call: {}", function_name)),
-                    });
                 }
             }
         }
@@ -752,9 +1198,7 @@ impl RustAnalyzer {
                 // Try to resolve the type to a file
                 // This is a best-effort approach as fully resolving types requires
                 // a complete type system understanding
-                if let Ok(target_path) =
-                    self.resolve_type_path(&type_name, source_path, project_root)
-                {
+                if let Some(target_path) = self.resolve_type_path(&type_name, source_path) {
                     dependencies.push(Dependency {
                         source: source_path.to_path_buf(),
                         target: target_path,
@@ -767,13 +1211,7 @@ impl RustAnalyzer {
         }
 
         // Extract struct and trait dependencies
-        self.extract_struct_trait_dependencies(
-            tree,
-            content,
-            source_path,
-            project_root,
-            &mut dependencies,
-        )?;
+        self.extract_struct_trait_dependencies(tree, content, source_path, &mut dependencies)?;
 
         Ok(dependencies)
     }
@@ -787,7 +1225,6 @@ impl RustAnalyzer {
         tree: &Tree,
         content: &str,
         source_path: &Path,
-        project_root: &Path,
         dependencies: &mut Vec<Dependency>,
     ) -> Result<()> {
         // Extract struct implementations and trait implementations
@@ -817,12 +1254,12 @@ impl RustAnalyzer {
 
             // If we have both trait and type, it's a trait implementation
             if let (Some(trait_node), Some(type_node)) = (trait_name_node, type_name_node) {
-                let trait_name = content[trait_node.byte_range()].to_string();
-                let type_name = content[type_node.byte_range()].to_string();
+                let trait_name = base_type_name(&content[trait_node.byte_range()]).to_string();
+                let type_name = base_type_name(&content[type_node.byte_range()]).to_string();
 
                 // Try to resolve the trait to a file
-                if let Ok(trait_path) =
-                    self.resolve_type_path(&trait_name, source_path, project_root)
+                if let Some(trait_path) =
+                    self.resolve_type_path(&content[trait_node.byte_range()], source_path)
                 {
                     dependencies.push(Dependency {
                         source: source_path.to_path_buf(),
@@ -837,7 +1274,8 @@ impl RustAnalyzer {
                 }
 
                 // Try to resolve the type to a file
-                if let Ok(type_path) = self.resolve_type_path(&type_name, source_path, project_root)
+                if let Some(type_path) =
+                    self.resolve_type_path(&content[type_node.byte_range()], source_path)
                 {
                     dependencies.push(Dependency {
                         source: source_path.to_path_buf(),
@@ -850,10 +1288,11 @@ impl RustAnalyzer {
             }
             // If we only have the type, it's a method implementation
             else if let Some(type_node) = type_name_node {
-                let type_name = content[type_node.byte_range()].to_string();
+                let type_name = base_type_name(&content[type_node.byte_range()]).to_string();
 
                 // Try to resolve the type to a file
-                if let Ok(type_path) = self.resolve_type_path(&type_name, source_path, project_root)
+                if let Some(type_path) =
+                    self.resolve_type_path(&content[type_node.byte_range()], source_path)
                 {
                     dependencies.push(Dependency {
                         source: source_path.to_path_buf(),
@@ -902,11 +1341,9 @@ impl RustAnalyzer {
                                         let type_name = content[child.byte_range()].to_string();
 
                                         // Try to resolve the field type to a file
-                                        if let Ok(type_path) = self.resolve_type_path(
-                                            &type_name,
-                                            source_path,
-                                            project_root,
-                                        ) {
+                                        if let Some(type_path) =
+                                            self.resolve_type_path(&type_name, source_path)
+                                        {
                                             dependencies.push(Dependency {
                                                 source: source_path.to_path_buf(),
                                                 target: type_path,
@@ -959,11 +1396,9 @@ impl RustAnalyzer {
                                         let type_name = content[child.byte_range()].to_string();
 
                                         // Try to resolve the variant type to a file
-                                        if let Ok(type_path) = self.resolve_type_path(
-                                            &type_name,
-                                            source_path,
-                                            project_root,
-                                        ) {
+                                        if let Some(type_path) =
+                                            self.resolve_type_path(&type_name, source_path)
+                                        {
                                             dependencies.push(Dependency {
                                                 source: source_path.to_path_buf(),
                                                 target: type_path,
@@ -987,232 +1422,65 @@ impl RustAnalyzer {
         Ok(())
     }
 
-    /// Resolve an import path to a file path
-    ///
-    /// This converts Rust module path syntax (e.g., 'std::fs::File')
-    /// to actual file paths within the project structure.
-    fn resolve_import_path(
-        &self,
-        import_path: &str,
-        source_path: &Path,
-        project_root: &Path,
-    ) -> Result<PathBuf> {
-        // Handle standard library imports
-        if ["std", "core", "alloc"].contains(&import_path.split("::").next().unwrap_or("")) {
-            // Standard library - use a special path
-            return Ok(PathBuf::from("rust")
-                .join("std")
-                .join(import_path.replace("::", "/")));
-        }
-
-        // Look for Cargo.toml to find crate root
-        let mut current_dir = source_path.parent().unwrap_or(Path::new(""));
-        let mut cargo_toml_path = None;
-
-        while current_dir.starts_with(project_root) {
-            let cargo_path = current_dir.join("Cargo.toml");
-            if cargo_path.exists() {
-                cargo_toml_path = Some(cargo_path);
-                break;
-            }
-
-            if let Some(parent) = current_dir.parent() {
-                current_dir = parent;
-            } else {
-                break;
-            }
-        }
-
-        // Determine crate root
-        let crate_root = if let Some(ref cargo_path) = cargo_toml_path {
-            cargo_path.parent().unwrap_or(project_root)
-        } else {
-            project_root
-        };
-
-        // Handle crate-relative paths
-        let parts: Vec<&str> = import_path.split("::").collect();
-
-        if parts.is_empty() {
-            return Err(ZseiError::Analyzer(format!(
-                "Invalid import path: {}",
-                import_path
-            )));
-        }
-
-        // Check for external crate
-        let is_external = {
-            if let Some(ref cargo_path) = cargo_toml_path {
-                // Parse Cargo.toml to get dependencies
-                if let Ok(content) = fs::read_to_string(cargo_path) {
-                    content.contains(&format!("name = \"{}\"", parts[0]))
-                        || content.contains(&format!("\"{} ", parts[0]))
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
-
-        if is_external {
-            // External crate - use a special path
-            Ok(PathBuf::from("external").join(parts.join("/")))
-        } else {
-            // Try different potential paths
-            let mut potential_paths = Vec::new();
-
-            // Special case for 'crate' paths
-            if import_path.starts_with("crate::") {
-                // Remove 'crate::' prefix and navigate from crate root
-                let path_without_prefix = import_path.replacen("crate::", "", 1);
-                let module_path = path_without_prefix.replace("::", "/");
-
-                // Check for module as file
-                potential_paths.push(crate_root.join("src").join(format!("{}.rs", module_path)));
-
-                // Check for module as directory with mod.rs
-                potential_paths.push(crate_root.join("src").join(&module_path).join("mod.rs"));
-            }
-            // Special case for 'self' or 'super' paths
-            else if import_path.starts_with("self::") || import_path.starts_with("super::") {
-                let source_dir = source_path.parent().unwrap_or(Path::new(""));
-
-                if import_path.starts_with("self::") {
-                    // Navigate from current directory
-                    let path_without_prefix = import_path.replacen("self::", "", 1);
-                    let module_path = path_without_prefix.replace("::", "/");
-
-                    potential_paths.push(source_dir.join(format!("{}.rs", module_path)));
-                    potential_paths.push(source_dir.join(&module_path).join("mod.rs"));
-                } else {
-                    // Navigate from parent directory
-                    let path_without_prefix = import_path.replacen("super::", "", 1);
-                    let module_path = path_without_prefix.replace("::", "/");
-
-                    if let Some(parent_dir) = source_dir.parent() {
-                        potential_paths.push(parent_dir.join(format!("{}.rs", module_path)));
-                        potential_paths.push(parent_dir.join(&module_path).join("mod.rs"));
-                    }
-                }
-            }
-            // Regular paths
-            else {
-                // Check for src/lib.rs or src/main.rs
-                potential_paths.push(crate_root.join("src").join("lib.rs"));
-                potential_paths.push(crate_root.join("src").join("main.rs"));
-
-                // Check for module file
-                let module_path = parts[0]; // First part of the path
-                potential_paths.push(crate_root.join("src").join(format!("{}.rs", module_path)));
-
-                // Check for module directory with mod.rs
-                potential_paths.push(crate_root.join("src").join(module_path).join("mod.rs"));
-
-                // Check for nested modules
-                if parts.len() > 1 {
-                    let full_module_path = parts.join("/");
-                    potential_paths.push(
-                        crate_root
-                            .join("src")
-                            .join(format!("{}.rs", full_module_path)),
-                    );
-                    potential_paths.push(
-                        crate_root
-                            .join("src")
-                            .join(&full_module_path)
-                            .join("mod.rs"),
-                    );
-                }
-            }
+    /// Get the module resolver for the project
+    fn resolver(&self) -> &ModuleResolver {
+        self.resolver
+            .get_or_init(|| ModuleResolver::new(self.config.project_root()))
+    }
 
-            // Try to find matching path
-            for path in potential_paths {
-                if path.exists() {
-                    return Ok(path);
+    /// Resolve a `use` declaration to the files defining the imported items
+    ///
+    /// Groups are expanded, so `use crate::{a::A, b::B};` can yield two
+    /// files. Standard library and registry crate imports yield nothing.
+    fn resolve_import_path(&self, import_path: &str, source_path: &Path) -> Vec<PathBuf> {
+        let mut targets = Vec::new();
+
+        for use_path in flatten_use_tree(import_path) {
+            match self.resolver().resolve_use(&use_path, source_path) {
+                Some(target) if target != source_path && !targets.contains(&target) => {
+                    targets.push(target)
                 }
+                Some(_) => {}
+                None => debug!(
+                    "Could not resolve import '{}' in {}",
+                    use_path.segments.join("::"),
+                    source_path.display()
+                ),
             }
-
-            // If not found, return a best guess path
-            let module_path = parts.join("/");
-            Ok(crate_root.join("src").join(format!("{}.rs", module_path)))
         }
+
+        targets
     }
 
-    /// Resolve a module path to a file path
+    /// Resolve a module-qualified function call (`module::function()`) to a file
     ///
-    /// This converts module identifiers in function calls (e.g., 'module::function()')
-    /// to file paths for tracking dependencies.
+    /// The module name is looked up through the file's `use` bindings and
+    /// child modules, so calls into other crates of the workspace resolve as well.
     fn resolve_module_path(
         &self,
         module_name: &str,
+        function_name: &str,
         source_path: &Path,
-        project_root: &Path,
-    ) -> Result<PathBuf> {
-        let source_dir = source_path.parent().unwrap_or(Path::new(""));
-
-        // Try module.rs in the same directory
-        let module_path = source_dir.join(format!("{}.rs", module_name));
-        if module_path.exists() {
-            return Ok(module_path);
-        }
-
-        // Try module/mod.rs
-        let module_dir_path = source_dir.join(module_name).join("mod.rs");
-        if module_dir_path.exists() {
-            return Ok(module_dir_path);
-        }
-
-        // Try to find the module in standard library or external crates
-        let import_path = format!("{}::", module_name);
-        self.resolve_import_path(&import_path, source_path, project_root)
+    ) -> Option<PathBuf> {
+        let segments = vec![module_name.to_string(), function_name.to_string()];
+        self.resolver()
+            .resolve_reference(&segments, source_path)
+            .filter(|target| target != source_path)
     }
 
-    /// Resolve a type path to a file path
+    /// Resolve a type to the file defining it
     ///
-    /// This attempts to find where a type is defined within the project
-    /// to map type dependencies correctly.
-    fn resolve_type_path(
-        &self,
-        type_name: &str,
-        source_path: &Path,
-        project_root: &Path,
-    ) -> Result<PathBuf> {
-        // Try to find type definition in the same file
-        // Types that are defined in the same file don't create external dependencies
-        // Since we can't easily check this from the analyzer, we'll assume types
-        // following certain naming patterns might be local types
-
-        // If type name contains '::', it's likely a path to another module
-        if type_name.contains("::") {
-            return self.resolve_import_path(type_name, source_path, project_root);
-        }
-
-        // Check if the type might be from std library or other common libraries
-        let std_types = [
-            "String", "Vec", "HashMap", "BTreeMap", "HashSet", "BTreeSet", "Option", "Result",
-            "Box", "Rc", "Arc", "Cell", "RefCell", "Mutex", "RwLock", "Cow", "PathBuf", "Path",
-        ];
-
-        if std_types.contains(&type_name) {
-            return Ok(PathBuf::from("rust").join("std"));
-        }
-
-        // Best effort: search for the type in the current directory
-        let source_dir = source_path.parent().unwrap_or(Path::new(""));
-
-        // Try to find a file named after the type (common Rust convention)
-        let snake_case = to_snake_case(type_name);
-        if !snake_case.is_empty() {
-            let type_file = source_dir.join(format!("{}.rs", snake_case));
-            if type_file.exists() {
-                return Ok(type_file);
-            }
+    /// Types defined in the source file itself, standard library types and
+    /// types from registry crates yield nothing.
+    fn resolve_type_path(&self, type_name: &str, source_path: &Path) -> Option<PathBuf> {
+        let segments = type_path_segments(type_name);
+        if segments.is_empty() {
+            return None;
         }
 
-        // If not found, return the source file itself as the best guess
-        // This is not always correct but better than no dependency
-        Ok(source_path.to_path_buf())
+        self.resolver()
+            .resolve_reference(&segments, source_path)
+            .filter(|target| target != source_path)
     }
 
     /// Calculate maintainability index
@@ -1245,27 +1513,40 @@ impl RustAnalyzer {
     }
 }
 
-/// Convert CamelCase to snake_case
+/// Strip path qualification, references and generic arguments from a type
 ///
-/// This is a utility function to help convert type names to potential file names.
-fn to_snake_case(s: &str) -> String {
-    let mut result = String::new();
-    let mut prev_is_upper = true; // Treat first char specially
-
-    for c in s.chars() {
-        if c.is_uppercase() {
-            if !prev_is_upper && !result.is_empty() {
-                result.push('_');
-            }
-            result.push(c.to_lowercase().next().unwrap());
-            prev_is_upper = true;
-        } else {
-            result.push(c);
-            prev_is_upper = false;
-        }
-    }
+/// `fmt::Display` becomes `Display` and `&mut Foo<T>` becomes `Foo`.
+fn base_type_name(type_name: &str) -> &str {
+    let without_generics = type_name.split('<').next().unwrap_or(type_name);
+    let without_path = without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics);
+
+    without_path
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim()
+}
 
-    result
+/// Split a type as written in source into path segments
+///
+/// References and generic arguments are dropped, so `&mut fmt::Formatter<'_>`
+/// becomes `["fmt", "Formatter"]`.
+fn type_path_segments(type_name: &str) -> Vec<String> {
+    let without_generics = type_name.split('<').next().unwrap_or(type_name);
+    let without_reference = without_generics
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim_start_matches("dyn ")
+        .trim();
+
+    without_reference
+        .split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
 }
 
 #[async_trait]
@@ -1280,99 +1561,203 @@ impl LanguageAnalyzer for RustAnalyzer {
 
     async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
         info!("Analyzing Rust file: {}", path.display());
-        let content = fs::read_to_string(path).map_err(|e| {
-            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
-        })?;
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
 
-        // Clone parser as it's not thread-safe
-        let mut parser = Parser::new();
-        let language = tree_sitter_rust::LANGUAGE;
-        parser
-            .set_language(&language.into())
-            .expect("Failed to set Rust language");
+        self.analyze_tree(path, &tree, &content)
+    }
 
-        // Parse file
-        let tree = parser.parse(content.as_bytes(), None).ok_or_else(|| {
-            ZseiError::Analyzer(format!("Failed to parse file {}", path.display()))
-        })?;
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!("Extracting dependencies from Rust file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
 
-        // Extract code elements
-        let functions = self.extract_functions(&tree, &content)?;
-        let imports = self.extract_imports(&tree, &content)?;
-        let variables = self.extract_variables(&tree, &content)?;
+        // Extract dependencies
+        let dependencies = self.extract_dependencies_from_tree(&tree, &content, path)?;
 
-        // Calculate metrics
-        let loc = content.lines().count();
-        let comment_lines = content
-            .lines()
-            .filter(|l| l.trim().starts_with("//") || l.trim().starts_with("/*"))
-            .count();
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
 
-        // Calculate aggregate complexity
-        let complexity = functions.iter().map(|f| f.metrics.complexity).sum();
+        Ok(dependencies)
+    }
 
-        // Calculate maintainability index
-        let maintainability_index =
-            self.calculate_maintainability_index(loc, comment_lines, complexity);
+    async fn extract_symbols(&self, path: &Path) -> Result<SymbolGraph> {
+        info!("Extracting symbols from Rust file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
 
-        let metrics = CodeMetrics {
-            loc,
-            comment_lines,
-            function_count: functions.len(),
-            class_count: 0, // Rust doesn't have classes, but we could count structs
-            import_count: imports.len(),
-            variable_count: variables.len(),
-            complexity,
-            maintainability_index,
-        };
+        let graph = SymbolExtractor::new(self.resolver(), path, &content).extract(&tree);
 
         debug!(
-            "Analysis complete: {} functions, {} imports, {} variables",
-            functions.len(),
-            imports.len(),
-            variables.len()
+            "Extracted {} symbols and {} calls from {}",
+            graph.symbols.len(),
+            graph.calls.len(),
+            path.display()
         );
 
-        Ok(FileAnalysis {
-            path: path.to_path_buf(),
-            language: "Rust".to_string(),
-            content: Some(content),
-            functions,
-            classes: Vec::new(), // Rust doesn't have classes in the traditional sense
-            variables,
-            imports,
-            metrics,
-        })
+        Ok(graph)
     }
 
-    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
-        info!("Extracting dependencies from Rust file: {}", path.display());
-        let content = fs::read_to_string(path).map_err(|e| {
-            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
-        })?;
-
-        // Clone parser as it's not thread-safe
-        let mut parser = Parser::new();
-        let language = tree_sitter_rust::LANGUAGE;
-        parser
-            .set_language(&language.into())
-            .expect("Failed to set Rust language");
-
-        // Parse file
-        let tree = parser.parse(content.as_bytes(), None).ok_or_else(|| {
-            ZseiError::Analyzer(format!("Failed to parse file {}", path.display()))
-        })?;
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing Rust file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
 
-        // Extract dependencies
-        let dependencies =
-            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root())?;
+        let dependencies = self.extract_dependencies_from_tree(&tree, &content, path)?;
+        let symbols = SymbolExtractor::new(self.resolver(), path, &content).extract(&tree);
 
         debug!(
-            "Extracted {} dependencies from {}",
+            "Extracted {} dependencies, {} symbols and {} calls from {}",
             dependencies.len(),
+            symbols.symbols.len(),
+            symbols.calls.len(),
             path.display()
         );
 
-        Ok(dependencies)
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content)?,
+            dependencies,
+            symbols,
+        })
+    }
+
+    fn reset_caches(&self) {
+        if let Some(resolver) = self.resolver.get() {
+            resolver.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(content: &str) -> FileAnalysis {
+        let analyzer = RustAnalyzer::new(Arc::new(Config::default()));
+        let path = Path::new("src/lib.rs");
+        let tree = analyzer.parse(path, content).unwrap();
+        analyzer.analyze_tree(path, &tree, content).unwrap()
+    }
+
+    /// Names of the methods of a class
+    fn method_names(class: &Class) -> Vec<&str> {
+        class.methods.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn types_are_classes_with_impl_methods() {
+        let content = "\
+pub struct Point {
+    pub x: i32,
+    y: i32,
+}
+
+pub struct Meters(pub f64);
+
+enum Shape {
+    Circle { radius: f64 },
+    Square(f64),
+}
+
+pub trait Area: std::fmt::Debug {
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        format!(\"{:?}\", self)
+    }
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    fn norm(&self) -> i32 {
+        self.x * self.x + self.y * self.y
+    }
+}
+
+impl Area for Shape {
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Circle { radius } => radius * radius * 3.14,
+            Shape::Square(side) => side * side,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings
+    }
+}
+
+fn helper() {}
+";
+        let analysis = analyze(content);
+
+        // Methods only belong to their types
+        let functions: Vec<&str> = analysis.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(functions, vec!["helper"]);
+
+        let classes: Vec<&str> = analysis.classes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            classes,
+            vec!["Point", "Meters", "Shape", "Area", "Settings"]
+        );
+        assert_eq!(analysis.metrics.class_count, 5);
+
+        let point = &analysis.classes[0];
+        assert!(point.is_public);
+        let fields: Vec<(&str, bool)> = point
+            .properties
+            .iter()
+            .map(|p| (p.name.as_str(), p.is_public))
+            .collect();
+        assert_eq!(fields, vec![("x", true), ("y", false)]);
+        assert_eq!(method_names(point), vec!["new", "norm"]);
+        assert!(point.methods[0].is_public);
+        assert!(!point.methods[1].is_public);
+        assert!(point.base_classes.is_empty());
+        assert_eq!(point.metrics.method_count, 2);
+        assert_eq!(point.metrics.property_count, 2);
+
+        let meters = &analysis.classes[1];
+        assert_eq!(meters.properties.len(), 1);
+        assert_eq!(meters.properties[0].name, "0");
+        assert_eq!(meters.properties[0].var_type.as_deref(), Some("f64"));
+        assert!(meters.properties[0].is_public);
+
+        let shape = &analysis.classes[2];
+        assert!(!shape.is_public);
+        let variants: Vec<&str> = shape.properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(variants, vec!["Circle", "Square"]);
+        assert_eq!(method_names(shape), vec!["area"]);
+        assert!(shape.methods[0].is_public);
+        assert_eq!(shape.base_classes, vec!["Area"]);
+        assert_eq!(shape.metrics.inheritance_depth, 1);
+
+        let area = &analysis.classes[3];
+        assert_eq!(method_names(area), vec!["area", "describe"]);
+        assert!(area.methods[0].body.is_none());
+        assert!(area.methods[1].body.is_some());
+        assert!(area.methods.iter().all(|m| m.is_public));
+        assert_eq!(area.base_classes, vec!["std::fmt::Debug"]);
+
+        // Types defined elsewhere span their impl blocks
+        let settings = &analysis.classes[4];
+        let impl_row = content
+            .lines()
+            .position(|line| line.starts_with("impl Default"))
+            .unwrap();
+        assert_eq!(method_names(settings), vec!["default"]);
+        assert_eq!(settings.base_classes, vec!["Default"]);
+        assert_eq!(
+            (settings.start_line, settings.end_line),
+            (impl_row, impl_row + 4)
+        );
     }
 }
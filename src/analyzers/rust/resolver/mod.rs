@@ -0,0 +1,1227 @@
+//! Rust module-tree and use-path resolution
+//!
+//! This module maps Rust `use` paths onto the files that define the named
+//! items. It builds the module tree of each crate target by following
+//! `mod foo;` declarations (including `#[path]` attributes and inline
+//! modules), records the items and `use` bindings of every module so that
+//! `pub use` re-exports can be followed, and resolves other workspace crates
+//! by their package names from `Cargo.toml`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+use tree_sitter::{Node, Parser};
+
+use crate::analyzers::cargo::{
+    find_workspace_root, read_manifest, CargoWorkspace, ExternalCrate, SYSROOT_CRATES,
+};
+use crate::utils::fs::normalize_path;
+
+/// Maximum depth when following re-exports, to guard against cycles
+const MAX_REEXPORT_DEPTH: usize = 16;
+
+/// Item kinds that define a name in their module
+const ITEM_KINDS: &[&str] = &[
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "function_item",
+    "function_signature_item",
+    "trait_item",
+    "type_item",
+    "const_item",
+    "static_item",
+    "macro_definition",
+];
+
+/// A single path imported by a `use` declaration, after expanding groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsePath {
+    /// Path segments, e.g. `["crate", "analyzers", "Analyzer"]`
+    pub segments: Vec<String>,
+
+    /// Alias from `as`, if any
+    pub alias: Option<String>,
+
+    /// Whether this is a glob import (`path::*`)
+    pub glob: bool,
+}
+
+impl UsePath {
+    /// Name this path is bound to in the importing module
+    ///
+    /// Glob imports and `as _` imports bind no name.
+    pub fn binding(&self) -> Option<&str> {
+        if self.glob {
+            return None;
+        }
+        match self.alias.as_deref() {
+            Some("_") => None,
+            Some(alias) => Some(alias),
+            None => self.segments.last().map(String::as_str),
+        }
+    }
+}
+
+/// Flatten the argument of a `use` declaration into individual paths
+///
+/// Expands nested groups, so `crate::a::{b::{C, D as E}, self, f::*}`
+/// produces `crate::a::b::C`, `crate::a::b::D as E`, `crate::a` and the
+/// glob `crate::a::f::*`.
+pub fn flatten_use_tree(text: &str) -> Vec<UsePath> {
+    let mut paths = Vec::new();
+    flatten_into(&[], text, &mut paths);
+    paths
+}
+
+/// Recursive helper for [`flatten_use_tree`]
+fn flatten_into(prefix: &[String], text: &str, paths: &mut Vec<UsePath>) {
+    for part in split_top_level(text) {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        if let Some(open) = part.find('{') {
+            let close = part.rfind('}').unwrap_or(part.len());
+            let mut group_prefix = prefix.to_vec();
+            group_prefix.extend(path_segments(part[..open].trim_end_matches("::")));
+            flatten_into(&group_prefix, &part[open + 1..close], paths);
+            continue;
+        }
+
+        let (path, alias) = match part.split_once(" as ") {
+            Some((path, alias)) => (path, Some(alias.trim().to_string())),
+            None => (part, None),
+        };
+
+        let mut segments = prefix.to_vec();
+        segments.extend(path_segments(path));
+
+        let glob = segments.last().map_or(false, |s| s == "*");
+        if glob {
+            segments.pop();
+        }
+
+        // `a::{self}` imports the module `a` itself
+        if segments.last().map_or(false, |s| s == "self") && segments.len() > 1 {
+            segments.pop();
+        }
+
+        if !segments.is_empty() {
+            paths.push(UsePath {
+                segments,
+                alias,
+                glob,
+            });
+        }
+    }
+}
+
+/// Split text at commas that are not nested inside braces
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Split a `::` separated path, keeping a leading `::` as an empty segment
+fn path_segments(path: &str) -> Vec<String> {
+    let path: String = path.chars().filter(|c| !c.is_whitespace()).collect();
+    if path.is_empty() {
+        return Vec::new();
+    }
+
+    let mut segments: Vec<String> = path.split("::").map(String::from).collect();
+    if segments.len() > 1 && segments[0].is_empty() {
+        // `::name` refers to an extern crate; keep the marker
+        segments[0] = "::".to_string();
+    }
+    segments.retain(|s| !s.is_empty());
+    segments
+}
+
+/// A module in a crate's module tree
+#[derive(Debug, Clone)]
+struct Module {
+    /// Module name (the crate name for the crate root)
+    name: String,
+
+    /// File containing the module (the parent's file for inline modules)
+    file: PathBuf,
+
+    /// Parent module, `None` for the crate root
+    parent: Option<usize>,
+
+    /// Child modules by name
+    children: HashMap<String, usize>,
+
+    /// Names of items defined directly in this module
+    items: HashSet<String>,
+
+    /// `use` declarations of this module
+    uses: Vec<UsePath>,
+}
+
+/// The module tree of a single crate target (library, binary, test, ...)
+#[derive(Debug, Default)]
+struct ModuleTree {
+    /// All modules; index 0 is the crate root
+    modules: Vec<Module>,
+
+    /// Module defined by each file (the outermost one for files with inline modules)
+    by_file: HashMap<PathBuf, usize>,
+}
+
+impl ModuleTree {
+    /// Build the module tree rooted at a crate root file
+    fn build(root_file: &Path, crate_name: &str) -> Self {
+        let mut tree = ModuleTree::default();
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .expect("Failed to set Rust language");
+
+        let root_dir = root_file.parent().unwrap_or(Path::new("")).to_path_buf();
+        tree.load_file(&mut parser, root_file, None, crate_name, root_dir);
+        tree
+    }
+
+    /// Parse a module file and add it, with its descendants, to the tree
+    fn load_file(
+        &mut self,
+        parser: &mut Parser,
+        file: &Path,
+        parent: Option<usize>,
+        name: &str,
+        children_dir: PathBuf,
+    ) -> Option<usize> {
+        let file = normalize_path(file);
+
+        // A file included twice (e.g. via `#[path]`) keeps its first module
+        if let Some(existing) = self.by_file.get(&file) {
+            return Some(*existing);
+        }
+
+        let content = fs::read_to_string(&file).ok()?;
+        let syntax = parser.parse(&content, None)?;
+
+        let id = self.add_module(file.clone(), parent, name);
+        self.by_file.insert(file.clone(), id);
+
+        self.load_scope(
+            parser,
+            syntax.root_node(),
+            &content,
+            id,
+            &file,
+            &children_dir,
+            false,
+        );
+
+        Some(id)
+    }
+
+    /// Add an empty module to the tree
+    fn add_module(&mut self, file: PathBuf, parent: Option<usize>, name: &str) -> usize {
+        let id = self.modules.len();
+        self.modules.push(Module {
+            name: name.to_string(),
+            file,
+            parent,
+            children: HashMap::new(),
+            items: HashSet::new(),
+            uses: Vec::new(),
+        });
+
+        if let Some(parent) = parent {
+            self.modules[parent].children.insert(name.to_string(), id);
+        }
+
+        id
+    }
+
+    /// Fully qualified path of a module, starting with the crate name
+    fn qualified_path(&self, module: usize) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(module);
+
+        while let Some(id) = current {
+            names.push(self.modules[id].name.as_str());
+            current = self.modules[id].parent;
+        }
+
+        names.reverse();
+        names.join("::")
+    }
+
+    /// Record the items, uses and submodules declared in a module body
+    ///
+    /// `children_dir` is where `mod foo;` looks for `foo.rs` and
+    /// `foo/mod.rs`. `inline` tells whether the body belongs to an inline
+    /// `mod foo { ... }`, which changes how `#[path]` is interpreted.
+    #[allow(clippy::too_many_arguments)]
+    fn load_scope(
+        &mut self,
+        parser: &mut Parser,
+        scope: Node<'_>,
+        content: &str,
+        module: usize,
+        file: &Path,
+        children_dir: &Path,
+        inline: bool,
+    ) {
+        let file_dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        for i in 0..scope.named_child_count() {
+            let Some(item) = scope.named_child(i) else {
+                continue;
+            };
+
+            match item.kind() {
+                "mod_item" => {
+                    let Some(name_node) = item.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = content[name_node.byte_range()].to_string();
+                    let path_attribute = path_attribute(item, content);
+
+                    match item.child_by_field_name("body") {
+                        // Inline module: same file, children one directory deeper
+                        Some(body) => {
+                            let id = self.add_module(file.to_path_buf(), Some(module), &name);
+                            let inner_dir = match &path_attribute {
+                                Some(path) => children_dir.join(path),
+                                None => children_dir.join(&name),
+                            };
+                            self.load_scope(parser, body, content, id, file, &inner_dir, true);
+                        }
+                        // Out-of-line module: `name.rs` or `name/mod.rs`
+                        None => {
+                            let (module_file, module_children_dir) = match &path_attribute {
+                                Some(path) => {
+                                    let base = if inline { children_dir } else { &file_dir };
+                                    let module_file = normalize_path(&base.join(path));
+                                    // Files loaded through `#[path]` own their directory
+                                    let dir =
+                                        module_file.parent().unwrap_or(Path::new("")).to_path_buf();
+                                    (module_file, dir)
+                                }
+                                None => {
+                                    let flat = children_dir.join(format!("{}.rs", name));
+                                    if flat.is_file() {
+                                        (flat, children_dir.join(&name))
+                                    } else {
+                                        (
+                                            children_dir.join(&name).join("mod.rs"),
+                                            children_dir.join(&name),
+                                        )
+                                    }
+                                }
+                            };
+
+                            if self
+                                .load_file(
+                                    parser,
+                                    &module_file,
+                                    Some(module),
+                                    &name,
+                                    module_children_dir,
+                                )
+                                .is_none()
+                            {
+                                debug!("Module '{}' not found at {}", name, module_file.display());
+                            }
+                        }
+                    }
+                }
+                "use_declaration" => {
+                    if let Some(argument) = item.child_by_field_name("argument") {
+                        self.modules[module]
+                            .uses
+                            .extend(flatten_use_tree(&content[argument.byte_range()]));
+                    }
+                }
+                "extern_crate_declaration" => {
+                    // `extern crate foo as bar;` binds `bar` to the crate `foo`
+                    let name = item
+                        .child_by_field_name("name")
+                        .map(|n| content[n.byte_range()].to_string());
+                    let alias = item
+                        .child_by_field_name("alias")
+                        .map(|n| content[n.byte_range()].to_string());
+                    if let Some(name) = name {
+                        self.modules[module].uses.push(UsePath {
+                            segments: vec!["::".to_string(), name],
+                            alias,
+                            glob: false,
+                        });
+                    }
+                }
+                kind if ITEM_KINDS.contains(&kind) => {
+                    if let Some(name_node) = item.child_by_field_name("name") {
+                        self.modules[module]
+                            .items
+                            .insert(content[name_node.byte_range()].to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Get the value of a `#[path = "..."]` attribute preceding an item
+fn path_attribute(item: Node<'_>, content: &str) -> Option<String> {
+    let mut sibling = item.prev_named_sibling();
+
+    while let Some(attribute) = sibling {
+        if attribute.kind() != "attribute_item" {
+            break;
+        }
+
+        // Other attributes, even ones starting with `path`, are skipped
+        let text = content[attribute.byte_range()].trim();
+        let inner = text.trim_start_matches("#[").trim_end_matches(']').trim();
+        let value = inner
+            .strip_prefix("path")
+            .and_then(|rest| rest.trim_start().strip_prefix('='));
+        if let Some(value) = value {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+
+        sibling = attribute.prev_named_sibling();
+    }
+
+    None
+}
+
+/// A Cargo package and its targets
+#[derive(Debug, Clone, Default)]
+pub struct CrateInfo {
+    /// Directory containing `Cargo.toml`
+    pub root_dir: PathBuf,
+
+    /// Package name from `[package] name`
+    pub package_name: String,
+
+    /// Library crate name as used in paths (`-` replaced by `_`)
+    pub crate_name: String,
+
+    /// Library root file, if the package has a library target
+    pub lib_root: Option<PathBuf>,
+
+    /// Root files of all other targets (binaries, tests, examples, benches)
+    pub other_roots: Vec<PathBuf>,
+
+    /// Local path dependencies by the name they are imported as
+    pub path_dependencies: HashMap<String, PathBuf>,
+}
+
+impl CrateInfo {
+    /// Load package information from the `Cargo.toml` in a directory
+    pub fn load(root_dir: &Path) -> Option<Self> {
+        let manifest = read_manifest(&root_dir.join("Cargo.toml"))?;
+        let package = manifest.get("package")?;
+        let package_name = package.get("name")?.as_str()?.to_string();
+
+        let lib = manifest.get("lib");
+        let crate_name = lib
+            .and_then(|l| l.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(&package_name)
+            .replace('-', "_");
+
+        let lib_root = lib
+            .and_then(|l| l.get("path"))
+            .and_then(|p| p.as_str())
+            .map(|p| root_dir.join(p))
+            .or_else(|| Some(root_dir.join("src").join("lib.rs")))
+            .filter(|p| p.is_file());
+
+        let mut other_roots = Vec::new();
+        let main = root_dir.join("src").join("main.rs");
+        if main.is_file() {
+            other_roots.push(main);
+        }
+
+        for section in ["bin", "test", "example", "bench"] {
+            if let Some(targets) = manifest.get(section).and_then(|t| t.as_array()) {
+                for target in targets {
+                    if let Some(path) = target.get("path").and_then(|p| p.as_str()) {
+                        other_roots.push(root_dir.join(path));
+                    }
+                }
+            }
+        }
+
+        // Auto-discovered targets
+        for dir in ["src/bin", "tests", "examples", "benches"] {
+            let Ok(entries) = fs::read_dir(root_dir.join(dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let target = if path.is_dir() {
+                    path.join("main.rs")
+                } else {
+                    path
+                };
+                if target.extension().map_or(false, |e| e == "rs")
+                    && target.is_file()
+                    && !other_roots.contains(&target)
+                {
+                    other_roots.push(target);
+                }
+            }
+        }
+
+        let mut path_dependencies = HashMap::new();
+        for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            let Some(dependencies) = manifest.get(section).and_then(|d| d.as_table()) else {
+                continue;
+            };
+            for (name, spec) in dependencies {
+                if let Some(path) = spec.get("path").and_then(|p| p.as_str()) {
+                    path_dependencies
+                        .insert(name.replace('-', "_"), normalize_path(&root_dir.join(path)));
+                }
+            }
+        }
+
+        Some(Self {
+            root_dir: root_dir.to_path_buf(),
+            package_name,
+            crate_name,
+            lib_root: lib_root.map(|p| normalize_path(&p)),
+            other_roots: other_roots.iter().map(|p| normalize_path(p)).collect(),
+            path_dependencies,
+        })
+    }
+
+    /// All target root files, library first
+    pub fn target_roots(&self) -> impl Iterator<Item = &PathBuf> {
+        self.lib_root.iter().chain(self.other_roots.iter())
+    }
+}
+
+/// Where a path resolution currently points
+#[derive(Debug, Clone)]
+enum Resolved {
+    /// A module of a crate target
+    Module(Arc<ModuleTree>, usize),
+
+    /// An item defined in a file, with its fully qualified path
+    Item(PathBuf, String),
+}
+
+/// Resolves Rust `use` paths to the files defining the named items
+///
+/// Crate information and module trees are cached until the resolver is
+/// cleared.
+pub struct ModuleResolver {
+    /// Project root; `Cargo.toml` lookups do not go above it
+    project_root: PathBuf,
+
+    /// Crate information per package directory
+    crates: Mutex<HashMap<PathBuf, Option<Arc<CrateInfo>>>>,
+
+    /// Module trees per target root file
+    trees: Mutex<HashMap<PathBuf, Arc<ModuleTree>>>,
+
+    /// Workspaces per workspace root
+    workspaces: Mutex<HashMap<PathBuf, Option<Arc<CargoWorkspace>>>>,
+}
+
+impl ModuleResolver {
+    /// Create a resolver for a project
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            crates: Mutex::new(HashMap::new()),
+            trees: Mutex::new(HashMap::new()),
+            workspaces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget the cached crates, module trees and workspaces
+    pub fn clear(&self) {
+        if let Ok(mut crates) = self.crates.lock() {
+            crates.clear();
+        }
+        if let Ok(mut trees) = self.trees.lock() {
+            trees.clear();
+        }
+        if let Ok(mut workspaces) = self.workspaces.lock() {
+            workspaces.clear();
+        }
+    }
+
+    /// Find the package a source file belongs to
+    pub fn crate_for_file(&self, source_path: &Path) -> Option<Arc<CrateInfo>> {
+        let mut current = source_path.parent();
+
+        while let Some(dir) = current {
+            if dir.join("Cargo.toml").is_file() {
+                if let Some(info) = self.crate_info(dir) {
+                    return Some(info);
+                }
+            }
+            if dir == self.project_root {
+                break;
+            }
+            current = dir.parent();
+        }
+
+        None
+    }
+
+    /// Get the (cached) package information for a package directory
+    fn crate_info(&self, root_dir: &Path) -> Option<Arc<CrateInfo>> {
+        let root_dir = normalize_path(root_dir);
+        let mut crates = self.crates.lock().ok()?;
+        crates
+            .entry(root_dir.clone())
+            .or_insert_with(|| CrateInfo::load(&root_dir).map(Arc::new))
+            .clone()
+    }
+
+    /// Get the (cached) module tree for a target root file
+    fn tree(&self, root_file: &Path, crate_name: &str) -> Option<Arc<ModuleTree>> {
+        let root_file = normalize_path(root_file);
+        let mut trees = self.trees.lock().ok()?;
+        let tree = trees
+            .entry(root_file.clone())
+            .or_insert_with(|| Arc::new(ModuleTree::build(&root_file, crate_name)))
+            .clone();
+
+        (!tree.modules.is_empty()).then_some(tree)
+    }
+
+    /// Find the module tree and module for a source file
+    ///
+    /// Files that are not reachable from any target of their package are
+    /// treated as the root of their own module tree.
+    fn module_for_file(&self, source_path: &Path) -> Option<(Arc<ModuleTree>, usize)> {
+        let source_path = normalize_path(source_path);
+
+        if let Some(info) = self.crate_for_file(&source_path) {
+            for root in info.target_roots() {
+                if let Some(tree) = self.tree(root, &info.crate_name) {
+                    if let Some(module) = tree.by_file.get(&source_path).copied() {
+                        return Some((tree, module));
+                    }
+                }
+            }
+        }
+
+        let crate_name = source_path.file_stem()?.to_string_lossy().replace('-', "_");
+        let tree = self.tree(&source_path, &crate_name)?;
+        Some((tree, 0))
+    }
+
+    /// Get the (cached) workspace a package belongs to
+    pub fn workspace_for_crate(&self, crate_dir: &Path) -> Option<Arc<CargoWorkspace>> {
+        let root = find_workspace_root(crate_dir, &self.project_root);
+        let mut workspaces = self.workspaces.lock().ok()?;
+        workspaces
+            .entry(root.clone())
+            .or_insert_with(|| CargoWorkspace::load(&root).map(Arc::new))
+            .clone()
+    }
+
+    /// Find the library module tree of a crate referenced by name from a file
+    ///
+    /// Looks at the package's own library (for binaries and tests), its path
+    /// dependencies and the other members of its workspace. Crates from a
+    /// registry are not resolved.
+    fn extern_crate(&self, name: &str, source_path: &Path) -> Option<Arc<ModuleTree>> {
+        let info = self.crate_for_file(source_path)?;
+
+        let crate_dir = if info.crate_name == name && info.lib_root.is_some() {
+            info.root_dir.clone()
+        } else if let Some(dir) = info.path_dependencies.get(name) {
+            dir.clone()
+        } else {
+            self.workspace_for_crate(&info.root_dir)?
+                .member_by_lib_name(name)?
+                .root_dir
+                .clone()
+        };
+
+        let info = self.crate_info(&crate_dir)?;
+        self.tree(info.lib_root.as_ref()?, &info.crate_name)
+    }
+
+    /// Identify the external crate a `use` path imports from
+    ///
+    /// Returns the toolchain crate (`std`, `core`, ...) or the registry
+    /// dependency, with its locked version, named by the path's first
+    /// segment. Paths into the project itself yield `None`.
+    pub fn external_crate(&self, use_path: &UsePath, source_path: &Path) -> Option<ExternalCrate> {
+        let mut segments = use_path.segments.iter().map(String::as_str);
+        let first = match segments.next()? {
+            "::" => segments.next()?,
+            first => first,
+        };
+
+        if matches!(first, "crate" | "self" | "super")
+            || self.resolve_use(use_path, source_path).is_some()
+        {
+            return None;
+        }
+
+        if SYSROOT_CRATES.contains(&first) {
+            return Some(ExternalCrate {
+                name: first.to_string(),
+                version: None,
+            });
+        }
+
+        let info = self.crate_for_file(source_path)?;
+        let workspace = self.workspace_for_crate(&info.root_dir)?;
+        let dependency = workspace
+            .crate_for_file(&normalize_path(source_path))?
+            .dependency(first)?;
+
+        // Path dependencies and workspace members are part of the project
+        if dependency.path.is_some() || workspace.member(&dependency.package).is_some() {
+            return None;
+        }
+
+        Some(ExternalCrate {
+            name: dependency.package.clone(),
+            version: dependency
+                .version
+                .clone()
+                .or_else(|| dependency.version_req.clone()),
+        })
+    }
+
+    /// Resolve a `use` path, as written in a source file, to the defining file
+    ///
+    /// Returns `None` for paths into the standard library and registry
+    /// crates.
+    pub fn resolve_use(&self, use_path: &UsePath, source_path: &Path) -> Option<PathBuf> {
+        let (tree, module) = self.module_for_file(source_path)?;
+        let resolved = self.resolve_segments(
+            &tree,
+            module,
+            &use_path.segments,
+            source_path,
+            0,
+            &mut HashSet::new(),
+        )?;
+
+        Some(match resolved {
+            Resolved::Module(tree, module) => tree.modules[module].file.clone(),
+            Resolved::Item(file, _) => file,
+        })
+    }
+
+    /// Resolve a path used in a source file to the fully qualified path of
+    /// the item it names, such as `zsei::analyzers::Analyzer::new`
+    ///
+    /// Qualified paths start with the crate name and follow re-exports to
+    /// the defining module.
+    pub fn resolve_symbol(&self, segments: &[String], source_path: &Path) -> Option<String> {
+        let (tree, module) = self.module_for_file(source_path)?;
+        let resolved =
+            self.resolve_segments(&tree, module, segments, source_path, 0, &mut HashSet::new())?;
+
+        Some(match resolved {
+            Resolved::Module(tree, module) => tree.qualified_path(module),
+            Resolved::Item(_, path) => path,
+        })
+    }
+
+    /// Fully qualified path of the module defined by a source file
+    pub fn module_path(&self, source_path: &Path) -> Option<String> {
+        let (tree, module) = self.module_for_file(source_path)?;
+        Some(tree.qualified_path(module))
+    }
+
+    /// Resolve a path used in expression or type position in a source file,
+    /// such as `fs::read` or `Config`
+    ///
+    /// The first segment is looked up through the file's `use` bindings,
+    /// local items and child modules.
+    pub fn resolve_reference(&self, segments: &[String], source_path: &Path) -> Option<PathBuf> {
+        self.resolve_use(
+            &UsePath {
+                segments: segments.to_vec(),
+                alias: None,
+                glob: false,
+            },
+            source_path,
+        )
+    }
+
+    /// Walk path segments starting from a module
+    ///
+    /// `visited` holds the names being looked up through the `use`
+    /// declarations of a module, keyed by crate root file and module, so
+    /// cyclic re-exports end instead of recursing.
+    fn resolve_segments(
+        &self,
+        tree: &Arc<ModuleTree>,
+        start: usize,
+        segments: &[String],
+        source_path: &Path,
+        depth: usize,
+        visited: &mut HashSet<(PathBuf, usize, String)>,
+    ) -> Option<Resolved> {
+        if depth > MAX_REEXPORT_DEPTH {
+            return None;
+        }
+
+        let mut tree = tree.clone();
+        let mut module = start;
+
+        for (i, segment) in segments.iter().enumerate() {
+            let first = i == 0;
+
+            match segment.as_str() {
+                "crate" if first => module = 0,
+                "self" => {}
+                "super" => module = tree.modules[module].parent?,
+                "::" if first => {
+                    // `::name` must be an extern crate
+                    let name = segments.get(1)?;
+                    tree = self.extern_crate(name, source_path)?;
+                    return self.resolve_segments(
+                        &tree,
+                        0,
+                        &segments[2..],
+                        source_path,
+                        depth + 1,
+                        visited,
+                    );
+                }
+                name => {
+                    let current = &tree.modules[module];
+
+                    if let Some(child) = current.children.get(name) {
+                        module = *child;
+                        continue;
+                    }
+
+                    if current.items.contains(name) {
+                        // Remaining segments name associated items or variants
+                        return Some(Resolved::Item(
+                            current.file.clone(),
+                            join_path(&tree.qualified_path(module), &segments[i..]),
+                        ));
+                    }
+
+                    if let Some(resolved) =
+                        self.resolve_binding(&tree, module, name, source_path, depth, visited)
+                    {
+                        match resolved {
+                            Resolved::Module(next_tree, next_module) => {
+                                tree = next_tree;
+                                module = next_module;
+                                continue;
+                            }
+                            Resolved::Item(file, path) => {
+                                return Some(Resolved::Item(
+                                    file,
+                                    join_path(&path, &segments[i + 1..]),
+                                ));
+                            }
+                        }
+                    }
+
+                    if first {
+                        // 2018 edition: a leading crate name
+                        let crate_tree = self.extern_crate(name, source_path)?;
+                        return self.resolve_segments(
+                            &crate_tree,
+                            0,
+                            &segments[1..],
+                            source_path,
+                            depth + 1,
+                            visited,
+                        );
+                    }
+
+                    // Unknown item in a known module (macro generated, cfg'd...)
+                    // is left unresolved rather than guessed
+                    return None;
+                }
+            }
+        }
+
+        Some(Resolved::Module(tree, module))
+    }
+
+    /// Resolve a name through the `use` declarations of a module
+    ///
+    /// Explicit bindings are tried before glob imports. A name that is
+    /// already being looked up in the module, through a cycle of re-exports,
+    /// is left unresolved.
+    fn resolve_binding(
+        &self,
+        tree: &Arc<ModuleTree>,
+        module: usize,
+        name: &str,
+        source_path: &Path,
+        depth: usize,
+        visited: &mut HashSet<(PathBuf, usize, String)>,
+    ) -> Option<Resolved> {
+        let key = (tree.modules[0].file.clone(), module, name.to_string());
+        if !visited.insert(key.clone()) {
+            return None;
+        }
+
+        let resolved = self.resolve_use_binding(tree, module, name, source_path, depth, visited);
+        visited.remove(&key);
+        resolved
+    }
+
+    /// Look a name up in the `use` declarations of a module
+    fn resolve_use_binding(
+        &self,
+        tree: &Arc<ModuleTree>,
+        module: usize,
+        name: &str,
+        source_path: &Path,
+        depth: usize,
+        visited: &mut HashSet<(PathBuf, usize, String)>,
+    ) -> Option<Resolved> {
+        let uses = &tree.modules[module].uses;
+
+        for use_path in uses.iter().filter(|u| u.binding() == Some(name)) {
+            if let Some(resolved) = self.resolve_segments(
+                tree,
+                module,
+                &use_path.segments,
+                source_path,
+                depth + 1,
+                visited,
+            ) {
+                return Some(resolved);
+            }
+        }
+
+        for use_path in uses.iter().filter(|u| u.glob) {
+            let Some(Resolved::Module(glob_tree, glob_module)) = self.resolve_segments(
+                tree,
+                module,
+                &use_path.segments,
+                source_path,
+                depth + 1,
+                visited,
+            ) else {
+                continue;
+            };
+
+            let target = &glob_tree.modules[glob_module];
+            if let Some(child) = target.children.get(name) {
+                return Some(Resolved::Module(glob_tree.clone(), *child));
+            }
+            if target.items.contains(name) {
+                return Some(Resolved::Item(
+                    target.file.clone(),
+                    join_path(&glob_tree.qualified_path(glob_module), &[name.to_string()]),
+                ));
+            }
+            if let Some(resolved) = self.resolve_binding(
+                &glob_tree,
+                glob_module,
+                name,
+                source_path,
+                depth + 1,
+                visited,
+            ) {
+                return Some(resolved);
+            }
+        }
+
+        None
+    }
+}
+
+/// Append path segments to a qualified path
+fn join_path(base: &str, segments: &[String]) -> String {
+    let mut path = base.to_string();
+    for segment in segments {
+        path.push_str("::");
+        path.push_str(segment);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "zsei-rust-resolver-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Use path with the given segments
+    fn use_path(segments: &[&str], alias: Option<&str>, glob: bool) -> UsePath {
+        UsePath {
+            segments: segments.iter().map(|s| s.to_string()).collect(),
+            alias: alias.map(String::from),
+            glob,
+        }
+    }
+
+    #[test]
+    fn use_trees_flatten_nested_groups() {
+        let paths = flatten_use_tree("crate::a::{b::{C, D as E}, self, f::*, G as _}");
+        assert_eq!(
+            paths,
+            vec![
+                use_path(&["crate", "a", "b", "C"], None, false),
+                use_path(&["crate", "a", "b", "D"], Some("E"), false),
+                use_path(&["crate", "a"], None, false),
+                use_path(&["crate", "a", "f"], None, true),
+                use_path(&["crate", "a", "G"], Some("_"), false),
+            ]
+        );
+
+        let bindings: Vec<Option<&str>> = paths.iter().map(UsePath::binding).collect();
+        assert_eq!(bindings, vec![Some("C"), Some("E"), Some("a"), None, None]);
+
+        assert_eq!(
+            flatten_use_tree("::std::io::{self, Read}"),
+            vec![
+                use_path(&["::", "std", "io"], None, false),
+                use_path(&["::", "std", "io", "Read"], None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn modules_follow_mod_declarations_and_path_attributes() {
+        let root = scratch_dir("modules");
+        write(
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"app-core\"\nversion = \"0.1.0\"\n",
+        );
+        write(
+            &root.join("src/lib.rs"),
+            "pub mod flat;
+pub mod nested;
+
+#[path = \"custom/renamed.rs\"]
+pub mod moved;
+
+#[path = \"inline_dir\"]
+pub mod inline {
+    #[path = \"deep.rs\"]
+    pub mod deep;
+    pub mod child;
+}
+
+#[path = \"other_file.rs\"]
+#[path_x]
+pub mod other;
+",
+        );
+        write(
+            &root.join("src/flat.rs"),
+            "pub struct Flat;\npub mod sub;\n",
+        );
+        let sub = root.join("src/flat/sub.rs");
+        write(&sub, "use super::Flat;\nuse crate::nested::Nested;\n");
+        write(&root.join("src/nested/mod.rs"), "pub struct Nested;\n");
+        write(&root.join("src/custom/renamed.rs"), "mod helper;\n");
+        write(&root.join("src/custom/helper.rs"), "pub fn help() {}\n");
+        write(&root.join("src/inline_dir/deep.rs"), "pub struct Deep;\n");
+        write(&root.join("src/inline_dir/child.rs"), "pub struct Child;\n");
+        write(&root.join("src/other_file.rs"), "pub struct Other;\n");
+
+        let resolver = ModuleResolver::new(&root);
+        let module_path = |file: &str| resolver.module_path(&root.join(file));
+
+        // `mod foo;` as both `foo.rs` and `foo/mod.rs`
+        assert_eq!(
+            module_path("src/flat.rs").as_deref(),
+            Some("app_core::flat")
+        );
+        assert_eq!(
+            module_path("src/flat/sub.rs").as_deref(),
+            Some("app_core::flat::sub")
+        );
+        assert_eq!(
+            module_path("src/nested/mod.rs").as_deref(),
+            Some("app_core::nested")
+        );
+
+        // `#[path]` on an out-of-line module, whose file owns its directory
+        assert_eq!(
+            module_path("src/custom/renamed.rs").as_deref(),
+            Some("app_core::moved")
+        );
+        assert_eq!(
+            module_path("src/custom/helper.rs").as_deref(),
+            Some("app_core::moved::helper")
+        );
+
+        // `#[path]` on an inline module and on a module inside it
+        assert_eq!(
+            module_path("src/inline_dir/deep.rs").as_deref(),
+            Some("app_core::inline::deep")
+        );
+        assert_eq!(
+            module_path("src/inline_dir/child.rs").as_deref(),
+            Some("app_core::inline::child")
+        );
+
+        // Other attributes between `#[path]` and the module are skipped
+        assert_eq!(
+            module_path("src/other_file.rs").as_deref(),
+            Some("app_core::other")
+        );
+
+        // `super::` and `crate::` paths
+        assert_eq!(
+            resolver
+                .resolve_symbol(&path_segments("super::Flat"), &sub)
+                .as_deref(),
+            Some("app_core::flat::Flat")
+        );
+        assert_eq!(
+            resolver.resolve_use(&use_path(&["crate", "nested", "Nested"], None, false), &sub),
+            Some(root.join("src/nested/mod.rs"))
+        );
+        assert_eq!(
+            resolver.resolve_reference(&path_segments("Nested"), &sub),
+            Some(root.join("src/nested/mod.rs"))
+        );
+        assert_eq!(
+            resolver
+                .resolve_symbol(&path_segments("crate::moved::helper::help"), &sub)
+                .as_deref(),
+            Some("app_core::moved::helper::help")
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn reexport_chains_resolve_and_cycles_end() {
+        let root = scratch_dir("reexports");
+        write(
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        );
+        write(
+            &root.join("src/lib.rs"),
+            "mod a;\nmod b;\nmod user;\n\npub use a::Thing;\npub use a::Loop;\n",
+        );
+        write(
+            &root.join("src/a.rs"),
+            "mod inner;\n\npub use self::inner::Thing;\npub use crate::b::Loop;\n",
+        );
+        write(&root.join("src/a/inner.rs"), "pub struct Thing;\n");
+        write(&root.join("src/b.rs"), "pub use crate::a::Loop;\n");
+        let user = root.join("src/user.rs");
+        write(&user, "use crate::Thing;\nuse crate::Loop;\n");
+
+        let resolver = ModuleResolver::new(&root);
+
+        // `crate::Thing` -> `a::Thing` -> `a::inner::Thing`
+        assert_eq!(
+            resolver.resolve_use(&use_path(&["crate", "Thing"], None, false), &user),
+            Some(root.join("src/a/inner.rs"))
+        );
+        assert_eq!(
+            resolver
+                .resolve_symbol(&path_segments("Thing"), &user)
+                .as_deref(),
+            Some("demo::a::inner::Thing")
+        );
+
+        // `a::Loop` and `b::Loop` re-export each other
+        assert_eq!(
+            resolver.resolve_symbol(&path_segments("crate::Loop"), &user),
+            None
+        );
+        assert_eq!(
+            resolver.resolve_reference(&path_segments("Loop"), &user),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn workspace_members_resolve_by_package_name() {
+        let root = scratch_dir("workspace");
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n\
+             [workspace.dependencies]\ndemo-core = { path = \"crates/core\" }\n",
+        );
+        write(
+            &root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"demo-core\"\nversion = \"0.1.0\"\n",
+        );
+        write(&root.join("crates/core/src/lib.rs"), "pub mod model;\n");
+        let model = root.join("crates/core/src/model.rs");
+        write(
+            &model,
+            "pub struct Model;\n\nimpl Model {\n    pub fn new() -> Self {\n        Model\n    }\n}\n",
+        );
+        write(
+            &root.join("crates/cli/Cargo.toml"),
+            "[package]\nname = \"demo-cli\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\ndemo-core = { workspace = true }\n",
+        );
+        let main = root.join("crates/cli/src/main.rs");
+        write(&main, "use demo_core::model::Model;\n\nfn main() {}\n");
+
+        let resolver = ModuleResolver::new(&root);
+        let import = use_path(&["demo_core", "model", "Model"], None, false);
+        assert_eq!(resolver.resolve_use(&import, &main), Some(model.clone()));
+        assert_eq!(
+            resolver.resolve_use(&use_path(&["::", "demo_core", "model"], None, false), &main),
+            Some(model.clone())
+        );
+        assert_eq!(
+            resolver
+                .resolve_symbol(&path_segments("Model::new"), &main)
+                .as_deref(),
+            Some("demo_core::model::Model::new")
+        );
+        assert_eq!(resolver.external_crate(&import, &main), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,467 @@
+//! Rust symbol and call extraction
+//!
+//! This module walks a Rust syntax tree to collect the functions, methods and
+//! types a file defines, keyed by fully qualified path, and the calls made
+//! from each function body. Callees are qualified through the module
+//! resolver; method calls are attributed when the receiver is `self` or a
+//! variable, parameter or field whose type is known.
+
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+use super::resolver::ModuleResolver;
+use super::type_path_segments;
+use crate::analyzers::common::{iter_tree, node_text, CallEdge, Symbol, SymbolGraph, SymbolKind};
+
+/// A function body whose calls still have to be collected
+struct PendingBody<'tree> {
+    /// Fully qualified path of the function
+    path: String,
+
+    /// Module the function is defined in
+    module_path: String,
+
+    /// Type of `self`, for methods
+    self_type: Option<String>,
+
+    /// Function node
+    node: Node<'tree>,
+}
+
+/// Extracts the symbol graph of a single Rust file
+pub struct SymbolExtractor<'a> {
+    /// Resolver for paths leaving the file
+    resolver: &'a ModuleResolver,
+
+    /// File being analyzed
+    source_path: &'a Path,
+
+    /// File content
+    content: &'a str,
+
+    /// Field types of the structs defined in the file, by qualified type path
+    fields: HashMap<String, HashMap<String, String>>,
+
+    /// Symbols and calls found so far
+    graph: SymbolGraph,
+}
+
+impl<'a> SymbolExtractor<'a> {
+    /// Create an extractor for a file
+    pub fn new(resolver: &'a ModuleResolver, source_path: &'a Path, content: &'a str) -> Self {
+        Self {
+            resolver,
+            source_path,
+            content,
+            fields: HashMap::new(),
+            graph: SymbolGraph::default(),
+        }
+    }
+
+    /// Extract the symbols and calls of a syntax tree
+    pub fn extract(mut self, tree: &Tree) -> SymbolGraph {
+        let module_path = self
+            .resolver
+            .module_path(self.source_path)
+            .unwrap_or_else(|| {
+                self.source_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().replace('-', "_"))
+                    .unwrap_or_default()
+            });
+
+        // Collect definitions first so calls can refer to items defined later
+        let mut bodies = Vec::new();
+        self.visit_scope(tree.root_node(), &module_path, &mut bodies);
+
+        for body in &bodies {
+            self.collect_calls(body);
+        }
+
+        self.graph
+    }
+
+    /// Record the items of a module body, descending into inline modules
+    fn visit_scope<'tree>(
+        &mut self,
+        scope: Node<'tree>,
+        module_path: &str,
+        bodies: &mut Vec<PendingBody<'tree>>,
+    ) {
+        for i in 0..scope.named_child_count() {
+            let Some(item) = scope.named_child(i) else {
+                continue;
+            };
+
+            match item.kind() {
+                "mod_item" => {
+                    if let (Some(name), Some(body)) = (
+                        item.child_by_field_name("name"),
+                        item.child_by_field_name("body"),
+                    ) {
+                        let inner = format!("{}::{}", module_path, node_text(name, self.content));
+                        self.visit_scope(body, &inner, bodies);
+                    }
+                }
+                "function_item" => {
+                    if let Some(path) = self.add_symbol(item, module_path, SymbolKind::Function) {
+                        bodies.push(PendingBody {
+                            path,
+                            module_path: module_path.to_string(),
+                            self_type: None,
+                            node: item,
+                        });
+                    }
+                }
+                "struct_item" => {
+                    if let Some(path) = self.add_symbol(item, module_path, SymbolKind::Type) {
+                        let fields = self.struct_fields(item);
+                        self.fields.insert(path, fields);
+                    }
+                }
+                "enum_item" | "union_item" | "type_item" => {
+                    self.add_symbol(item, module_path, SymbolKind::Type);
+                }
+                "trait_item" => {
+                    let Some(trait_path) = self.add_symbol(item, module_path, SymbolKind::Type)
+                    else {
+                        continue;
+                    };
+                    if let Some(body) = item.child_by_field_name("body") {
+                        self.visit_methods(body, &trait_path, module_path, bodies);
+                    }
+                }
+                "impl_item" => {
+                    let (Some(type_node), Some(body)) = (
+                        item.child_by_field_name("type"),
+                        item.child_by_field_name("body"),
+                    ) else {
+                        continue;
+                    };
+                    let type_text = node_text(type_node, self.content);
+                    if let Some(type_path) = self.resolve_type(type_text, module_path, None) {
+                        self.visit_methods(body, &type_path, module_path, bodies);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the methods of an impl or trait body
+    fn visit_methods<'tree>(
+        &mut self,
+        body: Node<'tree>,
+        owner_path: &str,
+        module_path: &str,
+        bodies: &mut Vec<PendingBody<'tree>>,
+    ) {
+        for i in 0..body.named_child_count() {
+            let Some(item) = body.named_child(i) else {
+                continue;
+            };
+            if !matches!(item.kind(), "function_item" | "function_signature_item") {
+                continue;
+            }
+
+            let Some(path) = self.add_symbol(item, owner_path, SymbolKind::Method) else {
+                continue;
+            };
+
+            // Trait method declarations without a default body make no calls
+            if item.kind() == "function_item" {
+                bodies.push(PendingBody {
+                    path,
+                    module_path: module_path.to_string(),
+                    self_type: Some(owner_path.to_string()),
+                    node: item,
+                });
+            }
+        }
+    }
+
+    /// Add a named item as a symbol under a parent path
+    fn add_symbol(
+        &mut self,
+        item: Node<'_>,
+        parent_path: &str,
+        kind: SymbolKind,
+    ) -> Option<String> {
+        let name = node_text(item.child_by_field_name("name")?, self.content).to_string();
+        let path = format!("{}::{}", parent_path, name);
+
+        self.graph.add_symbol(Symbol {
+            path: path.clone(),
+            name,
+            kind,
+            file: self.source_path.to_path_buf(),
+            line: item.start_position().row,
+        });
+
+        Some(path)
+    }
+
+    /// Get the named fields of a struct and their types as written
+    fn struct_fields(&self, item: Node<'_>) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+
+        if let Some(body) = item.child_by_field_name("body") {
+            for i in 0..body.named_child_count() {
+                let Some(field) = body.named_child(i) else {
+                    continue;
+                };
+                if let (Some(name), Some(field_type)) = (
+                    field.child_by_field_name("name"),
+                    field.child_by_field_name("type"),
+                ) {
+                    fields.insert(
+                        node_text(name, self.content).to_string(),
+                        node_text(field_type, self.content).to_string(),
+                    );
+                }
+            }
+        }
+
+        fields
+    }
+
+    /// Collect the calls made from a function body
+    fn collect_calls(&mut self, body: &PendingBody<'_>) {
+        let Some(block) = body.node.child_by_field_name("body") else {
+            return;
+        };
+
+        let variables = self.variable_types(body);
+
+        for node in iter_tree(block) {
+            if node.kind() != "call_expression" {
+                continue;
+            }
+            let Some(function) = node.child_by_field_name("function") else {
+                continue;
+            };
+
+            if let Some(callee) = self.resolve_callee(function, body, &variables) {
+                self.graph.add_call(CallEdge {
+                    caller: body.path.clone(),
+                    callee,
+                    file: self.source_path.to_path_buf(),
+                    line: node.start_position().row,
+                });
+            }
+        }
+    }
+
+    /// Types of the parameters and local variables of a function whose type
+    /// is known, by variable name
+    ///
+    /// A variable's type is known from an annotation, a struct literal or a
+    /// call to an associated function of a type (`Type::new(..)`).
+    fn variable_types(&self, body: &PendingBody<'_>) -> HashMap<String, String> {
+        let mut variables = HashMap::new();
+        let self_type = body.self_type.as_deref();
+
+        if let Some(parameters) = body.node.child_by_field_name("parameters") {
+            for i in 0..parameters.named_child_count() {
+                let Some(parameter) = parameters.named_child(i) else {
+                    continue;
+                };
+                let (Some(pattern), Some(param_type)) = (
+                    parameter.child_by_field_name("pattern"),
+                    parameter.child_by_field_name("type"),
+                ) else {
+                    continue;
+                };
+                if let Some(type_path) = self.resolve_type(
+                    node_text(param_type, self.content),
+                    &body.module_path,
+                    self_type,
+                ) {
+                    variables.insert(binding_name(pattern, self.content), type_path);
+                }
+            }
+        }
+
+        let Some(block) = body.node.child_by_field_name("body") else {
+            return variables;
+        };
+
+        for node in iter_tree(block) {
+            if node.kind() != "let_declaration" {
+                continue;
+            }
+            let Some(pattern) = node.child_by_field_name("pattern") else {
+                continue;
+            };
+
+            let type_text = match (
+                node.child_by_field_name("type"),
+                node.child_by_field_name("value"),
+            ) {
+                (Some(annotation), _) => Some(node_text(annotation, self.content)),
+                (None, Some(value)) => constructed_type(value, self.content),
+                (None, None) => None,
+            };
+
+            if let Some(type_path) =
+                type_text.and_then(|text| self.resolve_type(text, &body.module_path, self_type))
+            {
+                variables.insert(binding_name(pattern, self.content), type_path);
+            }
+        }
+
+        variables
+    }
+
+    /// Resolve the function expression of a call to the callee's qualified path
+    fn resolve_callee(
+        &self,
+        function: Node<'_>,
+        body: &PendingBody<'_>,
+        variables: &HashMap<String, String>,
+    ) -> Option<String> {
+        match function.kind() {
+            // `helper()`
+            "identifier" => {
+                let name = node_text(function, self.content);
+                let local = format!("{}::{}", body.module_path, name);
+                if self.graph.symbols.contains_key(&local) {
+                    return Some(local);
+                }
+                self.resolver
+                    .resolve_symbol(&[name.to_string()], self.source_path)
+            }
+            // `module::helper()`, `Type::new()`, `Self::helper()`
+            "scoped_identifier" => {
+                let text = node_text(function, self.content);
+                let segments = type_path_segments(text);
+                let (first, rest) = segments.split_first()?;
+
+                if first == "Self" {
+                    return Some(format!(
+                        "{}::{}",
+                        body.self_type.as_deref()?,
+                        rest.join("::")
+                    ));
+                }
+
+                let local = format!("{}::{}", body.module_path, first);
+                if self.graph.symbols.contains_key(&local) {
+                    let mut path = local;
+                    for segment in rest {
+                        path.push_str("::");
+                        path.push_str(segment);
+                    }
+                    return Some(path);
+                }
+
+                self.resolver.resolve_symbol(&segments, self.source_path)
+            }
+            // `receiver.method()`
+            "field_expression" => {
+                let method = node_text(function.child_by_field_name("field")?, self.content);
+                let receiver = function.child_by_field_name("value")?;
+                let receiver_type = self.receiver_type(receiver, body, variables)?;
+                Some(format!("{}::{}", receiver_type, method))
+            }
+            // `helper::<T>()`
+            "generic_function" => {
+                self.resolve_callee(function.child_by_field_name("function")?, body, variables)
+            }
+            _ => None,
+        }
+    }
+
+    /// Determine the type of a method call receiver, if known
+    fn receiver_type(
+        &self,
+        receiver: Node<'_>,
+        body: &PendingBody<'_>,
+        variables: &HashMap<String, String>,
+    ) -> Option<String> {
+        match receiver.kind() {
+            "self" => body.self_type.clone(),
+            "identifier" => variables.get(node_text(receiver, self.content)).cloned(),
+            // `self.field.method()` and `variable.field.method()`
+            "field_expression" => {
+                let owner =
+                    self.receiver_type(receiver.child_by_field_name("value")?, body, variables)?;
+                let field = node_text(receiver.child_by_field_name("field")?, self.content);
+                let field_type = self.fields.get(&owner)?.get(field)?;
+                self.resolve_type(field_type, &body.module_path, Some(&owner))
+            }
+            "parenthesized_expression" | "reference_expression" => {
+                let inner = receiver.named_child(receiver.named_child_count().checked_sub(1)?)?;
+                self.receiver_type(inner, body, variables)
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolve a type as written in a module to its qualified path
+    fn resolve_type(
+        &self,
+        type_text: &str,
+        module_path: &str,
+        self_type: Option<&str>,
+    ) -> Option<String> {
+        let segments = type_path_segments(type_text);
+        let (first, rest) = segments.split_first()?;
+
+        if first == "Self" && rest.is_empty() {
+            return self_type.map(String::from);
+        }
+
+        if rest.is_empty() {
+            let local = format!("{}::{}", module_path, first);
+            if self.graph.symbols.contains_key(&local) {
+                return Some(local);
+            }
+        }
+
+        self.resolver.resolve_symbol(&segments, self.source_path)
+    }
+}
+
+/// Name bound by a simple pattern (`name`, `mut name`, `&name`)
+fn binding_name(pattern: Node<'_>, content: &str) -> String {
+    node_text(pattern, content)
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim()
+        .to_string()
+}
+
+/// Type constructed by an initializer expression, as written
+///
+/// Recognizes struct literals (`Type { .. }`) and associated function calls
+/// on a type (`Type::new(..)`), optionally followed by `?` or `.unwrap()`.
+fn constructed_type<'c>(value: Node<'_>, content: &'c str) -> Option<&'c str> {
+    match value.kind() {
+        "struct_expression" => Some(node_text(value.child_by_field_name("name")?, content)),
+        "call_expression" => {
+            let function = value.child_by_field_name("function")?;
+            match function.kind() {
+                "scoped_identifier" => {
+                    let path = function.child_by_field_name("path")?;
+                    let type_name = node_text(path, content);
+                    let last = type_name.rsplit("::").next().unwrap_or(type_name);
+                    last.starts_with(char::is_uppercase).then_some(type_name)
+                }
+                // `Type::new(..).unwrap()` / `.expect(..)`
+                "field_expression" => {
+                    let method = node_text(function.child_by_field_name("field")?, content);
+                    if matches!(method, "unwrap" | "expect") {
+                        constructed_type(function.child_by_field_name("value")?, content)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            }
+        }
+        "try_expression" => constructed_type(value.named_child(0)?, content),
+        _ => None,
+    }
+}
@@ -0,0 +1,88 @@
+//! Persistent per-file symbol graphs
+//!
+//! This module saves the symbols and calls extracted from each file under
+//! `.zsei/metadata/symbol_graph.json` after every run. Incremental runs merge
+//! the saved graphs of unchanged files into the symbol graph instead of
+//! analyzing those files again, so calls made from unchanged files are kept
+//! without parsing the whole project. A saved graph is only reused while the
+//! file's content hash matches.
+//!
+//! Unlike the analysis cache, the store is not cleared by `--no-cache`; it
+//! describes the last analysis of each file rather than caching it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::analyzers::common::SymbolGraph;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+
+/// Symbols and calls of one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSymbols {
+    /// MD5 hash of the file content when its symbols were extracted
+    pub content_hash: String,
+
+    /// Symbols defined in the file and the calls they make, including calls
+    /// into other files that were not resolved yet
+    pub symbols: SymbolGraph,
+}
+
+/// On-disk store of per-file symbol graphs
+#[derive(Debug, Clone)]
+pub struct SymbolStore {
+    /// Path of the store file
+    path: PathBuf,
+}
+
+impl SymbolStore {
+    /// Create a store in the project's metadata directory
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            path: config.metadata_path()?.join("symbol_graph.json"),
+        })
+    }
+
+    /// Load the saved symbols by file
+    ///
+    /// A missing or unreadable store yields no files.
+    pub fn load(&self) -> HashMap<PathBuf, FileSymbols> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return HashMap::new();
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(files) => files,
+            Err(e) => {
+                debug!("Discarding unreadable symbol store: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Save the symbols by file, dropping files that no longer exist
+    pub fn save(&self, files: &HashMap<PathBuf, FileSymbols>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                ZseiError::Analyzer(format!("Failed to create metadata directory: {}", e))
+            })?;
+        }
+
+        let existing: HashMap<&PathBuf, &FileSymbols> = files
+            .iter()
+            .filter(|(path, _)| Path::new(path).is_file())
+            .collect();
+        let content = serde_json::to_string(&existing)
+            .map_err(|e| ZseiError::Analyzer(format!("Failed to serialize symbols: {}", e)))?;
+
+        // Write to a temporary file first so a crash never leaves a
+        // partially written store
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, content)
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .map_err(|e| ZseiError::Analyzer(format!("Failed to write symbol store: {}", e)))
+    }
+}
@@ -0,0 +1,1607 @@
+//! TypeScript and JavaScript code analyzer
+//!
+//! This module provides an analyzer for TypeScript, JavaScript, TSX and JSX
+//! files. It extracts functions (including arrow functions bound to names),
+//! classes, interfaces, variables, imports and exports using tree-sitter, and
+//! resolves ES module and CommonJS imports to files using Node resolution
+//! rules together with `tsconfig.json` `baseUrl` and `paths`.
+
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use crate::analyzers::common::{
+    class_cohesion, count_branch_points, indentation_cognitive_complexity, iter_tree,
+    maintainability_index, node_text, Class, ClassMetrics, CodeMetrics, Dependency, DependencyType,
+    FileAnalysis, FileReport, Function, FunctionMetrics, Import, Parameter, SymbolGraph, Variable,
+};
+use crate::analyzers::LanguageAnalyzer;
+use crate::core::config::Config;
+use crate::errors::{Result, ZseiError};
+use crate::utils::fs::normalize_path;
+
+/// Extensions tried, in order, when resolving an extensionless specifier
+const RESOLVE_EXTENSIONS: &[&str] = &[
+    ".ts", ".tsx", ".d.ts", ".mts", ".cts", ".js", ".jsx", ".mjs", ".cjs", ".json",
+];
+
+/// Patterns that create branches in JavaScript and TypeScript code
+const BRANCH_PATTERNS: &[&str] = &[
+    "if (", "if(", "for (", "for(", "while (", "while(", "case ", "catch", "&&", "||", "??",
+    "return", "break", "continue",
+];
+
+/// Statements that add to cognitive complexity in JavaScript and TypeScript code
+const COGNITIVE_PATTERNS: &[&str] = &[
+    "if (", "if(", "} else", "else", "for (", "for(", "while", "switch", "case ", "try", "} catch",
+    "catch",
+];
+
+/// Node kinds whose descendants are still considered top-level declarations
+const TOP_LEVEL_CONTAINERS: &[&str] = &[
+    "program",
+    "export_statement",
+    "lexical_declaration",
+    "variable_declaration",
+    "variable_declarator",
+    "ambient_declaration",
+];
+
+/// TypeScript/JavaScript analyzer implementation providing structural
+/// analysis and module resolution for JS/TS/TSX/JSX files using tree-sitter.
+pub struct TypeScriptAnalyzer {
+    /// Configuration options
+    config: Arc<Config>,
+
+    /// Nearest `tsconfig.json`/`jsconfig.json` per directory
+    tsconfig_cache: Mutex<HashMap<PathBuf, Option<Arc<TsConfig>>>>,
+}
+
+/// Module resolution settings from a `tsconfig.json` or `jsconfig.json`
+#[derive(Debug, Clone, Default)]
+struct TsConfig {
+    /// Resolved `compilerOptions.baseUrl`
+    base_url: Option<PathBuf>,
+
+    /// `compilerOptions.paths` mappings, most specific pattern first
+    paths: Vec<(String, Vec<String>)>,
+
+    /// Directory `paths` targets are relative to: the `baseUrl` in effect in
+    /// the config declaring `paths`, or that config's directory
+    paths_base: PathBuf,
+}
+
+/// An import or require site as written in the source
+#[derive(Debug, Clone)]
+struct ImportSite {
+    /// Module specifier
+    specifier: String,
+
+    /// Imported name, if the import binds one: the exported name for named
+    /// imports, `*` for namespace imports, or the local name otherwise
+    name: Option<String>,
+
+    /// Local name given with `as`
+    alias: Option<String>,
+
+    /// Line number
+    line: usize,
+
+    /// Kind of import, used for dependency information
+    kind: ImportKind,
+}
+
+impl ImportSite {
+    /// Local name bound by the import, if any
+    fn binding(&self) -> Option<&str> {
+        self.alias.as_deref().or(self.name.as_deref())
+    }
+}
+
+/// Kind of import site
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportKind {
+    /// `import ... from "x"`
+    EsModule,
+
+    /// `require("x")`
+    CommonJs,
+
+    /// `import("x")`
+    Dynamic,
+
+    /// `export ... from "x"`
+    ReExport,
+}
+
+impl TypeScriptAnalyzer {
+    /// Create a new TypeScript/JavaScript analyzer with the given configuration.
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            tsconfig_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the tree-sitter language and display name for a file
+    fn language_for(&self, path: &Path) -> (Language, &'static str) {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ts") | Some("mts") | Some("cts") => (
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+                "TypeScript",
+            ),
+            Some("tsx") => (tree_sitter_typescript::LANGUAGE_TSX.into(), "TypeScript"),
+            _ => (tree_sitter_javascript::LANGUAGE.into(), "JavaScript"),
+        }
+    }
+
+    /// Parse file content into a syntax tree
+    fn parse(&self, path: &Path, content: &str) -> Result<Tree> {
+        let (language, _) = self.language_for(path);
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language)
+            .expect("Failed to set TypeScript/JavaScript language");
+
+        parser
+            .parse(content, None)
+            .ok_or_else(|| ZseiError::Analyzer(format!("Failed to parse file {}", path.display())))
+    }
+
+    /// Read a file into a string
+    fn read_file(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Build a file analysis from a parsed syntax tree
+    fn analyze_tree(&self, path: &Path, tree: &Tree, content: &str) -> FileAnalysis {
+        let root = tree.root_node();
+        let (_, language) = self.language_for(path);
+
+        let exported_names = self.exported_names(root, content);
+
+        let mut functions = Vec::new();
+        let mut classes = Vec::new();
+        let mut variables = Vec::new();
+
+        // Class nodes already built, so a named class expression bound to a
+        // variable is not built again under its own name
+        let mut class_nodes = HashSet::new();
+
+        for node in iter_tree(root) {
+            if !self.is_top_level(node) {
+                continue;
+            }
+
+            match node.kind() {
+                "function_declaration" | "generator_function_declaration" => {
+                    let mut function = self.build_function(node, None, content);
+                    function.is_public |= self.is_exported(node, &exported_names, &function.name);
+                    functions.push(function);
+                }
+                "class_declaration" | "abstract_class_declaration" | "class" => {
+                    // Class expressions bound to a variable were built with it
+                    if !class_nodes.insert(node.id()) {
+                        continue;
+                    }
+
+                    // Other anonymous classes only declare something as the
+                    // default export
+                    let anonymous = node.child_by_field_name("name").is_none();
+                    let default_export = node.parent().map_or(false, |parent| {
+                        parent.kind() == "export_statement" && has_keyword_child(parent, "default")
+                    });
+                    if anonymous && !default_export {
+                        continue;
+                    }
+
+                    let mut class = self.build_class(node, content);
+                    if anonymous {
+                        class.name = "default".to_string();
+                    }
+                    class.is_public |= self.is_exported(node, &exported_names, &class.name);
+                    classes.push(class);
+                }
+                "interface_declaration" => {
+                    let mut interface = self.build_interface(node, content);
+                    interface.is_public |= self.is_exported(node, &exported_names, &interface.name);
+                    classes.push(interface);
+                }
+                "variable_declarator" => {
+                    let Some(name_node) = node.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = node_text(name_node, content).to_string();
+                    let exported = self.is_exported(node, &exported_names, &name);
+
+                    match node.child_by_field_name("value") {
+                        Some(value) if is_function_value(value) => {
+                            let mut function = self.build_function(value, Some(&name), content);
+                            function.is_public = exported;
+                            functions.push(function);
+                        }
+                        Some(value) if value.kind() == "class" => {
+                            class_nodes.insert(value.id());
+                            let mut class = self.build_class(value, content);
+                            class.name = name;
+                            class.is_public = exported;
+                            classes.push(class);
+                        }
+                        value => variables.push(Variable {
+                            name,
+                            var_type: node
+                                .child_by_field_name("type")
+                                .map(|t| type_annotation_text(t, content)),
+                            line: node.start_position().row,
+                            is_public: exported,
+                            init_value: value.map(|v| node_text(v, content).to_string()),
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let imports: Vec<Import> = self
+            .extract_import_sites(root, content)
+            .into_iter()
+            .map(|site| Import {
+                is_relative: site.specifier.starts_with('.'),
+                external_crate: None,
+                path: site.specifier,
+                name: site.name,
+                alias: site.alias,
+                line: site.line,
+            })
+            .collect();
+
+        // Calculate metrics
+        let loc = content.lines().count();
+        let comment_lines = content
+            .lines()
+            .map(str::trim_start)
+            .filter(|l| l.starts_with("//") || l.starts_with("/*") || l.starts_with('*'))
+            .count();
+
+        let complexity = functions
+            .iter()
+            .chain(classes.iter().flat_map(|c| c.methods.iter()))
+            .map(|f| f.metrics.complexity)
+            .sum();
+
+        let metrics = CodeMetrics {
+            loc,
+            comment_lines,
+            function_count: functions.len(),
+            class_count: classes.len(),
+            import_count: imports.len(),
+            variable_count: variables.len(),
+            complexity,
+            maintainability_index: maintainability_index(loc, comment_lines, complexity),
+        };
+
+        debug!(
+            "Analysis complete: {} functions, {} classes, {} imports, {} variables",
+            functions.len(),
+            classes.len(),
+            imports.len(),
+            variables.len()
+        );
+
+        FileAnalysis {
+            path: path.to_path_buf(),
+            language: language.to_string(),
+            content: Some(content.to_string()),
+            functions,
+            classes,
+            variables,
+            imports,
+            metrics,
+        }
+    }
+
+    /// Check whether a node is a top-level declaration
+    fn is_top_level(&self, node: Node<'_>) -> bool {
+        let mut current = node.parent();
+
+        while let Some(parent) = current {
+            if parent.kind() == "program" {
+                return true;
+            }
+            if !TOP_LEVEL_CONTAINERS.contains(&parent.kind()) {
+                return false;
+            }
+            current = parent.parent();
+        }
+
+        false
+    }
+
+    /// Collect names exported through `export { a, b as c }` clauses
+    fn exported_names(&self, root: Node<'_>, content: &str) -> HashSet<String> {
+        let mut names = HashSet::new();
+
+        for node in iter_tree(root) {
+            // Re-exports (`export { a } from "x"`) do not export local items
+            if node.kind() != "export_specifier" {
+                continue;
+            }
+
+            let statement = node.parent().and_then(|clause| clause.parent());
+            if statement.map_or(false, |s| s.child_by_field_name("source").is_some()) {
+                continue;
+            }
+
+            if let Some(name) = node.child_by_field_name("name") {
+                names.insert(node_text(name, content).to_string());
+            }
+        }
+
+        // `export default foo;`
+        for node in iter_tree(root) {
+            if node.kind() == "export_statement" {
+                if let Some(value) = node.child_by_field_name("value") {
+                    if value.kind() == "identifier" {
+                        names.insert(node_text(value, content).to_string());
+                    }
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Check whether a declaration is exported
+    fn is_exported(&self, node: Node<'_>, exported_names: &HashSet<String>, name: &str) -> bool {
+        if exported_names.contains(name) {
+            return true;
+        }
+
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            match parent.kind() {
+                "export_statement" => return true,
+                "program" => return false,
+                _ => current = parent.parent(),
+            }
+        }
+
+        false
+    }
+
+    /// Build a function from a function-like node
+    ///
+    /// `name` overrides the node's own name, for arrow functions and function
+    /// expressions bound to a variable.
+    fn build_function(&self, node: Node<'_>, name: Option<&str>, content: &str) -> Function {
+        let name = name.map(String::from).unwrap_or_else(|| {
+            node.child_by_field_name("name")
+                .map(|n| node_text(n, content).to_string())
+                .unwrap_or_else(|| "<anonymous>".to_string())
+        });
+
+        // Arrow functions with a single bare parameter use the `parameter` field
+        let parameters = match node.child_by_field_name("parameters") {
+            Some(params) => self.extract_parameters(params, content),
+            None => node
+                .child_by_field_name("parameter")
+                .map(|p| {
+                    vec![Parameter {
+                        name: node_text(p, content).to_string(),
+                        param_type: None,
+                        default_value: None,
+                        position: 0,
+                    }]
+                })
+                .unwrap_or_default(),
+        };
+
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| type_annotation_text(t, content));
+
+        let body_node = node.child_by_field_name("body");
+        let body = body_node.map(|b| node_text(b, content).to_string());
+
+        let signature_end = body_node.map_or(node.end_byte(), |b| b.start_byte());
+        let signature = content[node.start_byte()..signature_end]
+            .trim_end()
+            .trim_end_matches("=>")
+            .trim_end()
+            .to_string();
+
+        let body_text = body.as_deref().unwrap_or("");
+        let metrics = FunctionMetrics {
+            loc: body_text.lines().count(),
+            complexity: count_branch_points(body_text, BRANCH_PATTERNS),
+            parameter_count: parameters.len(),
+            cognitive_complexity: indentation_cognitive_complexity(body_text, COGNITIVE_PATTERNS),
+        };
+
+        Function {
+            is_public: false,
+            name,
+            signature,
+            start_line: node.start_position().row,
+            end_line: node.end_position().row,
+            body,
+            return_type,
+            parameters,
+            metrics,
+        }
+    }
+
+    /// Extract parameters from a `formal_parameters` node
+    fn extract_parameters(&self, params_node: Node<'_>, content: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let (name, param_type, default_value) = match child.kind() {
+                // TypeScript parameters
+                "required_parameter" | "optional_parameter" => {
+                    let name = child
+                        .child_by_field_name("pattern")
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default();
+                    let name = if child.kind() == "optional_parameter" {
+                        format!("{}?", name)
+                    } else {
+                        name
+                    };
+                    let param_type = child
+                        .child_by_field_name("type")
+                        .map(|t| type_annotation_text(t, content));
+                    let default_value = child
+                        .child_by_field_name("value")
+                        .map(|v| node_text(v, content).to_string());
+                    (name, param_type, default_value)
+                }
+                // JavaScript parameters
+                "assignment_pattern" => {
+                    let name = child
+                        .child_by_field_name("left")
+                        .map(|n| node_text(n, content).to_string())
+                        .unwrap_or_default();
+                    let default_value = child
+                        .child_by_field_name("right")
+                        .map(|v| node_text(v, content).to_string());
+                    (name, None, default_value)
+                }
+                "identifier" | "rest_pattern" | "object_pattern" | "array_pattern" => {
+                    (node_text(child, content).to_string(), None, None)
+                }
+                _ => continue,
+            };
+
+            if name.is_empty() {
+                continue;
+            }
+
+            parameters.push(Parameter {
+                name,
+                param_type,
+                default_value,
+                position: parameters.len(),
+            });
+        }
+
+        parameters
+    }
+
+    /// Build a class from a class declaration or expression
+    fn build_class(&self, node: Node<'_>, content: &str) -> Class {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_else(|| "<anonymous>".to_string());
+
+        let base_classes = self.heritage(node, content);
+
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                match member.kind() {
+                    "method_definition" | "abstract_method_signature" | "method_signature" => {
+                        let mut method = self.build_function(member, None, content);
+                        method.is_public = member_is_public(member, &method.name, content);
+
+                        // Getters are exposed as properties
+                        if has_keyword_child(member, "get") {
+                            properties.push(Variable {
+                                name: method.name.clone(),
+                                var_type: method.return_type.clone(),
+                                line: method.start_line,
+                                is_public: method.is_public,
+                                init_value: None,
+                            });
+                        } else if !has_keyword_child(member, "set") {
+                            methods.push(method);
+                        }
+                    }
+                    "field_definition" | "public_field_definition" => {
+                        let name_node = member
+                            .child_by_field_name("name")
+                            .or_else(|| member.child_by_field_name("property"));
+                        let Some(name_node) = name_node else {
+                            continue;
+                        };
+                        let name = node_text(name_node, content).to_string();
+
+                        let value = member.child_by_field_name("value");
+                        if value.map_or(false, is_function_value) {
+                            // Arrow function class fields behave like methods
+                            let mut method =
+                                self.build_function(value.unwrap(), Some(&name), content);
+                            method.is_public = member_is_public(member, &name, content);
+                            methods.push(method);
+                            continue;
+                        }
+
+                        properties.push(Variable {
+                            is_public: member_is_public(member, &name, content),
+                            name,
+                            var_type: member
+                                .child_by_field_name("type")
+                                .map(|t| type_annotation_text(t, content)),
+                            line: member.start_position().row,
+                            init_value: value.map(|v| node_text(v, content).to_string()),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // TypeScript parameter properties (`constructor(private readonly x: T)`)
+        for method in methods.iter().filter(|m| m.name == "constructor") {
+            for parameter in &method.parameters {
+                let declared = method
+                    .signature
+                    .contains(&format!("private {}", parameter.name))
+                    || method
+                        .signature
+                        .contains(&format!("public {}", parameter.name))
+                    || method
+                        .signature
+                        .contains(&format!("protected {}", parameter.name))
+                    || method
+                        .signature
+                        .contains(&format!("readonly {}", parameter.name));
+
+                if declared
+                    && !properties
+                        .iter()
+                        .any(|p: &Variable| p.name == parameter.name)
+                {
+                    properties.push(Variable {
+                        name: parameter.name.clone(),
+                        var_type: parameter.param_type.clone(),
+                        line: method.start_line,
+                        is_public: method
+                            .signature
+                            .contains(&format!("public {}", parameter.name)),
+                        init_value: None,
+                    });
+                }
+            }
+        }
+
+        let start_line = node.start_position().row;
+        let end_line = node.end_position().row;
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            inheritance_depth: usize::from(node_has_extends(node)),
+            cohesion: class_cohesion(&methods, &properties, "this."),
+        };
+
+        Class {
+            name,
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            is_public: false,
+            metrics,
+        }
+    }
+
+    /// Build a class entry from a TypeScript `interface_declaration`
+    fn build_interface(&self, node: Node<'_>, content: &str) -> Class {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| node_text(n, content).to_string())
+            .unwrap_or_default();
+
+        let mut base_classes = Vec::new();
+        let mut methods = Vec::new();
+        let mut properties = Vec::new();
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "extends_type_clause" => {
+                    let mut type_cursor = child.walk();
+                    for base in child.named_children(&mut type_cursor) {
+                        base_classes.push(node_text(base, content).to_string());
+                    }
+                }
+                "interface_body" | "object_type" => {
+                    let mut member_cursor = child.walk();
+                    for member in child.named_children(&mut member_cursor) {
+                        match member.kind() {
+                            "method_signature" => {
+                                let mut method = self.build_function(member, None, content);
+                                method.is_public = true;
+                                methods.push(method);
+                            }
+                            "property_signature" => {
+                                let Some(name_node) = member.child_by_field_name("name") else {
+                                    continue;
+                                };
+                                properties.push(Variable {
+                                    name: node_text(name_node, content).to_string(),
+                                    var_type: member
+                                        .child_by_field_name("type")
+                                        .map(|t| type_annotation_text(t, content)),
+                                    line: member.start_position().row,
+                                    is_public: true,
+                                    init_value: None,
+                                });
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let start_line = node.start_position().row;
+        let end_line = node.end_position().row;
+
+        let metrics = ClassMetrics {
+            loc: end_line - start_line + 1,
+            method_count: methods.len(),
+            property_count: properties.len(),
+            inheritance_depth: usize::from(!base_classes.is_empty()),
+            // Interfaces have no bodies to measure cohesion from
+            cohesion: 1.0,
+        };
+
+        Class {
+            name,
+            start_line,
+            end_line,
+            methods,
+            properties,
+            base_classes,
+            is_public: false,
+            metrics,
+        }
+    }
+
+    /// Extract `extends` and `implements` targets of a class
+    fn heritage(&self, class_node: Node<'_>, content: &str) -> Vec<String> {
+        let mut bases = Vec::new();
+
+        let mut cursor = class_node.walk();
+        for child in class_node.named_children(&mut cursor) {
+            if child.kind() != "class_heritage" {
+                continue;
+            }
+
+            let mut heritage_cursor = child.walk();
+            for clause in child.named_children(&mut heritage_cursor) {
+                match clause.kind() {
+                    // TypeScript: extends_clause / implements_clause
+                    "extends_clause" | "implements_clause" => {
+                        let mut clause_cursor = clause.walk();
+                        for base in clause.named_children(&mut clause_cursor) {
+                            if base.kind() != "type_arguments" {
+                                bases.push(node_text(base, content).to_string());
+                            }
+                        }
+                    }
+                    // JavaScript: the heritage holds the extended expression directly
+                    _ => bases.push(node_text(clause, content).to_string()),
+                }
+            }
+        }
+
+        bases
+    }
+
+    /// Extract all import, require, dynamic import and re-export sites
+    fn extract_import_sites(&self, root: Node<'_>, content: &str) -> Vec<ImportSite> {
+        let mut sites = Vec::new();
+
+        for node in iter_tree(root) {
+            match node.kind() {
+                "import_statement" => {
+                    let Some(source) = node.child_by_field_name("source") else {
+                        continue;
+                    };
+                    let specifier = string_literal_value(source, content);
+                    let line = node.start_position().row;
+
+                    let bindings = self.import_bindings(node, content);
+                    if bindings.is_empty() {
+                        // Side-effect import
+                        sites.push(ImportSite {
+                            specifier,
+                            name: None,
+                            alias: None,
+                            line,
+                            kind: ImportKind::EsModule,
+                        });
+                    } else {
+                        for (name, alias) in bindings {
+                            sites.push(ImportSite {
+                                specifier: specifier.clone(),
+                                name: Some(name),
+                                alias,
+                                line,
+                                kind: ImportKind::EsModule,
+                            });
+                        }
+                    }
+                }
+                "export_statement" => {
+                    if let Some(source) = node.child_by_field_name("source") {
+                        sites.push(ImportSite {
+                            specifier: string_literal_value(source, content),
+                            name: None,
+                            alias: None,
+                            line: node.start_position().row,
+                            kind: ImportKind::ReExport,
+                        });
+                    }
+                }
+                "call_expression" => {
+                    let Some(function) = node.child_by_field_name("function") else {
+                        continue;
+                    };
+
+                    let kind = match (function.kind(), node_text(function, content)) {
+                        ("identifier", "require") => ImportKind::CommonJs,
+                        ("import", _) => ImportKind::Dynamic,
+                        _ => continue,
+                    };
+
+                    let argument = node
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .filter(|arg| arg.kind() == "string");
+
+                    let Some(argument) = argument else {
+                        continue;
+                    };
+
+                    // `const x = require("y")` binds `x`
+                    let name = node
+                        .parent()
+                        .filter(|p| p.kind() == "variable_declarator")
+                        .and_then(|p| p.child_by_field_name("name"))
+                        .map(|n| node_text(n, content).to_string());
+
+                    sites.push(ImportSite {
+                        specifier: string_literal_value(argument, content),
+                        name,
+                        alias: None,
+                        line: node.start_position().row,
+                        kind,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        sites
+    }
+
+    /// Names imported by an `import` statement, with their aliases
+    fn import_bindings(
+        &self,
+        import_node: Node<'_>,
+        content: &str,
+    ) -> Vec<(String, Option<String>)> {
+        let mut bindings = Vec::new();
+
+        let mut cursor = import_node.walk();
+        for clause in import_node.named_children(&mut cursor) {
+            if clause.kind() != "import_clause" {
+                continue;
+            }
+
+            let mut clause_cursor = clause.walk();
+            for part in clause.named_children(&mut clause_cursor) {
+                match part.kind() {
+                    // Default import
+                    "identifier" => bindings.push((node_text(part, content).to_string(), None)),
+                    // `* as ns`
+                    "namespace_import" => {
+                        if let Some(name) = part.named_child(0) {
+                            let alias = node_text(name, content).to_string();
+                            bindings.push(("*".to_string(), Some(alias)));
+                        }
+                    }
+                    // `{ a, b as c }`
+                    "named_imports" => {
+                        let mut spec_cursor = part.walk();
+                        for spec in part.named_children(&mut spec_cursor) {
+                            if let Some(name) = spec.child_by_field_name("name") {
+                                let alias = spec
+                                    .child_by_field_name("alias")
+                                    .map(|a| node_text(a, content).to_string());
+                                bindings.push((node_text(name, content).to_string(), alias));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        bindings
+    }
+
+    /// Resolve a module specifier to a file using Node resolution rules
+    ///
+    /// Relative and absolute specifiers are resolved against the importing
+    /// file. Bare specifiers go through `tsconfig.json` `paths` and `baseUrl`
+    /// first, then `node_modules`. Packages installed from a registry resolve
+    /// to `None`; only workspace packages linked into `node_modules` are
+    /// followed to their sources.
+    fn resolve_specifier(
+        &self,
+        specifier: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Option<PathBuf> {
+        let source_dir = source_path.parent()?;
+
+        if specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || specifier == "."
+            || specifier == ".."
+            || specifier.starts_with('/')
+        {
+            let base = normalize_path(&source_dir.join(specifier));
+            return resolve_as_file(&base).or_else(|| resolve_as_directory(&base));
+        }
+
+        // Node built-ins and URL imports are never project files
+        if specifier.starts_with("node:") || specifier.contains("://") {
+            return None;
+        }
+
+        if let Some(tsconfig) = self.tsconfig_for(source_dir, project_root) {
+            if let Some(path) = tsconfig.resolve(specifier) {
+                return Some(path);
+            }
+        }
+
+        resolve_node_modules(specifier, source_dir)
+    }
+
+    /// Find the nearest `tsconfig.json` or `jsconfig.json` for a directory
+    fn tsconfig_for(&self, dir: &Path, project_root: &Path) -> Option<Arc<TsConfig>> {
+        if let Some(cached) = self.tsconfig_cache.lock().ok()?.get(dir) {
+            return cached.clone();
+        }
+
+        let mut current = Some(dir);
+        let mut found = None;
+
+        while let Some(candidate_dir) = current {
+            for file_name in ["tsconfig.json", "jsconfig.json"] {
+                let candidate = candidate_dir.join(file_name);
+                if candidate.is_file() {
+                    match TsConfig::load(&candidate, 0) {
+                        Ok(config) => found = Some(Arc::new(config)),
+                        Err(e) => debug!("Ignoring {}: {}", candidate.display(), e),
+                    }
+                    break;
+                }
+            }
+
+            if found.is_some() || candidate_dir == project_root {
+                break;
+            }
+
+            current = candidate_dir
+                .parent()
+                .filter(|parent| parent.starts_with(project_root));
+        }
+
+        if let Ok(mut cache) = self.tsconfig_cache.lock() {
+            cache.insert(dir.to_path_buf(), found.clone());
+        }
+
+        found
+    }
+
+    /// Extract dependencies from a parsed syntax tree
+    fn extract_dependencies_from_tree(
+        &self,
+        tree: &Tree,
+        content: &str,
+        source_path: &Path,
+        project_root: &Path,
+    ) -> Vec<Dependency> {
+        let root = tree.root_node();
+        let mut dependencies = Vec::new();
+
+        // Names bound by imports, used to resolve heritage clauses
+        let mut bindings: HashMap<String, PathBuf> = HashMap::new();
+
+        for site in self.extract_import_sites(root, content) {
+            let Some(target) = self.resolve_specifier(&site.specifier, source_path, project_root)
+            else {
+                debug!(
+                    "Could not resolve '{}' in {}",
+                    site.specifier,
+                    source_path.display()
+                );
+                continue;
+            };
+
+            if let Some(binding) = site.binding() {
+                bindings.insert(binding.to_string(), target.clone());
+            }
+
+            let info = match site.kind {
+                ImportKind::EsModule => format!("Import: {}", site.specifier),
+                ImportKind::CommonJs => format!("Require: {}", site.specifier),
+                ImportKind::Dynamic => format!("Dynamic import: {}", site.specifier),
+                ImportKind::ReExport => format!("Re-export: {}", site.specifier),
+            };
+
+            dependencies.push(Dependency {
+                source: source_path.to_path_buf(),
+                target,
+                dependency_type: DependencyType::Import,
+                line: Some(site.line),
+                info: Some(info),
+            });
+        }
+
+        // Inheritance and interface implementation across files
+        for node in iter_tree(root) {
+            if !matches!(
+                node.kind(),
+                "class_declaration" | "abstract_class_declaration" | "class"
+            ) {
+                continue;
+            }
+
+            let class_name = node
+                .child_by_field_name("name")
+                .map(|n| node_text(n, content).to_string())
+                .unwrap_or_else(|| "<anonymous>".to_string());
+
+            let mut cursor = node.walk();
+            for heritage in node.named_children(&mut cursor) {
+                if heritage.kind() != "class_heritage" {
+                    continue;
+                }
+
+                let mut heritage_cursor = heritage.walk();
+                for clause in heritage.named_children(&mut heritage_cursor) {
+                    let (dependency_type, bases): (DependencyType, Vec<Node<'_>>) =
+                        match clause.kind() {
+                            "implements_clause" => {
+                                let mut c = clause.walk();
+                                (
+                                    DependencyType::Implementation,
+                                    clause.named_children(&mut c).collect(),
+                                )
+                            }
+                            "extends_clause" => {
+                                let mut c = clause.walk();
+                                (
+                                    DependencyType::Inheritance,
+                                    clause.named_children(&mut c).collect(),
+                                )
+                            }
+                            _ => (DependencyType::Inheritance, vec![clause]),
+                        };
+
+                    for base in bases {
+                        let base_text = node_text(base, content);
+                        let head = base_text
+                            .split(|c: char| c == '.' || c == '<')
+                            .next()
+                            .unwrap_or(base_text);
+
+                        if let Some(target) = bindings.get(head) {
+                            dependencies.push(Dependency {
+                                source: source_path.to_path_buf(),
+                                target: target.clone(),
+                                dependency_type: dependency_type.clone(),
+                                line: Some(base.start_position().row),
+                                info: Some(format!(
+                                    "{}: {} -> {}",
+                                    clause.kind(),
+                                    class_name,
+                                    base_text
+                                )),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        dependencies
+    }
+}
+
+impl TsConfig {
+    /// Load a config file, following relative `extends` chains
+    fn load(path: &Path, depth: usize) -> Result<Self> {
+        let raw = fs::read_to_string(path).map_err(|e| {
+            ZseiError::Analyzer(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(&strip_json_comments(&raw)).map_err(|e| {
+                ZseiError::Analyzer(format!("Failed to parse {}: {}", path.display(), e))
+            })?;
+
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        // Start from the extended config, if it is a relative file reference
+        let mut config = match value.get("extends").and_then(|e| e.as_str()) {
+            Some(extends) if extends.starts_with('.') && depth < 8 => {
+                // `./tsconfig.base` names `./tsconfig.base.json`
+                let mut base_path = dir.join(extends);
+                if !extends.ends_with(".json") {
+                    base_path = append_extension(&base_path, ".json");
+                }
+                TsConfig::load(&normalize_path(&base_path), depth + 1).unwrap_or_default()
+            }
+            _ => TsConfig::default(),
+        };
+
+        if let Some(options) = value.get("compilerOptions") {
+            if let Some(base_url) = options.get("baseUrl").and_then(|b| b.as_str()) {
+                config.base_url = Some(normalize_path(&dir.join(base_url)));
+            }
+
+            if let Some(paths) = options.get("paths").and_then(|p| p.as_object()) {
+                config.paths = paths
+                    .iter()
+                    .map(|(pattern, targets)| {
+                        let targets = targets
+                            .as_array()
+                            .map(|t| {
+                                t.iter()
+                                    .filter_map(|t| t.as_str().map(String::from))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (pattern.clone(), targets)
+                    })
+                    .collect();
+                config
+                    .paths
+                    .sort_by_key(|(pattern, _)| std::cmp::Reverse(pattern_specificity(pattern)));
+
+                // Inherited paths stay relative to the config declaring them
+                config.paths_base = config.base_url.clone().unwrap_or_else(|| dir.clone());
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Resolve a bare specifier through `paths` and `baseUrl`
+    fn resolve(&self, specifier: &str) -> Option<PathBuf> {
+        // Like tsc, only the most specific matching pattern is tried
+        let matched = self.paths.iter().find_map(|(pattern, targets)| {
+            Some((match_path_pattern(pattern, specifier)?, targets))
+        });
+
+        if let Some((captured, targets)) = matched {
+            for target in targets {
+                let candidate =
+                    normalize_path(&self.paths_base.join(target.replace('*', captured)));
+                if let Some(path) =
+                    resolve_as_file(&candidate).or_else(|| resolve_as_directory(&candidate))
+                {
+                    return Some(path);
+                }
+            }
+        }
+
+        let base_url = self.base_url.as_ref()?;
+        let candidate = normalize_path(&base_url.join(specifier));
+        resolve_as_file(&candidate).or_else(|| resolve_as_directory(&candidate))
+    }
+}
+
+/// Rank a `paths` pattern: exact patterns first, then wildcard patterns by
+/// the length of their prefix
+fn pattern_specificity(pattern: &str) -> usize {
+    match pattern.split_once('*') {
+        Some((prefix, _)) => prefix.len(),
+        None => usize::MAX,
+    }
+}
+
+/// Match a `paths` pattern with at most one `*` against a specifier,
+/// returning the text matched by the wildcard
+fn match_path_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            if specifier.len() >= prefix.len() + suffix.len()
+                && specifier.starts_with(prefix)
+                && specifier.ends_with(suffix)
+            {
+                Some(&specifier[prefix.len()..specifier.len() - suffix.len()])
+            } else {
+                None
+            }
+        }
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+/// Resolve a path as a file, trying known extensions
+fn resolve_as_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+
+    for extension in RESOLVE_EXTENSIONS {
+        let candidate = append_extension(path, extension);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+
+    // TypeScript sources imported with their emitted `.js` extension
+    let replacements = [("js", "ts"), ("jsx", "tsx"), ("mjs", "mts"), ("cjs", "cts")];
+    let extension = path.extension().and_then(|e| e.to_str())?;
+    for (emitted, source) in replacements {
+        if extension == emitted {
+            let candidate = path.with_extension(source);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+/// Resolve a path as a directory via `package.json` entry points or `index` files
+fn resolve_as_directory(dir: &Path) -> Option<PathBuf> {
+    if !dir.is_dir() {
+        return None;
+    }
+
+    let package_json = dir.join("package.json");
+    if let Ok(raw) = fs::read_to_string(&package_json) {
+        if let Ok(package) = serde_json::from_str::<serde_json::Value>(&raw) {
+            for field in ["types", "typings", "module", "main"] {
+                if let Some(entry) = package.get(field).and_then(|e| e.as_str()) {
+                    let entry_path = normalize_path(&dir.join(entry));
+                    if let Some(path) = resolve_as_file(&entry_path).or_else(|| {
+                        // Avoid recursing into the same package.json
+                        (entry_path != dir)
+                            .then(|| resolve_index(&entry_path))
+                            .flatten()
+                    }) {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+    }
+
+    resolve_index(dir)
+}
+
+/// Resolve `index.*` within a directory
+fn resolve_index(dir: &Path) -> Option<PathBuf> {
+    RESOLVE_EXTENSIONS
+        .iter()
+        .map(|extension| append_extension(&dir.join("index"), extension))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Resolve a bare specifier through `node_modules` directories
+///
+/// Only packages whose real location is outside any `node_modules` directory
+/// (workspace packages linked by the package manager) are resolved.
+fn resolve_node_modules(specifier: &str, source_dir: &Path) -> Option<PathBuf> {
+    // Split "@scope/pkg/sub/path" or "pkg/sub/path" into package and subpath
+    let mut parts = specifier.splitn(if specifier.starts_with('@') { 3 } else { 2 }, '/');
+    let package_name = if specifier.starts_with('@') {
+        format!("{}/{}", parts.next()?, parts.next()?)
+    } else {
+        parts.next()?.to_string()
+    };
+    let subpath = parts.next();
+
+    let mut current = Some(source_dir);
+    while let Some(dir) = current {
+        let package_dir = dir.join("node_modules").join(&package_name);
+
+        if package_dir.exists() {
+            let real_dir = fs::canonicalize(&package_dir).ok()?;
+            if real_dir
+                .components()
+                .any(|c| c.as_os_str() == "node_modules")
+            {
+                return None;
+            }
+
+            return match subpath {
+                Some(sub) => {
+                    let target = real_dir.join(sub);
+                    resolve_as_file(&target).or_else(|| resolve_as_directory(&target))
+                }
+                None => resolve_as_directory(&real_dir),
+            };
+        }
+
+        current = dir.parent();
+    }
+
+    None
+}
+
+/// Append an extension to a path without replacing an existing one
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut os_string: OsString = path.as_os_str().to_owned();
+    os_string.push(extension);
+    PathBuf::from(os_string)
+}
+
+/// Strip `//` and `/* */` comments and trailing commas from JSON text
+///
+/// `tsconfig.json` files are JSONC, which `serde_json` does not accept. The
+/// text is scanned once; a comma is held back until the next significant
+/// character shows whether it is trailing.
+fn strip_json_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    // Whitespace following a held comma
+    let mut pending_comma: Option<String> = None;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (c, _) if c.is_whitespace() && pending_comma.is_some() => {
+                if let Some(whitespace) = &mut pending_comma {
+                    whitespace.push(c);
+                }
+            }
+            _ => {
+                // Drop the held comma if this character closes a container
+                if let Some(whitespace) = pending_comma.take() {
+                    if c != '}' && c != ']' {
+                        output.push(',');
+                    }
+                    output.push_str(&whitespace);
+                }
+
+                match c {
+                    ',' => pending_comma = Some(String::new()),
+                    '"' => {
+                        in_string = true;
+                        output.push(c);
+                    }
+                    _ => output.push(c),
+                }
+            }
+        }
+    }
+
+    if let Some(whitespace) = pending_comma {
+        output.push(',');
+        output.push_str(&whitespace);
+    }
+
+    output
+}
+
+/// Get the value of a string literal node without quotes
+fn string_literal_value(node: Node<'_>, content: &str) -> String {
+    node_text(node, content)
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// Get a type annotation without its leading colon
+fn type_annotation_text(node: Node<'_>, content: &str) -> String {
+    node_text(node, content)
+        .trim_start_matches(':')
+        .trim()
+        .to_string()
+}
+
+/// Check whether a value node is a function
+fn is_function_value(node: Node<'_>) -> bool {
+    matches!(
+        node.kind(),
+        "arrow_function" | "function_expression" | "function" | "generator_function"
+    )
+}
+
+/// Check whether a node has an anonymous keyword child such as `get` or `static`
+fn has_keyword_child(node: Node<'_>, keyword: &str) -> bool {
+    let mut cursor = node.walk();
+    let result = node
+        .children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == keyword);
+    result
+}
+
+/// Check whether a class member is public
+///
+/// Members are public unless marked `private`/`protected` or named with a
+/// `#` private name.
+fn member_is_public(member: Node<'_>, name: &str, content: &str) -> bool {
+    if name.starts_with('#') {
+        return false;
+    }
+
+    let mut cursor = member.walk();
+    let result = member.children(&mut cursor).all(|child| {
+        child.kind() != "accessibility_modifier" || node_text(child, content) == "public"
+    });
+    result
+}
+
+/// Check whether a class node has an `extends` clause
+fn node_has_extends(class_node: Node<'_>) -> bool {
+    let mut cursor = class_node.walk();
+    let result = class_node
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "class_heritage")
+        .any(|heritage| {
+            let mut heritage_cursor = heritage.walk();
+            let has_extends = heritage
+                .named_children(&mut heritage_cursor)
+                .any(|clause| clause.kind() != "implements_clause");
+            has_extends
+        });
+    result
+}
+
+#[async_trait]
+impl LanguageAnalyzer for TypeScriptAnalyzer {
+    fn language_name(&self) -> &'static str {
+        "TypeScript"
+    }
+
+    fn supported_extensions(&self) -> &[&'static str] {
+        &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"]
+    }
+
+    async fn analyze_file(&self, path: &Path) -> Result<FileAnalysis> {
+        info!("Analyzing TypeScript/JavaScript file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        Ok(self.analyze_tree(path, &tree, &content))
+    }
+
+    async fn extract_dependencies(&self, path: &Path) -> Result<Vec<Dependency>> {
+        info!(
+            "Extracting dependencies from TypeScript/JavaScript file: {}",
+            path.display()
+        );
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(dependencies)
+    }
+
+    async fn analyze_file_with_dependencies(&self, path: &Path) -> Result<FileReport> {
+        info!("Analyzing TypeScript/JavaScript file: {}", path.display());
+        let content = self.read_file(path)?;
+        let tree = self.parse(path, &content)?;
+
+        let dependencies =
+            self.extract_dependencies_from_tree(&tree, &content, path, self.config.project_root());
+
+        debug!(
+            "Extracted {} dependencies from {}",
+            dependencies.len(),
+            path.display()
+        );
+
+        Ok(FileReport {
+            analysis: self.analyze_tree(path, &tree, &content),
+            dependencies,
+            symbols: SymbolGraph::default(),
+        })
+    }
+
+    fn reset_caches(&self) {
+        if let Ok(mut cache) = self.tsconfig_cache.lock() {
+            cache.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zsei-typescript-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file, creating its parent directories
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn imports_keep_name_and_alias_apart() {
+        let analyzer = TypeScriptAnalyzer::new(Arc::new(Config::default()));
+        let content = "import React, { useState as useLocal, useEffect } from 'react';\n\
+                       import * as path from 'path';\n\
+                       import './styles.css';\n\
+                       const fs = require('fs');\n";
+        let path = Path::new("app.ts");
+        let tree = analyzer.parse(path, content).unwrap();
+        let analysis = analyzer.analyze_tree(path, &tree, content);
+
+        let imports: Vec<(&str, Option<&str>, Option<&str>)> = analysis
+            .imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.name.as_deref(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("react", Some("React"), None),
+                ("react", Some("useState"), Some("useLocal")),
+                ("react", Some("useEffect"), None),
+                ("path", Some("*"), Some("path")),
+                ("./styles.css", None, None),
+                ("fs", Some("fs"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn most_specific_path_pattern_wins() {
+        let root = scratch_dir("specificity");
+        write(
+            &root.join("tsconfig.json"),
+            r#"{
+                // Broad patterns first, as they are often written
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": {
+                        "*": ["vendor/*"],
+                        "@app/*": ["src/app/*"],
+                        "@app/core/*": ["src/core/*"],
+                        "@app/core/config": ["src/config.ts"]
+                    }
+                }
+            }"#,
+        );
+        for file in ["src/core/log.ts", "src/app/core/log.ts", "src/config.ts"] {
+            write(&root.join(file), "export {};\n");
+        }
+
+        let config = TsConfig::load(&root.join("tsconfig.json"), 0).unwrap();
+        assert_eq!(
+            config.resolve("@app/core/log"),
+            Some(root.join("src/core/log.ts"))
+        );
+        assert_eq!(
+            config.resolve("@app/core/config"),
+            Some(root.join("src/config.ts"))
+        );
+        assert_eq!(config.resolve("@app/other"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn inherited_paths_resolve_against_declaring_config() {
+        let root = scratch_dir("extends");
+        write(
+            &root.join("config/tsconfig.base.json"),
+            r#"{ "compilerOptions": { "baseUrl": "..", "paths": { "@lib/*": ["lib/*"] } } }"#,
+        );
+        write(
+            &root.join("packages/web/tsconfig.json"),
+            r#"{ "extends": "../../config/tsconfig.base", "compilerOptions": { "baseUrl": "src" } }"#,
+        );
+        write(&root.join("lib/format.ts"), "export {};\n");
+        write(&root.join("packages/web/src/main.ts"), "export {};\n");
+
+        let config = TsConfig::load(&root.join("packages/web/tsconfig.json"), 0).unwrap();
+        assert_eq!(
+            config.resolve("@lib/format"),
+            Some(root.join("lib/format.ts"))
+        );
+        assert_eq!(
+            config.resolve("main"),
+            Some(root.join("packages/web/src/main.ts"))
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn json_comments_and_trailing_commas_are_stripped() {
+        let input = "{\n  // line\n  \"a\": \"http://x\", /* block */ \"b\": 1,\n}";
+        let value: serde_json::Value = serde_json::from_str(&strip_json_comments(input)).unwrap();
+        assert_eq!(value["a"], "http://x");
+        assert_eq!(value["b"], 1);
+    }
+}
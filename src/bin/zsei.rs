@@ -8,7 +8,7 @@ use color_eyre::eyre::{Result, WrapErr};
 use std::path::PathBuf;
 use tracing::info;
 use zsei::cli::commands::{Cli, Commands};
-use zsei::core::config::Config;
+use zsei::core::config::{ChangeDetection, Config};
 use zsei::llm::LlmFactory;
 use zsei::utils::logger;
 use zsei::Zsei;
@@ -116,6 +116,28 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Apply CLI-provided analysis worker count
+    let jobs = match &cli.command {
+        Commands::Analyze(args) => args.jobs,
+        Commands::Index(args) => args.jobs,
+        Commands::Watch(args) => args.jobs,
+        _ => None,
+    };
+    if let Some(jobs) = jobs {
+        config.analysis.workers = jobs;
+    }
+
+    // Apply CLI-provided git change detection
+    let (git, since) = match &cli.command {
+        Commands::Analyze(args) => (args.git, args.since.clone()),
+        Commands::Index(args) => (args.git, args.since.clone()),
+        _ => (false, None),
+    };
+    if git || since.is_some() {
+        config.analysis.change_detection = ChangeDetection::Git;
+        config.analysis.git_since = since;
+    }
+
     // Initialize ZSEI system with the configured LLMs
     let zsei = Zsei::new(config).await?;
 
@@ -133,6 +155,9 @@ async fn main() -> Result<()> {
         Commands::Index(args) => {
             cli_handler.handle_index(args).await?;
         }
+        Commands::Watch(args) => {
+            cli_handler.handle_watch(args).await?;
+        }
         Commands::Query(args) => {
             cli_handler.handle_query(args).await?;
         }
@@ -1,6 +1,12 @@
+use chrono::{DateTime, Utc};
 use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::core::config::SearchMode;
+use crate::indexing::search::{
+    SearchFilter, SearchRequest, DEFAULT_MAX_RESULTS, DEFAULT_MIN_SCORE,
+};
+
 /// Zero-Shot Bolted Embedding Indexer (ZSEI)
 ///
 /// A tool for analyzing, indexing, and optimizing code with AI assistance.
@@ -37,6 +43,9 @@ pub enum Commands {
     /// Index a project for searching
     Index(IndexArgs),
 
+    /// Watch the project and keep its index fresh
+    Watch(WatchArgs),
+
     /// Query the indexed project
     Query(QueryArgs),
 
@@ -85,6 +94,22 @@ pub struct AnalyzeArgs {
     /// Run incremental analysis only on changed files
     #[arg(short, long)]
     pub incremental: bool,
+
+    /// Detect changed files from git instead of content hashes
+    #[arg(long)]
+    pub git: bool,
+
+    /// Git revision to detect changes from (implies --incremental and --git)
+    #[arg(long, value_name = "REV")]
+    pub since: Option<String>,
+
+    /// Number of files to analyze in parallel (defaults to the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Discard cached file analyses and analyze every file again
+    #[arg(long)]
+    pub no_cache: bool,
 }
 
 /// Arguments for the index command
@@ -109,6 +134,46 @@ pub struct IndexArgs {
     /// Run incremental indexing only on changed files
     #[arg(short, long)]
     pub incremental: bool,
+
+    /// Detect changed files from git instead of content hashes
+    #[arg(long)]
+    pub git: bool,
+
+    /// Git revision to detect changes from (implies --incremental and --git)
+    #[arg(long, value_name = "REV")]
+    pub since: Option<String>,
+
+    /// Number of files to analyze in parallel (defaults to the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Discard cached file analyses and analyze every file again
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Measure the memory saved by quantization and its recall after indexing
+    #[arg(long)]
+    pub quantization_report: bool,
+
+    /// Check the saved index against its checksums instead of indexing
+    #[arg(long)]
+    pub verify: bool,
+}
+
+/// Arguments for the watch command
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Milliseconds without changes that end a batch
+    #[arg(short, long, value_name = "MS", default_value_t = 500)]
+    pub debounce: u64,
+
+    /// Print one JSON summary per batch instead of a report
+    #[arg(long)]
+    pub json: bool,
+
+    /// Number of files to analyze in parallel (defaults to the number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
 }
 
 /// Arguments for the query command
@@ -127,7 +192,7 @@ pub struct QueryArgs {
     pub file: Option<PathBuf>,
 
     /// Maximum number of results to return
-    #[arg(short, long, default_value = "10")]
+    #[arg(short, long, default_value_t = DEFAULT_MAX_RESULTS)]
     pub max_results: usize,
 
     /// Output file for the query results
@@ -137,6 +202,73 @@ pub struct QueryArgs {
     /// Maximum context size for the query (in tokens)
     #[arg(long, default_value = "100000")]
     pub context_size: usize,
+
+    /// Minimum similarity score of vector matches
+    #[arg(long, default_value_t = DEFAULT_MIN_SCORE)]
+    pub min_score: f32,
+
+    /// Search mode: vector, lexical or hybrid (defaults to the configured mode)
+    #[arg(long, value_name = "MODE")]
+    pub mode: Option<SearchMode>,
+
+    /// Only return files in this language (repeatable)
+    #[arg(long = "language", value_name = "LANGUAGE")]
+    pub languages: Vec<String>,
+
+    /// Only return files matching this glob pattern (repeatable)
+    #[arg(long = "path", value_name = "GLOB")]
+    pub paths: Vec<String>,
+
+    /// Only return files with at least this many lines of code
+    #[arg(long, value_name = "LINES")]
+    pub min_loc: Option<usize>,
+
+    /// Only return files with at most this many lines of code
+    #[arg(long, value_name = "LINES")]
+    pub max_loc: Option<usize>,
+
+    /// Only return files with at least this complexity
+    #[arg(long, value_name = "COMPLEXITY")]
+    pub min_complexity: Option<usize>,
+
+    /// Only return files with at most this complexity
+    #[arg(long, value_name = "COMPLEXITY")]
+    pub max_complexity: Option<usize>,
+
+    /// Only return files defining this function or method (repeatable)
+    #[arg(long = "defines", value_name = "FUNCTION")]
+    pub defines: Vec<String>,
+
+    /// Only return files indexed at or after this RFC 3339 time
+    #[arg(long, value_name = "TIME")]
+    pub indexed_after: Option<DateTime<Utc>>,
+
+    /// Only return files indexed at or before this RFC 3339 time
+    #[arg(long, value_name = "TIME")]
+    pub indexed_before: Option<DateTime<Utc>>,
+}
+
+impl QueryArgs {
+    /// Build the search request for a query from the limits and filters
+    pub fn search_request(&self, query: &str) -> SearchRequest {
+        SearchRequest {
+            max_results: self.max_results,
+            min_score: self.min_score,
+            mode: self.mode,
+            filter: SearchFilter {
+                languages: self.languages.clone(),
+                path_globs: self.paths.clone(),
+                min_loc: self.min_loc,
+                max_loc: self.max_loc,
+                min_complexity: self.min_complexity,
+                max_complexity: self.max_complexity,
+                defines_functions: self.defines.clone(),
+                indexed_after: self.indexed_after,
+                indexed_before: self.indexed_before,
+            },
+            ..SearchRequest::new(query)
+        }
+    }
 }
 
 /// Arguments for the refactor command
@@ -4,15 +4,18 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 use crate::analyzers::{Analyzer, ProgressUpdate};
-use crate::cli::commands::{AnalyzeArgs, IndexArgs, InitArgs, QueryArgs, RefactorArgs, RunArgs};
+use crate::cli::commands::{
+    AnalyzeArgs, IndexArgs, InitArgs, QueryArgs, RefactorArgs, RunArgs, WatchArgs,
+};
 use crate::cli::ui;
 use crate::core::config::Config;
 use crate::core::project::Project;
 use crate::errors::{Result, ZseiError};
+use crate::indexing::watch::IndexWatcher;
 use crate::indexing::Indexer;
 use crate::query::QueryEngine;
 use crate::refactor::RefactoringEngine;
@@ -85,8 +88,12 @@ pub async fn handle_analyze(
         tokio::spawn(ui::display_progress_bar(rx, "Analyzing".to_string()));
     }
 
+    if args.no_cache {
+        analyzer.clear_cache()?;
+    }
+
     // Run analysis
-    let result = if args.incremental {
+    let result = if args.incremental || args.since.is_some() {
         analyzer.analyze_incremental(&paths, progress_tx).await?
     } else {
         analyzer.analyze_full(&paths, progress_tx).await?
@@ -126,6 +133,16 @@ pub async fn handle_index(
     info!("Starting project indexing");
     debug!("Index args: {:?}", args);
 
+    if args.verify {
+        let index_path = match args.output {
+            Some(output_path) => output_path,
+            None => config.index_path()?,
+        };
+        indexer.verify(&index_path)?;
+        ui::display_success(&format!("Index verified: {}", index_path.display()));
+        return Ok(());
+    }
+
     let start_time = Instant::now();
 
     let paths = if args.paths.is_empty() {
@@ -146,12 +163,17 @@ pub async fn handle_index(
         tokio::spawn(ui::display_progress_bar(rx, "Indexing".to_string()));
     }
 
+    if args.no_cache {
+        indexer.clear_analysis_cache()?;
+    }
+
     // Run indexing
-    if args.incremental {
-        indexer.index_incremental(&paths, progress_tx).await?;
+    let prune_report = if args.incremental || args.since.is_some() {
+        Some(indexer.index_incremental(&paths, progress_tx).await?)
     } else {
         indexer.index_full(&paths, progress_tx).await?;
-    }
+        None
+    };
 
     // Calculate elapsed time
     let elapsed = start_time.elapsed();
@@ -171,6 +193,49 @@ pub async fn handle_index(
         ui::display_success(&format!("Index saved to: {}", index_path.display()));
     }
 
+    // Report what the incremental run pruned
+    if let Some(report) = prune_report {
+        ui::display_prune_report(&report);
+    }
+
+    // Report the memory saved by quantization, which runs extra searches
+    if args.quantization_report {
+        match indexer.quantization_report().await {
+            Some(report) => ui::display_quantization_report(&report),
+            None => ui::display_warning("Quantization is disabled, nothing to report"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Watch command handler
+pub async fn handle_watch(
+    args: WatchArgs,
+    config: Arc<Config>,
+    project: Arc<Project>,
+    indexer: Arc<Indexer>,
+) -> Result<()> {
+    ui::display_banner();
+    ui::display_phase_header("Watch Mode");
+
+    info!("Starting watch mode");
+    debug!("Watch args: {:?}", args);
+
+    let watcher = IndexWatcher::new(
+        indexer,
+        project,
+        config.index_path()?,
+        Duration::from_millis(args.debounce),
+    );
+
+    ui::display_info("Watching for changes, press Ctrl-C to stop");
+    watcher
+        .run(|batch| ui::display_watch_batch(batch, args.json))
+        .await?;
+
+    ui::display_success("Stopped watching");
+
     Ok(())
 }
 
@@ -186,9 +251,9 @@ pub async fn handle_query(
     debug!("Query args: {:?}", args);
 
     // Get query text
-    let query_text = if let Some(query) = args.query {
-        query
-    } else if let Some(file_path) = args.file {
+    let query_text = if let Some(query) = &args.query {
+        query.clone()
+    } else if let Some(file_path) = &args.file {
         std::fs::read_to_string(file_path)?
     } else if args.interactive {
         // Interactive mode
@@ -203,7 +268,7 @@ pub async fn handle_query(
 
     // Execute query
     let result = query_engine
-        .query(&query_text, args.max_results, args.context_size)
+        .query_with_request(args.search_request(&query_text), args.context_size)
         .await?;
 
     // Calculate elapsed time
@@ -214,8 +279,8 @@ pub async fn handle_query(
     ));
 
     // Output results
-    if let Some(output_path) = args.output {
-        result.save_to_file(&output_path)?;
+    if let Some(output_path) = &args.output {
+        result.save_to_file(output_path)?;
         ui::display_success(&format!(
             "Query results saved to: {}",
             output_path.display()
@@ -232,7 +297,7 @@ pub async fn handle_query(
 
             let start_time = Instant::now();
             let result = query_engine
-                .query(&query, args.max_results, args.context_size)
+                .query_with_request(args.search_request(&query), args.context_size)
                 .await?;
             let elapsed = start_time.elapsed();
 
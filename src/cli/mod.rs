@@ -5,6 +5,7 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, info};
 
@@ -16,6 +17,7 @@ use crate::analyzers::Analyzer;
 use crate::core::config::Config;
 use crate::core::project::Project;
 use crate::errors::Result;
+use crate::indexing::watch::IndexWatcher;
 use crate::indexing::Indexer;
 use crate::query::QueryEngine;
 use crate::refactor::RefactoringEngine;
@@ -111,8 +113,12 @@ impl CliHandler {
             tokio::spawn(ui::display_progress_bar(rx, "Analyzing".to_string()));
         }
 
+        if args.no_cache {
+            self.analyzer.clear_cache()?;
+        }
+
         // Run analysis
-        let result = if args.incremental {
+        let result = if args.incremental || args.since.is_some() {
             self.analyzer
                 .analyze_incremental(&paths, progress_tx)
                 .await?
@@ -137,6 +143,16 @@ impl CliHandler {
         info!("Starting project indexing");
         debug!("Index args: {:?}", args);
 
+        if args.verify {
+            let index_path = match args.output {
+                Some(output_path) => output_path,
+                None => self.config.index_path()?,
+            };
+            self.indexer.verify(&index_path)?;
+            info!("Index verified: {}", index_path.display());
+            return Ok(());
+        }
+
         let paths = if args.paths.is_empty() {
             vec![self.config.project_root().to_path_buf()]
         } else {
@@ -155,12 +171,17 @@ impl CliHandler {
             tokio::spawn(ui::display_progress_bar(rx, "Indexing".to_string()));
         }
 
+        if args.no_cache {
+            self.analyzer.clear_cache()?;
+        }
+
         // Run indexing
-        if args.incremental {
-            self.indexer.index_incremental(&paths, progress_tx).await?;
+        let prune_report = if args.incremental || args.since.is_some() {
+            Some(self.indexer.index_incremental(&paths, progress_tx).await?)
         } else {
             self.indexer.index_full(&paths, progress_tx).await?;
-        }
+            None
+        };
 
         // Save index if output path is specified
         if let Some(output_path) = args.output {
@@ -173,17 +194,47 @@ impl CliHandler {
             info!("Index saved to: {}", index_path.display());
         }
 
+        // Report what the incremental run pruned
+        if let Some(report) = prune_report {
+            ui::display_prune_report(&report);
+        }
+
+        // Report the memory saved by quantization, which runs extra searches
+        if args.quantization_report {
+            match self.indexer.quantization_report().await {
+                Some(report) => ui::display_quantization_report(&report),
+                None => ui::display_warning("Quantization is disabled, nothing to report"),
+            }
+        }
+
         Ok(())
     }
 
+    /// Handle the watch command
+    pub async fn handle_watch(&self, args: WatchArgs) -> Result<()> {
+        info!("Starting watch mode");
+        debug!("Watch args: {:?}", args);
+
+        let watcher = IndexWatcher::new(
+            self.indexer.clone(),
+            self.project.clone(),
+            self.config.index_path()?,
+            Duration::from_millis(args.debounce),
+        );
+
+        watcher
+            .run(|batch| ui::display_watch_batch(batch, args.json))
+            .await
+    }
+
     /// Handle the query command
     pub async fn handle_query(&self, args: QueryArgs) -> Result<()> {
         debug!("Query args: {:?}", args);
 
         // Get query text
-        let query_text = if let Some(query) = args.query {
-            query
-        } else if let Some(file_path) = args.file {
+        let query_text = if let Some(query) = &args.query {
+            query.clone()
+        } else if let Some(file_path) = &args.file {
             std::fs::read_to_string(file_path)?
         } else if args.interactive {
             // Interactive mode
@@ -197,12 +248,12 @@ impl CliHandler {
         // Execute query
         let result = self
             .query_engine
-            .query(&query_text, args.max_results, args.context_size)
+            .query_with_request(args.search_request(&query_text), args.context_size)
             .await?;
 
         // Output results
-        if let Some(output_path) = args.output {
-            result.save_to_file(&output_path)?;
+        if let Some(output_path) = &args.output {
+            result.save_to_file(output_path)?;
             info!("Query results saved to: {}", output_path.display());
         } else {
             // Display results in console
@@ -215,7 +266,7 @@ impl CliHandler {
                 info!("Executing query: {}", query);
                 let result = self
                     .query_engine
-                    .query(&query, args.max_results, args.context_size)
+                    .query_with_request(args.search_request(&query), args.context_size)
                     .await?;
                 ui::display_query_results(&result);
             }
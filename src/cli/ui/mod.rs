@@ -94,6 +94,107 @@ pub fn display_analysis_summary(result: &crate::analyzers::common::AnalysisResul
     println!("─────────────────────────────────\n");
 }
 
+/// Display the entries pruned by an incremental indexing run
+pub fn display_prune_report(report: &crate::indexing::PruneReport) {
+    println!("\n{}", "Prune Report".bold().green());
+    println!("─────────────────────────────────");
+
+    if report.is_empty() {
+        println!("No deleted or renamed files");
+    }
+
+    if !report.deleted.is_empty() {
+        println!("Deleted files: {}", report.deleted.len().to_string().bold());
+        for path in &report.deleted {
+            println!("  - {}", path.display());
+        }
+    }
+
+    if !report.renamed.is_empty() {
+        println!("Renamed files: {}", report.renamed.len().to_string().bold());
+        for (from, to) in &report.renamed {
+            println!("  - {} -> {}", from.display(), to.display());
+        }
+    }
+
+    println!(
+        "Embeddings removed: {}",
+        report.embeddings_removed.to_string().bold()
+    );
+
+    println!("─────────────────────────────────\n");
+}
+
+/// Display the memory saved by quantization and its impact on recall
+pub fn display_quantization_report(
+    report: &crate::indexing::vector::quantization::QuantizationReport,
+) {
+    println!("\n{}", "Quantization Report".bold().green());
+    println!("─────────────────────────────────");
+
+    println!("Mode: {}", format!("{:?}", report.mode).bold());
+    println!("Vectors: {}", report.vectors.to_string().bold());
+    println!(
+        "Memory: {} -> {} resident ({} saved)",
+        crate::utils::format_size(report.full_bytes as u64),
+        crate::utils::format_size(report.resident_bytes as u64),
+        crate::utils::format_size(report.saved_bytes() as u64).bold()
+    );
+    println!(
+        "Codes: {}",
+        crate::utils::format_size(report.quantized_bytes as u64)
+    );
+    println!(
+        "Recall@{} over {} held-out queries: {:.3}",
+        report.k, report.queries, report.recall
+    );
+    if let Some(recall) = report.rescored_recall {
+        println!("Recall@{} after rescoring: {:.3}", report.k, recall);
+    }
+
+    println!("─────────────────────────────────\n");
+}
+
+/// Display the summary of a watch batch
+///
+/// With `json`, the summary is printed as a single JSON line instead.
+pub fn display_watch_batch(batch: &crate::indexing::watch::WatchBatch, json: bool) {
+    if json {
+        match serde_json::to_string(batch) {
+            Ok(line) => println!("{}", line),
+            Err(e) => display_error(&format!("Failed to serialize batch summary: {}", e)),
+        }
+        return;
+    }
+
+    let timestamp = chrono::Local::now().format("%H:%M:%S");
+    let scope = if batch.touched.is_empty() {
+        "project".to_string()
+    } else {
+        format!("{} files", batch.touched.len())
+    };
+
+    match &batch.error {
+        Some(error) => display_error(&format!(
+            "[{}] Failed to index {}: {}",
+            timestamp, scope, error
+        )),
+        None => println!(
+            "{} [{}] Indexed {} in {} ms ({} deleted, {} renamed)",
+            "Batch".bold().green(),
+            timestamp,
+            scope,
+            batch.elapsed_ms,
+            batch.pruned.deleted.len(),
+            batch.pruned.renamed.len()
+        ),
+    }
+
+    for path in &batch.touched {
+        println!("  - {}", path.display());
+    }
+}
+
 /// Display query results
 pub fn display_query_results(result: &QueryResult) {
     println!("\n{}", "Query Results".bold().green());
@@ -115,7 +216,7 @@ pub fn display_query_results(result: &QueryResult) {
             println!(
                 "{}. {} (score: {:.2})",
                 (i + 1).to_string().bold(),
-                snippet.path.display().to_string().cyan(),
+                snippet.location().cyan(),
                 snippet.score
             );
         }
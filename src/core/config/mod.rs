@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::errors::{Result, ZseiError};
@@ -14,20 +15,31 @@ use crate::llm::LlmConfig;
 /// Configuration for embedding generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingConfig {
-    /// Embedding dimension
+    /// Embedding dimension; with the ONNX backend, the semantic quarter is
+    /// replaced by the full dimension of the model
     pub dimension: usize,
 
-    /// Chunk size for text chunking
+    /// Maximum characters of a chunk of code outside of functions and
+    /// types; 0 disables chunk-level embeddings
     pub chunk_size: usize,
 
-    /// Chunk overlap for text chunking
+    /// Characters shared by consecutive chunks of code outside of functions
+    /// and types
     pub chunk_overlap: usize,
 
-    /// Embedding cache size
+    /// Number of query embeddings kept in memory; 0 disables the cache
     pub cache_size: usize,
 
-    /// Whether to use GPU for embedding generation
+    /// Whether to run the embedding model on the GPU
     pub use_gpu: bool,
+
+    /// Source of the semantic features of embeddings
+    #[serde(default)]
+    pub backend: EmbeddingBackend,
+
+    /// Sentence embedding model of the ONNX backend
+    #[serde(default)]
+    pub model: EmbeddingModelConfig,
 }
 
 impl Default for EmbeddingConfig {
@@ -38,16 +50,201 @@ impl Default for EmbeddingConfig {
             chunk_overlap: 128,
             cache_size: 10000,
             use_gpu: false,
+            backend: EmbeddingBackend::default(),
+            model: EmbeddingModelConfig::default(),
+        }
+    }
+}
+
+/// Sources of the semantic features of embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmbeddingBackend {
+    /// Embeddings from the configured LLM
+    #[default]
+    Llm,
+
+    /// Feature hashing over identifiers and syntax, with no model at all
+    Hashing,
+
+    /// Sentence embeddings from an ONNX encoder model, such as MiniLM
+    Onnx,
+}
+
+/// Configuration of a sentence embedding model
+///
+/// The model directory holds a `model.onnx` encoder, exported from a
+/// BERT-style model, and its `tokenizer.json`. It is configured separately
+/// from the LLM, which is not used to embed when this model is. Up to
+/// `EmbeddingConfig::cache_size` of its query embeddings are cached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingModelConfig {
+    /// Model directory; looked up in the standard locations when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+
+    /// Maximum number of tokens of an input; longer inputs are truncated
+    #[serde(default = "default_max_sequence_length")]
+    pub max_sequence_length: usize,
+
+    /// Number of inputs embedded in one inference run
+    #[serde(default = "default_embedding_batch_size")]
+    pub batch_size: usize,
+
+    /// How token embeddings are pooled into one embedding
+    #[serde(default)]
+    pub pooling: EmbeddingPooling,
+
+    /// Number of threads an inference run uses
+    #[serde(default = "default_embedding_threads")]
+    pub threads: usize,
+}
+
+impl Default for EmbeddingModelConfig {
+    fn default() -> Self {
+        Self {
+            model_path: None,
+            max_sequence_length: default_max_sequence_length(),
+            batch_size: default_embedding_batch_size(),
+            pooling: EmbeddingPooling::default(),
+            threads: default_embedding_threads(),
+        }
+    }
+}
+
+impl EmbeddingModelConfig {
+    /// Get the model directory, looking in the standard locations when none
+    /// is configured
+    ///
+    /// The `ZSEI_EMBEDDING_MODEL_PATH` environment variable comes first,
+    /// then `models/all-MiniLM-L6-v2` in the current, application data and
+    /// home directories.
+    pub fn find_model_path(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.model_path {
+            return Some(path.clone());
+        }
+
+        if let Ok(path) = std::env::var("ZSEI_EMBEDDING_MODEL_PATH") {
+            let path = PathBuf::from(path);
+            if Self::is_valid_model_dir(&path) {
+                return Some(path);
+            }
         }
+
+        let standard_locations = [
+            Some(PathBuf::from("./models/all-MiniLM-L6-v2")),
+            dirs::data_local_dir().map(|p| p.join("zsei/models/all-MiniLM-L6-v2")),
+            dirs::home_dir().map(|p| p.join("zsei/models/all-MiniLM-L6-v2")),
+        ];
+
+        standard_locations
+            .into_iter()
+            .flatten()
+            .find(|path| Self::is_valid_model_dir(path))
+    }
+
+    /// Check if a directory holds an encoder model and its tokenizer
+    fn is_valid_model_dir(path: &Path) -> bool {
+        path.join("model.onnx").exists() && path.join("tokenizer.json").exists()
     }
 }
 
+/// Default maximum number of tokens of an embedding model input
+fn default_max_sequence_length() -> usize {
+    256
+}
+
+/// Default number of inputs per embedding model run
+fn default_embedding_batch_size() -> usize {
+    32
+}
+
+/// Default number of embedding model threads: one per available CPU
+fn default_embedding_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Ways of pooling token embeddings into one embedding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmbeddingPooling {
+    /// Average of the token embeddings, ignoring padding
+    #[default]
+    Mean,
+
+    /// Embedding of the first token, such as BERT's `[CLS]`
+    Cls,
+}
+
+/// Configuration for code analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisConfig {
+    /// Additional include directories for resolving C/C++ `#include`
+    /// directives, relative to the project root unless absolute
+    #[serde(default)]
+    pub include_paths: Vec<PathBuf>,
+
+    /// Number of files analyzed concurrently
+    #[serde(default = "default_analysis_workers")]
+    pub workers: usize,
+
+    /// How incremental runs detect changed files
+    #[serde(default)]
+    pub change_detection: ChangeDetection,
+
+    /// Git revision to detect changes from, instead of the last indexed
+    /// commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_since: Option<String>,
+}
+
+/// Change detection strategies for incremental runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeDetection {
+    /// Compare file contents with the hashes recorded by the last run
+    #[default]
+    ContentHash,
+
+    /// Diff the git working tree against a commit
+    Git,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            include_paths: Vec::new(),
+            workers: default_analysis_workers(),
+            change_detection: ChangeDetection::default(),
+            git_since: None,
+        }
+    }
+}
+
+/// Default number of analysis workers: one per available CPU
+fn default_analysis_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
 /// Configuration for the indexing system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexingConfig {
     /// Vector storage type
     pub vector_store_type: VectorStoreType,
 
+    /// Parameters of the HNSW vector store
+    #[serde(default)]
+    pub hnsw: HnswConfig,
+
+    /// Quantization of the stored vectors
+    #[serde(default)]
+    pub quantization: QuantizationConfig,
+
+    /// Search over the index
+    #[serde(default)]
+    pub search: SearchConfig,
+
     /// Whether to store metadata
     pub store_metadata: bool,
 
@@ -68,6 +265,9 @@ impl Default for IndexingConfig {
     fn default() -> Self {
         Self {
             vector_store_type: VectorStoreType::Hnsw,
+            hnsw: HnswConfig::default(),
+            quantization: QuantizationConfig::default(),
+            search: SearchConfig::default(),
             store_metadata: true,
             store_content: true,
             max_files: None,
@@ -98,8 +298,145 @@ impl Default for IndexingConfig {
     }
 }
 
+/// Parameters of the HNSW vector store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HnswConfig {
+    /// Number of neighbors kept per node on the upper layers; the bottom
+    /// layer keeps twice as many
+    pub m: usize,
+
+    /// Size of the candidate list while inserting
+    pub ef_construction: usize,
+
+    /// Size of the candidate list while searching
+    pub ef_search: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
+/// Parameters of vector quantization
+///
+/// Quantized codes replace the full vectors in the scans of the flat vector
+/// store; the full vectors are kept and only read to rescore the best
+/// candidates. The HNSW graph always uses the full vectors, so quantization
+/// requires the flat vector store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuantizationConfig {
+    /// Quantization mode
+    pub mode: QuantizationMode,
+
+    /// Number of subspaces of product quantization, lowered to the nearest
+    /// divisor of the dimension
+    pub pq_subspaces: usize,
+
+    /// Number of candidates rescored with the full vectors, as a multiple of
+    /// the number of requested results; 0 disables rescoring
+    pub rescore_factor: usize,
+
+    /// Number of held-out queries of the quantization report
+    pub report_queries: usize,
+
+    /// Number of results per query compared by the quantization report
+    pub report_k: usize,
+}
+
+impl Default for QuantizationConfig {
+    fn default() -> Self {
+        Self {
+            mode: QuantizationMode::None,
+            pq_subspaces: 16,
+            rescore_factor: 4,
+            report_queries: 100,
+            report_k: 10,
+        }
+    }
+}
+
+/// Vector quantization modes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantizationMode {
+    /// Full-precision vectors only
+    #[default]
+    None,
+
+    /// One signed byte per value, scaled per vector
+    Int8,
+
+    /// One byte per subspace, indexing a trained codebook
+    Product,
+}
+
+/// Parameters of search over the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Search mode used when a request does not choose one
+    ///
+    /// In lexical mode files are indexed without embeddings, so no LLM is
+    /// needed.
+    pub mode: SearchMode,
+
+    /// Weight of the vector ranking in hybrid search
+    pub vector_weight: f32,
+
+    /// Weight of the lexical ranking in hybrid search
+    pub lexical_weight: f32,
+
+    /// Rank offset of reciprocal rank fusion; higher values reduce the
+    /// advantage of the top ranks
+    pub rrf_k: f32,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            mode: SearchMode::Hybrid,
+            vector_weight: 1.0,
+            lexical_weight: 1.0,
+            rrf_k: 60.0,
+        }
+    }
+}
+
+/// Search modes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchMode {
+    /// Embedding similarity only
+    Vector,
+
+    /// BM25 over the inverted index only
+    Lexical,
+
+    /// Vector and lexical rankings combined with reciprocal rank fusion
+    #[default]
+    Hybrid,
+}
+
+impl FromStr for SearchMode {
+    type Err = ZseiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "vector" => Ok(SearchMode::Vector),
+            "lexical" => Ok(SearchMode::Lexical),
+            "hybrid" => Ok(SearchMode::Hybrid),
+            _ => Err(ZseiError::Config(format!("Unknown search mode: {}", s))),
+        }
+    }
+}
+
 /// Vector store types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VectorStoreType {
     /// Flat vector store
     Flat,
@@ -156,6 +493,10 @@ pub struct Config {
     /// Embedding configuration
     pub embedding: EmbeddingConfig,
 
+    /// Analysis configuration
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+
     /// Indexing configuration
     pub indexing: IndexingConfig,
 
@@ -174,6 +515,7 @@ impl Default for Config {
             phase1_llm: None,
             phase2_llm: None,
             embedding: EmbeddingConfig::default(),
+            analysis: AnalysisConfig::default(),
             indexing: IndexingConfig::default(),
             refactor: RefactorConfig::default(),
             additional_project_paths: Vec::new(),
@@ -197,9 +539,25 @@ impl Config {
             })?;
         }
 
+        config.validate()?;
+
         Ok(config)
     }
 
+    /// Check that the configured options can be used together
+    pub fn validate(&self) -> Result<()> {
+        if self.indexing.quantization.mode != QuantizationMode::None
+            && self.indexing.vector_store_type != VectorStoreType::Flat
+        {
+            return Err(ZseiError::Config(format!(
+                "Quantization mode {:?} requires the Flat vector store, not {:?}",
+                self.indexing.quantization.mode, self.indexing.vector_store_type
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Save configuration to a file
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self)
@@ -227,6 +585,16 @@ impl Config {
         self.project_root = path;
     }
 
+    /// Check if indexing and search work without an LLM
+    ///
+    /// True when search is purely lexical or embeddings come from feature
+    /// hashing or a sentence embedding model. Query responses and
+    /// refactoring still need an LLM.
+    pub fn runs_without_llm(&self) -> bool {
+        self.indexing.search.mode == SearchMode::Lexical
+            || self.embedding.backend != EmbeddingBackend::Llm
+    }
+
     /// Get the index path
     pub fn index_path(&self) -> Result<PathBuf> {
         Ok(self.project_root.join(".zsei").join("index"))
@@ -257,3 +625,46 @@ impl Config {
         self.phase2_llm.as_ref().unwrap_or(&self.llm)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantization_requires_the_flat_vector_store() {
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+
+        config.indexing.quantization.mode = QuantizationMode::Int8;
+        config.indexing.vector_store_type = VectorStoreType::Hnsw;
+        assert!(matches!(config.validate(), Err(ZseiError::Config(_))));
+
+        config.indexing.vector_store_type = VectorStoreType::Faiss;
+        assert!(matches!(config.validate(), Err(ZseiError::Config(_))));
+
+        config.indexing.vector_store_type = VectorStoreType::Flat;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn config_files_are_validated() {
+        let path = std::env::temp_dir().join(format!(
+            "zsei-config-{}-quantization.toml",
+            std::process::id()
+        ));
+        let mut config = Config::default();
+        config.indexing.quantization.mode = QuantizationMode::Product;
+        config.save_to_file(&path).unwrap();
+
+        assert!(matches!(
+            Config::from_file(&path),
+            Err(ZseiError::Config(_))
+        ));
+
+        config.indexing.vector_store_type = VectorStoreType::Flat;
+        config.save_to_file(&path).unwrap();
+        assert!(Config::from_file(&path).is_ok());
+
+        let _ = fs::remove_file(&path);
+    }
+}
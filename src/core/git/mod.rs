@@ -0,0 +1,281 @@
+//! Git integration for ZSEI
+//!
+//! This module reads the local git repository of a project to find the files
+//! changed since a commit, so incremental runs do not depend on modification
+//! times. It never contacts a remote.
+
+use git2::{DiffOptions, ErrorCode, Repository};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::errors::{Result, ZseiError};
+
+/// A local git repository
+pub struct GitRepository {
+    /// Repository handle
+    repo: Repository,
+
+    /// Canonical path of the working tree
+    workdir: PathBuf,
+}
+
+impl GitRepository {
+    /// Open the repository containing a path
+    ///
+    /// Returns `None` if the path is not inside a git working tree.
+    pub fn discover(path: &Path) -> Result<Option<Self>> {
+        let repo = match Repository::discover(path) {
+            Ok(repo) => repo,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(ZseiError::Git(format!(
+                    "Failed to open repository at {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        // Bare repositories have no files to compare
+        let workdir = match repo.workdir() {
+            Some(workdir) => fs::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf()),
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self { repo, workdir }))
+    }
+
+    /// Get the ID of the commit checked out in the working tree
+    ///
+    /// Returns `None` on a branch without commits.
+    pub fn head_commit(&self) -> Result<Option<String>> {
+        match self.repo.head() {
+            Ok(head) => {
+                let commit = head
+                    .peel_to_commit()
+                    .map_err(|e| ZseiError::Git(format!("Failed to resolve HEAD: {}", e)))?;
+                Ok(Some(commit.id().to_string()))
+            }
+            Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => {
+                Ok(None)
+            }
+            Err(e) => Err(ZseiError::Git(format!("Failed to read HEAD: {}", e))),
+        }
+    }
+
+    /// Get the files that differ between a revision and the working tree
+    ///
+    /// Staged, unstaged and untracked changes are included; ignored files are
+    /// not. Paths are absolute and canonical, and include both sides of a
+    /// rename as well as deleted files.
+    pub fn changed_files(&self, since: &str) -> Result<HashSet<PathBuf>> {
+        let commit = self
+            .repo
+            .revparse_single(since)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| ZseiError::Git(format!("Failed to resolve revision {}: {}", since, e)))?;
+
+        let tree = commit
+            .tree()
+            .map_err(|e| ZseiError::Git(format!("Failed to read tree of {}: {}", since, e)))?;
+
+        let mut options = DiffOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+
+        let diff = self
+            .repo
+            .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))
+            .map_err(|e| ZseiError::Git(format!("Failed to diff against {}: {}", since, e)))?;
+
+        let mut changed = HashSet::new();
+        for delta in diff.deltas() {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path() {
+                    changed.insert(self.workdir.join(path));
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Get the canonical form of a path for lookups in a changed set
+    pub fn canonical_path(path: &Path) -> PathBuf {
+        fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{ChangeDetection, Config};
+    use crate::core::project::Project;
+    use git2::Signature;
+    use std::sync::Arc;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-git-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::canonicalize(&dir).unwrap()
+    }
+
+    /// Stage the given files and commit them, returning the commit ID
+    fn commit(repo: &Repository, files: &[&str], message: &str) -> String {
+        let mut index = repo.index().unwrap();
+        for file in files {
+            index.add_path(Path::new(file)).unwrap();
+        }
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("ZSEI", "zsei@example.com").unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    /// Files the project would index incrementally, sorted
+    fn files_to_index(project: &Project) -> Vec<PathBuf> {
+        let mut files = project.get_files_to_index(true).unwrap();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn outside_a_repository_is_none() {
+        let dir = scratch_dir("none");
+        assert!(GitRepository::discover(&dir).unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unborn_branch_has_no_head() {
+        let dir = scratch_dir("unborn");
+        Repository::init(&dir).unwrap();
+
+        let repo = GitRepository::discover(&dir).unwrap().unwrap();
+        assert_eq!(repo.head_commit().unwrap(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn changed_files_cover_working_tree_changes() {
+        let dir = scratch_dir("changes");
+        let git = Repository::init(&dir).unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        for file in [
+            "src/kept.rs",
+            "src/edited.rs",
+            "src/deleted.rs",
+            "src/moved.rs",
+        ] {
+            fs::write(dir.join(file), format!("// {}\n", file)).unwrap();
+        }
+        fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        let first = commit(
+            &git,
+            &[
+                "src/kept.rs",
+                "src/edited.rs",
+                "src/deleted.rs",
+                "src/moved.rs",
+                ".gitignore",
+            ],
+            "Initial commit",
+        );
+
+        let repo = GitRepository::discover(&dir.join("src")).unwrap().unwrap();
+        assert_eq!(repo.head_commit().unwrap(), Some(first.clone()));
+        assert!(repo.changed_files(&first).unwrap().is_empty());
+
+        fs::write(dir.join("src/edited.rs"), "fn edited() {}\n").unwrap();
+        fs::remove_file(dir.join("src/deleted.rs")).unwrap();
+        fs::rename(dir.join("src/moved.rs"), dir.join("src/renamed.rs")).unwrap();
+        fs::write(dir.join("src/untracked.rs"), "fn new() {}\n").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target/ignored.rs"), "fn ignored() {}\n").unwrap();
+
+        let changed = repo.changed_files(&first).unwrap();
+        let expected: HashSet<PathBuf> = [
+            "src/edited.rs",
+            "src/deleted.rs",
+            "src/moved.rs",
+            "src/renamed.rs",
+            "src/untracked.rs",
+        ]
+        .iter()
+        .map(|file| dir.join(file))
+        .collect();
+        assert_eq!(changed, expected);
+
+        // Committed changes are still reported against the earlier commit
+        let second = commit(&git, &["src/edited.rs"], "Edit");
+        let since_second = repo.changed_files(&second).unwrap();
+        assert!(since_second.contains(&dir.join("src/untracked.rs")));
+        assert!(!since_second.contains(&dir.join("src/edited.rs")));
+        assert_eq!(repo.changed_files(&first).unwrap(), expected);
+
+        assert!(repo.changed_files("no-such-revision").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uncommitted_changes_are_found_without_a_new_commit() {
+        let dir = scratch_dir("no-commit");
+        let git = Repository::init(&dir).unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/a.rs"), "fn a() {}\n").unwrap();
+        fs::write(dir.join("src/b.rs"), "fn b() {}\n").unwrap();
+        let head = commit(&git, &["src/a.rs", "src/b.rs"], "Initial commit");
+
+        let mut config = Config::default();
+        config.set_project_root(dir.clone());
+        config.analysis.change_detection = ChangeDetection::Git;
+        let project = Project::new(Arc::new(config)).unwrap();
+
+        let files = project.get_files_to_index(false).unwrap();
+        assert_eq!(files.len(), 2);
+        project
+            .update_indexed_files(files.iter().map(|path| (path.as_path(), None, None)))
+            .unwrap();
+        project.update_indexed_commit().unwrap();
+        assert!(files_to_index(&project).is_empty());
+
+        // Edit a tracked file and add an untracked one, without committing
+        fs::write(dir.join("src/a.rs"), "fn a() { b(); }\n").unwrap();
+        fs::write(dir.join("src/c.rs"), "fn c() {}\n").unwrap();
+        assert_eq!(
+            files_to_index(&project),
+            vec![dir.join("src/a.rs"), dir.join("src/c.rs")]
+        );
+
+        // Once indexed, they are not indexed again while HEAD stays put
+        let changed = files_to_index(&project);
+        project
+            .update_indexed_files(changed.iter().map(|path| (path.as_path(), None, None)))
+            .unwrap();
+        project.update_indexed_commit().unwrap();
+        let repo = GitRepository::discover(&dir).unwrap().unwrap();
+        assert_eq!(repo.head_commit().unwrap(), Some(head));
+        assert!(files_to_index(&project).is_empty());
+
+        // A later edit of a file left pending is still found
+        fs::write(dir.join("src/c.rs"), "fn c() { a(); }\n").unwrap();
+        assert_eq!(files_to_index(&project), vec![dir.join("src/c.rs")]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
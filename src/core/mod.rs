@@ -5,6 +5,7 @@
 //! structure handling.
 
 pub mod config;
+pub mod git;
 pub mod project;
 
 use std::sync::Arc;
@@ -26,7 +27,7 @@ pub async fn initialize(config: Arc<Config>) -> Result<(Arc<Project>, Arc<Analyz
     }
 
     // Create analyzer
-    let analyzer = Arc::new(Analyzer::new(config.clone()));
+    let analyzer = Arc::new(Analyzer::new(config.clone(), project.clone()));
 
     Ok((project, analyzer))
 }
@@ -5,14 +5,16 @@
 //! and project state.
 
 use chrono::{DateTime, Utc};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::{DirEntry, Walk, WalkBuilder};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use crate::core::config::Config;
+use crate::core::config::{ChangeDetection, Config};
+use crate::core::git::GitRepository;
 use crate::errors::{Result, ZseiError};
 
 /// Project state struct
@@ -32,6 +34,16 @@ pub struct ProjectState {
 
     /// Indexed files
     pub indexed_files: HashMap<PathBuf, FileState>,
+
+    /// Git commit checked out when the whole project was last indexed
+    #[serde(default)]
+    pub last_indexed_commit: Option<String>,
+
+    /// Files the index may not reflect despite the recorded commit:
+    /// uncommitted changes and changed files that failed to index. Git
+    /// change detection always reconsiders them.
+    #[serde(default)]
+    pub pending_paths: HashSet<PathBuf>,
 }
 
 /// File state struct
@@ -53,13 +65,138 @@ pub struct FileState {
     pub size: u64,
 }
 
+/// A tracked file that no longer exists at its recorded path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemovedFile {
+    /// The file was deleted
+    Deleted(PathBuf),
+
+    /// The file was moved, detected by an identical content hash
+    Renamed {
+        /// Recorded path
+        from: PathBuf,
+
+        /// New path
+        to: PathBuf,
+    },
+}
+
+/// Names of the ignore files a project scan honours
+const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Ignore rules of the `.gitignore` and `.ignore` files of a project
+///
+/// Each ignore file is parsed the first time a path below its directory is
+/// checked, and kept for later checks. A matcher is built for one run, or kept
+/// until an ignore file changes.
+#[derive(Debug, Default)]
+pub struct IgnoreMatcher {
+    /// Parsed ignore files of each directory checked so far
+    gitignores: HashMap<PathBuf, Vec<Gitignore>>,
+}
+
+impl IgnoreMatcher {
+    /// Create a matcher that has not parsed any ignore file yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if a path is an ignore file, whose change outdates a matcher
+    pub fn is_ignore_file(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| IGNORE_FILE_NAMES.contains(&name))
+    }
+
+    /// Check if a path is ignored by the ignore files between a root and it
+    ///
+    /// Ignore files in deeper directories take precedence, as in a scan.
+    pub fn is_ignored(&mut self, root: &Path, path: &Path) -> bool {
+        let mut ignored = false;
+
+        let mut dirs: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root))
+            .collect();
+        dirs.reverse();
+
+        for dir in dirs {
+            for gitignore in self.gitignores_of(dir) {
+                let matched = gitignore.matched_path_or_any_parents(path, false);
+                if matched.is_ignore() {
+                    ignored = true;
+                } else if matched.is_whitelist() {
+                    ignored = false;
+                }
+            }
+        }
+
+        ignored
+    }
+
+    /// Get the parsed ignore files of a directory, parsing them on first use
+    fn gitignores_of(&mut self, dir: &Path) -> &[Gitignore] {
+        self.gitignores.entry(dir.to_path_buf()).or_insert_with(|| {
+            let mut gitignores = Vec::new();
+
+            for name in IGNORE_FILE_NAMES {
+                let ignore_file = dir.join(name);
+                if !ignore_file.is_file() {
+                    continue;
+                }
+
+                let mut builder = GitignoreBuilder::new(dir);
+                if let Some(e) = builder.add(&ignore_file) {
+                    tracing::warn!("Error reading {}: {}", ignore_file.display(), e);
+                }
+
+                match builder.build() {
+                    Ok(gitignore) => gitignores.push(gitignore),
+                    Err(e) => tracing::warn!("Error parsing {}: {}", ignore_file.display(), e),
+                }
+            }
+
+            gitignores
+        })
+    }
+}
+
+/// Processing stage whose file states are tracked
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    /// Code analysis
+    Analysis,
+
+    /// Embedding indexing
+    Indexing,
+}
+
+impl Stage {
+    /// Get the files tracked for this stage
+    fn tracked_files(self, state: &ProjectState) -> &HashMap<PathBuf, FileState> {
+        match self {
+            Stage::Analysis => &state.analyzed_files,
+            Stage::Indexing => &state.indexed_files,
+        }
+    }
+
+    /// Get the files tracked for this stage for modification
+    fn tracked_files_mut(self, state: &mut ProjectState) -> &mut HashMap<PathBuf, FileState> {
+        match self {
+            Stage::Analysis => &mut state.analyzed_files,
+            Stage::Indexing => &mut state.indexed_files,
+        }
+    }
+}
+
 /// Project struct
 pub struct Project {
     /// Project config
     config: Arc<Config>,
 
-    /// Project state
-    state: ProjectState,
+    /// Project state, shared by the analyzer and indexer
+    state: RwLock<ProjectState>,
 
     /// Project state file path
     state_path: PathBuf,
@@ -84,12 +221,14 @@ impl Project {
                 last_refactoring: Utc::now(),
                 analyzed_files: HashMap::new(),
                 indexed_files: HashMap::new(),
+                last_indexed_commit: None,
+                pending_paths: HashSet::new(),
             }
         };
 
         Ok(Self {
             config,
-            state,
+            state: RwLock::new(state),
             state_path,
         })
     }
@@ -137,7 +276,7 @@ impl Project {
                 .map_err(|e| ZseiError::Config(format!("Failed to create directories: {}", e)))?;
         }
 
-        let content = serde_json::to_string_pretty(&self.state)
+        let content = serde_json::to_string_pretty(&*self.read_state())
             .map_err(|e| ZseiError::Config(format!("Failed to serialize state: {}", e)))?;
 
         fs::write(&self.state_path, content)
@@ -164,67 +303,184 @@ impl Project {
 
     /// Get files that need to be analyzed
     pub fn get_files_to_analyze(&self, incremental: bool) -> Result<Vec<PathBuf>> {
-        self.get_files_to_process(incremental, &self.state.analyzed_files)
+        self.get_files_to_process(incremental, Stage::Analysis)
     }
 
     /// Get files that need to be indexed
     pub fn get_files_to_index(&self, incremental: bool) -> Result<Vec<PathBuf>> {
-        self.get_files_to_process(incremental, &self.state.indexed_files)
+        self.get_files_to_process(incremental, Stage::Indexing)
+    }
+
+    /// Keep only the given files that changed since they were last analyzed
+    pub fn filter_files_to_analyze(&self, files: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+        self.filter_changed_files(files, Stage::Analysis)
+    }
+
+    /// Keep only the given files that changed since they were last indexed
+    pub fn filter_files_to_index(&self, files: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+        self.filter_changed_files(files, Stage::Indexing)
     }
 
     /// Find files that need to be processed
-    fn get_files_to_process(
-        &self,
-        incremental: bool,
-        tracked_files: &HashMap<PathBuf, FileState>,
-    ) -> Result<Vec<PathBuf>> {
+    fn get_files_to_process(&self, incremental: bool, stage: Stage) -> Result<Vec<PathBuf>> {
         let mut files_to_process = Vec::new();
 
         // Get all files in the project
         let project_files = self.scan_project_files()?;
 
         for file_path in project_files {
-            if self.should_process_file(&file_path, incremental, tracked_files)? {
+            if !self.is_excluded(&file_path)? {
                 files_to_process.push(file_path);
             }
         }
 
+        if incremental {
+            files_to_process = self.filter_changed_files(files_to_process, stage)?;
+        }
+
         Ok(files_to_process)
     }
 
-    /// Check if a file should be processed
-    fn should_process_file(
-        &self,
-        path: &Path,
-        incremental: bool,
-        tracked_files: &HashMap<PathBuf, FileState>,
-    ) -> Result<bool> {
-        // Skip if excluded by pattern
-        if self.is_excluded(path)? {
-            return Ok(false);
+    /// Keep only files that are new or changed since the given stage last
+    /// processed them
+    ///
+    /// The content hash of a file is compared with its recorded state. With
+    /// git change detection, only tracked files git reports as changed, or
+    /// that were left pending, are hashed; the others are skipped.
+    fn filter_changed_files(&self, files: Vec<PathBuf>, stage: Stage) -> Result<Vec<PathBuf>> {
+        let git_changes = self.git_changed_files()?;
+
+        let state = self.read_state();
+        let tracked_files = stage.tracked_files(&state);
+
+        let mut changed = Vec::new();
+        for path in files {
+            let file_state = tracked_files.get(&path);
+            if let (Some(git_changes), Some(_)) = (&git_changes, file_state) {
+                if !git_changes.contains(&GitRepository::canonical_path(&path)) {
+                    continue;
+                }
+            }
+
+            match Self::has_changed(&path, file_state) {
+                Ok(true) => changed.push(path),
+                Ok(false) => {}
+                // Deleted since the scan, so it is pruned with the removed files
+                Err(_) if !path.exists() => {}
+                Err(e) => return Err(e),
+            }
         }
 
-        // Always process if not incremental
-        if !incremental {
-            return Ok(true);
+        Ok(changed)
+    }
+
+    /// Get the files git reports as changed, if git change detection applies
+    ///
+    /// Changes are taken against the configured revision, or the last indexed
+    /// commit, and include the paths left pending by the last indexing.
+    /// Returns `None` when git detection is disabled, the project is not in a
+    /// git repository or there is no commit to compare against.
+    fn git_changed_files(&self) -> Result<Option<HashSet<PathBuf>>> {
+        if self.config.analysis.change_detection != ChangeDetection::Git {
+            return Ok(None);
         }
 
-        // Check if file is modified
-        if let Some(state) = tracked_files.get(path) {
-            let metadata = fs::metadata(path)
-                .map_err(|e| ZseiError::Config(format!("Failed to get file metadata: {}", e)))?;
+        let since = match self
+            .config
+            .analysis
+            .git_since
+            .clone()
+            .or_else(|| self.read_state().last_indexed_commit.clone())
+        {
+            Some(since) => since,
+            None => {
+                tracing::info!("No indexed commit recorded yet, comparing content hashes");
+                return Ok(None);
+            }
+        };
+
+        let repo = match GitRepository::discover(self.config.project_root())? {
+            Some(repo) => repo,
+            None => {
+                tracing::warn!("Project is not in a git repository, comparing content hashes");
+                return Ok(None);
+            }
+        };
 
-            let modified: DateTime<Utc> = metadata
-                .modified()
-                .map_err(|e| ZseiError::Config(format!("Failed to get modified time: {}", e)))?
-                .into();
+        let mut changed = repo.changed_files(&since)?;
+        changed.extend(self.read_state().pending_paths.iter().cloned());
+        tracing::debug!(
+            "Git reports {} files changed since {}",
+            changed.len(),
+            since
+        );
 
-            // Process if modified after last tracking
-            Ok(modified > state.last_modified)
-        } else {
+        Ok(Some(changed))
+    }
+
+    /// Record the git commit the project was indexed at
+    ///
+    /// The commit does not cover uncommitted changes, nor changed files whose
+    /// indexed state does not match their content because they failed to
+    /// index, so both are recorded as pending. Call this after the indexed
+    /// files are recorded. Does nothing outside a git repository or before
+    /// the first commit.
+    pub fn update_indexed_commit(&self) -> Result<()> {
+        let repo = match GitRepository::discover(self.config.project_root())? {
+            Some(repo) => repo,
+            None => return Ok(()),
+        };
+        let head = match repo.head_commit()? {
+            Some(head) => head,
+            None => return Ok(()),
+        };
+
+        let mut pending = repo.changed_files(&head)?;
+
+        if let Some(changed) = self.git_changed_files()? {
+            let state = self.read_state();
+            for (path, file_state) in &state.indexed_files {
+                let canonical = GitRepository::canonical_path(path);
+                if changed.contains(&canonical)
+                    && path.exists()
+                    && Self::has_changed(path, Some(file_state))?
+                {
+                    pending.insert(canonical);
+                }
+            }
+        }
+
+        {
+            let mut state = self.write_state();
+            state.last_indexed_commit = Some(head);
+            state.pending_paths = pending;
+        }
+        self.save_state()
+    }
+
+    /// Check if a file differs from its recorded state
+    ///
+    /// The content hash decides, so a file whose modification time changed
+    /// without a change in content is not reprocessed. The size is compared
+    /// first to avoid hashing files that obviously changed.
+    fn has_changed(path: &Path, state: Option<&FileState>) -> Result<bool> {
+        let state = match state {
+            Some(state) => state,
             // Always process new files
-            Ok(true)
+            None => return Ok(true),
+        };
+
+        let metadata = fs::metadata(path)
+            .map_err(|e| ZseiError::Config(format!("Failed to get file metadata: {}", e)))?;
+
+        if metadata.len() != state.size {
+            return Ok(true);
         }
+
+        let content =
+            fs::read(path).map_err(|e| ZseiError::Config(format!("Failed to read file: {}", e)))?;
+
+        Ok(format!("{:x}", md5::compute(&content)) != state.hash)
     }
 
     /// Check if a file is excluded by patterns
@@ -259,6 +515,116 @@ impl Project {
         Ok(false)
     }
 
+    /// Get the directories scanned for project files
+    pub fn source_roots(&self) -> Vec<PathBuf> {
+        let mut roots = vec![self.config.project_root().to_path_buf()];
+        roots.extend(self.config.additional_project_paths.iter().cloned());
+        roots
+    }
+
+    /// Check if a path would be picked up by a project scan
+    ///
+    /// Applies the same rules as the scan: the path must be under one of the
+    /// source roots, not ignored by `.gitignore` or `.ignore` files and not
+    /// excluded by extension or pattern. Paths of deleted files are accepted
+    /// too, so their removal can be processed.
+    pub fn is_project_file(&self, path: &Path, ignores: &mut IgnoreMatcher) -> Result<bool> {
+        let root = match self.source_root_of(path) {
+            Some(root) => root,
+            None => return Ok(false),
+        };
+
+        if path.is_dir() || ignores.is_ignored(&root, path) {
+            return Ok(false);
+        }
+
+        Ok(!self.is_excluded(path)?)
+    }
+
+    /// Expand a path reported by a filesystem event into project files
+    ///
+    /// A directory stands for the project files under it. A directory that
+    /// no longer exists stands for the indexed files that were under it, so
+    /// their removal can be processed. Other paths are kept if they are
+    /// project files.
+    pub fn expand_touched_path(
+        &self,
+        path: &Path,
+        ignores: &mut IgnoreMatcher,
+    ) -> Result<Vec<PathBuf>> {
+        if path.is_dir() {
+            let root = match self.source_root_of(path) {
+                Some(root) => root,
+                None => return Ok(Vec::new()),
+            };
+            if ignores.is_ignored(&root, path) {
+                return Ok(Vec::new());
+            }
+
+            let mut files = Vec::new();
+            self.scan_directory(path, &mut files)?;
+
+            let mut project_files = Vec::new();
+            for file in files {
+                if self.is_project_file(&file, ignores)? {
+                    project_files.push(file);
+                }
+            }
+            return Ok(project_files);
+        }
+
+        if !path.exists() {
+            let state = self.read_state();
+            let removed: Vec<PathBuf> = state
+                .indexed_files
+                .keys()
+                .filter(|indexed| indexed.starts_with(path) && indexed.as_path() != path)
+                .cloned()
+                .collect();
+            if !removed.is_empty() {
+                return Ok(removed);
+            }
+        }
+
+        if self.is_project_file(path, ignores)? {
+            Ok(vec![path.to_path_buf()])
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Get the source root containing a path, unless the path is inside
+    /// ZSEI's own data or a git directory
+    fn source_root_of(&self, path: &Path) -> Option<PathBuf> {
+        let root = self
+            .source_roots()
+            .into_iter()
+            .find(|root| path.starts_with(root))?;
+
+        let relative = path.strip_prefix(&root).unwrap_or(path);
+        if relative
+            .components()
+            .any(|component| component.as_os_str() == ".zsei" || component.as_os_str() == ".git")
+        {
+            return None;
+        }
+
+        Some(root)
+    }
+
+    /// Get every file a project scan finds, whatever its extension
+    ///
+    /// ZSEI's own `.zsei` directory is left out.
+    pub fn scan_files(&self) -> Result<Vec<PathBuf>> {
+        let zsei_dir = self.config.project_root().join(".zsei");
+
+        Ok(self
+            .scan_project_files()?
+            .into_iter()
+            .filter(|path| !path.starts_with(&zsei_dir))
+            .collect())
+    }
+
     /// Scan project files
     fn scan_project_files(&self) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
@@ -299,29 +665,182 @@ impl Project {
     }
 
     /// Update file state after analysis
-    pub fn update_analyzed_file(&mut self, path: &Path, language: Option<String>) -> Result<()> {
-        let file_state = self.create_file_state(path, language)?;
-        self.state
-            .analyzed_files
-            .insert(path.to_path_buf(), file_state);
-        self.state.last_analysis = Utc::now();
-        self.save_state()?;
-        Ok(())
+    pub fn update_analyzed_file(&self, path: &Path, language: Option<String>) -> Result<()> {
+        self.update_analyzed_files(std::iter::once((path, language, None)))
     }
 
     /// Update file state after indexing
-    pub fn update_indexed_file(&mut self, path: &Path, language: Option<String>) -> Result<()> {
-        let file_state = self.create_file_state(path, language)?;
-        self.state
-            .indexed_files
-            .insert(path.to_path_buf(), file_state);
-        self.state.last_indexing = Utc::now();
-        self.save_state()?;
-        Ok(())
+    pub fn update_indexed_file(&self, path: &Path, language: Option<String>) -> Result<()> {
+        self.update_indexed_files(std::iter::once((path, language, None)))
+    }
+
+    /// Update the state of several files after analysis and save it once
+    pub fn update_analyzed_files<'a>(
+        &self,
+        files: impl IntoIterator<Item = (&'a Path, Option<String>, Option<&'a str>)>,
+    ) -> Result<()> {
+        self.update_files(files, Stage::Analysis)
+    }
+
+    /// Update the state of several files after indexing and save it once
+    pub fn update_indexed_files<'a>(
+        &self,
+        files: impl IntoIterator<Item = (&'a Path, Option<String>, Option<&'a str>)>,
+    ) -> Result<()> {
+        self.update_files(files, Stage::Indexing)
+    }
+
+    /// Record the current state of files for a stage and save the state
+    ///
+    /// Each file comes with its language and, when known, the hash of the
+    /// content it was processed at. Recording that hash rather than the
+    /// current one keeps a file changed during processing marked as changed;
+    /// files without a hash are read again.
+    fn update_files<'a>(
+        &self,
+        files: impl IntoIterator<Item = (&'a Path, Option<String>, Option<&'a str>)>,
+        stage: Stage,
+    ) -> Result<()> {
+        // Read the files before taking the lock
+        let mut file_states = Vec::new();
+        for (path, language, content_hash) in files {
+            match self.create_file_state(path, language, content_hash) {
+                Ok(file_state) => file_states.push(file_state),
+                // The file may have been removed since it was processed
+                Err(e) => tracing::warn!("Not recording state of {}: {}", path.display(), e),
+            }
+        }
+
+        {
+            let mut state = self.write_state();
+            let now = Utc::now();
+            match stage {
+                Stage::Analysis => state.last_analysis = now,
+                Stage::Indexing => state.last_indexing = now,
+            }
+
+            let tracked_files = stage.tracked_files_mut(&mut state);
+            for file_state in file_states {
+                tracked_files.insert(file_state.path.clone(), file_state);
+            }
+        }
+
+        self.save_state()
+    }
+
+    /// Find indexed files that were deleted, renamed or are no longer
+    /// project files
+    ///
+    /// Only tracked files under one of `scope` are considered, or all of them
+    /// when `scope` is empty. A deleted file is reported as renamed when one
+    /// of the untracked `candidates` has the same content hash. Files that
+    /// still exist but are now excluded or ignored are reported as deleted.
+    pub fn find_removed_indexed_files(
+        &self,
+        scope: &[PathBuf],
+        candidates: &[PathBuf],
+    ) -> Result<Vec<RemovedFile>> {
+        let state = self.read_state();
+        let mut ignores = IgnoreMatcher::new();
+
+        let mut missing: Vec<&FileState> = Vec::new();
+        let mut excluded: Vec<PathBuf> = Vec::new();
+        for file_state in state.indexed_files.values() {
+            if !scope.is_empty() && !scope.iter().any(|dir| file_state.path.starts_with(dir)) {
+                continue;
+            }
+
+            if !file_state.path.exists() {
+                missing.push(file_state);
+            } else if !self.is_project_file(&file_state.path, &mut ignores)? {
+                excluded.push(file_state.path.clone());
+            }
+        }
+        missing.sort_by(|a, b| a.path.cmp(&b.path));
+        excluded.sort();
+
+        let mut removed: Vec<RemovedFile> =
+            excluded.into_iter().map(RemovedFile::Deleted).collect();
+
+        if missing.is_empty() {
+            return Ok(removed);
+        }
+
+        // Hash the new files to spot moves
+        let mut new_files_by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for path in candidates {
+            if state.indexed_files.contains_key(path) {
+                continue;
+            }
+
+            let content = match fs::read(path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+
+            new_files_by_hash
+                .entry(format!("{:x}", md5::compute(&content)))
+                .or_default()
+                .push(path.clone());
+        }
+
+        for file_state in missing {
+            let moved_to = new_files_by_hash
+                .get_mut(&file_state.hash)
+                .and_then(|paths| paths.pop());
+
+            removed.push(match moved_to {
+                Some(to) => RemovedFile::Renamed {
+                    from: file_state.path.clone(),
+                    to,
+                },
+                None => RemovedFile::Deleted(file_state.path.clone()),
+            });
+        }
+
+        Ok(removed)
+    }
+
+    /// Drop or re-key the states of removed files and save the state
+    ///
+    /// Both the analysis and the indexing records are updated, so a renamed
+    /// file is not processed again at its new path.
+    pub fn apply_removed_files(&self, removed: &[RemovedFile]) -> Result<()> {
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        {
+            let mut state = self.write_state();
+            for stage in [Stage::Analysis, Stage::Indexing] {
+                let tracked_files = stage.tracked_files_mut(&mut state);
+
+                for file in removed {
+                    match file {
+                        RemovedFile::Deleted(path) => {
+                            tracked_files.remove(path);
+                        }
+                        RemovedFile::Renamed { from, to } => {
+                            if let Some(mut file_state) = tracked_files.remove(from) {
+                                file_state.path = to.clone();
+                                tracked_files.insert(to.clone(), file_state);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        self.save_state()
     }
 
     /// Update file state
-    fn create_file_state(&self, path: &Path, language: Option<String>) -> Result<FileState> {
+    fn create_file_state(
+        &self,
+        path: &Path,
+        language: Option<String>,
+        content_hash: Option<&str>,
+    ) -> Result<FileState> {
         let metadata = fs::metadata(path)
             .map_err(|e| ZseiError::Config(format!("Failed to get file metadata: {}", e)))?;
 
@@ -330,10 +849,14 @@ impl Project {
             .map_err(|e| ZseiError::Config(format!("Failed to get modified time: {}", e)))?
             .into();
 
-        let content =
-            fs::read(path).map_err(|e| ZseiError::Config(format!("Failed to read file: {}", e)))?;
-
-        let hash = format!("{:x}", md5::compute(&content));
+        let hash = match content_hash {
+            Some(content_hash) => content_hash.to_string(),
+            None => {
+                let content = fs::read(path)
+                    .map_err(|e| ZseiError::Config(format!("Failed to read file: {}", e)))?;
+                format!("{:x}", md5::compute(&content))
+            }
+        };
 
         Ok(FileState {
             path: path.to_path_buf(),
@@ -345,8 +868,19 @@ impl Project {
     }
 
     /// Get all tracked file states
-    pub fn get_tracked_files(&self) -> &HashMap<PathBuf, FileState> {
-        &self.state.analyzed_files
+    pub fn get_tracked_files(&self) -> HashMap<PathBuf, FileState> {
+        self.read_state().analyzed_files.clone()
+    }
+
+    /// Lock the state for reading
+    fn read_state(&self) -> std::sync::RwLockReadGuard<'_, ProjectState> {
+        // The state stays consistent even if a writer panicked
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the state for writing
+    fn write_state(&self) -> std::sync::RwLockWriteGuard<'_, ProjectState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Get project structure
@@ -556,3 +1090,134 @@ impl ProjectStructure {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zsei-project-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Project rooted at `root`
+    fn project(root: &Path) -> Project {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        Project::new(Arc::new(config)).unwrap()
+    }
+
+    /// Record files as analyzed at their current content
+    fn record_analyzed(project: &Project, files: &[&PathBuf]) {
+        project
+            .update_analyzed_files(files.iter().map(|path| (path.as_path(), None, None)))
+            .unwrap();
+    }
+
+    #[test]
+    fn touched_but_unmodified_files_are_skipped() {
+        let root = scratch_dir("touched");
+        let path = root.join("app.py");
+        fs::write(&path, "x = 1\n").unwrap();
+
+        let project = project(&root);
+        record_analyzed(&project, &[&path]);
+        assert_eq!(
+            project.read_state().analyzed_files[&path].hash,
+            format!("{:x}", md5::compute("x = 1\n"))
+        );
+
+        // Rewriting the same content only touches the file
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&path, "x = 1\n").unwrap();
+        assert!(project
+            .filter_files_to_analyze(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+
+        // A change of the same size is still found by the hash
+        fs::write(&path, "x = 2\n").unwrap();
+        assert_eq!(
+            project.filter_files_to_analyze(vec![path.clone()]).unwrap(),
+            vec![path.clone()]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn explicit_subsets_keep_only_their_changed_files() {
+        let root = scratch_dir("subset");
+        let (a, b, c) = (root.join("a.py"), root.join("b.py"), root.join("c.py"));
+        for path in [&a, &b, &c] {
+            fs::write(path, "x = 1\n").unwrap();
+        }
+
+        let project = project(&root);
+        record_analyzed(&project, &[&a, &b, &c]);
+
+        fs::write(&a, "x = 2\n").unwrap();
+        fs::write(&c, "x = 3\n").unwrap();
+        let d = root.join("d.py");
+        fs::write(&d, "x = 4\n").unwrap();
+
+        // Changed and new files of the subset are kept, the rest is not added
+        assert_eq!(
+            project
+                .filter_files_to_analyze(vec![a.clone(), b.clone(), d.clone()])
+                .unwrap(),
+            vec![a.clone(), d.clone()]
+        );
+
+        let mut changed = project.get_files_to_analyze(true).unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![a, c, d]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn analysis_and_indexing_states_are_recorded_separately() {
+        let root = scratch_dir("stages");
+        let path = root.join("app.py");
+        fs::write(&path, "x = 1\n").unwrap();
+
+        let project = project(&root);
+        record_analyzed(&project, &[&path]);
+        assert!(project
+            .filter_files_to_analyze(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            project.filter_files_to_index(vec![path.clone()]).unwrap(),
+            vec![path.clone()]
+        );
+
+        project
+            .update_indexed_files(std::iter::once((
+                path.as_path(),
+                Some("Python".to_string()),
+                None,
+            )))
+            .unwrap();
+        assert!(project
+            .filter_files_to_index(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+
+        // Both states are saved with the project
+        let reloaded = self::project(&root);
+        let state = reloaded.read_state();
+        assert!(state.analyzed_files.contains_key(&path));
+        assert_eq!(
+            state.indexed_files[&path].language.as_deref(),
+            Some("Python")
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,167 @@
+//! Embedding cache
+//!
+//! This module provides a least recently used cache of embedding vectors,
+//! so text that is embedded again, like a repeated search query, does not
+//! run the model again.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Cached vector
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    /// Embedding vector
+    vector: Vec<f32>,
+
+    /// Tick of the last use
+    used: u64,
+}
+
+/// Least recently used cache of embedding vectors, keyed by text
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingCache {
+    /// Maximum number of vectors; 0 disables the cache
+    capacity: usize,
+
+    /// Vectors by key
+    entries: HashMap<String, CacheEntry>,
+
+    /// Keys by the tick of their last use, oldest first
+    recency: BTreeMap<u64, String>,
+
+    /// Tick of the latest use
+    clock: u64,
+}
+
+impl EmbeddingCache {
+    /// Create a cache holding up to `capacity` vectors
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Get the maximum number of vectors
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached vectors
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the vector of a key, marking it as recently used
+    pub fn get(&mut self, key: &str) -> Option<Vec<f32>> {
+        let tick = self.tick();
+        let entry = self.entries.get_mut(key)?;
+
+        self.recency.remove(&entry.used);
+        self.recency.insert(tick, key.to_string());
+        entry.used = tick;
+
+        Some(entry.vector.clone())
+    }
+
+    /// Cache the vector of a key, evicting the least recently used vector
+    /// when the cache is full
+    pub fn insert(&mut self, key: &str, vector: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let tick = self.tick();
+        if let Some(previous) = self
+            .entries
+            .insert(key.to_string(), CacheEntry { vector, used: tick })
+        {
+            self.recency.remove(&previous.used);
+        }
+        self.recency.insert(tick, key.to_string());
+
+        while self.entries.len() > self.capacity {
+            match self.recency.pop_first() {
+                Some((_, oldest)) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Remove all vectors
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Advance the clock
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = EmbeddingCache::new(2);
+        cache.insert("a", vec![1.0]);
+        cache.insert("b", vec![2.0]);
+
+        // Using `a` makes `b` the oldest
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+        cache.insert("c", vec![3.0]);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+        assert_eq!(cache.get("c"), Some(vec![3.0]));
+    }
+
+    #[test]
+    fn reinserting_replaces_and_refreshes() {
+        let mut cache = EmbeddingCache::new(2);
+        cache.insert("a", vec![1.0]);
+        cache.insert("b", vec![2.0]);
+        cache.insert("a", vec![1.5]);
+        cache.insert("c", vec![3.0]);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), Some(vec![1.5]));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.recency.len(), cache.entries.len());
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let mut cache = EmbeddingCache::new(0);
+        cache.insert("a", vec![1.0]);
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("a"), None);
+    }
+
+    #[test]
+    fn clear_empties_the_cache() {
+        let mut cache = EmbeddingCache::new(4);
+        cache.insert("a", vec![1.0]);
+        cache.insert("b", vec![2.0]);
+        cache.clear();
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.capacity(), 4);
+        assert_eq!(cache.get("a"), None);
+
+        cache.insert("c", vec![3.0]);
+        assert_eq!(cache.get("c"), Some(vec![3.0]));
+    }
+}
@@ -0,0 +1,447 @@
+//! Code chunking
+//!
+//! This module splits an analyzed file into the parts that are embedded on
+//! their own: one chunk per function, method and type found by the
+//! analyzer, and sliding windows over the code outside of them. Windows
+//! hold up to `chunk_size` characters and overlap by up to `chunk_overlap`
+//! characters, always on line boundaries.
+
+use std::collections::HashSet;
+
+use crate::analyzers::common::FileAnalysis;
+use crate::embedding::LineRange;
+
+/// Windows with fewer non-blank characters are not embedded, like the
+/// braces and blank lines between functions
+const MIN_WINDOW_LEN: usize = 32;
+
+/// Kind of chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkKind {
+    /// Free function
+    Function,
+
+    /// Method of a type
+    Method,
+
+    /// Type, such as a class, struct or impl block
+    Type,
+
+    /// Window over code outside of any symbol
+    Window,
+}
+
+impl ChunkKind {
+    /// Get the name of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkKind::Function => "function",
+            ChunkKind::Method => "method",
+            ChunkKind::Type => "type",
+            ChunkKind::Window => "window",
+        }
+    }
+}
+
+/// Part of a file that is embedded on its own
+#[derive(Debug, Clone)]
+pub struct CodeChunk {
+    /// Name, unique within the file
+    pub name: String,
+
+    /// Kind
+    pub kind: ChunkKind,
+
+    /// Lines covered
+    pub lines: LineRange,
+
+    /// Text of the lines
+    pub content: String,
+}
+
+/// Split an analyzed file into chunks
+///
+/// Returns no chunks when the analysis has no content, when `chunk_size`
+/// is 0, or when a single window would cover the whole file.
+pub fn chunk_file(
+    analysis: &FileAnalysis,
+    chunk_size: usize,
+    chunk_overlap: usize,
+) -> Vec<CodeChunk> {
+    let content = match &analysis.content {
+        Some(content) if chunk_size > 0 => content,
+        _ => return Vec::new(),
+    };
+
+    let mut chunker = Chunker::new(content.lines().collect());
+    if chunker.lines.is_empty() {
+        return Vec::new();
+    }
+
+    // Methods come before free functions, since some analyzers list
+    // methods as functions too and the qualified name is more useful
+    for class in &analysis.classes {
+        chunker.add_symbol(
+            ChunkKind::Type,
+            format!("type {}", class.name),
+            class.start_line,
+            class.end_line,
+        );
+    }
+
+    for class in &analysis.classes {
+        for method in &class.methods {
+            chunker.add_symbol(
+                ChunkKind::Method,
+                format!("fn {}::{}", class.name, method.name),
+                method.start_line,
+                method.end_line,
+            );
+        }
+    }
+
+    for function in &analysis.functions {
+        chunker.add_symbol(
+            ChunkKind::Function,
+            format!("fn {}", function.name),
+            function.start_line,
+            function.end_line,
+        );
+    }
+
+    // Windows over the remaining code
+    let mut row = 0;
+    while row < chunker.lines.len() {
+        if chunker.covered[row] {
+            row += 1;
+            continue;
+        }
+
+        let start = row;
+        while row < chunker.lines.len() && !chunker.covered[row] {
+            row += 1;
+        }
+        chunker.add_windows(start, row, chunk_size, chunk_overlap);
+    }
+
+    chunker.chunks
+}
+
+/// State of the chunking of a file
+struct Chunker<'a> {
+    /// Lines of the file
+    lines: Vec<&'a str>,
+
+    /// Chunks found so far
+    chunks: Vec<CodeChunk>,
+
+    /// Names of the chunks found so far
+    names: HashSet<String>,
+
+    /// Row ranges of the symbols found so far
+    ranges: HashSet<(usize, usize)>,
+
+    /// Rows covered by a symbol
+    covered: Vec<bool>,
+}
+
+impl<'a> Chunker<'a> {
+    /// Create a chunker over the lines of a file
+    fn new(lines: Vec<&'a str>) -> Self {
+        let covered = vec![false; lines.len()];
+        Self {
+            lines,
+            chunks: Vec::new(),
+            names: HashSet::new(),
+            ranges: HashSet::new(),
+            covered,
+        }
+    }
+
+    /// Add a symbol spanning 0-based rows
+    ///
+    /// Symbols spanning the same rows as an earlier one are skipped, and
+    /// names already taken get the first line appended.
+    fn add_symbol(&mut self, kind: ChunkKind, name: String, start_row: usize, end_row: usize) {
+        if start_row >= self.lines.len() {
+            return;
+        }
+        let end_row = end_row.clamp(start_row, self.lines.len() - 1);
+
+        if !self.ranges.insert((start_row, end_row)) {
+            return;
+        }
+
+        let name = if self.names.contains(&name) {
+            format!("{} (line {})", name, start_row + 1)
+        } else {
+            name
+        };
+        self.names.insert(name.clone());
+
+        for covered in &mut self.covered[start_row..=end_row] {
+            *covered = true;
+        }
+
+        self.chunks.push(CodeChunk {
+            name,
+            kind,
+            lines: LineRange::from_rows(start_row, end_row),
+            content: self.lines[start_row..=end_row].join("\n"),
+        });
+    }
+
+    /// Add sliding windows over the rows from `start` up to `end`
+    fn add_windows(&mut self, start: usize, end: usize, chunk_size: usize, chunk_overlap: usize) {
+        let mut first = start;
+
+        while first < end {
+            // Grow the window up to the chunk size, keeping at least a line
+            let mut last = first;
+            let mut len = self.lines[first].len() + 1;
+            while last + 1 < end && len + self.lines[last + 1].len() < chunk_size {
+                last += 1;
+                len += self.lines[last].len() + 1;
+            }
+
+            self.add_window(first, last);
+            if last + 1 >= end {
+                break;
+            }
+
+            // Start the next window up to the overlap before this one ends
+            let mut next = last + 1;
+            let mut overlap = 0;
+            while next - 1 > first && overlap + self.lines[next - 1].len() < chunk_overlap {
+                next -= 1;
+                overlap += self.lines[next].len() + 1;
+            }
+            first = next;
+        }
+    }
+
+    /// Add a window over 0-based rows, unless it holds too little code or
+    /// covers the whole file
+    fn add_window(&mut self, first: usize, last: usize) {
+        if first == 0 && last + 1 == self.lines.len() {
+            return;
+        }
+
+        let content = self.lines[first..=last].join("\n");
+        let code_len = content.chars().filter(|c| !c.is_whitespace()).count();
+        if code_len < MIN_WINDOW_LEN {
+            return;
+        }
+
+        let lines = LineRange::from_rows(first, last);
+        self.chunks.push(CodeChunk {
+            name: format!("lines {}", lines),
+            kind: ChunkKind::Window,
+            lines,
+            content,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::common::{Class, ClassMetrics, CodeMetrics, Function, FunctionMetrics};
+    use std::path::PathBuf;
+
+    /// Function spanning 0-based rows
+    fn function(name: &str, start_line: usize, end_line: usize) -> Function {
+        Function {
+            name: name.to_string(),
+            signature: format!("fn {}()", name),
+            start_line,
+            end_line,
+            body: None,
+            return_type: None,
+            parameters: Vec::new(),
+            is_public: true,
+            metrics: FunctionMetrics {
+                loc: end_line - start_line + 1,
+                complexity: 1,
+                parameter_count: 0,
+                cognitive_complexity: 0,
+            },
+        }
+    }
+
+    /// Class spanning 0-based rows
+    fn class(name: &str, start_line: usize, end_line: usize, methods: Vec<Function>) -> Class {
+        Class {
+            name: name.to_string(),
+            start_line,
+            end_line,
+            methods,
+            properties: Vec::new(),
+            base_classes: Vec::new(),
+            is_public: true,
+            metrics: ClassMetrics {
+                loc: end_line - start_line + 1,
+                method_count: 0,
+                property_count: 0,
+                inheritance_depth: 0,
+                cohesion: 1.0,
+            },
+        }
+    }
+
+    /// Analysis of numbered lines of code with the given symbols
+    fn analysis(line_count: usize, classes: Vec<Class>, functions: Vec<Function>) -> FileAnalysis {
+        let content: Vec<String> = (1..=line_count)
+            .map(|line| format!("let value_{} = compute_something({});", line, line))
+            .collect();
+
+        FileAnalysis {
+            path: PathBuf::from("src/sample.rs"),
+            language: "rust".to_string(),
+            content: Some(content.join("\n")),
+            functions,
+            classes,
+            variables: Vec::new(),
+            imports: Vec::new(),
+            metrics: CodeMetrics {
+                loc: line_count,
+                comment_lines: 0,
+                function_count: 0,
+                class_count: 0,
+                import_count: 0,
+                variable_count: 0,
+                complexity: 0,
+                maintainability_index: 100.0,
+            },
+        }
+    }
+
+    /// Check that a chunk holds exactly the lines of its range
+    fn assert_content_matches_lines(analysis: &FileAnalysis, chunk: &CodeChunk) {
+        let lines: Vec<&str> = analysis.content.as_deref().unwrap().lines().collect();
+        assert_eq!(
+            chunk.content,
+            lines[chunk.lines.start - 1..chunk.lines.end].join("\n"),
+            "{}",
+            chunk.name
+        );
+    }
+
+    #[test]
+    fn symbols_get_one_based_line_ranges() {
+        let analysis = analysis(
+            40,
+            vec![class("Parser", 10, 19, vec![function("parse", 12, 15)])],
+            vec![function("main", 2, 5)],
+        );
+        let chunks = chunk_file(&analysis, 200, 50);
+
+        let find = |name: &str| chunks.iter().find(|chunk| chunk.name == name).unwrap();
+
+        let parser = find("type Parser");
+        assert_eq!(parser.kind, ChunkKind::Type);
+        assert_eq!((parser.lines.start, parser.lines.end), (11, 20));
+
+        let parse = find("fn Parser::parse");
+        assert_eq!(parse.kind, ChunkKind::Method);
+        assert_eq!((parse.lines.start, parse.lines.end), (13, 16));
+
+        let main = find("fn main");
+        assert_eq!(main.kind, ChunkKind::Function);
+        assert_eq!((main.lines.start, main.lines.end), (3, 6));
+        assert_eq!(
+            main.content.lines().next().unwrap(),
+            "let value_3 = compute_something(3);"
+        );
+
+        for chunk in &chunks {
+            assert_content_matches_lines(&analysis, chunk);
+        }
+    }
+
+    #[test]
+    fn duplicate_symbols_are_skipped_or_renamed() {
+        let analysis = analysis(
+            30,
+            vec![class("Parser", 0, 9, vec![function("parse", 2, 4)])],
+            vec![
+                // Listed as a method already
+                function("parse", 2, 4),
+                function("helper", 12, 13),
+                function("helper", 20, 21),
+                // Past the end of the file
+                function("tail", 28, 99),
+                function("gone", 45, 50),
+            ],
+        );
+        let chunks = chunk_file(&analysis, 200, 50);
+        let names: Vec<&str> = chunks
+            .iter()
+            .filter(|chunk| chunk.kind != ChunkKind::Window)
+            .map(|chunk| chunk.name.as_str())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "type Parser",
+                "fn Parser::parse",
+                "fn helper",
+                "fn helper (line 21)",
+                "fn tail"
+            ]
+        );
+
+        let tail = chunks.iter().find(|chunk| chunk.name == "fn tail").unwrap();
+        assert_eq!((tail.lines.start, tail.lines.end), (29, 30));
+    }
+
+    #[test]
+    fn windows_cover_code_outside_symbols_with_overlap() {
+        let analysis = analysis(60, Vec::new(), vec![function("middle", 20, 29)]);
+        let chunks = chunk_file(&analysis, 400, 100);
+
+        let windows: Vec<&CodeChunk> = chunks
+            .iter()
+            .filter(|chunk| chunk.kind == ChunkKind::Window)
+            .collect();
+        assert!(windows.len() > 2);
+
+        let mut covered = [false; 60];
+        for window in &windows {
+            assert_content_matches_lines(&analysis, window);
+            assert_eq!(window.name, format!("lines {}", window.lines));
+            assert!(window.content.len() <= 400);
+
+            // Windows stay on one side of the function
+            assert!(window.lines.end <= 20 || window.lines.start >= 31);
+            for line in window.lines.start..=window.lines.end {
+                covered[line - 1] = true;
+            }
+        }
+
+        let outside = (0..60).filter(|row| !(20..=29).contains(row));
+        assert!(outside.into_iter().all(|row| covered[row]));
+
+        // Consecutive windows share lines
+        for pair in windows.windows(2) {
+            if pair[1].lines.start > 20 && pair[0].lines.end <= 20 {
+                continue;
+            }
+            assert!(pair[1].lines.start <= pair[0].lines.end);
+            assert!(pair[1].lines.start > pair[0].lines.start);
+        }
+    }
+
+    #[test]
+    fn small_files_are_not_chunked() {
+        // A single window would cover the whole file
+        assert!(chunk_file(&analysis(3, Vec::new(), Vec::new()), 1000, 100).is_empty());
+
+        assert!(chunk_file(&analysis(30, Vec::new(), Vec::new()), 0, 0).is_empty());
+
+        let mut empty = analysis(1, Vec::new(), Vec::new());
+        empty.content = None;
+        assert!(chunk_file(&empty, 1000, 100).is_empty());
+    }
+}
@@ -8,13 +8,30 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
-use crate::analyzers::common::{Class, FileAnalysis, Function, Import, Variable};
-use crate::core::config::EmbeddingConfig;
+use crate::analyzers::common::{Class, CodeMetrics, FileAnalysis, Function, Import, Variable};
+use crate::core::config::{EmbeddingBackend, EmbeddingConfig};
+use crate::embedding::hashing::HashingEncoder;
+use crate::embedding::model::EmbeddingModel;
 use crate::embedding::{Embedding, EmbeddingGenerator, EmbeddingMetadata, EmbeddingType};
 use crate::errors::{Result, ZseiError};
 use crate::llm::prompt::PromptManager;
 use crate::llm::Model;
 
+pub mod chunk;
+
+use chunk::CodeChunk;
+
+/// Share of the norm of a hashing backend embedding given to each block of
+/// features: the syntactic, semantic, structural and relationship features,
+/// in order
+///
+/// Queries only have semantic features, so their similarity to code is the
+/// cosine of the semantic features scaled by the square root of its share.
+/// The semantic share dominates so that good matches stay above the default
+/// minimum score. The other backends concatenate the raw blocks, so their
+/// existing indexes keep matching newly generated embeddings.
+const BLOCK_WEIGHTS: [f32; 4] = [0.1, 0.7, 0.1, 0.1];
+
 /// Code embedding generator
 pub struct CodeEmbeddingGenerator {
     /// LLM for enhanced embedding generation
@@ -25,18 +42,61 @@ pub struct CodeEmbeddingGenerator {
 
     /// Prompt manager
     prompt_manager: PromptManager,
+
+    /// Encoder for the semantic features of the hashing backend
+    hashing: Option<HashingEncoder>,
+
+    /// Sentence embedding model for the semantic features of the ONNX
+    /// backend
+    model: Option<Arc<dyn EmbeddingModel>>,
 }
 
 impl CodeEmbeddingGenerator {
     /// Create a new code embedding generator
-    pub fn new(llm: Arc<dyn Model>, config: EmbeddingConfig) -> Self {
+    ///
+    /// The sentence embedding model is only used by the ONNX backend.
+    pub fn new(
+        llm: Arc<dyn Model>,
+        model: Option<Arc<dyn EmbeddingModel>>,
+        config: EmbeddingConfig,
+    ) -> Self {
+        let hashing = match config.backend {
+            EmbeddingBackend::Hashing => Some(HashingEncoder::new(config.dimension / 4)),
+            EmbeddingBackend::Llm | EmbeddingBackend::Onnx => None,
+        };
+        let model = match config.backend {
+            EmbeddingBackend::Onnx => model,
+            EmbeddingBackend::Llm | EmbeddingBackend::Hashing => None,
+        };
+
         Self {
             llm,
             config,
             prompt_manager: PromptManager::new(),
+            hashing,
+            model,
         }
     }
 
+    /// Get the dimension of the semantic features
+    ///
+    /// A sentence embedding model keeps its own dimension, since truncating
+    /// its vectors would lose most of their meaning; the other features take
+    /// a quarter of the configured dimension each.
+    fn semantic_dimension(&self) -> usize {
+        match &self.model {
+            Some(model) => model.dimension(),
+            None => self.config.dimension / 4,
+        }
+    }
+
+    /// Get the sentence embedding model of the ONNX backend
+    fn embedding_model(&self) -> Result<&Arc<dyn EmbeddingModel>> {
+        self.model.as_ref().ok_or_else(|| {
+            ZseiError::Embedding("The ONNX embedding backend has no model loaded".to_string())
+        })
+    }
+
     /// Generate syntactic features from code analysis
     async fn generate_syntactic_features(&self, analysis: &FileAnalysis) -> Result<Vec<f32>> {
         // Extract syntactic features from code analysis
@@ -79,6 +139,23 @@ impl CodeEmbeddingGenerator {
             analysis.path.display()
         );
 
+        // Without an LLM, hash the identifiers and syntax of the code
+        if let Some(hashing) = &self.hashing {
+            return Ok(hashing.encode_code(
+                analysis.content.as_deref().unwrap_or_default(),
+                &analysis.language,
+                &analysis.path,
+            ));
+        }
+
+        // Or embed it with the sentence embedding model
+        if self.config.backend == EmbeddingBackend::Onnx {
+            return self
+                .embedding_model()?
+                .embed(analysis.content.as_deref().unwrap_or_default())
+                .await;
+        }
+
         // Create prompt for semantic analysis
         let mut variables = HashMap::new();
         variables.insert(
@@ -538,6 +615,92 @@ impl EmbeddingGenerator for CodeEmbeddingGenerator {
     async fn generate(&self, input: &Self::Input) -> Result<Embedding> {
         info!("Generating embedding for {}", input.path.display());
 
+        self.embed_analysis(input).await
+    }
+
+    async fn generate_chunks(&self, input: &Self::Input) -> Result<Vec<Embedding>> {
+        let chunks = chunk::chunk_file(input, self.config.chunk_size, self.config.chunk_overlap);
+        info!(
+            "Generating {} chunk embeddings for {}",
+            chunks.len(),
+            input.path.display()
+        );
+
+        // A sentence embedding model embeds all chunks in batches
+        let semantic_features = if self.config.backend == EmbeddingBackend::Onnx {
+            let contents: Vec<String> = chunks.iter().map(|chunk| chunk.content.clone()).collect();
+            self.embedding_model()?
+                .embed_batch(&contents)
+                .await?
+                .into_iter()
+                .map(Some)
+                .collect()
+        } else {
+            vec![None; chunks.len()]
+        };
+
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        for (chunk, semantic) in chunks.iter().zip(semantic_features) {
+            let mut embedding = self
+                .embed_analysis_with(&chunk_analysis(input, chunk), semantic)
+                .await?;
+
+            embedding.metadata.chunk = Some(chunk.name.clone());
+            embedding.metadata.lines = Some(chunk.lines);
+            embedding
+                .metadata
+                .attributes
+                .insert("chunk_kind".to_string(), chunk.kind.as_str().to_string());
+
+            embeddings.push(embedding);
+        }
+
+        Ok(embeddings)
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        let quarter = self.config.dimension / 4;
+        let semantic = match (&self.hashing, self.config.backend) {
+            (Some(hashing), _) => hashing.encode_text(query),
+            (None, EmbeddingBackend::Onnx) => self.embedding_model()?.embed(query).await?,
+            (None, _) => {
+                let mut features = self.llm.embed(query).await?;
+                self.pad_or_truncate(&mut features, quarter)?;
+                features
+            }
+        };
+
+        // Queries only match the semantic features, so the other features
+        // of the query stay zero and the query has the dimension of the
+        // generated embeddings (see `BLOCK_WEIGHTS`)
+        let mut vector = vec![0.0; self.dimension()];
+        vector[quarter..quarter + semantic.len()].copy_from_slice(&semantic);
+
+        Ok(vector)
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.dimension / 4 * 3 + self.semantic_dimension()
+    }
+
+    fn embedding_type(&self) -> EmbeddingType {
+        EmbeddingType::Code
+    }
+}
+
+impl CodeEmbeddingGenerator {
+    /// Embed a file, or the part of a file described by a narrowed analysis
+    async fn embed_analysis(&self, input: &FileAnalysis) -> Result<Embedding> {
+        self.embed_analysis_with(input, None).await
+    }
+
+    /// Embed a narrowed analysis, with semantic features computed ahead of
+    /// time if given
+    async fn embed_analysis_with(
+        &self,
+        input: &FileAnalysis,
+        semantic_features: Option<Vec<f32>>,
+    ) -> Result<Embedding> {
         // 1. Generate feature vectors
         let syntactic_features = self.generate_syntactic_features(input).await?;
         debug!(
@@ -545,7 +708,10 @@ impl EmbeddingGenerator for CodeEmbeddingGenerator {
             syntactic_features.len()
         );
 
-        let semantic_features = self.generate_semantic_features(input).await?;
+        let semantic_features = match semantic_features {
+            Some(features) => features,
+            None => self.generate_semantic_features(input).await?,
+        };
         debug!(
             "Generated semantic features: {} dimensions",
             semantic_features.len()
@@ -563,12 +729,24 @@ impl EmbeddingGenerator for CodeEmbeddingGenerator {
             relationship_features.len()
         );
 
-        // 2. Combine vectors
-        let mut vector = Vec::with_capacity(self.config.dimension);
-        vector.extend_from_slice(&syntactic_features);
-        vector.extend_from_slice(&semantic_features);
-        vector.extend_from_slice(&structural_features);
-        vector.extend_from_slice(&relationship_features);
+        // 2. Combine vectors. The hashing backend normalizes each block on
+        // its own so that no block outweighs the others by the scale of its
+        // raw features
+        let blocks = [
+            syntactic_features,
+            semantic_features,
+            structural_features,
+            relationship_features,
+        ];
+        let mut vector = Vec::with_capacity(self.dimension());
+        for (mut block, weight) in blocks.into_iter().zip(BLOCK_WEIGHTS) {
+            if self.hashing.is_some() {
+                crate::embedding::utils::normalize(&mut block);
+                let scale = weight.sqrt();
+                block.iter_mut().for_each(|value| *value *= scale);
+            }
+            vector.extend(block);
+        }
 
         // 3. Normalize vector
         crate::embedding::utils::normalize(&mut vector);
@@ -583,6 +761,8 @@ impl EmbeddingGenerator for CodeEmbeddingGenerator {
         let metadata = EmbeddingMetadata {
             source_path: input.path.clone(),
             content_hash,
+            chunk: None,
+            lines: None,
             language: Some(input.language.clone()),
             timestamp: chrono::Utc::now(),
             attributes: self.extract_metadata_attributes(input),
@@ -598,16 +778,6 @@ impl EmbeddingGenerator for CodeEmbeddingGenerator {
         Ok(embedding)
     }
 
-    fn dimension(&self) -> usize {
-        self.config.dimension
-    }
-
-    fn embedding_type(&self) -> EmbeddingType {
-        EmbeddingType::Code
-    }
-}
-
-impl CodeEmbeddingGenerator {
     /// Extract metadata attributes
     fn extract_metadata_attributes(&self, analysis: &FileAnalysis) -> HashMap<String, String> {
         let mut attributes = HashMap::new();
@@ -654,3 +824,66 @@ impl CodeEmbeddingGenerator {
         attributes
     }
 }
+
+/// Narrow the analysis of a file to a chunk
+///
+/// The chunk keeps the symbols and variables within its lines and the
+/// imports of the file, which form its context. Metrics are recomputed for
+/// the chunk where the analysis allows it.
+fn chunk_analysis(analysis: &FileAnalysis, chunk: &CodeChunk) -> FileAnalysis {
+    let start_row = chunk.lines.start - 1;
+    let end_row = chunk.lines.end - 1;
+    let within = |start: usize, end: usize| start >= start_row && end <= end_row;
+
+    // Methods may be listed both as functions and under their class
+    let mut ranges = std::collections::HashSet::new();
+    let functions: Vec<Function> = analysis
+        .functions
+        .iter()
+        .chain(analysis.classes.iter().flat_map(|class| &class.methods))
+        .filter(|function| within(function.start_line, function.end_line))
+        .filter(|function| ranges.insert((function.start_line, function.end_line)))
+        .cloned()
+        .collect();
+
+    let classes: Vec<Class> = analysis
+        .classes
+        .iter()
+        .filter(|class| within(class.start_line, class.end_line))
+        .cloned()
+        .collect();
+
+    let variables: Vec<Variable> = analysis
+        .variables
+        .iter()
+        .filter(|variable| within(variable.line, variable.line))
+        .cloned()
+        .collect();
+
+    let loc = chunk.lines.len();
+    let metrics = CodeMetrics {
+        loc,
+        comment_lines: analysis.metrics.comment_lines * loc / analysis.metrics.loc.max(1),
+        function_count: functions.len(),
+        class_count: classes.len(),
+        import_count: analysis.imports.len(),
+        variable_count: variables.len(),
+        complexity: functions
+            .iter()
+            .map(|function| function.metrics.complexity)
+            .sum::<usize>()
+            .max(1),
+        maintainability_index: analysis.metrics.maintainability_index,
+    };
+
+    FileAnalysis {
+        path: analysis.path.clone(),
+        language: analysis.language.clone(),
+        content: Some(chunk.content.clone()),
+        functions,
+        classes,
+        variables,
+        imports: analysis.imports.clone(),
+        metrics,
+    }
+}
@@ -0,0 +1,283 @@
+//! Feature hashing embeddings
+//!
+//! This module embeds code and queries without a model. Each feature is
+//! hashed to one of the vector's buckets with a sign taken from the hash,
+//! so unrelated features cancel out on average instead of piling up.
+//!
+//! Text yields the identifier sub-tokens of the lexical index, bigrams of
+//! consecutive tokens and character trigrams of each token. Code also
+//! yields the kinds of its syntax tree nodes, alone and under their parent,
+//! when the language has a grammar. Hashing uses FNV-1a, so the same input
+//! gives the same vector on every machine and run.
+
+use std::path::Path;
+use tree_sitter::{Language, Parser};
+
+use crate::embedding::utils::normalize;
+use crate::indexing::lexical::tokenize;
+
+/// Weight of an identifier token
+const TOKEN_WEIGHT: f32 = 1.0;
+
+/// Weight of a pair of consecutive tokens
+const BIGRAM_WEIGHT: f32 = 0.5;
+
+/// Weight of a character trigram of a token
+const TRIGRAM_WEIGHT: f32 = 0.25;
+
+/// Weight of a syntax node kind
+const NODE_WEIGHT: f32 = 0.5;
+
+/// FNV-1a offset basis
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Embeds text and code by feature hashing
+#[derive(Debug, Clone)]
+pub struct HashingEncoder {
+    /// Number of buckets
+    dimension: usize,
+}
+
+impl HashingEncoder {
+    /// Create an encoder producing vectors of a dimension
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension }
+    }
+
+    /// Get the vector dimension
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Embed text, such as a search query
+    pub fn encode_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimension];
+        self.add_text(&mut vector, text);
+        finish(&mut vector);
+        vector
+    }
+
+    /// Embed code, including its syntax when the language has a grammar
+    ///
+    /// The language is the name reported by the analyzers; the path tells
+    /// dialects like TSX apart.
+    pub fn encode_code(&self, code: &str, language: &str, path: &Path) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimension];
+        self.add_text(&mut vector, code);
+        if let Some(grammar) = grammar_for(language, path) {
+            self.add_syntax(&mut vector, code, &grammar);
+        }
+        finish(&mut vector);
+        vector
+    }
+
+    /// Add the token, bigram and trigram features of text
+    fn add_text(&self, vector: &mut [f32], text: &str) {
+        let tokens = tokenize(text);
+
+        for token in &tokens {
+            self.add(vector, "t", token, TOKEN_WEIGHT);
+
+            let chars: Vec<char> = format!("^{}$", token).chars().collect();
+            for trigram in chars.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add(vector, "g", &trigram, TRIGRAM_WEIGHT);
+            }
+        }
+
+        for pair in tokens.windows(2) {
+            self.add(
+                vector,
+                "b",
+                &format!("{} {}", pair[0], pair[1]),
+                BIGRAM_WEIGHT,
+            );
+        }
+    }
+
+    /// Add the node kind features of code parsed with a grammar
+    fn add_syntax(&self, vector: &mut [f32], code: &str, grammar: &Language) {
+        let mut parser = Parser::new();
+        if parser.set_language(grammar).is_err() {
+            return;
+        }
+        let tree = match parser.parse(code, None) {
+            Some(tree) => tree,
+            None => return,
+        };
+
+        // Depth-first walk, keeping the kinds of the named ancestors
+        let mut cursor = tree.walk();
+        let mut ancestors: Vec<&str> = Vec::new();
+        loop {
+            let node = cursor.node();
+            let named = node.is_named();
+            if named {
+                self.add(vector, "n", node.kind(), NODE_WEIGHT);
+                if let Some(parent) = ancestors.last() {
+                    let pair = format!("{}>{}", parent, node.kind());
+                    self.add(vector, "p", &pair, NODE_WEIGHT);
+                }
+            }
+
+            if cursor.goto_first_child() {
+                if named {
+                    ancestors.push(node.kind());
+                }
+                continue;
+            }
+
+            loop {
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+                if !cursor.goto_parent() {
+                    return;
+                }
+                if cursor.node().is_named() {
+                    ancestors.pop();
+                }
+            }
+        }
+    }
+
+    /// Add a feature to its bucket, with the sign given by its hash
+    fn add(&self, vector: &mut [f32], namespace: &str, feature: &str, weight: f32) {
+        if vector.is_empty() {
+            return;
+        }
+
+        let hash = fnv1a(&[namespace.as_bytes(), b":", feature.as_bytes()]);
+        let bucket = (hash % vector.len() as u64) as usize;
+        if hash >> 63 == 0 {
+            vector[bucket] += weight;
+        } else {
+            vector[bucket] -= weight;
+        }
+    }
+}
+
+/// Damp the feature counts and normalize the vector
+///
+/// Counts are damped logarithmically, so long files are not dominated by
+/// their most repeated identifiers.
+fn finish(vector: &mut [f32]) {
+    for value in vector.iter_mut() {
+        *value = value.signum() * value.abs().ln_1p();
+    }
+    normalize(vector);
+}
+
+/// Hash byte strings with FNV-1a
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for part in parts {
+        for &byte in *part {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Get the grammar for a language name reported by the analyzers
+fn grammar_for(language: &str, path: &Path) -> Option<Language> {
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    let grammar = match language {
+        "Rust" => tree_sitter_rust::LANGUAGE.into(),
+        "Python" => tree_sitter_python::LANGUAGE.into(),
+        "Go" => tree_sitter_go::LANGUAGE.into(),
+        "Java" => tree_sitter_java::LANGUAGE.into(),
+        "Kotlin" => tree_sitter_kotlin_ng::LANGUAGE.into(),
+        "C" if extension == Some("c") => tree_sitter_c::LANGUAGE.into(),
+        "C" | "C++" => tree_sitter_cpp::LANGUAGE.into(),
+        "JavaScript" => tree_sitter_javascript::LANGUAGE.into(),
+        "TypeScript" if extension == Some("tsx") => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "TypeScript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        _ => return None,
+    };
+
+    Some(grammar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::utils::cosine_similarity;
+
+    const LOAD_RECORDS: &str = "def load_records(path):\n    \
+                                with open(path) as handle:\n        \
+                                return [line.strip() for line in handle]\n";
+
+    const READ_RECORDS: &str = "def read_records(file_path):\n    \
+                                with open(file_path) as records:\n        \
+                                return [record.strip() for record in records]\n";
+
+    const RENDER_CHART: &str = "class Chart:\n    \
+                                def draw(self, canvas, colors):\n        \
+                                canvas.fill(colors[0])\n";
+
+    /// Euclidean norm of a vector
+    fn norm(vector: &[f32]) -> f32 {
+        vector.iter().map(|value| value * value).sum::<f32>().sqrt()
+    }
+
+    #[test]
+    fn encoding_is_deterministic() {
+        let path = Path::new("records.py");
+        let first = HashingEncoder::new(256).encode_code(LOAD_RECORDS, "Python", path);
+        let second = HashingEncoder::new(256).encode_code(LOAD_RECORDS, "Python", path);
+        assert_eq!(first, second);
+
+        let encoder = HashingEncoder::new(256);
+        assert_eq!(
+            encoder.encode_text("load records"),
+            encoder.encode_text("load records")
+        );
+
+        // FNV-1a does not depend on the process or platform
+        assert_eq!(fnv1a(&[b"a"]), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(&[b"a", b"b"]), fnv1a(&[b"ab"]));
+    }
+
+    #[test]
+    fn vectors_have_the_encoder_dimension_and_unit_norm() {
+        for dimension in [16, 64, 384] {
+            let encoder = HashingEncoder::new(dimension);
+            assert_eq!(encoder.dimension(), dimension);
+
+            for vector in [
+                encoder.encode_text("load records from a file"),
+                encoder.encode_code(LOAD_RECORDS, "Python", Path::new("records.py")),
+                encoder.encode_code(LOAD_RECORDS, "Unknown", Path::new("records.txt")),
+            ] {
+                assert_eq!(vector.len(), dimension);
+                assert!((norm(&vector) - 1.0).abs() < 1e-5);
+            }
+        }
+
+        // Text without features stays zero instead of dividing by zero
+        let empty = HashingEncoder::new(64).encode_text("  ");
+        assert!(empty.iter().all(|&value| value == 0.0));
+        assert!(HashingEncoder::new(0).encode_text("records").is_empty());
+    }
+
+    #[test]
+    fn similar_code_scores_above_unrelated_code() {
+        let encoder = HashingEncoder::new(384);
+        let path = Path::new("records.py");
+        let load = encoder.encode_code(LOAD_RECORDS, "Python", path);
+        let read = encoder.encode_code(READ_RECORDS, "Python", path);
+        let chart = encoder.encode_code(RENDER_CHART, "Python", path);
+
+        assert!(cosine_similarity(&load, &read) > cosine_similarity(&load, &chart));
+        assert!(cosine_similarity(&read, &load) > cosine_similarity(&read, &chart));
+
+        let query = encoder.encode_text("load records");
+        assert!(cosine_similarity(&query, &load) > cosine_similarity(&query, &chart));
+    }
+}
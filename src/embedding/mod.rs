@@ -6,10 +6,13 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+pub mod cache;
 pub mod code;
+pub mod hashing;
+pub mod model;
 // Future modalities will be added as separate modules:
 // pub mod image;
 // pub mod audio;
@@ -18,6 +21,7 @@ pub mod code;
 use crate::core::config::Config;
 use crate::errors::{Result, ZseiError};
 use crate::llm::Model;
+use model::EmbeddingModel;
 
 /// Embedding type
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +61,15 @@ pub struct EmbeddingMetadata {
     /// Source content hash
     pub content_hash: String,
 
+    /// Chunk or symbol of the source, if the embedding does not cover the
+    /// whole source
+    #[serde(default)]
+    pub chunk: Option<String>,
+
+    /// Lines of the source covered by the chunk
+    #[serde(default)]
+    pub lines: Option<LineRange>,
+
     /// Source language
     pub language: Option<String>,
 
@@ -67,6 +80,81 @@ pub struct EmbeddingMetadata {
     pub attributes: HashMap<String, String>,
 }
 
+impl EmbeddingMetadata {
+    /// Get the ID of the embedding
+    ///
+    /// See [`embedding_id`].
+    pub fn id(&self) -> String {
+        embedding_id(&self.source_path, self.chunk.as_deref(), &self.content_hash)
+    }
+}
+
+/// Range of lines in a source, 1-based and inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    /// First line
+    pub start: usize,
+
+    /// Last line
+    pub end: usize,
+}
+
+impl LineRange {
+    /// Create a range from 0-based rows, as reported by the analyzers
+    pub fn from_rows(start_row: usize, end_row: usize) -> Self {
+        Self {
+            start: start_row + 1,
+            end: end_row.max(start_row) + 1,
+        }
+    }
+
+    /// Get the number of lines
+    pub fn len(&self) -> usize {
+        (self.end + 1).saturating_sub(self.start)
+    }
+
+    /// Check if the range covers no lines
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the lines of a text within the range
+    pub fn extract(&self, text: &str) -> String {
+        text.lines()
+            .skip(self.start.saturating_sub(1))
+            .take(self.len())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl std::fmt::Display for LineRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Build the ID of an embedding
+///
+/// The ID combines a hash of the location, meaning the source path and
+/// chunk, with the content hash. Identical content at different locations
+/// gets different IDs, while a location keeps its ID until its content
+/// changes.
+pub fn embedding_id(path: &Path, chunk: Option<&str>, content_hash: &str) -> String {
+    let mut location = path.to_string_lossy().into_owned();
+    if let Some(chunk) = chunk {
+        location.push('#');
+        location.push_str(chunk);
+    }
+
+    format!("{:x}:{}", md5::compute(location.as_bytes()), content_hash)
+}
+
+/// Hash content the way embedding generators do
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", md5::compute(content.as_bytes()))
+}
+
 /// Embedding generator trait
 #[async_trait]
 pub trait EmbeddingGenerator: Send + Sync {
@@ -76,6 +164,18 @@ pub trait EmbeddingGenerator: Send + Sync {
     /// Generate embeddings for the input
     async fn generate(&self, input: &Self::Input) -> Result<Embedding>;
 
+    /// Generate embeddings for the parts of the input, such as the
+    /// functions of a file
+    ///
+    /// Each embedding names its chunk and the lines it covers. Inputs
+    /// without parts have no chunk embeddings.
+    async fn generate_chunks(&self, _input: &Self::Input) -> Result<Vec<Embedding>> {
+        Ok(Vec::new())
+    }
+
+    /// Embed a search query so it can be compared with generated embeddings
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>>;
+
     /// Get the embedding dimension
     fn dimension(&self) -> usize;
 
@@ -88,14 +188,24 @@ pub struct EmbeddingFactory {
     /// LLM for enhanced embedding generation
     llm: Arc<dyn Model>,
 
+    /// Sentence embedding model, for the ONNX backend
+    model: Option<Arc<dyn EmbeddingModel>>,
+
     /// Configuration
     config: Arc<Config>,
 }
 
 impl EmbeddingFactory {
     /// Create a new embedding factory
-    pub fn new(llm: Arc<dyn Model>, config: Arc<Config>) -> Self {
-        Self { llm, config }
+    ///
+    /// The sentence embedding model is shared by every generator, so it is
+    /// loaded once.
+    pub fn new(
+        llm: Arc<dyn Model>,
+        model: Option<Arc<dyn EmbeddingModel>>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self { llm, model, config }
     }
 
     /// Create a code embedding generator
@@ -104,6 +214,7 @@ impl EmbeddingFactory {
     ) -> Box<dyn EmbeddingGenerator<Input = crate::analyzers::common::FileAnalysis>> {
         Box::new(code::CodeEmbeddingGenerator::new(
             self.llm.clone(),
+            self.model.clone(),
             self.config.embedding.clone(),
         ))
     }
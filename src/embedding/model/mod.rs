@@ -0,0 +1,49 @@
+//! Sentence embedding models
+//!
+//! This module provides models that embed text for retrieval, separately
+//! from the generative LLM. Encoder models trained on sentence pairs place
+//! a query close to the code that answers it, which the pooled output of a
+//! causal LM does not.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub mod onnx;
+
+use crate::core::config::EmbeddingConfig;
+use crate::errors::{Result, ZseiError};
+
+/// Sentence embedding model trait
+#[async_trait]
+pub trait EmbeddingModel: Send + Sync {
+    /// Embed texts, returning one normalized vector per text in order
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a single text
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| ZseiError::Embedding("Embedding model returned no vector".to_string()))
+    }
+
+    /// Get the dimension of the vectors
+    fn dimension(&self) -> usize;
+
+    /// Get the maximum number of tokens of an input; longer inputs are
+    /// truncated
+    fn max_sequence_length(&self) -> usize;
+}
+
+/// Load the sentence embedding model of a configuration
+pub fn create_model(config: &EmbeddingConfig) -> Result<Arc<dyn EmbeddingModel>> {
+    let model_path = config.model.find_model_path().ok_or_else(|| {
+        ZseiError::Embedding(
+            "No embedding model found; set embedding.model.model_path or ZSEI_EMBEDDING_MODEL_PATH"
+                .to_string(),
+        )
+    })?;
+
+    let model = onnx::OnnxEmbeddingModel::new(model_path, &config.model, config.use_gpu)?;
+    Ok(Arc::new(model))
+}
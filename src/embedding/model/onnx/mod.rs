@@ -0,0 +1,394 @@
+//! ONNX sentence embedding models
+//!
+//! This module runs BERT-style encoders exported to ONNX, such as
+//! `all-MiniLM-L6-v2`. Inputs are tokenized and truncated to the maximum
+//! sequence length, padded to the longest input of their batch, and the
+//! token embeddings are pooled using the attention mask so padding never
+//! counts.
+
+use async_trait::async_trait;
+use ndarray::{Array, ArrayView2, ArrayViewD, CowArray, IxDyn};
+use ort::execution_providers::CUDAExecutionProviderOptions;
+use ort::{Environment, ExecutionProvider, GraphOptimizationLevel, Session, SessionBuilder, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokenizers::{Tokenizer, TruncationParams};
+use tracing::{debug, info};
+
+use crate::core::config::{EmbeddingModelConfig, EmbeddingPooling};
+use crate::embedding::model::EmbeddingModel;
+use crate::embedding::utils::normalize;
+use crate::errors::{Result, ZseiError};
+
+/// Text embedded at load time to find the dimension of models whose
+/// output shape does not state it
+const DIMENSION_PROBE: &str = "dimension";
+
+/// Sentence embedding model running an ONNX encoder
+pub struct OnnxEmbeddingModel {
+    /// Model directory
+    model_path: PathBuf,
+
+    /// Tokenizer, truncating to the maximum sequence length
+    tokenizer: Tokenizer,
+
+    /// Inference session
+    session: Session,
+
+    /// Environment of the session
+    _env: Arc<Environment>,
+
+    /// Vector dimension
+    dimension: usize,
+
+    /// Maximum number of tokens of an input
+    max_sequence_length: usize,
+
+    /// Number of inputs per inference run
+    batch_size: usize,
+
+    /// Pooling of the token embeddings
+    pooling: EmbeddingPooling,
+}
+
+impl OnnxEmbeddingModel {
+    /// Load the model and tokenizer from a model directory
+    pub fn new(model_path: PathBuf, config: &EmbeddingModelConfig, use_gpu: bool) -> Result<Self> {
+        info!("Loading embedding model from: {}", model_path.display());
+
+        let model_file = model_path.join("model.onnx");
+        let tokenizer_file = model_path.join("tokenizer.json");
+        if !model_file.exists() || !tokenizer_file.exists() {
+            return Err(ZseiError::Embedding(format!(
+                "Embedding model directory must contain model.onnx and tokenizer.json: {}",
+                model_path.display()
+            )));
+        }
+
+        // Load tokenizer, truncating inputs the model cannot take whole
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_file)
+            .map_err(|e| ZseiError::Embedding(format!("Failed to load tokenizer: {}", e)))?;
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: config.max_sequence_length,
+                ..Default::default()
+            }))
+            .map_err(|e| ZseiError::Embedding(format!("Failed to configure tokenizer: {}", e)))?;
+
+        // Create environment
+        let mut env_builder = Environment::builder()
+            .with_name("zsei-embedding")
+            .with_log_level(ort::LoggingLevel::Warning);
+
+        if use_gpu {
+            debug!("Enabling GPU for embedding model");
+            let providers: Vec<ExecutionProvider> = vec![ExecutionProvider::CUDA(
+                CUDAExecutionProviderOptions::default(),
+            )];
+            env_builder = env_builder.with_execution_providers(&providers);
+        }
+
+        let env = Arc::new(env_builder.build().map_err(ort_error)?);
+
+        // Load model
+        let session = SessionBuilder::new(&env)
+            .map_err(ort_error)?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(ort_error)?
+            .with_intra_threads(config.threads.clamp(1, i16::MAX as usize) as i16)
+            .map_err(ort_error)?
+            .with_model_from_file(&model_file)
+            .map_err(ort_error)?;
+
+        let stated_dimension = session
+            .outputs
+            .first()
+            .and_then(|output| output.dimensions.last().copied().flatten());
+
+        let mut model = Self {
+            model_path,
+            tokenizer,
+            session,
+            _env: env,
+            dimension: stated_dimension.unwrap_or(0) as usize,
+            max_sequence_length: config.max_sequence_length,
+            batch_size: config.batch_size.max(1),
+            pooling: config.pooling,
+        };
+
+        if model.dimension == 0 {
+            model.dimension = model
+                .run(&[DIMENSION_PROBE.to_string()])?
+                .first()
+                .map_or(0, |vector| vector.len());
+        }
+
+        if model.dimension == 0 {
+            return Err(ZseiError::Embedding(format!(
+                "Could not determine the dimension of the embedding model at {}",
+                model.model_path.display()
+            )));
+        }
+
+        info!("Loaded embedding model with dimension {}", model.dimension);
+
+        Ok(model)
+    }
+
+    /// Embed one batch of texts
+    fn run(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let inputs: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let encodings = self
+            .tokenizer
+            .encode_batch(inputs, true)
+            .map_err(|e| ZseiError::Embedding(format!("Failed to encode text: {}", e)))?;
+
+        // Pad every input to the longest one of the batch
+        let batch = encodings.len();
+        let length = encodings
+            .iter()
+            .map(|encoding| encoding.get_ids().len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let mut input_ids = vec![0i64; batch * length];
+        let mut attention_mask = vec![0i64; batch * length];
+        let mut token_type_ids = vec![0i64; batch * length];
+        for (row, encoding) in encodings.iter().enumerate() {
+            let offset = row * length;
+            for (column, &id) in encoding.get_ids().iter().enumerate() {
+                input_ids[offset + column] = id as i64;
+            }
+            for (column, &mask) in encoding.get_attention_mask().iter().enumerate() {
+                attention_mask[offset + column] = mask as i64;
+            }
+            for (column, &type_id) in encoding.get_type_ids().iter().enumerate() {
+                token_type_ids[offset + column] = type_id as i64;
+            }
+        }
+
+        // Feed the inputs the model declares, in its order
+        let arrays = self
+            .session
+            .inputs
+            .iter()
+            .map(|input| {
+                let data = match input.name.as_str() {
+                    "input_ids" => &input_ids,
+                    "attention_mask" => &attention_mask,
+                    "token_type_ids" => &token_type_ids,
+                    name => {
+                        return Err(ZseiError::Embedding(format!(
+                            "Unsupported embedding model input: {}",
+                            name
+                        )))
+                    }
+                };
+
+                let array = Array::from_shape_vec(vec![batch, length], data.clone())
+                    .map_err(|e| ZseiError::Embedding(format!("Failed to shape input: {}", e)))?;
+                Ok(CowArray::from(array))
+            })
+            .collect::<Result<Vec<CowArray<i64, IxDyn>>>>()?;
+
+        let allocator = self.session.allocator();
+        let values = arrays
+            .iter()
+            .map(|array| Value::from_array(allocator, array))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(ort_error)?;
+
+        // Run inference
+        let outputs = self.session.run(values).map_err(ort_error)?;
+        let output = outputs
+            .first()
+            .ok_or_else(|| ZseiError::Embedding("Embedding model has no output".to_string()))?;
+        let tensor = output.try_extract::<f32>().map_err(ort_error)?;
+
+        let attention_mask = ArrayView2::from_shape((batch, length), &attention_mask)
+            .map_err(|e| ZseiError::Embedding(format!("Failed to shape attention mask: {}", e)))?;
+        pool(tensor.view(), attention_mask, self.pooling)
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for OnnxEmbeddingModel {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        debug!(
+            "Embedding {} texts with {}",
+            texts.len(),
+            self.model_path.display()
+        );
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            vectors.extend(self.run(batch)?);
+        }
+
+        Ok(vectors)
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn max_sequence_length(&self) -> usize {
+        self.max_sequence_length
+    }
+}
+
+/// Pool a model output into one normalized vector per input
+///
+/// The attention mask has one row per input and one column per token.
+/// Outputs of shape `[batch, tokens, hidden]` are pooled over the tokens the
+/// mask keeps; outputs of shape `[batch, hidden]` are already pooled.
+fn pool(
+    output: ArrayViewD<f32>,
+    attention_mask: ArrayView2<i64>,
+    pooling: EmbeddingPooling,
+) -> Result<Vec<Vec<f32>>> {
+    let (batch, length) = attention_mask.dim();
+    let shape = output.shape().to_vec();
+    if shape.first() != Some(&batch) {
+        return Err(ZseiError::Embedding(format!(
+            "Unexpected embedding output shape: {:?}",
+            shape
+        )));
+    }
+
+    let mut vectors = Vec::with_capacity(batch);
+    match shape.len() {
+        2 => {
+            for row in 0..batch {
+                vectors.push((0..shape[1]).map(|i| output[[row, i]]).collect());
+            }
+        }
+        3 => {
+            let tokens = shape[1].min(length);
+            let hidden = shape[2];
+
+            for row in 0..batch {
+                let mut vector = vec![0.0; hidden];
+
+                match pooling {
+                    EmbeddingPooling::Cls => {
+                        for (i, value) in vector.iter_mut().enumerate() {
+                            *value = output[[row, 0, i]];
+                        }
+                    }
+                    EmbeddingPooling::Mean => {
+                        let mut kept = 0.0;
+                        for token in 0..tokens {
+                            if attention_mask[[row, token]] == 0 {
+                                continue;
+                            }
+                            for (i, value) in vector.iter_mut().enumerate() {
+                                *value += output[[row, token, i]];
+                            }
+                            kept += 1.0;
+                        }
+                        if kept > 0.0 {
+                            for value in vector.iter_mut() {
+                                *value /= kept;
+                            }
+                        }
+                    }
+                }
+
+                vectors.push(vector);
+            }
+        }
+        _ => {
+            return Err(ZseiError::Embedding(format!(
+                "Unexpected embedding output shape: {:?}",
+                shape
+            )))
+        }
+    }
+
+    for vector in &mut vectors {
+        normalize(vector);
+    }
+
+    Ok(vectors)
+}
+
+/// Convert an ONNX Runtime error into an embedding error
+fn ort_error(err: ort::OrtError) -> ZseiError {
+    ZseiError::Embedding(format!("ONNX Runtime error: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    /// Output tensor of a shape
+    fn output(shape: &[usize], values: Vec<f32>) -> Array<f32, IxDyn> {
+        Array::from_shape_vec(IxDyn(shape), values).unwrap()
+    }
+
+    /// Check that vectors match the expected values
+    fn assert_close(vectors: &[Vec<f32>], expected: &[&[f32]]) {
+        assert_eq!(vectors.len(), expected.len());
+        for (vector, expected) in vectors.iter().zip(expected) {
+            assert_eq!(vector.len(), expected.len());
+            for (value, expected) in vector.iter().zip(*expected) {
+                assert!((value - expected).abs() < 1e-6, "{:?}", vectors);
+            }
+        }
+    }
+
+    /// Two inputs of three tokens with two hidden values each; the last
+    /// token of the first input and the last two of the second are padding
+    fn token_output() -> Array<f32, IxDyn> {
+        output(
+            &[2, 3, 2],
+            vec![
+                3.0, 0.0, 0.0, 4.0, 100.0, -100.0, 0.0, 2.0, 9.0, 9.0, 9.0, 9.0,
+            ],
+        )
+    }
+
+    #[test]
+    fn mean_pooling_skips_masked_tokens() {
+        let mask = arr2(&[[1, 1, 0], [1, 0, 0]]);
+        let vectors = pool(token_output().view(), mask.view(), EmbeddingPooling::Mean).unwrap();
+        assert_close(&vectors, &[&[0.6, 0.8], &[0.0, 1.0]]);
+    }
+
+    #[test]
+    fn cls_pooling_takes_the_first_token() {
+        let mask = arr2(&[[1, 1, 0], [1, 0, 0]]);
+        let vectors = pool(token_output().view(), mask.view(), EmbeddingPooling::Cls).unwrap();
+        assert_close(&vectors, &[&[1.0, 0.0], &[0.0, 1.0]]);
+    }
+
+    #[test]
+    fn pooled_outputs_are_normalized() {
+        let mask = arr2(&[[1, 1], [1, 1], [1, 0]]);
+        let pooled = output(&[3, 2], vec![3.0, 4.0, -0.5, 0.0, 0.0, 0.0]);
+        for pooling in [EmbeddingPooling::Mean, EmbeddingPooling::Cls] {
+            let vectors = pool(pooled.view(), mask.view(), pooling).unwrap();
+
+            // Zero vectors stay zero instead of dividing by zero
+            assert_close(&vectors, &[&[0.6, 0.8], &[-1.0, 0.0], &[0.0, 0.0]]);
+        }
+
+        // Fully masked inputs pool to zero
+        let mask = arr2(&[[1, 1, 0], [0, 0, 0]]);
+        let vectors = pool(token_output().view(), mask.view(), EmbeddingPooling::Mean).unwrap();
+        assert_close(&vectors, &[&[0.6, 0.8], &[0.0, 0.0]]);
+    }
+
+    #[test]
+    fn unexpected_shapes_are_rejected() {
+        let mask = arr2(&[[1, 1, 0]]);
+        assert!(pool(token_output().view(), mask.view(), EmbeddingPooling::Mean).is_err());
+
+        let mask = arr2(&[[1], [1]]);
+        let tensor = output(&[2, 1, 1, 1], vec![1.0, 1.0]);
+        assert!(pool(tensor.view(), mask.view(), EmbeddingPooling::Mean).is_err());
+    }
+}
@@ -0,0 +1,536 @@
+//! Lexical index for ZSEI
+//!
+//! This module provides an inverted index over the content, function and
+//! class names and imports of indexed files, scored with BM25. It finds
+//! exact identifier hits that embedding search misses, and needs no LLM.
+//!
+//! Identifiers are indexed whole and split into their words, so
+//! `ParseError` matches queries for `ParseError`, `parse_error` and `parse`.
+//! Names of functions and classes weigh more than the content.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use crate::errors::{Result, ZseiError};
+use crate::indexing::store::IndexStore;
+
+/// BM25 term frequency saturation
+const K1: f32 = 1.2;
+
+/// BM25 document length normalization
+const B: f32 = 0.75;
+
+/// Weight of terms in function and class names
+const NAME_WEIGHT: u32 = 3;
+
+/// Weight of terms in imports
+const IMPORT_WEIGHT: u32 = 2;
+
+/// Weight of terms in the content
+const CONTENT_WEIGHT: u32 = 1;
+
+/// Indexed fields of a file
+#[derive(Debug, Clone, Default)]
+pub struct LexicalFields<'a> {
+    /// File content
+    pub content: Option<&'a str>,
+
+    /// Function names
+    pub functions: Vec<&'a str>,
+
+    /// Class names
+    pub classes: Vec<&'a str>,
+
+    /// Import paths
+    pub imports: Vec<&'a str>,
+}
+
+/// Indexed document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LexicalDocument {
+    /// Weighted number of terms
+    length: u32,
+
+    /// Distinct terms, for removal
+    terms: Vec<String>,
+}
+
+/// Inverted index scored with BM25
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LexicalIndex {
+    /// Documents by ID
+    documents: HashMap<String, LexicalDocument>,
+
+    /// Weighted term frequency of each document, by term
+    postings: HashMap<String, HashMap<String, u32>>,
+
+    /// Sum of the document lengths
+    total_length: u64,
+}
+
+impl LexicalIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Index a document, replacing any document with the same ID
+    pub fn add_document(&mut self, id: &str, fields: &LexicalFields) {
+        self.remove_document(id);
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        let mut add = |text: &str, weight: u32| {
+            for term in tokenize(text) {
+                *frequencies.entry(term).or_insert(0) += weight;
+            }
+        };
+
+        if let Some(content) = fields.content {
+            add(content, CONTENT_WEIGHT);
+        }
+        for name in fields.functions.iter().chain(&fields.classes) {
+            add(name, NAME_WEIGHT);
+        }
+        for import in &fields.imports {
+            add(import, IMPORT_WEIGHT);
+        }
+
+        let length: u32 = frequencies.values().sum();
+        let mut terms = Vec::with_capacity(frequencies.len());
+        for (term, frequency) in frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(id.to_string(), frequency);
+            terms.push(term);
+        }
+
+        self.total_length += length as u64;
+        self.documents
+            .insert(id.to_string(), LexicalDocument { length, terms });
+    }
+
+    /// Remove a document
+    ///
+    /// Returns whether a document with the ID was indexed.
+    pub fn remove_document(&mut self, id: &str) -> bool {
+        let document = match self.documents.remove(id) {
+            Some(document) => document,
+            None => return false,
+        };
+
+        for term in &document.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.remove(id);
+                if postings.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_length = self.total_length.saturating_sub(document.length as u64);
+
+        true
+    }
+
+    /// Give a document a new ID, replacing any document with that ID
+    ///
+    /// Returns whether a document with the old ID was indexed.
+    pub fn rename_document(&mut self, from: &str, to: &str) -> bool {
+        if from == to {
+            return self.documents.contains_key(from);
+        }
+
+        let document = match self.documents.remove(from) {
+            Some(document) => document,
+            None => return false,
+        };
+
+        self.remove_document(to);
+        for term in &document.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                if let Some(frequency) = postings.remove(from) {
+                    postings.insert(to.to_string(), frequency);
+                }
+            }
+        }
+        self.documents.insert(to.to_string(), document);
+
+        true
+    }
+
+    /// Find the documents accepted by a filter that best match a query
+    ///
+    /// Returns document IDs with their BM25 score, best first.
+    pub fn search(
+        &self,
+        query: &str,
+        max_results: usize,
+        accept: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, f32)> {
+        if self.documents.is_empty() {
+            return Vec::new();
+        }
+
+        let count = self.documents.len() as f32;
+        let average_length = (self.total_length as f32 / count).max(1.0);
+
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let mut accepted: HashMap<&str, bool> = HashMap::new();
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+
+        for term in &terms {
+            let postings = match self.postings.get(term) {
+                Some(postings) => postings,
+                None => continue,
+            };
+
+            let frequency = postings.len() as f32;
+            let idf = (1.0 + (count - frequency + 0.5) / (frequency + 0.5)).ln();
+
+            for (id, &term_frequency) in postings {
+                let id = id.as_str();
+                if !*accepted.entry(id).or_insert_with(|| accept(id)) {
+                    continue;
+                }
+
+                let length = self.documents.get(id).map_or(0, |document| document.length);
+                let term_frequency = term_frequency as f32;
+                let norm = K1 * (1.0 - B + B * length as f32 / average_length);
+                *scores.entry(id).or_insert(0.0) +=
+                    idf * term_frequency * (K1 + 1.0) / (term_frequency + norm);
+            }
+        }
+
+        let mut results: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(id, score)| (id.to_string(), score))
+            .collect();
+        results.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        results.truncate(max_results);
+
+        results
+    }
+}
+
+impl IndexStore for LexicalIndex {
+    fn save(&self, path: &Path) -> Result<()> {
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ZseiError::Indexing(format!("Failed to create directory: {}", e)))?;
+        }
+
+        let file = File::create(path)
+            .map_err(|e| ZseiError::Indexing(format!("Failed to create file: {}", e)))?;
+
+        let writer = BufWriter::new(file);
+
+        serde_json::to_writer(writer, self).map_err(|e| {
+            ZseiError::Indexing(format!("Failed to serialize lexical index: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &Path) -> Result<()> {
+        let file = File::open(path)
+            .map_err(|e| ZseiError::Indexing(format!("Failed to open file: {}", e)))?;
+
+        let reader = BufReader::new(file);
+
+        *self = serde_json::from_reader(reader).map_err(|e| {
+            ZseiError::Indexing(format!("Failed to deserialize lexical index: {}", e))
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Split text into lowercase terms
+///
+/// Each identifier yields itself and, when it has several words in snake
+/// or camel case, each of its words. Single characters are dropped.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for identifier in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|identifier| !identifier.is_empty())
+    {
+        let whole = identifier.trim_matches('_').to_lowercase();
+        if whole.chars().count() < 2 {
+            continue;
+        }
+
+        let words = split_words(identifier);
+        if words.len() > 1 {
+            terms.extend(words.into_iter().filter(|word| word.chars().count() >= 2));
+        }
+        terms.push(whole);
+    }
+
+    terms
+}
+
+/// Split an identifier into its lowercase words
+///
+/// Words are separated by underscores and case changes, so
+/// `HTTPServerError` and `http_server_error` both give `http`, `server`
+/// and `error`.
+fn split_words(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        let boundary = c.is_uppercase()
+            && i > 0
+            && (chars[i - 1].is_lowercase()
+                || chars[i - 1].is_numeric()
+                || (chars[i - 1].is_uppercase()
+                    && chars.get(i + 1).map_or(false, |next| next.is_lowercase())));
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        word.extend(c.to_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Combine rankings with reciprocal rank fusion
+///
+/// Each ranking is a list of IDs, best first, with a weight. An ID scores
+/// `weight / (k + rank)` in each ranking it appears in, with ranks starting
+/// at 1. Returns IDs with their fused score, best first.
+pub fn reciprocal_rank_fusion(rankings: &[(&[String], f32)], k: f32) -> Vec<(String, f32)> {
+    let mut scores: HashMap<&str, f32> = HashMap::new();
+
+    for (ranking, weight) in rankings {
+        for (rank, id) in ranking.iter().enumerate() {
+            *scores.entry(id.as_str()).or_insert(0.0) += weight / (k + rank as f32 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(String, f32)> = scores
+        .into_iter()
+        .map(|(id, score)| (id.to_string(), score))
+        .collect();
+    fused.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Index of a few documents with distinct names and content
+    fn sample_index() -> LexicalIndex {
+        let mut index = LexicalIndex::new();
+        index.add_document(
+            "parser",
+            &LexicalFields {
+                content: Some("fn parse(input: &str) -> Result<Ast, ParseError> { todo!() }"),
+                functions: vec!["parse"],
+                classes: vec!["ParseError"],
+                imports: vec!["crate::lexer::Token"],
+            },
+        );
+        index.add_document(
+            "lexer",
+            &LexicalFields {
+                content: Some("pub struct Token; fn next_token(input: &str) -> Token { Token }"),
+                functions: vec!["next_token"],
+                classes: vec!["Token"],
+                imports: Vec::new(),
+            },
+        );
+        index.add_document(
+            "server",
+            &LexicalFields {
+                content: Some("struct HttpServer; impl HttpServer { fn serve(&self) {} }"),
+                functions: vec!["serve"],
+                classes: vec!["HttpServer"],
+                imports: vec!["std::net::TcpListener"],
+            },
+        );
+        index
+    }
+
+    /// IDs of the results of a search accepting every document
+    fn ids(index: &LexicalIndex, query: &str) -> Vec<String> {
+        index
+            .search(query, 10, &|_| true)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_keeps_identifiers_and_their_words() {
+        assert_eq!(
+            tokenize("HTTPServerError"),
+            vec!["http", "server", "error", "httpservererror"]
+        );
+        assert_eq!(
+            tokenize("parse_error"),
+            vec!["parse", "error", "parse_error"]
+        );
+        assert_eq!(tokenize("__init__"), vec!["init"]);
+        assert_eq!(tokenize("a + b2c"), vec!["b2c"]);
+        assert_eq!(tokenize("utf8Decode"), vec!["utf8", "decode", "utf8decode"]);
+        assert!(tokenize("x = y; // !").is_empty());
+    }
+
+    #[test]
+    fn search_matches_identifiers_in_any_case() {
+        let index = sample_index();
+
+        assert_eq!(ids(&index, "ParseError"), vec!["parser"]);
+        assert_eq!(ids(&index, "parse_error")[0], "parser");
+        assert_eq!(ids(&index, "http server"), vec!["server"]);
+        assert!(ids(&index, "unrelated").is_empty());
+    }
+
+    #[test]
+    fn names_outweigh_content() {
+        let index = sample_index();
+
+        // Both files mention tokens, the lexer names them
+        let results = index.search("token", 10, &|_| true);
+        assert_eq!(results[0].0, "lexer");
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn bm25_ranks_by_frequency_and_rarity() {
+        let mut index = LexicalIndex::new();
+        for (id, content) in [
+            ("repeated", "alpha alpha common"),
+            ("once", "alpha other common"),
+            ("rare", "rarest other words"),
+        ] {
+            index.add_document(
+                id,
+                &LexicalFields {
+                    content: Some(content),
+                    ..LexicalFields::default()
+                },
+            );
+        }
+
+        // More occurrences in documents of the same length rank higher
+        assert_eq!(ids(&index, "alpha"), vec!["repeated", "once"]);
+
+        // A term in fewer documents outweighs a common one
+        assert_eq!(ids(&index, "common rarest")[0], "rare");
+    }
+
+    #[test]
+    fn search_applies_filter_and_limit() {
+        let index = sample_index();
+
+        let results = index.search("token", 10, &|id| id != "lexer");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "parser");
+
+        assert_eq!(index.search("input token", 1, &|_| true).len(), 1);
+    }
+
+    #[test]
+    fn remove_and_rename_keep_postings_consistent() {
+        let mut index = sample_index();
+        let total_length = index.total_length;
+
+        assert!(index.rename_document("lexer", "tokens"));
+        assert!(!index.rename_document("lexer", "other"));
+        assert_eq!(ids(&index, "next"), vec!["tokens"]);
+        assert_eq!(index.total_length, total_length);
+
+        assert!(index.remove_document("tokens"));
+        assert!(!index.remove_document("tokens"));
+        assert_eq!(index.len(), 2);
+        assert!(ids(&index, "next").is_empty());
+        assert!(!index.postings.contains_key("next_token"));
+
+        // Re-adding a document replaces it
+        index.add_document(
+            "server",
+            &LexicalFields {
+                content: Some("fn main() {}"),
+                ..LexicalFields::default()
+            },
+        );
+        assert_eq!(index.len(), 2);
+        assert!(ids(&index, "HttpServer").is_empty());
+        assert_eq!(ids(&index, "main"), vec!["server"]);
+
+        let expected: u64 = index.documents.values().map(|d| d.length as u64).sum();
+        assert_eq!(index.total_length, expected);
+    }
+
+    #[test]
+    fn index_round_trips() {
+        let index = sample_index();
+        let path = std::env::temp_dir().join(format!("zsei-lexical-{}.json", std::process::id()));
+        index.save(&path).unwrap();
+
+        let mut loaded = LexicalIndex::new();
+        loaded.load(&path).unwrap();
+        assert_eq!(
+            loaded.search("parse token", 10, &|_| true),
+            index.search("parse token", 10, &|_| true)
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn fusion_rewards_agreement() {
+        let vector = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let lexical = vec!["b".to_string(), "c".to_string(), "d".to_string()];
+
+        let fused = reciprocal_rank_fusion(&[(&vector, 1.0), (&lexical, 1.0)], 60.0);
+        let order: Vec<&str> = fused.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a", "d"]);
+        assert!((fused[0].1 - (1.0 / 62.0 + 1.0 / 61.0)).abs() < 1e-6);
+
+        // A zero weight ignores a ranking
+        let fused = reciprocal_rank_fusion(&[(&vector, 1.0), (&lexical, 0.0)], 60.0);
+        assert_eq!(fused[0].0, "a");
+    }
+}
@@ -5,23 +5,36 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{debug, info};
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, info, warn};
 
+pub mod lexical;
+pub mod search;
 pub mod store;
 pub mod vector;
+pub mod watch;
 
+use crate::analyzers::common::FileAnalysis;
 use crate::analyzers::{Analyzer, ProgressUpdate};
-use crate::core::config::Config;
-use crate::core::project::Project;
-use crate::embedding::{Embedding, EmbeddingFactory, EmbeddingGenerator, EmbeddingType};
+use crate::core::config::{Config, EmbeddingBackend, SearchMode};
+use crate::core::project::{Project, RemovedFile};
+use crate::embedding::cache::EmbeddingCache;
+use crate::embedding::model::EmbeddingModel;
+use crate::embedding::{Embedding, EmbeddingFactory, EmbeddingGenerator, EmbeddingType, LineRange};
 use crate::errors::{Result, ZseiError};
+use lexical::{LexicalFields, LexicalIndex};
+use search::SearchRequest;
 use store::{FileMetadataStore, IndexStore, MetadataStore};
+use vector::quantization::QuantizationReport;
 use vector::{VectorSearchParams, VectorStore};
 
+/// Vector hits first fetched per wanted result, since the embeddings of a
+/// file's chunks compete with each other until hits are grouped by file
+const VECTOR_HITS_PER_RESULT: usize = 4;
+
 /// Indexer struct
 pub struct Indexer {
     /// Configuration
@@ -36,27 +49,49 @@ pub struct Indexer {
     /// Metadata store
     metadata_store: Arc<RwLock<MetadataStore>>,
 
-    /// Embedding factory
-    embedding_factory: EmbeddingFactory,
+    /// Lexical index
+    lexical_index: Arc<RwLock<LexicalIndex>>,
+
+    /// Code embedding generator, shared by indexing and queries so both
+    /// are embedded by the same model
+    code_generator: Box<dyn EmbeddingGenerator<Input = FileAnalysis>>,
+
+    /// Recent query embeddings
+    query_cache: Mutex<EmbeddingCache>,
+
+    /// Backend, model and dimension of the query embeddings, prefixed to
+    /// the query cache keys
+    query_cache_namespace: String,
 }
 
 impl Indexer {
     /// Create a new indexer
+    ///
+    /// The sentence embedding model is required by the ONNX embedding
+    /// backend and unused by the others.
     pub fn new(
         config: Arc<Config>,
         analyzer: Arc<Analyzer>,
         llm: Arc<dyn crate::llm::Model>,
+        embedding_model: Option<Arc<dyn EmbeddingModel>>,
     ) -> Self {
-        let vector_store = Arc::new(RwLock::new(VectorStore::new()));
+        let vector_store = Arc::new(RwLock::new(VectorStore::from_config(&config.indexing)));
         let metadata_store = Arc::new(RwLock::new(MetadataStore::new()));
-        let embedding_factory = EmbeddingFactory::new(llm, config.clone());
+        let lexical_index = Arc::new(RwLock::new(LexicalIndex::new()));
+        let code_generator =
+            EmbeddingFactory::new(llm, embedding_model, config.clone()).create_code_generator();
+        let query_cache = Mutex::new(EmbeddingCache::new(config.embedding.cache_size));
+        let query_cache_namespace = query_cache_namespace(&config);
 
         Self {
             config,
             analyzer,
             vector_store,
             metadata_store,
-            embedding_factory,
+            lexical_index,
+            code_generator,
+            query_cache,
+            query_cache_namespace,
         }
     }
 
@@ -75,43 +110,244 @@ impl Indexer {
             .await?;
 
         // Index files
-        self.index_files(&analysis_result.file_analyses, progress_tx)
-            .await?;
+        self.index_files(
+            &analysis_result.file_analyses,
+            &analysis_result.content_hashes,
+            progress_tx,
+        )
+        .await?;
+
+        self.record_indexed_commit(paths)?;
 
         Ok(())
     }
 
     /// Index files (incremental indexing)
+    ///
+    /// The existing index is loaded and only files whose content changed
+    /// since they were last indexed are analyzed and embedded. Entries of
+    /// deleted files, and of files now excluded or ignored, are purged and
+    /// those of renamed files are moved to the new path; the returned report
+    /// lists both.
     pub async fn index_incremental(
         &self,
         paths: &[PathBuf],
         progress_tx: Option<mpsc::Sender<ProgressUpdate>>,
-    ) -> Result<()> {
+    ) -> Result<PruneReport> {
         info!("Starting incremental indexing");
 
+        // Start from the saved index so unchanged files keep their entries
+        let index_path = self.config.index_path()?;
+        if index_path.join("vectors.bin").exists() && index_path.join("metadata.json").exists() {
+            self.load(&index_path).await?;
+        }
+
+        self.index_changes(paths, progress_tx).await
+    }
+
+    /// Index the files changed since they were last indexed into the index
+    /// held in memory
+    ///
+    /// Like [`Indexer::index_incremental`], but without loading the saved
+    /// index first, for callers that keep the indexer loaded across runs.
+    pub async fn index_changes(
+        &self,
+        paths: &[PathBuf],
+        progress_tx: Option<mpsc::Sender<ProgressUpdate>>,
+    ) -> Result<PruneReport> {
+        // Select the files changed since they were last indexed
+        let project = self.analyzer.project();
+        let files = self.analyzer.collect_files_to_analyze(paths, false)?;
+        let mut files = project.filter_files_to_index(files)?;
+
+        // Purge deleted files and move renamed ones
+        let removed = project.find_removed_indexed_files(paths, &files)?;
+        let report = self.prune_removed_files(&removed).await?;
+
+        // Renamed files keep their embedding
+        files.retain(|path| !report.renamed.iter().any(|(_, to)| to == path));
+
+        if files.is_empty() {
+            info!("No files changed since the last indexing");
+            self.record_indexed_commit(paths)?;
+            return Ok(report);
+        }
+
+        debug!("Found {} changed files to index", files.len());
+
         // Analyze files
         let analysis_result = self
             .analyzer
-            .analyze_incremental(paths, progress_tx.clone())
+            .analyze_full(&files, progress_tx.clone())
             .await?;
 
         // Index files
-        self.index_files(&analysis_result.file_analyses, progress_tx)
-            .await?;
+        self.index_files(
+            &analysis_result.file_analyses,
+            &analysis_result.content_hashes,
+            progress_tx,
+        )
+        .await?;
+
+        self.record_indexed_commit(paths)?;
+
+        Ok(report)
+    }
 
+    /// Record the current git commit if the run covered the whole project
+    ///
+    /// Runs over a subset of the project leave the recorded commit alone, so
+    /// git change detection still finds the changes made outside the subset.
+    fn record_indexed_commit(&self, paths: &[PathBuf]) -> Result<()> {
+        let project_root = self.config.project_root();
+        if paths.is_empty() || paths.iter().any(|path| path == project_root) {
+            self.analyzer.project().update_indexed_commit()?;
+        }
         Ok(())
     }
 
+    /// Remove the index entries of deleted files and re-key renamed ones
+    async fn prune_removed_files(&self, removed: &[RemovedFile]) -> Result<PruneReport> {
+        let mut report = PruneReport::default();
+        let mut applied = Vec::new();
+        let mut stale_paths = Vec::new();
+
+        for file in removed {
+            match file {
+                RemovedFile::Deleted(path) => {
+                    report.embeddings_removed += self.remove_file_entries(path).await?;
+                    report.deleted.push(path.clone());
+                    stale_paths.push(path.clone());
+                    applied.push(file.clone());
+                }
+                RemovedFile::Renamed { from, to } => {
+                    let renamed = self.rename_file_entries(from, to).await?;
+
+                    if renamed {
+                        report.renamed.push((from.clone(), to.clone()));
+                        applied.push(file.clone());
+                    } else {
+                        // Nothing to move, so the new path is indexed afresh
+                        applied.push(RemovedFile::Deleted(from.clone()));
+                        report.deleted.push(from.clone());
+                    }
+                    stale_paths.push(from.clone());
+                }
+            }
+        }
+
+        self.analyzer.evict_cached_files(&stale_paths);
+        self.analyzer.project().apply_removed_files(&applied)?;
+
+        Ok(report)
+    }
+
+    /// Move the metadata of a file, its embeddings and its lexical document
+    /// to a new path
+    ///
+    /// Chunk embeddings are matched to their new IDs by chunk name, and any
+    /// old chunk embedding without a match is removed. Returns whether
+    /// metadata was stored for the old path.
+    async fn rename_file_entries(&self, from: &Path, to: &Path) -> Result<bool> {
+        let mut metadata_store = self.metadata_store.write().await;
+
+        let (old_file_id, mut old_chunk_ids) = match metadata_store.get_file_metadata(from) {
+            Some(metadata) => (
+                metadata.embedding_id.clone(),
+                metadata
+                    .chunks
+                    .iter()
+                    .map(|chunk| (chunk.name.clone(), chunk.embedding_id.clone()))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            None => return Ok(false),
+        };
+
+        metadata_store.rename_file_metadata(from, to)?;
+
+        if let Some(metadata) = metadata_store.get_file_metadata(to) {
+            let mut vector_store = self.vector_store.write().await;
+            vector_store.rename_embedding(&old_file_id, &metadata.embedding_id);
+            for chunk in &metadata.chunks {
+                if let Some(old_id) = old_chunk_ids.remove(&chunk.name) {
+                    vector_store.rename_embedding(&old_id, &chunk.embedding_id);
+                }
+            }
+            for old_id in old_chunk_ids.values() {
+                vector_store.remove_embedding(old_id);
+            }
+
+            let mut lexical_index = self.lexical_index.write().await;
+            lexical_index.rename_document(&old_file_id, &metadata.embedding_id);
+        }
+
+        Ok(true)
+    }
+
+    /// Remove the metadata of a file, its embeddings and its lexical document
+    ///
+    /// The file embedding is kept while other files still refer to it, which
+    /// only happens in indexes saved before IDs included the location; the
+    /// lexical document is removed regardless, so the file no longer matches
+    /// lexical searches. Returns the number of embeddings removed.
+    async fn remove_file_entries(&self, path: &Path) -> Result<usize> {
+        let mut metadata_store = self.metadata_store.write().await;
+
+        let (embedding_id, chunk_ids) = match metadata_store.get_file_metadata(path) {
+            Some(metadata) => (
+                metadata.embedding_id.clone(),
+                metadata
+                    .chunks
+                    .iter()
+                    .map(|chunk| chunk.embedding_id.clone())
+                    .collect::<Vec<_>>(),
+            ),
+            None => return Ok(0),
+        };
+
+        metadata_store.remove_file_metadata(path)?;
+
+        let shared = metadata_store
+            .get_all_file_metadata()
+            .values()
+            .any(|metadata| metadata.embedding_id == embedding_id);
+
+        self.lexical_index
+            .write()
+            .await
+            .remove_document(&embedding_id);
+
+        let mut vector_store = self.vector_store.write().await;
+        let mut removed = 0;
+        for chunk_id in &chunk_ids {
+            removed += usize::from(vector_store.remove_embedding(chunk_id));
+        }
+        if shared {
+            return Ok(removed);
+        }
+        removed += usize::from(vector_store.remove_embedding(&embedding_id));
+
+        Ok(removed)
+    }
+
     /// Index analyzed files
+    ///
+    /// Every file is added to the lexical index. Unless search is configured
+    /// to be purely lexical, each file gets an embedding for the whole file
+    /// and one for each of its chunks: functions, methods, types and windows
+    /// over the remaining code.
     async fn index_files(
         &self,
         file_analyses: &[crate::analyzers::common::FileAnalysis],
+        content_hashes: &HashMap<PathBuf, String>,
         progress_tx: Option<mpsc::Sender<ProgressUpdate>>,
     ) -> Result<()> {
         let total_files = file_analyses.len();
 
-        // Create code embedding generator
-        let code_generator = self.embedding_factory.create_code_generator();
+        let lexical_only = self.config.indexing.search.mode == SearchMode::Lexical;
+        if !lexical_only {
+            self.check_embedding_dimension().await?;
+        }
 
         // Process each file
         for (i, analysis) in file_analyses.iter().enumerate() {
@@ -129,16 +365,69 @@ impl Indexer {
                 }
             }
 
-            // Generate embedding
-            let embedding = code_generator.generate(analysis).await?;
+            // Generate embeddings
+            let (embedding, chunk_embeddings) = if lexical_only {
+                (None, Vec::new())
+            } else {
+                (
+                    Some(self.code_generator.generate(analysis).await?),
+                    self.code_generator.generate_chunks(analysis).await?,
+                )
+            };
+
+            let (embedding_id, content_hash) = match &embedding {
+                Some(embedding) => (
+                    embedding.metadata.id(),
+                    embedding.metadata.content_hash.clone(),
+                ),
+                None => {
+                    let content_hash = analysis
+                        .content
+                        .as_deref()
+                        .map(crate::embedding::content_hash)
+                        .unwrap_or_default();
+                    (
+                        crate::embedding::embedding_id(&analysis.path, None, &content_hash),
+                        content_hash,
+                    )
+                }
+            };
 
-            // Store embedding
-            self.add_embedding(&embedding).await?;
+            // Drop the entries of the previous version of the file
+            self.remove_file_entries(&analysis.path).await?;
+
+            // Store embeddings
+            if let Some(embedding) = &embedding {
+                self.add_embedding(embedding).await?;
+            }
+            for chunk_embedding in &chunk_embeddings {
+                self.add_embedding(chunk_embedding).await?;
+            }
 
             // Store metadata
-            self.add_metadata(analysis, &embedding).await?;
+            let chunks = chunk_embeddings
+                .iter()
+                .filter_map(ChunkMetadata::from_embedding)
+                .collect();
+            self.add_metadata(analysis, &embedding_id, &content_hash, chunks)
+                .await?;
+
+            // Store lexical document
+            self.add_lexical_document(analysis, &embedding_id).await;
         }
 
+        // Record the indexed files in the project state, at the content they
+        // were analyzed at
+        self.analyzer
+            .project()
+            .update_indexed_files(file_analyses.iter().map(|analysis| {
+                (
+                    analysis.path.as_path(),
+                    Some(analysis.language.clone()),
+                    content_hashes.get(&analysis.path).map(String::as_str),
+                )
+            }))?;
+
         Ok(())
     }
 
@@ -153,7 +442,9 @@ impl Indexer {
     async fn add_metadata(
         &self,
         analysis: &crate::analyzers::common::FileAnalysis,
-        embedding: &Embedding,
+        embedding_id: &str,
+        content_hash: &str,
+        chunks: Vec<ChunkMetadata>,
     ) -> Result<()> {
         let mut metadata_store = self.metadata_store.write().await;
 
@@ -161,11 +452,18 @@ impl Indexer {
         let file_metadata = FileMetadata {
             path: analysis.path.clone(),
             language: analysis.language.clone(),
-            embedding_id: embedding.metadata.content_hash.clone(),
+            embedding_id: embedding_id.to_string(),
+            content_hash: content_hash.to_string(),
+            chunks,
             loc: analysis.metrics.loc,
             complexity: analysis.metrics.complexity,
             functions: analysis.functions.iter().map(|f| f.name.clone()).collect(),
             classes: analysis.classes.iter().map(|c| c.name.clone()).collect(),
+            methods: analysis
+                .classes
+                .iter()
+                .flat_map(|c| c.methods.iter().map(|m| m.name.clone()))
+                .collect(),
             imports: analysis.imports.iter().map(|i| i.path.clone()).collect(),
             last_indexed: chrono::Utc::now(),
         };
@@ -175,41 +473,172 @@ impl Indexer {
         Ok(())
     }
 
-    /// Search for similar files
-    pub async fn search(
+    /// Add a file to the lexical index
+    async fn add_lexical_document(
         &self,
-        query: &str,
-        embedding_type: EmbeddingType,
-        max_results: usize,
-    ) -> Result<Vec<SearchResult>> {
-        info!("Searching for: {}", query);
+        analysis: &crate::analyzers::common::FileAnalysis,
+        embedding_id: &str,
+    ) {
+        let fields = LexicalFields {
+            content: analysis.content.as_deref(),
+            functions: analysis.functions.iter().map(|f| f.name.as_str()).collect(),
+            classes: analysis.classes.iter().map(|c| c.name.as_str()).collect(),
+            imports: analysis.imports.iter().map(|i| i.path.as_str()).collect(),
+        };
 
-        // Create query embedding
-        let query_embedding = self.create_query_embedding(query, embedding_type).await?;
+        let mut lexical_index = self.lexical_index.write().await;
+        lexical_index.add_document(embedding_id, &fields);
+    }
 
-        // Search vector store
-        let params = VectorSearchParams {
-            max_results,
-            min_score: 0.5,
+    /// Search for files matching a query
+    ///
+    /// The request's mode, or the configured one, selects vector search,
+    /// BM25 search over the lexical index, or both combined with reciprocal
+    /// rank fusion. Hybrid search falls back to lexical search when no query
+    /// embedding can be created. Files failing the request's filters are
+    /// skipped inside the stores, so they never take the place of matching
+    /// files. Each file is returned once, with the lines of its best matching
+    /// chunk when a chunk ranked above the whole file. Files with the same
+    /// content as a result are returned with it.
+    pub async fn search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>> {
+        info!("Searching for: {}", request.query);
+
+        let matcher = request.filter.matcher(self.config.project_root())?;
+        let mut mode = request.mode.unwrap_or(self.config.indexing.search.mode);
+
+        // Create query embedding, unless the search is purely lexical
+        let query_embedding = match mode {
+            SearchMode::Lexical => None,
+            SearchMode::Vector => Some(
+                self.create_query_embedding(&request.query, request.embedding_type.clone())
+                    .await?,
+            ),
+            SearchMode::Hybrid => match self
+                .create_query_embedding(&request.query, request.embedding_type.clone())
+                .await
+            {
+                Ok(embedding) => Some(embedding),
+                Err(e) => {
+                    warn!("Falling back to lexical search: {}", e);
+                    mode = SearchMode::Lexical;
+                    None
+                }
+            },
         };
 
-        let vector_results = {
-            let vector_store = self.vector_store.read().await;
-            vector_store.search(&query_embedding.vector, params)?
+        // Rank deeper before fusing, so files ranked well by one search only
+        // still make the cut
+        let depth = match mode {
+            SearchMode::Hybrid => request.max_results.saturating_mul(2),
+            SearchMode::Vector | SearchMode::Lexical => request.max_results,
         };
 
-        // Get metadata for results
-        let mut search_results = Vec::new();
+        let metadata_store = self.metadata_store.read().await;
+        let accept = |id: &str| {
+            matcher.is_empty()
+                || metadata_store
+                    .get_file_metadata_by_embedding_id(id)
+                    .map_or(false, |metadata| matcher.matches(metadata))
+        };
 
-        {
-            let metadata_store = self.metadata_store.read().await;
+        // Search vector store, keeping the best hit of each file ranked by the
+        // file's embedding ID. Files with many matching chunks can crowd the
+        // hits, so more are fetched until enough files are found or the store
+        // has no more hits.
+        let mut best_chunks = HashMap::new();
+        let mut vector_results = Vec::new();
+        if let Some(query_embedding) = &query_embedding {
+            let vector_store = self.vector_store.read().await;
+            let mut max_hits = depth.saturating_mul(VECTOR_HITS_PER_RESULT);
+
+            loop {
+                let params = VectorSearchParams {
+                    max_results: max_hits,
+                    min_score: request.min_score,
+                };
+                let hits = if matcher.is_empty() {
+                    vector_store.search(&query_embedding.vector, params)?
+                } else {
+                    vector_store.search_filtered(&query_embedding.vector, params, &accept)?
+                };
+                let exhausted = hits.len() < max_hits || max_hits >= vector_store.len();
+
+                best_chunks.clear();
+                vector_results.clear();
+                for hit in hits {
+                    let metadata = match metadata_store.get_file_metadata_by_embedding_id(&hit.id) {
+                        Some(metadata) => metadata,
+                        None => continue,
+                    };
+
+                    if !best_chunks.contains_key(&metadata.embedding_id) {
+                        best_chunks.insert(
+                            metadata.embedding_id.clone(),
+                            metadata.chunk(&hit.id).cloned(),
+                        );
+                        vector_results.push((metadata.embedding_id.clone(), hit.score));
+                    }
+                }
 
-            for result in vector_results {
-                if let Some(metadata) = metadata_store.get_file_metadata_by_embedding_id(&result.id)
-                {
+                if vector_results.len() >= depth || exhausted {
+                    break;
+                }
+                max_hits = max_hits.saturating_mul(2);
+            }
+        }
+        vector_results.truncate(depth);
+
+        // Search lexical index
+        let lexical_results = match mode {
+            SearchMode::Vector => Vec::new(),
+            SearchMode::Lexical | SearchMode::Hybrid => {
+                let lexical_index = self.lexical_index.read().await;
+                lexical_index.search(&request.query, depth, &accept)
+            }
+        };
+
+        let ranked = match mode {
+            SearchMode::Vector => vector_results,
+            SearchMode::Lexical => lexical_results,
+            SearchMode::Hybrid => {
+                let search_config = &self.config.indexing.search;
+                let vector_ids: Vec<String> =
+                    vector_results.into_iter().map(|(id, _)| id).collect();
+                let lexical_ids: Vec<String> =
+                    lexical_results.into_iter().map(|(id, _)| id).collect();
+
+                let mut fused = lexical::reciprocal_rank_fusion(
+                    &[
+                        (vector_ids.as_slice(), search_config.vector_weight),
+                        (lexical_ids.as_slice(), search_config.lexical_weight),
+                    ],
+                    search_config.rrf_k,
+                );
+                fused.truncate(request.max_results);
+                fused
+            }
+        };
+
+        // Get metadata for results, including every file with the same content
+        let mut search_results = Vec::new();
+        let mut seen = HashSet::new();
+
+        for (id, score) in ranked {
+            let metadata = match metadata_store.get_file_metadata_by_embedding_id(&id) {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+
+            let chunk = best_chunks.get(&id).and_then(Option::as_ref);
+            let duplicates =
+                metadata_store.get_file_metadata_by_content_hash(&metadata.content_hash);
+            for metadata in std::iter::once(metadata).chain(duplicates) {
+                if matcher.matches(metadata) && seen.insert(metadata.path.clone()) {
                     search_results.push(SearchResult {
                         path: metadata.path.clone(),
-                        score: result.score,
+                        score,
+                        chunk: chunk.map(|chunk| chunk.name.clone()),
+                        lines: chunk.map(|chunk| chunk.lines),
                         metadata: metadata.clone(),
                     });
                 }
@@ -220,19 +649,44 @@ impl Indexer {
     }
 
     /// Create a query embedding
+    ///
+    /// Queries are embedded by the generator that embedded the indexed
+    /// code, and recent ones are served from the query cache.
     async fn create_query_embedding(
         &self,
         query: &str,
         embedding_type: EmbeddingType,
     ) -> Result<Embedding> {
-        // Use LLM to create embedding for the query
-        let llm = Arc::new(crate::llm::LlmFactory::create_model(self.config.llm.clone()).await?);
+        self.check_embedding_dimension().await?;
+
+        let key = self.query_cache_key(query);
+        let cached = self.query_cache.lock().await.get(&key);
+        let vector = match cached {
+            Some(vector) => {
+                debug!("Using cached embedding for query: {}", query);
+                vector
+            }
+            None => {
+                // Embed the query the way the indexed code was embedded
+                let vector = self.code_generator.embed_query(query).await?;
+                if vector.len() != self.code_generator.dimension() {
+                    return Err(ZseiError::Embedding(format!(
+                        "Query embedding has dimension {}, but code embeddings have dimension {}",
+                        vector.len(),
+                        self.code_generator.dimension()
+                    )));
+                }
 
-        let vector = llm.embed(query).await?;
+                self.query_cache.lock().await.insert(&key, vector.clone());
+                vector
+            }
+        };
 
         let metadata = crate::embedding::EmbeddingMetadata {
             source_path: PathBuf::from("query"),
             content_hash: "query".to_string(),
+            chunk: None,
+            lines: None,
             language: None,
             timestamp: chrono::Utc::now(),
             attributes: HashMap::new(),
@@ -245,6 +699,40 @@ impl Indexer {
         })
     }
 
+    /// Get the query cache key of a query
+    fn query_cache_key(&self, query: &str) -> String {
+        format!("{}\n{}", self.query_cache_namespace, query)
+    }
+
+    /// Check that the stored vectors have the dimension of the embedding
+    /// generator
+    ///
+    /// An index built with another embedding model or configuration cannot
+    /// be searched or extended, and must be rebuilt.
+    async fn check_embedding_dimension(&self) -> Result<()> {
+        let stored = self.vector_store.read().await.dimension();
+        let generated = self.code_generator.dimension();
+
+        if stored != 0 && stored != generated {
+            return Err(ZseiError::Indexing(format!(
+                "The index holds embeddings of dimension {}, but the current embedding configuration produces dimension {}; rebuild the index",
+                stored, generated
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Measure the memory saved by quantizing the vector store and its
+    /// impact on recall
+    ///
+    /// Returns `None` when quantization is disabled.
+    pub async fn quantization_report(&self) -> Option<QuantizationReport> {
+        let vector_store = self.vector_store.read().await;
+        let quantization = &self.config.indexing.quantization;
+        vector_store.quantization_report(quantization.report_queries, quantization.report_k)
+    }
+
     /// Save index to disk
     pub async fn save(&self, path: &Path) -> Result<()> {
         info!("Saving index to {}", path.display());
@@ -258,7 +746,8 @@ impl Indexer {
         // Save vector store
         let vector_store_path = path.join("vectors.bin");
         {
-            let vector_store = self.vector_store.read().await;
+            let mut vector_store = self.vector_store.write().await;
+            vector_store.refresh_quantization();
             vector_store.save(&vector_store_path)?;
         }
 
@@ -269,9 +758,30 @@ impl Indexer {
             metadata_store.save(&metadata_store_path)?;
         }
 
+        // Save lexical index
+        let lexical_index_path = path.join("lexical.json");
+        {
+            let lexical_index = self.lexical_index.read().await;
+            lexical_index.save(&lexical_index_path)?;
+        }
+
         Ok(())
     }
 
+    /// Remove every cached file analysis, so the next run analyzes all files
+    /// again
+    pub fn clear_analysis_cache(&self) -> Result<()> {
+        self.analyzer.clear_cache()
+    }
+
+    /// Check the vectors of a saved index against their checksum
+    ///
+    /// Loading an index skips this, since it reads every vector.
+    pub fn verify(&self, path: &Path) -> Result<()> {
+        info!("Verifying index at {}", path.display());
+        VectorStore::verify(&path.join("vectors.bin"))
+    }
+
     /// Load index from disk
     pub async fn load(&self, path: &Path) -> Result<()> {
         info!("Loading index from {}", path.display());
@@ -300,8 +810,68 @@ impl Indexer {
             )));
         }
 
+        // Load lexical index, rebuilding it for indexes saved without one
+        let lexical_index_path = path.join("lexical.json");
+        if lexical_index_path.exists() {
+            let mut lexical_index = self.lexical_index.write().await;
+            lexical_index.load(&lexical_index_path)?;
+        } else {
+            warn!(
+                "Lexical index file not found: {}; rebuilding it",
+                lexical_index_path.display()
+            );
+            self.rebuild_lexical_index().await;
+        }
+
         Ok(())
     }
+
+    /// Rebuild the lexical index from the stored metadata and the files on
+    /// disk
+    async fn rebuild_lexical_index(&self) {
+        let metadata_store = self.metadata_store.read().await;
+        let mut lexical_index = self.lexical_index.write().await;
+        *lexical_index = LexicalIndex::new();
+
+        for metadata in metadata_store.get_all_file_metadata().values() {
+            let content = std::fs::read_to_string(&metadata.path).ok();
+            let fields = LexicalFields {
+                content: content.as_deref(),
+                functions: metadata.functions.iter().map(String::as_str).collect(),
+                classes: metadata.classes.iter().map(String::as_str).collect(),
+                imports: metadata.imports.iter().map(String::as_str).collect(),
+            };
+            lexical_index.add_document(&metadata.embedding_id, &fields);
+        }
+    }
+}
+
+/// Name the backend, model and dimension that embed queries
+///
+/// Query cache keys start with this name, so a cached vector is never
+/// served for another embedding configuration.
+fn query_cache_namespace(config: &Config) -> String {
+    let embedding = &config.embedding;
+    let model = match embedding.backend {
+        EmbeddingBackend::Hashing => "hashing".to_string(),
+        EmbeddingBackend::Onnx => format!(
+            "onnx:{}:{}:{:?}",
+            embedding
+                .model
+                .find_model_path()
+                .unwrap_or_default()
+                .display(),
+            embedding.model.max_sequence_length,
+            embedding.model.pooling
+        ),
+        EmbeddingBackend::Llm => format!(
+            "llm:{:?}:{}",
+            config.llm.model_type,
+            config.llm.find_model_path().unwrap_or_default().display()
+        ),
+    };
+
+    format!("{}:{}", model, embedding.dimension)
 }
 
 /// File metadata
@@ -316,6 +886,14 @@ pub struct FileMetadata {
     /// Embedding ID
     pub embedding_id: String,
 
+    /// Hash of the indexed content, shared by identical files
+    #[serde(default)]
+    pub content_hash: String,
+
+    /// Chunks of the file with an embedding of their own
+    #[serde(default)]
+    pub chunks: Vec<ChunkMetadata>,
+
     /// Lines of code
     pub loc: usize,
 
@@ -328,6 +906,10 @@ pub struct FileMetadata {
     /// Classes
     pub classes: Vec<String>,
 
+    /// Methods of the classes
+    #[serde(default)]
+    pub methods: Vec<String>,
+
     /// Imports
     pub imports: Vec<String>,
 
@@ -335,15 +917,540 @@ pub struct FileMetadata {
     pub last_indexed: chrono::DateTime<chrono::Utc>,
 }
 
+impl FileMetadata {
+    /// Get the IDs of the file's embedding and its chunk embeddings
+    pub fn embedding_ids(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.embedding_id.as_str())
+            .chain(self.chunks.iter().map(|chunk| chunk.embedding_id.as_str()))
+    }
+
+    /// Get a chunk by its embedding ID
+    pub fn chunk(&self, embedding_id: &str) -> Option<&ChunkMetadata> {
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.embedding_id == embedding_id)
+    }
+}
+
+/// Metadata of a chunk of a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkMetadata {
+    /// Name, unique within the file
+    pub name: String,
+
+    /// Lines covered
+    pub lines: LineRange,
+
+    /// Embedding ID
+    pub embedding_id: String,
+
+    /// Hash of the chunk content
+    pub content_hash: String,
+}
+
+impl ChunkMetadata {
+    /// Get the metadata of a chunk embedding
+    ///
+    /// Returns `None` for embeddings of whole files.
+    pub fn from_embedding(embedding: &Embedding) -> Option<Self> {
+        let metadata = &embedding.metadata;
+        Some(Self {
+            name: metadata.chunk.clone()?,
+            lines: metadata.lines?,
+            embedding_id: metadata.id(),
+            content_hash: metadata.content_hash.clone(),
+        })
+    }
+}
+
+/// Index entries pruned by an incremental run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    /// Files deleted since the last indexing
+    pub deleted: Vec<PathBuf>,
+
+    /// Files moved since the last indexing, as old and new path
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+
+    /// Number of embeddings removed
+    pub embeddings_removed: usize,
+}
+
+impl PruneReport {
+    /// Check if nothing was pruned
+    pub fn is_empty(&self) -> bool {
+        self.deleted.is_empty() && self.renamed.is_empty()
+    }
+}
+
 /// Search result
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     /// File path
     pub path: PathBuf,
 
-    /// Similarity score
+    /// Score: cosine similarity in vector search, BM25 score in lexical
+    /// search and reciprocal rank fusion score in hybrid search
     pub score: f32,
 
+    /// Name of the best matching chunk, if it ranked above the whole file
+    pub chunk: Option<String>,
+
+    /// Lines of the best matching chunk
+    pub lines: Option<LineRange>,
+
     /// File metadata
     pub metadata: FileMetadata,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::EmbeddingBackend;
+    use crate::llm::UnavailableModel;
+    use std::fs;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("zsei-indexer-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Indexer for the project at `root`, embedding with feature hashing so
+    /// no model is needed
+    fn indexer(root: &Path) -> Indexer {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        config.embedding.backend = EmbeddingBackend::Hashing;
+        let config = Arc::new(config);
+        let project = Arc::new(Project::new(config.clone()).unwrap());
+        let analyzer = Arc::new(Analyzer::new(config.clone(), project));
+        let llm = Arc::new(UnavailableModel::new("not needed with feature hashing"));
+        Indexer::new(config, analyzer, llm, None)
+    }
+
+    /// Paths of the files found by a search
+    async fn found(indexer: &Indexer, request: &SearchRequest) -> Vec<PathBuf> {
+        indexer
+            .search(request)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.path)
+            .collect()
+    }
+
+    /// Words of code, as a query a user might type for it
+    fn identifiers(code: &str) -> String {
+        code.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// IDs of the embeddings stored for a file
+    async fn stored_ids(indexer: &Indexer, path: &Path) -> Vec<String> {
+        indexer
+            .metadata_store
+            .read()
+            .await
+            .get_file_metadata(path)
+            .map(|metadata| metadata.embedding_ids().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn defines_filter_matches_class_methods() {
+        let root = scratch_dir("defines");
+        fs::write(
+            root.join("app.py"),
+            "class Greeter:\n    def greet(self, name):\n        return 'Hello ' + name\n",
+        )
+        .unwrap();
+        fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        let mut request = SearchRequest::new("Greeter helper");
+        request.mode = Some(SearchMode::Lexical);
+        assert_eq!(found(&indexer, &request).await.len(), 2);
+
+        request.filter.defines_functions = vec!["greet".to_string()];
+        assert_eq!(found(&indexer, &request).await, vec![root.join("app.py")]);
+
+        request.filter.defines_functions = vec!["helper".to_string()];
+        assert_eq!(found(&indexer, &request).await, vec![root.join("util.py")]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn own_identifiers_find_file_at_default_min_score() {
+        let root = scratch_dir("min-score");
+        let content = "def load_customer_records(database_connection, customer_identifier):\n    \
+                       return database_connection.fetch_customer_records(customer_identifier)\n";
+        fs::write(root.join("customers.py"), content).unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        let mut request = SearchRequest::new(identifiers(content));
+        request.mode = Some(SearchMode::Vector);
+        assert_eq!(request.min_score, search::DEFAULT_MIN_SCORE);
+
+        let results = indexer.search(&request).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, root.join("customers.py"));
+        assert!(results[0].score >= search::DEFAULT_MIN_SCORE);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn symbols_and_windows_get_own_embeddings() {
+        let root = scratch_dir("chunks");
+        let lines = [
+            "class Store:",
+            "    def load_records(self, path):",
+            "        with open(path) as handle:",
+            "            return handle.readlines()",
+            "",
+            "    def save_records(self, path, records):",
+            "        with open(path, 'w') as handle:",
+            "            handle.writelines(records)",
+            "",
+            "",
+            "def parse_config(config_text):",
+            "    settings = {}",
+            "    for line in config_text.splitlines():",
+            "        key, value = line.split('=', 1)",
+            "        settings[key.strip()] = value.strip()",
+            "    return settings",
+            "",
+            "",
+            "def render_report(records):",
+            "    return ', '.join(str(record) for record in records)",
+            "",
+            "",
+            "DEFAULT_CONFIG_PATH = 'settings.ini'",
+            "DEFAULT_REPORT_WIDTH = 80",
+            "DEFAULT_REPORT_TITLE = 'Customer report'",
+        ];
+        let path = root.join("store.py");
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        {
+            let metadata_store = indexer.metadata_store.read().await;
+            let vector_store = indexer.vector_store.read().await;
+            let metadata = metadata_store.get_file_metadata(&path).unwrap();
+
+            let mut chunks: Vec<(&str, usize, usize)> = metadata
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.name.as_str(), chunk.lines.start, chunk.lines.end))
+                .collect();
+            chunks.sort();
+            assert_eq!(
+                chunks,
+                vec![
+                    ("fn Store::load_records", 2, 4),
+                    ("fn Store::save_records", 6, 8),
+                    ("fn parse_config", 11, 16),
+                    ("fn render_report", 19, 20),
+                    ("lines 21-25", 21, 25),
+                    ("type Store", 1, 8),
+                ]
+            );
+
+            let mut ids = HashSet::new();
+            assert!(vector_store.contains_embedding(&metadata.embedding_id));
+            for chunk in &metadata.chunks {
+                assert_ne!(chunk.embedding_id, metadata.embedding_id);
+                assert!(ids.insert(chunk.embedding_id.as_str()));
+                assert!(vector_store.contains_embedding(&chunk.embedding_id));
+            }
+        }
+
+        let mut request = SearchRequest::new(identifiers(&lines[10..16].join("\n")));
+        request.mode = Some(SearchMode::Vector);
+        request.min_score = 0.0;
+        let results = indexer.search(&request).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.as_deref(), Some("fn parse_config"));
+        let range = results[0].lines.unwrap();
+        assert_eq!((range.start, range.end), (11, 16));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn query_embeddings_are_cached_per_model() {
+        let root = scratch_dir("query-cache");
+        fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        let first = indexer
+            .create_query_embedding("helper", EmbeddingType::Code)
+            .await
+            .unwrap();
+        let second = indexer
+            .create_query_embedding("helper", EmbeddingType::Code)
+            .await
+            .unwrap();
+        assert_eq!(first.vector, second.vector);
+        assert_eq!(indexer.query_cache.lock().await.len(), 1);
+
+        // A cached vector is served instead of embedding the query again
+        let dimension = first.vector.len();
+        let cached = vec![1.0; dimension];
+        indexer
+            .query_cache
+            .lock()
+            .await
+            .insert(&indexer.query_cache_key("cached"), cached.clone());
+        let embedding = indexer
+            .create_query_embedding("cached", EmbeddingType::Code)
+            .await
+            .unwrap();
+        assert_eq!(embedding.vector, cached);
+
+        // Vectors of another backend are not
+        let mut config = Config::default();
+        config.embedding.backend = EmbeddingBackend::Onnx;
+        let other = format!("{}\nfresh", query_cache_namespace(&config));
+        indexer.query_cache.lock().await.insert(&other, cached.clone());
+        let embedding = indexer
+            .create_query_embedding("fresh", EmbeddingType::Code)
+            .await
+            .unwrap();
+        assert_ne!(embedding.vector, cached);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn index_runs_record_analysis_and_indexing_state() {
+        let root = scratch_dir("state");
+        let path = root.join("app.py");
+        fs::write(&path, "def run():\n    return 1\n").unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        let project = indexer.analyzer.project();
+        assert!(project
+            .filter_files_to_analyze(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+        assert!(project
+            .filter_files_to_index(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+
+        // Analyzing alone leaves the file to be indexed
+        fs::write(&path, "def run():\n    return 2\n").unwrap();
+        indexer
+            .analyzer
+            .analyze_incremental(&[], None)
+            .await
+            .unwrap();
+        assert!(project
+            .filter_files_to_analyze(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            project.filter_files_to_index(vec![path.clone()]).unwrap(),
+            vec![path.clone()]
+        );
+
+        indexer.index_incremental(&[], None).await.unwrap();
+        assert!(project
+            .filter_files_to_index(vec![path.clone()])
+            .unwrap()
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn deleted_files_are_purged() {
+        let root = scratch_dir("delete");
+        let kept = root.join("kept.py");
+        let deleted = root.join("deleted.py");
+        fs::write(&kept, "def keep_records():\n    return 1\n").unwrap();
+        fs::write(
+            &deleted,
+            "def drop_records():\n    return 2\n\n\ndef purge_records():\n    return 3\n",
+        )
+        .unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+        let deleted_ids = stored_ids(&indexer, &deleted).await;
+        assert_eq!(deleted_ids.len(), 3);
+        let stored = indexer.vector_store.read().await.len();
+
+        fs::remove_file(&deleted).unwrap();
+        let report = indexer.index_incremental(&[], None).await.unwrap();
+        assert_eq!(report.deleted, vec![deleted.clone()]);
+        assert!(report.renamed.is_empty());
+        assert_eq!(report.embeddings_removed, 3);
+
+        {
+            let vector_store = indexer.vector_store.read().await;
+            assert_eq!(vector_store.len(), stored - 3);
+            for id in &deleted_ids {
+                assert!(!vector_store.contains_embedding(id));
+            }
+        }
+        assert!(stored_ids(&indexer, &deleted).await.is_empty());
+        assert_eq!(stored_ids(&indexer, &kept).await.len(), 2);
+
+        let project = indexer.analyzer.project();
+        assert!(!project.get_tracked_files().contains_key(&deleted));
+        assert!(project
+            .find_removed_indexed_files(&[], &[])
+            .unwrap()
+            .is_empty());
+
+        let mut request = SearchRequest::new("drop_records purge_records");
+        request.mode = Some(SearchMode::Lexical);
+        assert!(!found(&indexer, &request).await.contains(&deleted));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn renamed_files_are_rekeyed() {
+        let root = scratch_dir("rename");
+        let from = root.join("records.py");
+        let to = root.join("moved").join("records.py");
+        fs::write(
+            &from,
+            "def load_records():\n    return 1\n\n\ndef save_records():\n    return 2\n",
+        )
+        .unwrap();
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+        let old_ids = stored_ids(&indexer, &from).await;
+        assert_eq!(old_ids.len(), 3);
+        let stored = indexer.vector_store.read().await.len();
+
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+        fs::rename(&from, &to).unwrap();
+        let report = indexer.index_incremental(&[], None).await.unwrap();
+        assert_eq!(report.renamed, vec![(from.clone(), to.clone())]);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.embeddings_removed, 0);
+
+        // Every embedding moves to the ID of its chunk at the new path
+        let new_ids = stored_ids(&indexer, &to).await;
+        assert!(stored_ids(&indexer, &from).await.is_empty());
+        assert_eq!(new_ids.len(), 3);
+        {
+            let metadata_store = indexer.metadata_store.read().await;
+            let metadata = metadata_store.get_file_metadata(&to).unwrap();
+            assert_eq!(
+                metadata.embedding_id,
+                crate::embedding::embedding_id(&to, None, &metadata.content_hash)
+            );
+            for chunk in &metadata.chunks {
+                assert_eq!(
+                    chunk.embedding_id,
+                    crate::embedding::embedding_id(&to, Some(&chunk.name), &chunk.content_hash)
+                );
+            }
+
+            let vector_store = indexer.vector_store.read().await;
+            assert_eq!(vector_store.len(), stored);
+            for id in &new_ids {
+                assert!(vector_store.contains_embedding(id));
+            }
+            for id in &old_ids {
+                assert!(!vector_store.contains_embedding(id));
+            }
+        }
+
+        let project = indexer.analyzer.project();
+        let tracked = project.get_tracked_files();
+        assert!(tracked.contains_key(&to) && !tracked.contains_key(&from));
+        assert!(project
+            .filter_files_to_index(vec![to.clone()])
+            .unwrap()
+            .is_empty());
+
+        let mut request = SearchRequest::new("load_records save_records");
+        request.mode = Some(SearchMode::Lexical);
+        assert_eq!(found(&indexer, &request).await, vec![to.clone()]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn identical_files_keep_their_own_entries() {
+        let root = scratch_dir("identical");
+        let content = "def load_vendored_records(records_path):\n    \
+                       return open(records_path).readlines()\n";
+        let first = root.join("first").join("records.py");
+        let second = root.join("second").join("records.py");
+        for path in [&first, &second] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let indexer = indexer(&root);
+        indexer.index_full(&[], None).await.unwrap();
+
+        let first_ids = stored_ids(&indexer, &first).await;
+        let second_ids = stored_ids(&indexer, &second).await;
+        assert!(!first_ids.is_empty());
+        assert_eq!(first_ids.len(), second_ids.len());
+        for id in &first_ids {
+            assert!(!second_ids.contains(id));
+        }
+        {
+            let metadata_store = indexer.metadata_store.read().await;
+            let content_hash = &metadata_store
+                .get_file_metadata(&first)
+                .unwrap()
+                .content_hash;
+            let mut locations: Vec<&Path> = metadata_store
+                .get_file_metadata_by_content_hash(content_hash)
+                .into_iter()
+                .map(|metadata| metadata.path.as_path())
+                .collect();
+            locations.sort();
+            assert_eq!(locations, vec![first.as_path(), second.as_path()]);
+        }
+
+        let mut request = SearchRequest::new(identifiers(content));
+        request.mode = Some(SearchMode::Vector);
+        let mut paths = found(&indexer, &request).await;
+        paths.sort();
+        assert_eq!(paths, vec![first.clone(), second.clone()]);
+
+        // Deleting one copy leaves the other in place
+        fs::remove_file(&first).unwrap();
+        indexer.index_incremental(&[], None).await.unwrap();
+        assert_eq!(stored_ids(&indexer, &second).await, second_ids);
+        {
+            let vector_store = indexer.vector_store.read().await;
+            for id in &second_ids {
+                assert!(vector_store.contains_embedding(id));
+            }
+        }
+        assert_eq!(found(&indexer, &request).await, vec![second.clone()]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
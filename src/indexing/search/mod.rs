@@ -0,0 +1,356 @@
+//! Search requests for ZSEI
+//!
+//! This module describes a search over the index: the query, how many
+//! results to return, the similarity threshold and filters on the metadata
+//! of the indexed files. Filters are applied while the vector store and
+//! the lexical index are searched, so files that do not match never take a
+//! place in the top results.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::core::config::SearchMode;
+use crate::embedding::EmbeddingType;
+use crate::errors::{Result, ZseiError};
+use crate::indexing::FileMetadata;
+
+/// Default maximum number of results
+pub const DEFAULT_MAX_RESULTS: usize = 10;
+
+/// Default minimum similarity score
+pub const DEFAULT_MIN_SCORE: f32 = 0.5;
+
+/// Search request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRequest {
+    /// Query text
+    pub query: String,
+
+    /// Type of the embeddings to search
+    pub embedding_type: EmbeddingType,
+
+    /// Maximum number of results
+    pub max_results: usize,
+
+    /// Minimum similarity score of vector matches
+    pub min_score: f32,
+
+    /// Search mode, or `None` for the configured mode
+    #[serde(default)]
+    pub mode: Option<SearchMode>,
+
+    /// Filters on file metadata
+    pub filter: SearchFilter,
+}
+
+impl SearchRequest {
+    /// Create a request for code matching a query, with default limits and
+    /// no filters
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            embedding_type: EmbeddingType::Code,
+            max_results: DEFAULT_MAX_RESULTS,
+            min_score: DEFAULT_MIN_SCORE,
+            mode: None,
+            filter: SearchFilter::default(),
+        }
+    }
+}
+
+/// Filters on the metadata of indexed files
+///
+/// A file matches when it passes every filter that is set. List filters
+/// match when any of their values matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// Languages, compared case-insensitively
+    #[serde(default)]
+    pub languages: Vec<String>,
+
+    /// Glob patterns matched against the file path, either absolute or
+    /// relative to the project root
+    #[serde(default)]
+    pub path_globs: Vec<String>,
+
+    /// Minimum lines of code
+    #[serde(default)]
+    pub min_loc: Option<usize>,
+
+    /// Maximum lines of code
+    #[serde(default)]
+    pub max_loc: Option<usize>,
+
+    /// Minimum complexity
+    #[serde(default)]
+    pub min_complexity: Option<usize>,
+
+    /// Maximum complexity
+    #[serde(default)]
+    pub max_complexity: Option<usize>,
+
+    /// Names of functions or methods the file must define
+    #[serde(default)]
+    pub defines_functions: Vec<String>,
+
+    /// Earliest indexing time
+    #[serde(default)]
+    pub indexed_after: Option<DateTime<Utc>>,
+
+    /// Latest indexing time
+    #[serde(default)]
+    pub indexed_before: Option<DateTime<Utc>>,
+}
+
+impl SearchFilter {
+    /// Check if no filter is set
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+            && self.path_globs.is_empty()
+            && self.min_loc.is_none()
+            && self.max_loc.is_none()
+            && self.min_complexity.is_none()
+            && self.max_complexity.is_none()
+            && self.defines_functions.is_empty()
+            && self.indexed_after.is_none()
+            && self.indexed_before.is_none()
+    }
+
+    /// Compile the filter for matching files under a project root
+    pub fn matcher<'a>(&'a self, project_root: &'a Path) -> Result<FilterMatcher<'a>> {
+        let path_globs = self
+            .path_globs
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| ZseiError::Query(format!("Invalid glob pattern: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(FilterMatcher {
+            filter: self,
+            project_root,
+            path_globs,
+        })
+    }
+}
+
+/// Search filter compiled for matching
+pub struct FilterMatcher<'a> {
+    /// Filter
+    filter: &'a SearchFilter,
+
+    /// Project root, for matching relative paths
+    project_root: &'a Path,
+
+    /// Compiled path patterns
+    path_globs: Vec<glob::Pattern>,
+}
+
+impl FilterMatcher<'_> {
+    /// Check if every file matches
+    pub fn is_empty(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    /// Check if a file matches the filter
+    pub fn matches(&self, metadata: &FileMetadata) -> bool {
+        let filter = self.filter;
+
+        if !filter.languages.is_empty()
+            && !filter
+                .languages
+                .iter()
+                .any(|language| language.eq_ignore_ascii_case(&metadata.language))
+        {
+            return false;
+        }
+
+        if !self.path_globs.is_empty() {
+            let relative = metadata
+                .path
+                .strip_prefix(self.project_root)
+                .unwrap_or(&metadata.path);
+            let matched = self.path_globs.iter().any(|pattern| {
+                pattern.matches_path(&metadata.path) || pattern.matches_path(relative)
+            });
+            if !matched {
+                return false;
+            }
+        }
+
+        if !in_range(metadata.loc, filter.min_loc, filter.max_loc)
+            || !in_range(
+                metadata.complexity,
+                filter.min_complexity,
+                filter.max_complexity,
+            )
+        {
+            return false;
+        }
+
+        if !filter.defines_functions.is_empty()
+            && !filter
+                .defines_functions
+                .iter()
+                .any(|name| metadata.functions.contains(name) || metadata.methods.contains(name))
+        {
+            return false;
+        }
+
+        if filter
+            .indexed_after
+            .map_or(false, |after| metadata.last_indexed < after)
+            || filter
+                .indexed_before
+                .map_or(false, |before| metadata.last_indexed > before)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Check if a value is within optional bounds
+fn in_range(value: usize, min: Option<usize>, max: Option<usize>) -> bool {
+    min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Metadata of a Rust file under `/project` indexed at `last_indexed`
+    fn metadata(path: &str, last_indexed: DateTime<Utc>) -> FileMetadata {
+        FileMetadata {
+            path: PathBuf::from("/project").join(path),
+            language: "Rust".to_string(),
+            embedding_id: String::new(),
+            content_hash: String::new(),
+            chunks: Vec::new(),
+            loc: 50,
+            complexity: 5,
+            functions: vec!["parse".to_string()],
+            classes: Vec::new(),
+            methods: vec!["render".to_string()],
+            imports: Vec::new(),
+            last_indexed,
+        }
+    }
+
+    /// Check if a filter matches the metadata
+    fn matches(filter: &SearchFilter, metadata: &FileMetadata) -> bool {
+        filter
+            .matcher(Path::new("/project"))
+            .unwrap()
+            .matches(metadata)
+    }
+
+    /// Noon on a day of January 2026
+    fn day(day: u32) -> DateTime<Utc> {
+        format!("2026-01-{:02}T12:00:00Z", day).parse().unwrap()
+    }
+
+    #[test]
+    fn empty_filter_matches_every_file() {
+        let filter = SearchFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matcher(Path::new("/project")).unwrap().is_empty());
+        assert!(matches(&filter, &metadata("src/lib.rs", day(1))));
+    }
+
+    #[test]
+    fn languages_are_compared_case_insensitively() {
+        let file = metadata("src/lib.rs", day(1));
+        let mut filter = SearchFilter {
+            languages: vec!["python".to_string(), "rust".to_string()],
+            ..SearchFilter::default()
+        };
+        assert!(matches(&filter, &file));
+
+        filter.languages = vec!["RUST".to_string()];
+        assert!(matches(&filter, &file));
+
+        filter.languages = vec!["python".to_string()];
+        assert!(!matches(&filter, &file));
+    }
+
+    #[test]
+    fn globs_match_absolute_and_relative_paths() {
+        let file = metadata("src/indexing/mod.rs", day(1));
+        let mut filter = SearchFilter::default();
+
+        for pattern in ["src/**/*.rs", "/project/src/**/*.rs", "**/mod.rs"] {
+            filter.path_globs = vec![pattern.to_string()];
+            assert!(matches(&filter, &file), "{}", pattern);
+        }
+
+        for pattern in ["tests/**/*.rs", "/other/src/**/*.rs", "src/*.py"] {
+            filter.path_globs = vec![pattern.to_string()];
+            assert!(!matches(&filter, &file), "{}", pattern);
+        }
+
+        filter.path_globs = vec!["tests/**".to_string(), "src/**".to_string()];
+        assert!(matches(&filter, &file));
+
+        filter.path_globs = vec!["[".to_string()];
+        assert!(filter.matcher(Path::new("/project")).is_err());
+    }
+
+    #[test]
+    fn size_bounds_are_inclusive() {
+        let file = metadata("src/lib.rs", day(1));
+
+        let loc = |min_loc, max_loc| SearchFilter {
+            min_loc,
+            max_loc,
+            ..SearchFilter::default()
+        };
+        assert!(matches(&loc(Some(50), Some(50)), &file));
+        assert!(matches(&loc(Some(10), None), &file));
+        assert!(!matches(&loc(Some(51), None), &file));
+        assert!(!matches(&loc(None, Some(49)), &file));
+
+        let complexity = |min_complexity, max_complexity| SearchFilter {
+            min_complexity,
+            max_complexity,
+            ..SearchFilter::default()
+        };
+        assert!(matches(&complexity(Some(5), Some(5)), &file));
+        assert!(matches(&complexity(None, Some(10)), &file));
+        assert!(!matches(&complexity(Some(6), None), &file));
+        assert!(!matches(&complexity(None, Some(4)), &file));
+    }
+
+    #[test]
+    fn defined_functions_include_methods() {
+        let file = metadata("src/lib.rs", day(1));
+        let mut filter = SearchFilter::default();
+
+        for name in ["parse", "render"] {
+            filter.defines_functions = vec![name.to_string()];
+            assert!(matches(&filter, &file), "{}", name);
+        }
+
+        filter.defines_functions = vec!["load".to_string()];
+        assert!(!matches(&filter, &file));
+    }
+
+    #[test]
+    fn indexing_time_bounds_are_inclusive() {
+        let file = metadata("src/lib.rs", day(10));
+
+        let indexed = |indexed_after, indexed_before| SearchFilter {
+            indexed_after,
+            indexed_before,
+            ..SearchFilter::default()
+        };
+        assert!(matches(&indexed(Some(day(10)), Some(day(10))), &file));
+        assert!(matches(&indexed(Some(day(1)), Some(day(20))), &file));
+        assert!(!matches(&indexed(Some(day(11)), None), &file));
+        assert!(!matches(&indexed(None, Some(day(9))), &file));
+    }
+}
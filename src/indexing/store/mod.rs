@@ -9,6 +9,7 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 
+use crate::embedding::embedding_id;
 use crate::errors::{Result, ZseiError};
 use crate::indexing::FileMetadata;
 
@@ -29,11 +30,21 @@ pub trait FileMetadataStore {
     /// Get file metadata by path
     fn get_file_metadata(&self, path: &Path) -> Option<&FileMetadata>;
 
-    /// Get file metadata by embedding ID
+    /// Get file metadata by the ID of the file's embedding or of one of its
+    /// chunk embeddings
     fn get_file_metadata_by_embedding_id(&self, embedding_id: &str) -> Option<&FileMetadata>;
 
+    /// Get the metadata of every file with some content hash
+    fn get_file_metadata_by_content_hash(&self, content_hash: &str) -> Vec<&FileMetadata>;
+
     /// Remove file metadata
     fn remove_file_metadata(&mut self, path: &Path) -> Result<()>;
+
+    /// Move file metadata to a new path
+    ///
+    /// The embedding IDs of the file and its chunks follow the path.
+    /// Returns whether metadata was stored for the old path.
+    fn rename_file_metadata(&mut self, from: &Path, to: &Path) -> Result<bool>;
 }
 
 /// Metadata store
@@ -42,8 +53,12 @@ pub struct MetadataStore {
     /// File metadata indexed by path
     file_metadata: HashMap<PathBuf, FileMetadata>,
 
-    /// File metadata indexed by embedding ID
+    /// File metadata indexed by file and chunk embedding ID
     file_metadata_by_embedding_id: HashMap<String, PathBuf>,
+
+    /// Embedding IDs indexed by content hash, rebuilt on load
+    #[serde(skip)]
+    embedding_ids_by_content_hash: HashMap<String, Vec<String>>,
 }
 
 impl MetadataStore {
@@ -52,6 +67,7 @@ impl MetadataStore {
         Self {
             file_metadata: HashMap::new(),
             file_metadata_by_embedding_id: HashMap::new(),
+            embedding_ids_by_content_hash: HashMap::new(),
         }
     }
 
@@ -59,17 +75,51 @@ impl MetadataStore {
     pub fn get_all_file_metadata(&self) -> &HashMap<PathBuf, FileMetadata> {
         &self.file_metadata
     }
+
+    /// Add an embedding ID to the content hash index
+    fn index_content_hash(&mut self, metadata: &FileMetadata) {
+        if metadata.content_hash.is_empty() {
+            return;
+        }
+
+        let ids = self
+            .embedding_ids_by_content_hash
+            .entry(metadata.content_hash.clone())
+            .or_default();
+        if !ids.contains(&metadata.embedding_id) {
+            ids.push(metadata.embedding_id.clone());
+        }
+    }
+
+    /// Remove an embedding ID from the content hash index
+    fn unindex_content_hash(&mut self, metadata: &FileMetadata) {
+        if let Some(ids) = self
+            .embedding_ids_by_content_hash
+            .get_mut(&metadata.content_hash)
+        {
+            ids.retain(|id| id != &metadata.embedding_id);
+            if ids.is_empty() {
+                self.embedding_ids_by_content_hash
+                    .remove(&metadata.content_hash);
+            }
+        }
+    }
 }
 
 impl FileMetadataStore for MetadataStore {
     fn add_file_metadata(&mut self, metadata: FileMetadata) -> Result<()> {
-        // Add to both indexes
+        // Drop the entries of the previous version of the file
+        self.remove_file_metadata(&metadata.path)?;
+
+        // Add to all indexes
         let path = metadata.path.clone();
-        let embedding_id = metadata.embedding_id.clone();
 
-        self.file_metadata.insert(path.clone(), metadata);
-        self.file_metadata_by_embedding_id
-            .insert(embedding_id, path);
+        self.index_content_hash(&metadata);
+        for embedding_id in metadata.embedding_ids() {
+            self.file_metadata_by_embedding_id
+                .insert(embedding_id.to_string(), path.clone());
+        }
+        self.file_metadata.insert(path, metadata);
 
         Ok(())
     }
@@ -84,15 +134,58 @@ impl FileMetadataStore for MetadataStore {
             .and_then(|path| self.file_metadata.get(path))
     }
 
+    fn get_file_metadata_by_content_hash(&self, content_hash: &str) -> Vec<&FileMetadata> {
+        self.embedding_ids_by_content_hash
+            .get(content_hash)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| self.get_file_metadata_by_embedding_id(id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn remove_file_metadata(&mut self, path: &Path) -> Result<()> {
-        // Remove from both indexes
+        // Remove from all indexes
         if let Some(metadata) = self.file_metadata.remove(path) {
-            self.file_metadata_by_embedding_id
-                .remove(&metadata.embedding_id);
+            for embedding_id in metadata.embedding_ids() {
+                self.file_metadata_by_embedding_id.remove(embedding_id);
+            }
+            self.unindex_content_hash(&metadata);
         }
 
         Ok(())
     }
+
+    fn rename_file_metadata(&mut self, from: &Path, to: &Path) -> Result<bool> {
+        let mut metadata = match self.file_metadata.remove(from) {
+            Some(metadata) => metadata,
+            None => return Ok(false),
+        };
+
+        for embedding_id in metadata.embedding_ids() {
+            self.file_metadata_by_embedding_id.remove(embedding_id);
+        }
+        self.unindex_content_hash(&metadata);
+
+        // Indexes saved before IDs included the location keep their ID
+        if metadata.embedding_id == embedding_id(from, None, &metadata.content_hash) {
+            metadata.embedding_id = embedding_id(to, None, &metadata.content_hash);
+        }
+        for chunk in &mut metadata.chunks {
+            chunk.embedding_id = embedding_id(to, Some(&chunk.name), &chunk.content_hash);
+        }
+        metadata.path = to.to_path_buf();
+
+        self.index_content_hash(&metadata);
+        for embedding_id in metadata.embedding_ids() {
+            self.file_metadata_by_embedding_id
+                .insert(embedding_id.to_string(), to.to_path_buf());
+        }
+        self.file_metadata.insert(to.to_path_buf(), metadata);
+
+        Ok(true)
+    }
 }
 
 impl IndexStore for MetadataStore {
@@ -130,6 +223,18 @@ impl IndexStore for MetadataStore {
         self.file_metadata = store.file_metadata;
         self.file_metadata_by_embedding_id = store.file_metadata_by_embedding_id;
 
+        // Rebuild the content hash index
+        let mut embedding_ids_by_content_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for metadata in self.file_metadata.values() {
+            if !metadata.content_hash.is_empty() {
+                embedding_ids_by_content_hash
+                    .entry(metadata.content_hash.clone())
+                    .or_default()
+                    .push(metadata.embedding_id.clone());
+            }
+        }
+        self.embedding_ids_by_content_hash = embedding_ids_by_content_hash;
+
         Ok(())
     }
 }
@@ -0,0 +1,52 @@
+//! Saved store files
+//!
+//! This module reads a saved vector store into one buffer aligned for `f32`
+//! values, so the rows of its matrix can be read in place without copying
+//! each of them. The file is closed once read, so saving a store may replace
+//! it by renaming a new file over it on every platform.
+//!
+//! The crate forbids `unsafe` code, so the file is read rather than
+//! memory-mapped.
+
+use std::fs::File;
+use std::io::Read;
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::errors::{Result, ZseiError};
+
+/// Contents of a file, aligned for `f32` values
+#[derive(Debug)]
+pub struct StoreFile {
+    /// File contents, padded to a whole number of words
+    words: Box<[u32]>,
+
+    /// Length of the file in bytes
+    len: usize,
+}
+
+impl StoreFile {
+    /// Read a whole file
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut file = File::open(path)
+            .map_err(|e| ZseiError::Indexing(format!("Failed to open file: {}", e)))?;
+        let len = file
+            .metadata()
+            .map_err(|e| ZseiError::Indexing(format!("Failed to get file metadata: {}", e)))?
+            .len() as usize;
+
+        let mut words = vec![0u32; (len + 3) / 4].into_boxed_slice();
+        file.read_exact(&mut bytemuck::cast_slice_mut(&mut words[..])[..len])
+            .map_err(|e| ZseiError::Indexing(format!("Failed to read file: {}", e)))?;
+
+        Ok(Self { words, len })
+    }
+}
+
+impl Deref for StoreFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &bytemuck::cast_slice(&self.words[..])[..self.len]
+    }
+}
@@ -0,0 +1,229 @@
+//! Binary vector store format
+//!
+//! This module defines the layout of `vectors.bin`. All integers are
+//! little-endian. The file starts with a fixed header, with every field at
+//! an offset that is a multiple of its size:
+//!
+//! | Offset | Size | Field                                    |
+//! |--------|------|------------------------------------------|
+//! | 0      | 8    | Magic bytes `ZSEIVECS`                   |
+//! | 8      | 4    | Format version                           |
+//! | 12     | 4    | Vector dimension                         |
+//! | 16     | 8    | Number of slots                          |
+//! | 24     | 8    | Offset of the ID table                   |
+//! | 32     | 8    | Offset of the HNSW graph, 0 if none      |
+//! | 40     | 8    | Offset of the quantized codes, 0 if none |
+//! | 48     | 8    | Reserved, zero                           |
+//! | 56     | 4    | CRC-32 of the sections after the matrix  |
+//! | 60     | 4    | CRC-32 of the matrix                     |
+//!
+//! The header is followed by the `f32` matrix with one row per slot, so its
+//! rows can be used in place once read, then the ID table, the optional graph and
+//! the optional quantized codes. Each ID table entry holds the embedding
+//! type code (`0xFF` for a free slot), the ID length as `u32` and the UTF-8
+//! ID.
+//!
+//! The matrix has its own checksum, so opening a store only reads the
+//! sections after it; the matrix is checked by an explicit verification.
+
+use crate::embedding::EmbeddingType;
+use crate::errors::{Result, ZseiError};
+
+/// Magic bytes at the start of a binary vector store
+pub const MAGIC: &[u8; 8] = b"ZSEIVECS";
+
+/// Current format version
+pub const VERSION: u32 = 1;
+
+/// Length of the header, which is also the offset of the matrix
+pub const HEADER_LEN: usize = 64;
+
+/// Embedding type code of a free slot
+pub const FREE_SLOT: u8 = 0xFF;
+
+/// Header of a binary vector store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Vector dimension
+    pub dimension: usize,
+
+    /// Number of slots, including free ones
+    pub slots: usize,
+
+    /// Offset of the ID table
+    pub ids_offset: usize,
+
+    /// Offset of the HNSW graph, if saved
+    pub graph_offset: Option<usize>,
+
+    /// Offset of the quantized codes, if saved
+    pub quantization_offset: Option<usize>,
+
+    /// CRC-32 of the ID table, graph and quantized codes
+    pub checksum: u32,
+
+    /// CRC-32 of the matrix
+    pub matrix_checksum: u32,
+}
+
+impl Header {
+    /// Encode the header
+    pub fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..8].copy_from_slice(MAGIC);
+        bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[12..16].copy_from_slice(&(self.dimension as u32).to_le_bytes());
+        bytes[16..24].copy_from_slice(&(self.slots as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(self.ids_offset as u64).to_le_bytes());
+        bytes[32..40].copy_from_slice(&(self.graph_offset.unwrap_or(0) as u64).to_le_bytes());
+        bytes[40..48]
+            .copy_from_slice(&(self.quantization_offset.unwrap_or(0) as u64).to_le_bytes());
+        bytes[56..60].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes[60..64].copy_from_slice(&self.matrix_checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a header, checking the magic bytes and version
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if !is_binary(bytes) || bytes.len() < HEADER_LEN {
+            return Err(ZseiError::Indexing("Not a binary vector store".to_string()));
+        }
+
+        let mut reader = ByteReader::new(&bytes[8..HEADER_LEN]);
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(ZseiError::Indexing(format!(
+                "Unsupported vector store version {}",
+                version
+            )));
+        }
+
+        let dimension = reader.u32()? as usize;
+        let slots = reader.u64()? as usize;
+        let ids_offset = reader.u64()? as usize;
+        let graph_offset = match reader.u64()? {
+            0 => None,
+            offset => Some(offset as usize),
+        };
+        let quantization_offset = match reader.u64()? {
+            0 => None,
+            offset => Some(offset as usize),
+        };
+        reader.bytes(8)?;
+        let checksum = reader.u32()?;
+        let matrix_checksum = reader.u32()?;
+
+        Ok(Self {
+            dimension,
+            slots,
+            ids_offset,
+            graph_offset,
+            quantization_offset,
+            checksum,
+            matrix_checksum,
+        })
+    }
+}
+
+/// Check if bytes start like a binary vector store
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Get the code of an embedding type
+pub fn embedding_type_code(embedding_type: &EmbeddingType) -> u8 {
+    match embedding_type {
+        EmbeddingType::Code => 0,
+        EmbeddingType::Image => 1,
+        EmbeddingType::Audio => 2,
+        EmbeddingType::Video => 3,
+    }
+}
+
+/// Get the embedding type of a code
+pub fn embedding_type_from_code(code: u8) -> Result<EmbeddingType> {
+    match code {
+        0 => Ok(EmbeddingType::Code),
+        1 => Ok(EmbeddingType::Image),
+        2 => Ok(EmbeddingType::Audio),
+        3 => Ok(EmbeddingType::Video),
+        _ => Err(ZseiError::Indexing(format!(
+            "Unknown embedding type code {}",
+            code
+        ))),
+    }
+}
+
+/// Append a `u32` to a buffer
+pub fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Append a `u64` to a buffer
+pub fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Append an `f32` to a buffer
+pub fn put_f32(out: &mut Vec<u8>, value: f32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Reader over little-endian encoded bytes
+pub struct ByteReader<'a> {
+    /// Bytes to read
+    bytes: &'a [u8],
+
+    /// Position of the next byte
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Create a reader at the start of some bytes
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Read a number of bytes
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| ZseiError::Indexing("Unexpected end of vector store".to_string()))?;
+
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Read a byte
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Read a `u16`
+    pub fn u16(&mut self) -> Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Read a `u32`
+    pub fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Read a `u64`
+    pub fn u64(&mut self) -> Result<u64> {
+        let bytes = self.bytes(8)?;
+        let mut array = [0u8; 8];
+        array.copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(array))
+    }
+
+    /// Read an `f32`
+    pub fn f32(&mut self) -> Result<f32> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+}
@@ -0,0 +1,898 @@
+//! HNSW graph for approximate nearest neighbor search
+//!
+//! This module implements the Hierarchical Navigable Small World graph of
+//! Malkov and Yashunin. The graph only holds neighbor lists; vectors are read
+//! by slot from a [`VectorSource`], normally the matrix of a
+//! [`VectorStore`]. Vectors are expected to be normalized, so their dot
+//! product is the cosine similarity.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use super::format::{put_u32, put_u64, ByteReader};
+use crate::core::config::HnswConfig;
+use crate::errors::{Result, ZseiError};
+
+/// Highest layer a node can be assigned to
+const MAX_LEVEL: usize = 16;
+
+/// Source of the vectors indexed by the graph
+pub trait VectorSource {
+    /// Get the vector stored in a slot
+    fn vector(&self, slot: usize) -> &[f32];
+}
+
+/// Cosine similarity of two normalized vectors
+pub fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Graph node reached during a search, ordered by similarity
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    /// Similarity to the query
+    similarity: f32,
+
+    /// Slot of the node
+    slot: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .total_cmp(&other.similarity)
+            .then_with(|| other.slot.cmp(&self.slot))
+    }
+}
+
+/// HNSW graph over vector slots
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    /// Graph parameters
+    config: HnswConfig,
+
+    /// Neighbor lists of each slot, one per layer the node belongs to;
+    /// empty for slots that are not in the graph
+    layers: Vec<Vec<Vec<usize>>>,
+
+    /// Nodes linking to each slot, per layer; the reverse of `layers`, so
+    /// removing a node does not scan the graph for links to it
+    incoming: Vec<Vec<Vec<usize>>>,
+
+    /// Node on the highest layer, where searches start
+    entry_point: Option<usize>,
+
+    /// Number of nodes in the graph
+    len: usize,
+
+    /// State of the generator drawing node levels
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    /// Create an empty graph
+    pub fn new(config: HnswConfig) -> Self {
+        Self {
+            config,
+            layers: Vec::new(),
+            incoming: Vec::new(),
+            entry_point: None,
+            len: 0,
+            rng_state: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Get the graph parameters
+    pub fn config(&self) -> &HnswConfig {
+        &self.config
+    }
+
+    /// Set the size of the candidate list while searching
+    ///
+    /// Unlike the other parameters, this does not require rebuilding the
+    /// graph.
+    pub fn set_ef_search(&mut self, ef_search: usize) {
+        self.config.ef_search = ef_search;
+    }
+
+    /// Get the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the graph is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if a slot is in the graph
+    pub fn contains(&self, slot: usize) -> bool {
+        self.layers
+            .get(slot)
+            .map_or(false, |layers| !layers.is_empty())
+    }
+
+    /// Add the vector in a slot to the graph
+    ///
+    /// A slot that is already in the graph is reinserted.
+    pub fn insert<S: VectorSource + ?Sized>(&mut self, slot: usize, vectors: &S) {
+        if self.contains(slot) {
+            self.remove(slot, vectors);
+        }
+
+        let level = self.random_level();
+        if self.layers.len() <= slot {
+            self.layers.resize(slot + 1, Vec::new());
+            self.incoming.resize(slot + 1, Vec::new());
+        }
+        self.layers[slot] = vec![Vec::new(); level + 1];
+        self.incoming[slot] = vec![Vec::new(); level + 1];
+        self.len += 1;
+
+        let entry_point = match self.entry_point {
+            Some(entry_point) => entry_point,
+            None => {
+                self.entry_point = Some(slot);
+                return;
+            }
+        };
+
+        let query = vectors.vector(slot);
+        let top_level = self.level_of(entry_point);
+
+        // Descend to the node's top layer
+        let mut entry = Candidate {
+            similarity: similarity(query, vectors.vector(entry_point)),
+            slot: entry_point,
+        };
+        for layer in (level + 1..=top_level).rev() {
+            entry = self.greedy_search(query, entry, layer, vectors);
+        }
+
+        // Connect the node on each of its layers
+        let mut entries = vec![entry];
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates = self.search_layer(
+                query,
+                &entries,
+                self.config.ef_construction.max(1),
+                layer,
+                vectors,
+                &|_| true,
+            );
+            let neighbors = self.select_neighbors(&candidates, self.config.m.max(1), vectors);
+
+            for &neighbor in &neighbors {
+                self.connect(neighbor, slot, layer, vectors);
+            }
+            self.set_neighbors(slot, layer, neighbors);
+
+            entries = candidates;
+        }
+
+        if level > top_level {
+            self.entry_point = Some(slot);
+        }
+    }
+
+    /// Remove a slot from the graph
+    ///
+    /// Nodes that linked to it are reconnected among its former neighbors, so
+    /// the graph stays navigable without tombstones. Only those nodes are
+    /// visited, found through the reverse links. The vectors of the other
+    /// slots must still be available.
+    pub fn remove<S: VectorSource + ?Sized>(&mut self, slot: usize, vectors: &S) {
+        if !self.contains(slot) {
+            return;
+        }
+
+        let removed_layers = std::mem::take(&mut self.layers[slot]);
+        let removed_incoming = std::mem::take(&mut self.incoming[slot]);
+        self.len -= 1;
+
+        for (layer, removed_neighbors) in removed_layers.iter().enumerate() {
+            for &neighbor in removed_neighbors {
+                self.unlink(neighbor, layer, slot);
+            }
+        }
+
+        for (layer, removed_neighbors) in removed_layers.iter().enumerate() {
+            for &node in &removed_incoming[layer] {
+                let node_vector = vectors.vector(node);
+
+                let mut candidate_slots: HashSet<usize> = self.layers[node][layer]
+                    .iter()
+                    .copied()
+                    .filter(|&neighbor| neighbor != slot)
+                    .collect();
+                candidate_slots.extend(
+                    removed_neighbors
+                        .iter()
+                        .copied()
+                        .filter(|&neighbor| neighbor != node),
+                );
+
+                let mut candidates: Vec<Candidate> = candidate_slots
+                    .into_iter()
+                    .map(|neighbor| Candidate {
+                        similarity: similarity(node_vector, vectors.vector(neighbor)),
+                        slot: neighbor,
+                    })
+                    .collect();
+                candidates.sort_by(|a, b| b.cmp(a));
+
+                let neighbors =
+                    self.select_neighbors(&candidates, self.max_connections(layer), vectors);
+                self.set_neighbors(node, layer, neighbors);
+            }
+        }
+
+        // The highest former neighbor takes over as entry point; the graph
+        // is only scanned when the removed node had no links at all
+        if self.entry_point == Some(slot) {
+            self.entry_point = removed_layers
+                .iter()
+                .chain(&removed_incoming)
+                .flatten()
+                .copied()
+                .filter(|&node| self.contains(node))
+                .max_by_key(|&node| (self.level_of(node), Reverse(node)));
+
+            if self.entry_point.is_none() && self.len > 0 {
+                self.entry_point = (0..self.layers.len())
+                    .filter(|&node| self.contains(node))
+                    .max_by_key(|&node| (self.level_of(node), Reverse(node)));
+            }
+        }
+    }
+
+    /// Find the `k` slots most similar to a normalized query
+    ///
+    /// Returns slots with their similarity, most similar first.
+    pub fn search<S: VectorSource + ?Sized>(
+        &self,
+        query: &[f32],
+        k: usize,
+        vectors: &S,
+    ) -> Vec<(usize, f32)> {
+        self.search_filtered(query, k, vectors, &|_| true)
+    }
+
+    /// Find the `k` accepted slots most similar to a normalized query
+    ///
+    /// Rejected slots are still traversed, so they do not disconnect the
+    /// graph, but never take a place in the results. The more slots are
+    /// rejected, the more of the graph is visited.
+    pub fn search_filtered<S: VectorSource + ?Sized>(
+        &self,
+        query: &[f32],
+        k: usize,
+        vectors: &S,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<(usize, f32)> {
+        let entry_point = match self.entry_point {
+            Some(entry_point) => entry_point,
+            None => return Vec::new(),
+        };
+
+        let mut entry = Candidate {
+            similarity: similarity(query, vectors.vector(entry_point)),
+            slot: entry_point,
+        };
+        for layer in (1..=self.level_of(entry_point)).rev() {
+            entry = self.greedy_search(query, entry, layer, vectors);
+        }
+
+        let ef = self.config.ef_search.max(k);
+        self.search_layer(query, &[entry], ef, 0, vectors, accept)
+            .into_iter()
+            .take(k)
+            .map(|candidate| (candidate.slot, candidate.similarity))
+            .collect()
+    }
+
+    /// Append the graph in the binary store format
+    ///
+    /// Parameters and generator state come first, then the neighbor lists of
+    /// each slot as a layer count followed by `u16` counts and `u32` slots.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        put_u32(out, self.config.m as u32);
+        put_u32(out, self.config.ef_construction as u32);
+        put_u32(out, self.config.ef_search as u32);
+        put_u64(out, self.entry_point.map_or(u64::MAX, |slot| slot as u64));
+        put_u64(out, self.len as u64);
+        put_u64(out, self.rng_state);
+        put_u64(out, self.layers.len() as u64);
+
+        for layers in &self.layers {
+            out.push(layers.len() as u8);
+            for neighbors in layers {
+                out.extend_from_slice(&(neighbors.len() as u16).to_le_bytes());
+                for &neighbor in neighbors {
+                    put_u32(out, neighbor as u32);
+                }
+            }
+        }
+    }
+
+    /// Decode a graph written by [`HnswIndex::encode`]
+    pub fn decode(reader: &mut ByteReader<'_>) -> Result<Self> {
+        let config = HnswConfig {
+            m: reader.u32()? as usize,
+            ef_construction: reader.u32()? as usize,
+            ef_search: reader.u32()? as usize,
+        };
+        let entry_point = match reader.u64()? {
+            u64::MAX => None,
+            slot => Some(slot as usize),
+        };
+        let len = reader.u64()? as usize;
+        let rng_state = reader.u64()?;
+        let slot_count = reader.u64()? as usize;
+
+        let mut layers = Vec::with_capacity(slot_count.min(1 << 24));
+        for _ in 0..slot_count {
+            let layer_count = reader.u8()? as usize;
+            let mut node = Vec::with_capacity(layer_count);
+            for _ in 0..layer_count {
+                let neighbor_count = reader.u16()? as usize;
+                let mut neighbors = Vec::with_capacity(neighbor_count);
+                for _ in 0..neighbor_count {
+                    let neighbor = reader.u32()? as usize;
+                    if neighbor >= slot_count {
+                        return Err(ZseiError::Indexing(format!(
+                            "HNSW neighbor {} out of range",
+                            neighbor
+                        )));
+                    }
+                    neighbors.push(neighbor);
+                }
+                node.push(neighbors);
+            }
+            layers.push(node);
+        }
+
+        if entry_point.map_or(false, |slot| slot >= slot_count) {
+            return Err(ZseiError::Indexing(
+                "HNSW entry point out of range".to_string(),
+            ));
+        }
+
+        // Rebuild the reverse links
+        let mut incoming: Vec<Vec<Vec<usize>>> = layers
+            .iter()
+            .map(|node| vec![Vec::new(); node.len()])
+            .collect();
+        for (slot, node) in layers.iter().enumerate() {
+            for (layer, neighbors) in node.iter().enumerate() {
+                for &neighbor in neighbors {
+                    let links = incoming[neighbor].get_mut(layer).ok_or_else(|| {
+                        ZseiError::Indexing(format!(
+                            "HNSW neighbor {} is not on layer {}",
+                            neighbor, layer
+                        ))
+                    })?;
+                    links.push(slot);
+                }
+            }
+        }
+
+        Ok(Self {
+            config,
+            layers,
+            incoming,
+            entry_point,
+            len,
+            rng_state,
+        })
+    }
+
+    /// Get the top layer of a node
+    fn level_of(&self, slot: usize) -> usize {
+        self.layers[slot].len().saturating_sub(1)
+    }
+
+    /// Get the neighbors of a node on a layer
+    fn neighbors(&self, slot: usize, layer: usize) -> &[usize] {
+        self.layers
+            .get(slot)
+            .and_then(|layers| layers.get(layer))
+            .map(|neighbors| neighbors.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Maximum number of neighbors on a layer
+    fn max_connections(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.config.m.max(1) * 2
+        } else {
+            self.config.m.max(1)
+        }
+    }
+
+    /// Draw the top layer of a new node
+    ///
+    /// Levels follow a geometric distribution with factor `1 / ln(M)`. The
+    /// generator is seeded deterministically, so the same insertions build
+    /// the same graph.
+    fn random_level(&mut self) -> usize {
+        // xorshift64*
+        let mut x = self.rng_state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state = x;
+        let bits = x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11;
+
+        let uniform = (bits as f64 + 0.5) / (1u64 << 53) as f64;
+        let level_factor = 1.0 / (self.config.m.max(2) as f64).ln();
+        ((-uniform.ln() * level_factor) as usize).min(MAX_LEVEL)
+    }
+
+    /// Follow the most similar neighbor on a layer until no neighbor is closer
+    fn greedy_search<S: VectorSource + ?Sized>(
+        &self,
+        query: &[f32],
+        entry: Candidate,
+        layer: usize,
+        vectors: &S,
+    ) -> Candidate {
+        let mut best = entry;
+
+        loop {
+            let mut improved = false;
+
+            for &neighbor in self.neighbors(best.slot, layer) {
+                let similarity = similarity(query, vectors.vector(neighbor));
+                if similarity > best.similarity {
+                    best = Candidate {
+                        similarity,
+                        slot: neighbor,
+                    };
+                    improved = true;
+                }
+            }
+
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Beam search on one layer
+    ///
+    /// Returns up to `ef` accepted nodes, most similar first.
+    fn search_layer<S: VectorSource + ?Sized>(
+        &self,
+        query: &[f32],
+        entries: &[Candidate],
+        ef: usize,
+        layer: usize,
+        vectors: &S,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entries.iter().map(|entry| entry.slot).collect();
+        let mut candidates: BinaryHeap<Candidate> = entries.iter().copied().collect();
+        let mut results: BinaryHeap<Reverse<Candidate>> = entries
+            .iter()
+            .copied()
+            .filter(|entry| accept(entry.slot))
+            .map(Reverse)
+            .collect();
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = results.peek().map_or(f32::MIN, |worst| worst.0.similarity);
+            if results.len() >= ef && current.similarity < worst {
+                break;
+            }
+
+            for &neighbor in self.neighbors(current.slot, layer) {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+
+                let candidate = Candidate {
+                    similarity: similarity(query, vectors.vector(neighbor)),
+                    slot: neighbor,
+                };
+
+                let worst = results.peek().map_or(f32::MIN, |worst| worst.0.similarity);
+                if results.len() < ef || candidate.similarity > worst {
+                    candidates.push(candidate);
+                    if accept(neighbor) {
+                        results.push(Reverse(candidate));
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Candidate> = results.into_iter().map(|result| result.0).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    /// Pick up to `m` neighbors from candidates sorted by similarity
+    ///
+    /// Uses the diversity heuristic of the HNSW paper: a candidate is
+    /// preferred when it is closer to the base node than to any neighbor
+    /// already picked. Remaining places are filled with the closest skipped
+    /// candidates.
+    fn select_neighbors<S: VectorSource + ?Sized>(
+        &self,
+        candidates: &[Candidate],
+        m: usize,
+        vectors: &S,
+    ) -> Vec<usize> {
+        let mut selected: Vec<usize> = Vec::with_capacity(m);
+        let mut skipped = Vec::new();
+
+        for candidate in candidates {
+            if selected.len() >= m {
+                break;
+            }
+
+            let candidate_vector = vectors.vector(candidate.slot);
+            let diverse = selected.iter().all(|&neighbor| {
+                similarity(candidate_vector, vectors.vector(neighbor)) < candidate.similarity
+            });
+
+            if diverse {
+                selected.push(candidate.slot);
+            } else {
+                skipped.push(candidate.slot);
+            }
+        }
+
+        for slot in skipped {
+            if selected.len() >= m {
+                break;
+            }
+            selected.push(slot);
+        }
+
+        selected
+    }
+
+    /// Add a link from a node to a new neighbor, pruning if it has too many
+    fn connect<S: VectorSource + ?Sized>(
+        &mut self,
+        node: usize,
+        neighbor: usize,
+        layer: usize,
+        vectors: &S,
+    ) {
+        let max_connections = self.max_connections(layer);
+        self.layers[node][layer].push(neighbor);
+        self.incoming[neighbor][layer].push(node);
+
+        if self.layers[node][layer].len() <= max_connections {
+            return;
+        }
+
+        let node_vector = vectors.vector(node);
+        let mut candidates: Vec<Candidate> = self.layers[node][layer]
+            .iter()
+            .map(|&slot| Candidate {
+                similarity: similarity(node_vector, vectors.vector(slot)),
+                slot,
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.cmp(a));
+
+        let pruned = self.select_neighbors(&candidates, max_connections, vectors);
+        self.set_neighbors(node, layer, pruned);
+    }
+
+    /// Replace the neighbors of a node on a layer, keeping the reverse links
+    /// in step
+    fn set_neighbors(&mut self, node: usize, layer: usize, neighbors: Vec<usize>) {
+        let previous = std::mem::replace(&mut self.layers[node][layer], neighbors);
+
+        for &neighbor in &previous {
+            if !self.layers[node][layer].contains(&neighbor) {
+                self.unlink(neighbor, layer, node);
+            }
+        }
+        for index in 0..self.layers[node][layer].len() {
+            let neighbor = self.layers[node][layer][index];
+            if !previous.contains(&neighbor) {
+                self.incoming[neighbor][layer].push(node);
+            }
+        }
+    }
+
+    /// Forget that a node links to a neighbor on a layer
+    fn unlink(&mut self, neighbor: usize, layer: usize, node: usize) {
+        if let Some(links) = self
+            .incoming
+            .get_mut(neighbor)
+            .and_then(|layers| layers.get_mut(layer))
+        {
+            if let Some(index) = links.iter().position(|&link| link == node) {
+                links.swap_remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vectors held in memory, one per slot
+    struct Vectors(Vec<Vec<f32>>);
+
+    impl VectorSource for Vectors {
+        fn vector(&self, slot: usize) -> &[f32] {
+            &self.0[slot]
+        }
+    }
+
+    /// Draw normalized vectors around a few cluster centers
+    fn clustered_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = seed;
+        let mut next = move || {
+            rng ^= rng << 13;
+            rng ^= rng >> 7;
+            rng ^= rng << 17;
+            (rng >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+        };
+
+        let centers: Vec<Vec<f32>> = (0..16)
+            .map(|_| (0..dimension).map(|_| next()).collect())
+            .collect();
+
+        (0..count)
+            .map(|i| {
+                let mut vector: Vec<f32> = centers[i % centers.len()]
+                    .iter()
+                    .map(|c| c + next() * 0.5)
+                    .collect();
+                let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+                vector.iter_mut().for_each(|v| *v /= norm);
+                vector
+            })
+            .collect()
+    }
+
+    fn config() -> HnswConfig {
+        HnswConfig {
+            m: 16,
+            ef_construction: 100,
+            ef_search: 64,
+        }
+    }
+
+    /// Exact top `k` slots among the accepted ones
+    fn brute_force(
+        query: &[f32],
+        k: usize,
+        vectors: &Vectors,
+        accept: impl Fn(usize) -> bool,
+    ) -> HashSet<usize> {
+        let mut scored: Vec<(usize, f32)> = (0..vectors.0.len())
+            .filter(|&slot| accept(slot))
+            .map(|slot| (slot, similarity(query, vectors.vector(slot))))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(slot, _)| slot).collect()
+    }
+
+    /// Fraction of the exact top `k` found by the graph
+    fn recall(
+        index: &HnswIndex,
+        queries: &[Vec<f32>],
+        k: usize,
+        vectors: &Vectors,
+        accept: impl Fn(usize) -> bool + Copy,
+    ) -> f32 {
+        let mut found = 0;
+        let mut expected = 0;
+        for query in queries {
+            let exact = brute_force(query, k, vectors, accept);
+            expected += exact.len();
+            found += index
+                .search(query, k, vectors)
+                .iter()
+                .filter(|(slot, _)| exact.contains(slot))
+                .count();
+        }
+        found as f32 / expected as f32
+    }
+
+    /// Check that every link has its reverse link and no link leaves the
+    /// graph
+    fn assert_consistent(index: &HnswIndex) {
+        for (slot, node) in index.layers.iter().enumerate() {
+            assert_eq!(node.len(), index.incoming[slot].len());
+            for (layer, neighbors) in node.iter().enumerate() {
+                for &neighbor in neighbors {
+                    assert!(
+                        index.contains(neighbor),
+                        "{} links to removed {}",
+                        slot,
+                        neighbor
+                    );
+                    assert!(index.incoming[neighbor][layer].contains(&slot));
+                }
+                for &node in &index.incoming[slot][layer] {
+                    assert!(index.layers[node][layer].contains(&slot));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn search_recall_matches_brute_force() {
+        let vectors = Vectors(clustered_vectors(2000, 32, 0x9e37_79b9_7f4a_7c15));
+        let queries = clustered_vectors(50, 32, 0x2545_f491_4f6c_dd1d);
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..vectors.0.len() {
+            index.insert(slot, &vectors);
+        }
+
+        assert_eq!(index.len(), 2000);
+        let recall = recall(&index, &queries, 10, &vectors, |_| true);
+        assert!(recall >= 0.9, "recall {} below 0.9", recall);
+    }
+
+    #[test]
+    fn remove_unlinks_and_keeps_recall() {
+        let vectors = Vectors(clustered_vectors(1000, 16, 0x853c_49e6_748f_ea9b));
+        let queries = clustered_vectors(50, 16, 0xda3e_39cb_94b9_5bdb);
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..vectors.0.len() {
+            index.insert(slot, &vectors);
+        }
+
+        let entry_point = index.entry_point.unwrap();
+        index.remove(entry_point, &vectors);
+        for slot in (0..vectors.0.len()).step_by(2) {
+            index.remove(slot, &vectors);
+        }
+
+        assert_consistent(&index);
+        assert!(index.entry_point.map_or(false, |slot| index.contains(slot)));
+
+        let remaining = |slot: usize| slot % 2 == 1 && slot != entry_point;
+        assert_eq!(
+            index.len(),
+            (0..vectors.0.len()).filter(|&slot| remaining(slot)).count()
+        );
+        for query in &queries {
+            for (slot, _) in index.search(query, 10, &vectors) {
+                assert!(remaining(slot), "removed slot {} returned", slot);
+            }
+        }
+
+        let recall = recall(&index, &queries, 10, &vectors, remaining);
+        assert!(recall >= 0.9, "recall {} below 0.9 after removals", recall);
+    }
+
+    #[test]
+    fn remove_last_node_empties_graph() {
+        let vectors = Vectors(clustered_vectors(3, 8, 7));
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..3 {
+            index.insert(slot, &vectors);
+        }
+        for slot in 0..3 {
+            index.remove(slot, &vectors);
+        }
+
+        assert!(index.is_empty());
+        assert_eq!(index.entry_point, None);
+        assert!(index.search(&vectors.0[0], 5, &vectors).is_empty());
+    }
+
+    #[test]
+    fn reinsert_keeps_links_consistent() {
+        let vectors = Vectors(clustered_vectors(200, 8, 11));
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..vectors.0.len() {
+            index.insert(slot, &vectors);
+        }
+        for slot in (0..vectors.0.len()).step_by(3) {
+            index.insert(slot, &vectors);
+        }
+
+        assert_eq!(index.len(), 200);
+        assert_consistent(&index);
+    }
+
+    #[test]
+    fn remove_and_reinsert_keeps_recall() {
+        let vectors = Vectors(clustered_vectors(1500, 24, 0x6a09_e667_f3bc_c908));
+        let queries = clustered_vectors(50, 24, 0xbb67_ae85_84ca_a73b);
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..vectors.0.len() {
+            index.insert(slot, &vectors);
+        }
+        let initial = recall(&index, &queries, 10, &vectors, |_| true);
+
+        // Churn three quarters of the graph, as a large reindex does
+        let churned: Vec<usize> = (0..vectors.0.len()).filter(|slot| slot % 4 != 0).collect();
+        for &slot in &churned {
+            index.remove(slot, &vectors);
+        }
+        assert_consistent(&index);
+        assert_eq!(index.len(), vectors.0.len() - churned.len());
+
+        for &slot in churned.iter().rev() {
+            index.insert(slot, &vectors);
+        }
+        assert_consistent(&index);
+        assert_eq!(index.len(), vectors.0.len());
+
+        let churned_recall = recall(&index, &queries, 10, &vectors, |_| true);
+        assert!(
+            churned_recall >= 0.9,
+            "recall {} below 0.9 after reinsertion (initially {})",
+            churned_recall,
+            initial
+        );
+    }
+
+    #[test]
+    fn encode_decode_round_trips() {
+        let vectors = Vectors(clustered_vectors(300, 8, 13));
+
+        let mut index = HnswIndex::new(config());
+        for slot in 0..vectors.0.len() {
+            index.insert(slot, &vectors);
+        }
+        index.remove(5, &vectors);
+
+        let mut bytes = Vec::new();
+        index.encode(&mut bytes);
+        let mut decoded = HnswIndex::decode(&mut ByteReader::new(&bytes)).unwrap();
+
+        assert_eq!(decoded.len(), index.len());
+        assert_eq!(decoded.entry_point, index.entry_point);
+        assert_eq!(decoded.rng_state, index.rng_state);
+        assert_eq!(decoded.config(), index.config());
+        assert_eq!(decoded.layers, index.layers);
+        assert_consistent(&decoded);
+
+        // Encoding the decoded graph gives the same bytes
+        let mut reencoded = Vec::new();
+        decoded.encode(&mut reencoded);
+        assert_eq!(reencoded, bytes);
+        assert_eq!(
+            decoded.search(&vectors.0[42], 10, &vectors),
+            index.search(&vectors.0[42], 10, &vectors)
+        );
+
+        decoded.remove(42, &vectors);
+        assert_consistent(&decoded);
+    }
+}
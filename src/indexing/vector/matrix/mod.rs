@@ -0,0 +1,165 @@
+//! Vector matrix storage
+//!
+//! This module holds the vectors of a vector store as rows of `f32` values.
+//! Rows of a saved store are read in place from the buffer the store file
+//! was read into. Rows added or replaced since are held on their own until
+//! the store is saved and read again; modifying a row never copies the
+//! others.
+
+use super::file::StoreFile;
+use super::hnsw::VectorSource;
+use crate::errors::{Result, ZseiError};
+
+/// Rows of `f32` values
+#[derive(Debug)]
+pub struct VectorMatrix {
+    /// Length of each row
+    dimension: usize,
+
+    /// Where each row is held
+    rows: Vec<Row>,
+
+    /// Rows read from a saved store, if any
+    saved: Option<SavedRows>,
+}
+
+/// Where a row is held
+#[derive(Debug, Clone)]
+enum Row {
+    /// Read from the saved store file
+    Saved,
+
+    /// Held in memory
+    Owned(Box<[f32]>),
+
+    /// Cleared, with no values
+    Cleared,
+}
+
+/// Rows read from a saved store file
+#[derive(Debug)]
+struct SavedRows {
+    /// Contents of the file
+    file: StoreFile,
+
+    /// Byte offset of the first row in the file
+    offset: usize,
+}
+
+impl VectorMatrix {
+    /// Create an empty matrix
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            rows: Vec::new(),
+            saved: None,
+        }
+    }
+
+    /// Use `rows` rows of `dimension` little-endian `f32` values from a
+    /// saved store file, starting at byte `offset`
+    ///
+    /// `offset` must be a multiple of 4, so rows can be read in place. On
+    /// big-endian targets the values are copied and converted instead.
+    pub fn from_file(
+        file: StoreFile,
+        dimension: usize,
+        rows: usize,
+        offset: usize,
+    ) -> Result<Self> {
+        let end = rows
+            .checked_mul(dimension)
+            .and_then(|values| values.checked_mul(4))
+            .and_then(|len| len.checked_add(offset))
+            .filter(|&end| offset % 4 == 0 && end <= file.len())
+            .ok_or_else(|| {
+                ZseiError::Indexing("Vector matrix does not fit in the file".to_string())
+            })?;
+
+        if cfg!(target_endian = "big") {
+            let rows = file[offset..end]
+                .chunks_exact(dimension.max(1) * 4)
+                .map(|bytes| {
+                    let values: Vec<f32> = bytes
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect();
+                    Row::Owned(values.into_boxed_slice())
+                })
+                .collect();
+            return Ok(Self {
+                dimension,
+                rows,
+                saved: None,
+            });
+        }
+
+        Ok(Self {
+            dimension,
+            rows: vec![Row::Saved; rows],
+            saved: Some(SavedRows { file, offset }),
+        })
+    }
+
+    /// Get the length of each row
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check if rows are read from a saved store file
+    pub fn is_file_backed(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    /// Get a row
+    ///
+    /// Returns an empty slice for cleared rows and rows past the end.
+    pub fn row(&self, row: usize) -> &[f32] {
+        match (self.rows.get(row), &self.saved) {
+            (Some(Row::Owned(values)), _) => values,
+            (Some(Row::Saved), Some(saved)) => {
+                let start = saved.offset + row * self.dimension * 4;
+                bytemuck::cast_slice(&saved.file[start..start + self.dimension * 4])
+            }
+            _ => &[],
+        }
+    }
+
+    /// Overwrite a row, appending cleared rows up to it if needed
+    pub fn set_row(&mut self, row: usize, values: &[f32]) {
+        if self.rows.len() <= row {
+            self.rows.resize(row + 1, Row::Cleared);
+        }
+        self.rows[row] = Row::Owned(values.into());
+    }
+
+    /// Clear a row
+    pub fn clear_row(&mut self, row: usize) {
+        if let Some(slot) = self.rows.get_mut(row) {
+            *slot = Row::Cleared;
+        }
+    }
+
+    /// Get the memory used by the rows, in bytes
+    pub fn resident_bytes(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| match row {
+                Row::Owned(values) => values.len() * std::mem::size_of::<f32>(),
+                Row::Saved => self.dimension * std::mem::size_of::<f32>(),
+                Row::Cleared => 0,
+            })
+            .sum()
+    }
+}
+
+impl VectorSource for VectorMatrix {
+    fn vector(&self, slot: usize) -> &[f32] {
+        self.row(slot)
+    }
+}
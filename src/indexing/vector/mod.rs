@@ -3,14 +3,29 @@
 //! This module provides vector storage functionality for the
 //! Zero-Shot Bolted Embedding Indexer.
 
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufReader, BufWriter, Seek, SeekFrom, Write};
 use std::path::Path;
+use tracing::{debug, info, warn};
 
+pub mod file;
+pub mod format;
+pub mod hnsw;
+pub mod matrix;
+pub mod quantization;
+
+use crate::core::config::{
+    HnswConfig, IndexingConfig, QuantizationConfig, QuantizationMode, VectorStoreType,
+};
 use crate::embedding::Embedding;
 use crate::errors::{Result, ZseiError};
+use file::StoreFile;
+use format::{ByteReader, Header, HEADER_LEN};
+use hnsw::{similarity, HnswIndex};
+use matrix::VectorMatrix;
+use quantization::{product_subspaces, QuantizedVectors};
 
 /// Vector search parameters
 #[derive(Debug, Clone)]
@@ -36,13 +51,53 @@ pub struct VectorSearchResult {
 }
 
 /// Vector store
+///
+/// Embeddings are kept in slots: the vectors, normalized on insertion so
+/// similarity is a plain dot product, are the rows of a matrix read from
+/// the saved store, and the IDs are kept alongside. The HNSW backend
+/// additionally maintains a graph over the slots; the flat backend scans all
+/// of them, using quantized codes when quantization is enabled.
 pub struct VectorStore {
-    /// Embeddings indexed by ID
-    embeddings: HashMap<String, StoredEmbedding>,
+    /// Search backend
+    backend: VectorStoreType,
+
+    /// Parameters of the HNSW graph
+    hnsw_config: HnswConfig,
+
+    /// Stored embeddings by slot; `None` for free slots
+    entries: Vec<Option<StoredEntry>>,
+
+    /// Vectors by slot
+    matrix: VectorMatrix,
+
+    /// Slot of each embedding ID
+    slots: HashMap<String, usize>,
+
+    /// Free slots, reused by the next insertions
+    free_slots: Vec<usize>,
+
+    /// HNSW graph, for the HNSW backend
+    hnsw: Option<HnswIndex>,
+
+    /// Quantization parameters
+    quantization: QuantizationConfig,
+
+    /// Quantized vectors by slot, once built
+    quantized: Option<QuantizedVectors>,
+}
+
+/// Stored embedding, without its vector
+#[derive(Debug, Clone)]
+struct StoredEntry {
+    /// Embedding ID
+    id: String,
+
+    /// Embedding type
+    embedding_type: crate::embedding::EmbeddingType,
 }
 
-/// Stored embedding
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stored embedding in the JSON layout of earlier versions
+#[derive(Debug, Clone, Deserialize)]
 struct StoredEmbedding {
     /// Embedding ID
     id: String,
@@ -52,120 +107,946 @@ struct StoredEmbedding {
 
     /// Embedding type
     embedding_type: crate::embedding::EmbeddingType,
-
-    /// Embedding dimension
-    dimension: usize,
 }
 
 impl VectorStore {
-    /// Create a new vector store
+    /// Create a new flat vector store
     pub fn new() -> Self {
+        Self::with_backend(VectorStoreType::Flat, HnswConfig::default())
+    }
+
+    /// Create a vector store with the backend selected in the configuration
+    pub fn from_config(config: &IndexingConfig) -> Self {
+        Self::with_backend(config.vector_store_type.clone(), config.hnsw.clone())
+            .with_quantization(config.quantization.clone())
+    }
+
+    /// Create a vector store with a given backend
+    ///
+    /// Faiss is not built in, so it falls back to HNSW.
+    pub fn with_backend(backend: VectorStoreType, hnsw_config: HnswConfig) -> Self {
+        let backend = match backend {
+            VectorStoreType::Faiss => {
+                warn!("Faiss vector store is not available, using HNSW");
+                VectorStoreType::Hnsw
+            }
+            backend => backend,
+        };
+
+        let hnsw = match backend {
+            VectorStoreType::Hnsw => Some(HnswIndex::new(hnsw_config.clone())),
+            _ => None,
+        };
+
         Self {
-            embeddings: HashMap::new(),
+            backend,
+            hnsw_config,
+            entries: Vec::new(),
+            matrix: VectorMatrix::new(0),
+            slots: HashMap::new(),
+            free_slots: Vec::new(),
+            hnsw,
+            quantization: QuantizationConfig::default(),
+            quantized: None,
+        }
+    }
+
+    /// Set the quantization parameters
+    ///
+    /// Quantization only applies to the flat backend; configurations
+    /// combining it with HNSW are rejected by
+    /// [`Config::validate`](crate::core::config::Config::validate), and the
+    /// HNSW backend ignores it. Existing vectors are quantized on the next
+    /// [`VectorStore::refresh_quantization`].
+    pub fn with_quantization(mut self, mut quantization: QuantizationConfig) -> Self {
+        if self.hnsw.is_some() && quantization.mode != QuantizationMode::None {
+            warn!("Quantization is only used by the flat vector store");
+            quantization.mode = QuantizationMode::None;
         }
+
+        self.quantization = quantization;
+        self.quantized = None;
+        self
+    }
+
+    /// Get the search backend
+    pub fn backend(&self) -> &VectorStoreType {
+        &self.backend
+    }
+
+    /// Get the dimension of the stored vectors, 0 if none were stored yet
+    pub fn dimension(&self) -> usize {
+        self.matrix.dimension()
+    }
+
+    /// Get the number of stored embeddings
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Check if the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Check if the vectors are read from a saved store file
+    pub fn is_file_backed(&self) -> bool {
+        self.matrix.is_file_backed()
     }
 
     /// Add an embedding to the store
+    ///
+    /// An embedding with the same ID is replaced. All embeddings must have
+    /// the dimension of the first one.
     pub fn add_embedding(&mut self, embedding: Embedding) -> Result<()> {
         // Extract info from embedding
-        let id = embedding.metadata.content_hash.clone();
-        let vector = embedding.vector;
+        let id = embedding.metadata.id();
+        let mut vector = embedding.vector;
         let embedding_type = embedding.embedding_type;
         let dimension = vector.len();
 
-        // Create stored embedding
-        let stored_embedding = StoredEmbedding {
-            id: id.clone(),
-            vector,
-            embedding_type,
-            dimension,
-        };
+        if dimension == 0 {
+            return Err(ZseiError::Indexing(format!(
+                "Embedding {} has no values",
+                id
+            )));
+        }
+
+        if self.is_empty() {
+            self.reset(dimension);
+        } else if dimension != self.dimension() {
+            return Err(ZseiError::Indexing(format!(
+                "Embedding {} has dimension {}, but the store has dimension {}",
+                id,
+                dimension,
+                self.dimension()
+            )));
+        }
+
+        crate::embedding::utils::normalize(&mut vector);
+
+        // Replace an earlier embedding with the same ID
+        self.remove_embedding(&id);
 
         // Add to store
-        self.embeddings.insert(id, stored_embedding);
+        let slot = self.insert_entry(StoredEntry { id, embedding_type }, &vector);
+        if let Some(hnsw) = &mut self.hnsw {
+            hnsw.insert(slot, &self.matrix);
+        }
+        if let Some(quantized) = &mut self.quantized {
+            quantized.set_row(slot, &vector);
+        }
 
         Ok(())
     }
 
+    /// Remove an embedding from the store
+    ///
+    /// Returns whether an embedding with the ID was stored.
+    pub fn remove_embedding(&mut self, id: &str) -> bool {
+        let slot = match self.slots.remove(id) {
+            Some(slot) => slot,
+            None => return false,
+        };
+
+        // Unlink the slot while the vectors of its neighbors are available
+        if let Some(hnsw) = &mut self.hnsw {
+            hnsw.remove(slot, &self.matrix);
+        }
+
+        self.entries[slot] = None;
+        self.matrix.clear_row(slot);
+        if let Some(quantized) = &mut self.quantized {
+            quantized.clear_row(slot);
+        }
+        self.free_slots.push(slot);
+
+        true
+    }
+
+    /// Give an embedding a new ID, replacing any embedding with that ID
+    ///
+    /// Returns whether an embedding with the old ID was stored.
+    pub fn rename_embedding(&mut self, from: &str, to: &str) -> bool {
+        if from == to {
+            return self.contains_embedding(from);
+        }
+
+        let slot = match self.slots.remove(from) {
+            Some(slot) => slot,
+            None => return false,
+        };
+
+        self.remove_embedding(to);
+        if let Some(entry) = &mut self.entries[slot] {
+            entry.id = to.to_string();
+        }
+        self.slots.insert(to.to_string(), slot);
+
+        true
+    }
+
+    /// Check if an embedding is stored
+    pub fn contains_embedding(&self, id: &str) -> bool {
+        self.slots.contains_key(id)
+    }
+
     /// Search for similar embeddings
+    ///
+    /// Uses the HNSW graph when the store has one, a scan of the quantized
+    /// codes when quantization is enabled, and an exact scan otherwise.
     pub fn search(
         &self,
         query: &[f32],
         params: VectorSearchParams,
     ) -> Result<Vec<VectorSearchResult>> {
+        self.search_filtered(query, params, &|_| true)
+    }
+
+    /// Search for similar embeddings whose ID is accepted by a filter
+    ///
+    /// The filter is applied during the search, so up to
+    /// `params.max_results` accepted embeddings are returned however many
+    /// more similar ones are rejected.
+    pub fn search_filtered(
+        &self,
+        query: &[f32],
+        params: VectorSearchParams,
+        accept: &dyn Fn(&str) -> bool,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let accept_slot = |slot: usize| {
+            self.entries
+                .get(slot)
+                .and_then(Option::as_ref)
+                .map_or(false, |entry| accept(&entry.id))
+        };
+
+        let hnsw = match (&self.hnsw, &self.quantized) {
+            (Some(hnsw), _) => hnsw,
+            (None, Some(quantized)) => {
+                let rescore_factor = self.quantization.rescore_factor;
+                return Ok(self.search_quantized(
+                    quantized,
+                    query,
+                    params,
+                    rescore_factor,
+                    &accept_slot,
+                ));
+            }
+            (None, None) => return Ok(self.scan_exact(query, params, &accept_slot)),
+        };
+
+        let mut query = query.to_vec();
+        crate::embedding::utils::normalize(&mut query);
+
+        let results = hnsw
+            .search_filtered(&query, params.max_results, &self.matrix, &accept_slot)
+            .into_iter()
+            .filter(|&(_, score)| score >= params.min_score)
+            .filter_map(|(slot, score)| self.result(slot, score))
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Search for similar embeddings by scanning the whole store
+    pub fn search_exact(
+        &self,
+        query: &[f32],
+        params: VectorSearchParams,
+    ) -> Vec<VectorSearchResult> {
+        self.scan_exact(query, params, &|_| true)
+    }
+
+    /// Scan the accepted slots with the full vectors
+    fn scan_exact(
+        &self,
+        query: &[f32],
+        params: VectorSearchParams,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<VectorSearchResult> {
+        let mut query = query.to_vec();
+        crate::embedding::utils::normalize(&mut query);
+
         let mut results = Vec::new();
 
         // Calculate similarity for each embedding
-        for (id, embedding) in &self.embeddings {
-            let score = self.cosine_similarity(query, &embedding.vector);
+        for slot in self.slots.values().copied() {
+            if !accept(slot) {
+                continue;
+            }
 
+            let score = similarity(&query, self.matrix.row(slot));
             if score >= params.min_score {
-                results.push(VectorSearchResult {
-                    id: id.clone(),
-                    score,
-                    embedding_type: embedding.embedding_type.clone(),
-                });
+                results.push((slot, score));
             }
         }
 
         // Sort by score (descending)
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        results.sort_by(by_score);
 
         // Limit to max results
-        if results.len() > params.max_results {
-            results.truncate(params.max_results);
+        results.truncate(params.max_results);
+
+        results
+            .into_iter()
+            .filter_map(|(slot, score)| self.result(slot, score))
+            .collect()
+    }
+
+    /// Scan the accepted slots with the quantized codes
+    ///
+    /// With a `rescore_factor`, that many times the requested number of
+    /// candidates are rescored with their full vectors when available.
+    fn search_quantized(
+        &self,
+        quantized: &QuantizedVectors,
+        query: &[f32],
+        params: VectorSearchParams,
+        rescore_factor: usize,
+        accept: &dyn Fn(usize) -> bool,
+    ) -> Vec<VectorSearchResult> {
+        let mut query = query.to_vec();
+        crate::embedding::utils::normalize(&mut query);
+
+        let prepared = quantized.prepare(&query);
+        let mut candidates: Vec<(usize, f32)> = self
+            .slots
+            .values()
+            .copied()
+            .filter(|&slot| accept(slot))
+            .map(|slot| (slot, quantized.score(&prepared, slot)))
+            .collect();
+        candidates.sort_by(by_score);
+
+        if rescore_factor > 0 {
+            candidates.truncate(params.max_results.saturating_mul(rescore_factor));
+
+            let dimension = self.dimension();
+            for (slot, score) in &mut candidates {
+                let row = self.matrix.row(*slot);
+                if row.len() == dimension {
+                    *score = similarity(&query, row);
+                }
+            }
+            candidates.sort_by(by_score);
         }
 
-        Ok(results)
+        candidates
+            .into_iter()
+            .filter(|&(_, score)| score >= params.min_score)
+            .take(params.max_results)
+            .filter_map(|(slot, score)| self.result(slot, score))
+            .collect()
     }
 
-    /// Save the vector store to disk
-    pub fn save(&self, path: &Path) -> Result<()> {
-        // Serialize to binary format
-        let file = File::create(path)
-            .map_err(|e| ZseiError::Indexing(format!("Failed to create file: {}", e)))?;
+    /// Build or update the quantized codes to match the quantization mode
+    ///
+    /// Scalar codes are built once and then kept up to date on insertion.
+    /// A product quantization codebook is trained on the stored vectors,
+    /// and retrained when the store has doubled in size since.
+    pub fn refresh_quantization(&mut self) {
+        if self.quantization.mode == QuantizationMode::None {
+            self.quantized = None;
+            return;
+        }
+
+        let dimension = self.dimension();
+        let stale = match &self.quantized {
+            Some(quantized) if quantized.mode() == self.quantization.mode => {
+                quantized.dimension() != dimension
+                    || quantized.subspaces().map_or(false, |subspaces| {
+                        subspaces != product_subspaces(dimension, self.quantization.pq_subspaces)
+                            || self.len() >= quantized.trained_on().saturating_mul(2)
+                    })
+            }
+            _ => true,
+        };
 
-        let writer = BufWriter::new(file);
+        if !stale || self.is_empty() {
+            return;
+        }
+
+        let mut quantized = match self.quantization.mode {
+            QuantizationMode::Product => {
+                debug!(
+                    "Training product quantization codebook on {} embeddings",
+                    self.len()
+                );
+                let vectors: Vec<&[f32]> = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.is_some())
+                    .map(|(slot, _)| self.matrix.row(slot))
+                    .collect();
+                QuantizedVectors::train_product(dimension, self.quantization.pq_subspaces, &vectors)
+            }
+            _ => QuantizedVectors::scalar(dimension),
+        };
+
+        for (slot, entry) in self.entries.iter().enumerate() {
+            if entry.is_some() {
+                quantized.set_row(slot, self.matrix.row(slot));
+            }
+        }
+        self.quantized = Some(quantized);
+    }
 
-        serde_json::to_writer(writer, &self.embeddings)
-            .map_err(|e| ZseiError::Indexing(format!("Failed to serialize vector store: {}", e)))?;
+    /// Save the vector store to disk
+    ///
+    /// Writes the binary layout described in [`format`] to a temporary file
+    /// and renames it over `path`, so a crash never leaves a partially
+    /// written store. The vectors are then read back from the saved file into
+    /// one buffer, replacing the vectors added since the last save.
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let temp_path = path.with_extension("bin.tmp");
+        self.write_binary(&temp_path)
+            .and_then(|_| fs::rename(&temp_path, path))
+            .map_err(|e| ZseiError::Indexing(format!("Failed to write vector store: {}", e)))?;
+
+        let file = StoreFile::open(path)?;
+        self.matrix =
+            VectorMatrix::from_file(file, self.dimension(), self.entries.len(), HEADER_LEN)?;
 
         Ok(())
     }
 
     /// Load the vector store from disk
+    ///
+    /// Binary stores are read into one buffer the vectors are used from in
+    /// place. JSON stores written by earlier versions are still accepted
+    /// and are converted to the binary layout on the next save. The saved
+    /// HNSW graph is reused when it was built with the configured parameters;
+    /// otherwise the graph is rebuilt.
     pub fn load(&mut self, path: &Path) -> Result<()> {
-        // Deserialize from binary format
+        let file = StoreFile::open(path)?;
+
+        if !format::is_binary(&file) {
+            drop(file);
+            info!("Converting JSON vector store {}", path.display());
+            return self.load_json(path);
+        }
+
+        // Only the sections after the matrix are checked here, so opening a
+        // store does not hash every vector
+        let header = Header::decode(&file)?;
+        let table = file
+            .get(header.ids_offset..)
+            .filter(|_| header.ids_offset >= HEADER_LEN)
+            .ok_or_else(|| ZseiError::Indexing("ID table out of range".to_string()))?;
+        if crc32fast::hash(table) != header.checksum {
+            return Err(ZseiError::Indexing(format!(
+                "Vector store {} is corrupted: checksum mismatch",
+                path.display()
+            )));
+        }
+
+        // Read the ID table
+        let mut reader = ByteReader::new(table);
+        let mut entries = Vec::with_capacity(header.slots);
+        for _ in 0..header.slots {
+            let code = reader.u8()?;
+            let len = reader.u32()? as usize;
+            let id = reader.bytes(len)?;
+
+            if code == format::FREE_SLOT {
+                entries.push(None);
+                continue;
+            }
+
+            let id = String::from_utf8(id.to_vec())
+                .map_err(|e| ZseiError::Indexing(format!("Invalid embedding ID: {}", e)))?;
+            entries.push(Some(StoredEntry {
+                id,
+                embedding_type: format::embedding_type_from_code(code)?,
+            }));
+        }
+
+        // Read the graph
+        let hnsw = match header.graph_offset {
+            Some(offset) => {
+                let graph = file
+                    .get(offset..)
+                    .ok_or_else(|| ZseiError::Indexing("HNSW graph out of range".to_string()))?;
+                Some(HnswIndex::decode(&mut ByteReader::new(graph))?)
+            }
+            None => None,
+        };
+
+        // Read the quantized codes
+        let quantized = match header.quantization_offset {
+            Some(offset) => {
+                let codes = file.get(offset..).ok_or_else(|| {
+                    ZseiError::Indexing("Quantized codes out of range".to_string())
+                })?;
+                Some(QuantizedVectors::decode(&mut ByteReader::new(codes))?)
+            }
+            None => None,
+        };
+
+        let matrix = VectorMatrix::from_file(file, header.dimension, header.slots, HEADER_LEN)?;
+
+        self.set_entries(entries);
+        self.matrix = matrix;
+        self.hnsw = hnsw;
+        self.quantized = quantized;
+        self.sync_hnsw();
+        self.refresh_quantization();
+
+        Ok(())
+    }
+
+    /// Check the matrix of a saved binary store against its checksum
+    ///
+    /// [`VectorStore::load`] only checks the sections after the matrix, so
+    /// corrupted vectors go unnoticed until this is run. Reads every vector.
+    pub fn verify(path: &Path) -> Result<()> {
+        let file = StoreFile::open(path)?;
+        let header = Header::decode(&file)?;
+
+        let matrix = file
+            .get(HEADER_LEN..header.ids_offset)
+            .ok_or_else(|| ZseiError::Indexing("Vector matrix out of range".to_string()))?;
+        if crc32fast::hash(matrix) != header.matrix_checksum {
+            return Err(ZseiError::Indexing(format!(
+                "Vector store {} is corrupted: matrix checksum mismatch",
+                path.display()
+            )));
+        }
+
+        let tail = &file[header.ids_offset..];
+        if crc32fast::hash(tail) != header.checksum {
+            return Err(ZseiError::Indexing(format!(
+                "Vector store {} is corrupted: checksum mismatch",
+                path.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Write the store in the binary layout
+    fn write_binary(&self, path: &Path) -> std::io::Result<()> {
+        let file = File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        let mut matrix_hasher = crc32fast::Hasher::new();
+
+        // Reserve the header, written once the checksums are known
+        writer.write_all(&[0u8; HEADER_LEN])?;
+
+        // Matrix
+        let dimension = self.dimension();
+        let mut row_bytes = Vec::with_capacity(dimension * 4);
+        for slot in 0..self.entries.len() {
+            row_bytes.clear();
+            let row = self.matrix.row(slot);
+            if row.len() == dimension {
+                for value in row {
+                    row_bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            } else {
+                row_bytes.resize(dimension * 4, 0);
+            }
+
+            matrix_hasher.update(&row_bytes);
+            writer.write_all(&row_bytes)?;
+        }
+
+        // ID table
+        let ids_offset = HEADER_LEN + self.entries.len() * dimension * 4;
+        let mut tail = Vec::new();
+        for entry in &self.entries {
+            match entry {
+                Some(entry) => {
+                    tail.push(format::embedding_type_code(&entry.embedding_type));
+                    format::put_u32(&mut tail, entry.id.len() as u32);
+                    tail.extend_from_slice(entry.id.as_bytes());
+                }
+                None => {
+                    tail.push(format::FREE_SLOT);
+                    format::put_u32(&mut tail, 0);
+                }
+            }
+        }
+
+        // Graph
+        let graph_offset = self.hnsw.as_ref().map(|hnsw| {
+            let offset = ids_offset + tail.len();
+            hnsw.encode(&mut tail);
+            offset
+        });
+
+        // Quantized codes
+        let quantization_offset = self.quantized.as_ref().map(|quantized| {
+            let offset = ids_offset + tail.len();
+            quantized.encode(&mut tail);
+            offset
+        });
+
+        writer.write_all(&tail)?;
+
+        let header = Header {
+            dimension,
+            slots: self.entries.len(),
+            ids_offset,
+            graph_offset,
+            quantization_offset,
+            checksum: crc32fast::hash(&tail),
+            matrix_checksum: matrix_hasher.finalize(),
+        };
+
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header.encode())?;
+        file.sync_all()
+    }
+
+    /// Load a store saved as JSON by earlier versions
+    fn load_json(&mut self, path: &Path) -> Result<()> {
         let file = File::open(path)
             .map_err(|e| ZseiError::Indexing(format!("Failed to open file: {}", e)))?;
 
         let reader = BufReader::new(file);
 
-        self.embeddings = serde_json::from_reader(reader).map_err(|e| {
-            ZseiError::Indexing(format!("Failed to deserialize vector store: {}", e))
-        })?;
+        let embeddings: HashMap<String, StoredEmbedding> = serde_json::from_reader(reader)
+            .map_err(|e| {
+                ZseiError::Indexing(format!("Failed to deserialize vector store: {}", e))
+            })?;
+
+        let mut embeddings: Vec<StoredEmbedding> = embeddings.into_values().collect();
+        embeddings.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let dimension = embeddings
+            .iter()
+            .map(|embedding| embedding.vector.len())
+            .find(|&len| len > 0)
+            .unwrap_or(0);
+
+        let mut entries = Vec::with_capacity(embeddings.len());
+        let mut matrix = VectorMatrix::new(dimension);
+        for mut embedding in embeddings {
+            if embedding.vector.len() != dimension {
+                warn!(
+                    "Dropping embedding {} with dimension {} instead of {}",
+                    embedding.id,
+                    embedding.vector.len(),
+                    dimension
+                );
+                continue;
+            }
+
+            crate::embedding::utils::normalize(&mut embedding.vector);
+            matrix.set_row(entries.len(), &embedding.vector);
+            entries.push(Some(StoredEntry {
+                id: embedding.id,
+                embedding_type: embedding.embedding_type,
+            }));
+        }
+
+        self.set_entries(entries);
+        self.matrix = matrix;
+        self.hnsw = None;
+        self.quantized = None;
+        self.sync_hnsw();
+        self.refresh_quantization();
 
         Ok(())
     }
 
-    /// Calculate cosine similarity between two vectors
-    fn cosine_similarity(&self, a: &[f32], b: &[f32]) -> f32 {
-        if a.len() != b.len() {
-            return 0.0;
+    /// Remove all embeddings and set the dimension of the next ones
+    fn reset(&mut self, dimension: usize) {
+        self.entries.clear();
+        self.slots.clear();
+        self.free_slots.clear();
+        self.matrix = VectorMatrix::new(dimension);
+        if let Some(hnsw) = &mut self.hnsw {
+            *hnsw = HnswIndex::new(self.hnsw_config.clone());
+        }
+        self.quantized = match self.quantization.mode {
+            QuantizationMode::Int8 => Some(QuantizedVectors::scalar(dimension)),
+            _ => None,
+        };
+    }
+
+    /// Replace all entries and rebuild the ID and free slot tables
+    fn set_entries(&mut self, entries: Vec<Option<StoredEntry>>) {
+        self.slots.clear();
+        self.free_slots.clear();
+
+        for (slot, entry) in entries.iter().enumerate() {
+            match entry {
+                Some(entry) => {
+                    self.slots.insert(entry.id.clone(), slot);
+                }
+                None => self.free_slots.push(slot),
+            }
+        }
+
+        self.entries = entries;
+    }
+
+    /// Make the HNSW graph match the backend and its parameters
+    fn sync_hnsw(&mut self) {
+        if self.backend != VectorStoreType::Hnsw {
+            self.hnsw = None;
+            return;
+        }
+
+        let reusable = self.hnsw.as_ref().map_or(false, |hnsw| {
+            hnsw.len() == self.slots.len()
+                && hnsw.config().m == self.hnsw_config.m
+                && hnsw.config().ef_construction == self.hnsw_config.ef_construction
+        });
+
+        if reusable {
+            if let Some(hnsw) = &mut self.hnsw {
+                hnsw.set_ef_search(self.hnsw_config.ef_search);
+            }
+            return;
+        }
+
+        debug!("Building HNSW graph over {} embeddings", self.slots.len());
+        let mut hnsw = HnswIndex::new(self.hnsw_config.clone());
+        for (slot, entry) in self.entries.iter().enumerate() {
+            if entry.is_some() {
+                hnsw.insert(slot, &self.matrix);
+            }
+        }
+        self.hnsw = Some(hnsw);
+    }
+
+    /// Put an embedding in a free slot
+    fn insert_entry(&mut self, entry: StoredEntry, vector: &[f32]) -> usize {
+        let id = entry.id.clone();
+
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.entries[slot] = Some(entry);
+                slot
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+
+        self.matrix.set_row(slot, vector);
+        self.slots.insert(id, slot);
+        slot
+    }
+
+    /// Build the search result for a slot
+    fn result(&self, slot: usize, score: f32) -> Option<VectorSearchResult> {
+        self.entries[slot].as_ref().map(|entry| VectorSearchResult {
+            id: entry.id.clone(),
+            score,
+            embedding_type: entry.embedding_type.clone(),
+        })
+    }
+}
+
+/// Order slots by descending score, then by slot
+fn by_score(a: &(usize, f32), b: &(usize, f32)) -> std::cmp::Ordering {
+    b.1.partial_cmp(&a.1)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| a.0.cmp(&b.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::{EmbeddingMetadata, EmbeddingType};
+    use std::path::PathBuf;
+
+    /// Embedding of a numbered chunk with a deterministic vector
+    fn embedding(index: usize, dimension: usize) -> Embedding {
+        let vector = (0..dimension)
+            .map(|i| (((index * 31 + i * 17) % 97) as f32 - 48.0) / 48.0)
+            .collect();
+
+        Embedding {
+            vector,
+            embedding_type: EmbeddingType::Code,
+            metadata: EmbeddingMetadata {
+                source_path: PathBuf::from(format!("src/file_{}.rs", index % 7)),
+                content_hash: format!("{:x}", index),
+                chunk: None,
+                lines: None,
+                language: Some("rust".to_string()),
+                timestamp: chrono::Utc::now(),
+                attributes: HashMap::new(),
+            },
+        }
+    }
+
+    /// ID of the embedding of a numbered chunk
+    fn id(index: usize) -> String {
+        embedding(index, 24).metadata.id()
+    }
+
+    /// Store of the given backend holding `count` embeddings, with every
+    /// fifth one removed again to leave free slots
+    fn store(backend: VectorStoreType, count: usize) -> VectorStore {
+        let mut store = VectorStore::with_backend(backend, HnswConfig::default());
+        for index in 0..count {
+            store.add_embedding(embedding(index, 24)).unwrap();
+        }
+        for index in (0..count).step_by(5) {
+            assert!(store.remove_embedding(&id(index)));
+        }
+        store
+    }
+
+    /// Path of a scratch file for a test, removed if left by an earlier run
+    fn scratch_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("zsei-vector-{}-{}.bin", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    /// IDs and scores of the best matches of a query
+    fn top(store: &VectorStore, query: &[f32]) -> Vec<(String, f32)> {
+        let params = VectorSearchParams {
+            max_results: 10,
+            min_score: -1.0,
+        };
+        store
+            .search(query, params)
+            .unwrap()
+            .into_iter()
+            .map(|result| (result.id, result.score))
+            .collect()
+    }
+
+    /// Flip one bit of a saved file
+    fn corrupt(path: &Path, offset: usize) {
+        let mut bytes = fs::read(path).unwrap();
+        bytes[offset] ^= 0x01;
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn flat_store_round_trips() {
+        let path = scratch_path("flat");
+        let mut saved = store(VectorStoreType::Flat, 60);
+        saved.save(&path).unwrap();
+        assert!(saved.is_file_backed());
+
+        let mut loaded = VectorStore::new();
+        loaded.load(&path).unwrap();
+        assert!(loaded.is_file_backed());
+        VectorStore::verify(&path).unwrap();
+
+        assert_eq!(loaded.len(), saved.len());
+        assert_eq!(loaded.dimension(), 24);
+        for index in 0..60 {
+            let id = id(index);
+            assert_eq!(loaded.contains_embedding(&id), index % 5 != 0);
         }
 
-        let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let query = embedding(3, 24).vector;
+        assert_eq!(top(&loaded, &query), top(&saved, &query));
+
+        // Free slots are reused after loading
+        loaded.add_embedding(embedding(1000, 24)).unwrap();
+        assert_eq!(loaded.len(), saved.len() + 1);
+        assert_eq!(loaded.entries.len(), 60);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn hnsw_store_reuses_saved_graph() {
+        let path = scratch_path("hnsw");
+        let mut saved = store(VectorStoreType::Hnsw, 80);
+        saved.save(&path).unwrap();
+
+        let mut loaded = VectorStore::with_backend(VectorStoreType::Hnsw, HnswConfig::default());
+        loaded.load(&path).unwrap();
+
+        let query = embedding(42, 24).vector;
+        assert_eq!(top(&loaded, &query), top(&saved, &query));
+        assert_eq!(
+            loaded.hnsw.as_ref().map(HnswIndex::len),
+            saved.hnsw.as_ref().map(HnswIndex::len)
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn flipped_byte_fails_tail_checksum() {
+        let path = scratch_path("corrupt-table");
+        store(VectorStoreType::Flat, 20).save(&path).unwrap();
 
-        if norm_a == 0.0 || norm_b == 0.0 {
-            0.0
-        } else {
-            dot_product / (norm_a * norm_b)
+        let header = Header::decode(&fs::read(&path).unwrap()).unwrap();
+        corrupt(&path, header.ids_offset + 6);
+
+        assert!(VectorStore::new().load(&path).is_err());
+        assert!(VectorStore::verify(&path).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn verify_detects_corrupted_matrix() {
+        let path = scratch_path("corrupt-matrix");
+        store(VectorStoreType::Flat, 20).save(&path).unwrap();
+        corrupt(&path, HEADER_LEN + 4 * 24 + 1);
+
+        // Opening does not read the matrix, verifying does
+        assert!(VectorStore::new().load(&path).is_ok());
+        assert!(VectorStore::verify(&path).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn truncated_store_is_rejected() {
+        let path = scratch_path("truncated");
+        store(VectorStoreType::Hnsw, 20).save(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let header = Header::decode(&bytes).unwrap();
+
+        // Cut after the matrix, inside it and inside the header
+        for len in [bytes.len() - 3, HEADER_LEN + 4 * 24 + 2, HEADER_LEN / 2] {
+            fs::write(&path, &bytes[..len]).unwrap();
+            assert!(
+                VectorStore::new().load(&path).is_err(),
+                "store cut to {} bytes loaded",
+                len
+            );
+            assert!(VectorStore::verify(&path).is_err());
         }
+        assert!(header.ids_offset > HEADER_LEN + 4 * 24 + 2);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn json_store_is_converted() {
+        let path = scratch_path("json");
+        let json = serde_json::json!({
+            "a": { "id": "a", "vector": [1.0, 0.0], "embedding_type": "Code", "dimension": 2 },
+            "b": { "id": "b", "vector": [0.0, 2.0], "embedding_type": "Code", "dimension": 2 },
+            "c": { "id": "c", "vector": [1.0], "embedding_type": "Code", "dimension": 1 },
+        });
+        fs::write(&path, json.to_string()).unwrap();
+
+        let mut store = VectorStore::new();
+        store.load(&path).unwrap();
+        // Embeddings of another dimension are dropped
+        assert_eq!(store.len(), 2);
+        assert!(!store.contains_embedding("c"));
+        assert_eq!(top(&store, &[0.0, 1.0])[0].0, "b");
+
+        store.save(&path).unwrap();
+        let mut reloaded = VectorStore::new();
+        reloaded.load(&path).unwrap();
+        assert_eq!(top(&reloaded, &[0.0, 1.0]), top(&store, &[0.0, 1.0]));
+
+        let _ = fs::remove_file(&path);
     }
 }
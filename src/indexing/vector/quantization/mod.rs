@@ -0,0 +1,864 @@
+//! Vector quantization
+//!
+//! This module compresses the vectors of a vector store so large indexes can
+//! be scanned without reading every full-precision vector: scans read the
+//! codes, while the full vectors are only read to rescore the best
+//! candidates. Scalar
+//! quantization keeps one signed byte per value and a scale per vector.
+//! Product quantization splits vectors into subspaces and keeps, for each
+//! subspace, the index of the nearest centroid of a codebook trained with
+//! k-means.
+//!
+//! Scores computed from codes only approximate the dot product of the
+//! normalized vectors, so the store rescores its best candidates with the
+//! full vectors.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::format::{put_f32, put_u32, put_u64, ByteReader};
+use super::{VectorSearchParams, VectorSearchResult, VectorStore};
+use crate::core::config::QuantizationMode;
+use crate::errors::{Result, ZseiError};
+
+/// Maximum number of centroids per subspace, so codes fit in a byte
+const MAX_CENTROIDS: usize = 256;
+
+/// Number of k-means iterations when training a codebook
+const TRAINING_ITERATIONS: usize = 8;
+
+/// Maximum number of vectors used to train a codebook
+const TRAINING_SAMPLE: usize = 10_000;
+
+/// Format code of scalar quantized vectors
+const SCALAR_CODE: u8 = 1;
+
+/// Format code of product quantized vectors
+const PRODUCT_CODE: u8 = 2;
+
+/// Quantized vectors by slot
+#[derive(Debug, Clone)]
+pub enum QuantizedVectors {
+    /// One signed byte per value, scaled per vector
+    Scalar {
+        /// Length of each vector
+        dimension: usize,
+
+        /// Scale of each vector
+        scales: Vec<f32>,
+
+        /// Values divided by the scale of their vector, row after row
+        codes: Vec<i8>,
+    },
+
+    /// One centroid index per subspace
+    Product {
+        /// Length of each vector
+        dimension: usize,
+
+        /// Number of subspaces, a divisor of the dimension
+        subspaces: usize,
+
+        /// Number of centroids per subspace
+        centroids: usize,
+
+        /// Centroids of each subspace, subspace after subspace
+        codebook: Vec<f32>,
+
+        /// Centroid indexes, row after row
+        codes: Vec<u8>,
+
+        /// Number of vectors the codebook was trained on
+        trained_on: usize,
+    },
+}
+
+impl QuantizedVectors {
+    /// Create empty scalar quantized vectors
+    pub fn scalar(dimension: usize) -> Self {
+        QuantizedVectors::Scalar {
+            dimension,
+            scales: Vec::new(),
+            codes: Vec::new(),
+        }
+    }
+
+    /// Train a product quantization codebook on some vectors
+    ///
+    /// The number of subspaces is lowered to a divisor of the dimension. No
+    /// vectors are encoded yet.
+    pub fn train_product(dimension: usize, subspaces: usize, vectors: &[&[f32]]) -> Self {
+        let subspaces = product_subspaces(dimension, subspaces);
+        let sub_dimension = dimension / subspaces;
+
+        // Train on evenly spaced vectors
+        let step = (vectors.len() / TRAINING_SAMPLE).max(1);
+        let sample: Vec<&[f32]> = vectors
+            .iter()
+            .step_by(step)
+            .copied()
+            .filter(|vector| vector.len() == dimension)
+            .collect();
+        let centroids = sample.len().clamp(1, MAX_CENTROIDS);
+
+        let mut codebook = vec![0.0; subspaces * centroids * sub_dimension];
+        for subspace in 0..subspaces {
+            let range = subspace * sub_dimension..(subspace + 1) * sub_dimension;
+            let points: Vec<&[f32]> = sample.iter().map(|vector| &vector[range.clone()]).collect();
+            let trained = kmeans(&points, centroids, sub_dimension);
+
+            let start = subspace * centroids * sub_dimension;
+            codebook[start..start + trained.len()].copy_from_slice(&trained);
+        }
+
+        QuantizedVectors::Product {
+            dimension,
+            subspaces,
+            centroids,
+            codebook,
+            codes: Vec::new(),
+            trained_on: vectors.len(),
+        }
+    }
+
+    /// Get the quantization mode
+    pub fn mode(&self) -> QuantizationMode {
+        match self {
+            QuantizedVectors::Scalar { .. } => QuantizationMode::Int8,
+            QuantizedVectors::Product { .. } => QuantizationMode::Product,
+        }
+    }
+
+    /// Get the length of each vector
+    pub fn dimension(&self) -> usize {
+        match self {
+            QuantizedVectors::Scalar { dimension, .. }
+            | QuantizedVectors::Product { dimension, .. } => *dimension,
+        }
+    }
+
+    /// Get the number of product quantization subspaces
+    pub fn subspaces(&self) -> Option<usize> {
+        match self {
+            QuantizedVectors::Scalar { .. } => None,
+            QuantizedVectors::Product { subspaces, .. } => Some(*subspaces),
+        }
+    }
+
+    /// Get the number of vectors the codebook was trained on
+    ///
+    /// Scalar quantization needs no training and returns 0.
+    pub fn trained_on(&self) -> usize {
+        match self {
+            QuantizedVectors::Scalar { .. } => 0,
+            QuantizedVectors::Product { trained_on, .. } => *trained_on,
+        }
+    }
+
+    /// Encode a vector into a slot
+    pub fn set_row(&mut self, slot: usize, vector: &[f32]) {
+        match self {
+            QuantizedVectors::Scalar {
+                dimension,
+                scales,
+                codes,
+            } => {
+                let dimension = *dimension;
+                if scales.len() <= slot {
+                    scales.resize(slot + 1, 0.0);
+                    codes.resize((slot + 1) * dimension, 0);
+                }
+
+                let max = vector
+                    .iter()
+                    .fold(0.0f32, |max, value| max.max(value.abs()));
+                let scale = if max > 0.0 { max / 127.0 } else { 0.0 };
+                scales[slot] = scale;
+
+                let row = &mut codes[slot * dimension..(slot + 1) * dimension];
+                for (code, value) in row.iter_mut().zip(vector) {
+                    *code = if scale > 0.0 {
+                        (value / scale).round().clamp(-127.0, 127.0) as i8
+                    } else {
+                        0
+                    };
+                }
+            }
+            QuantizedVectors::Product {
+                dimension,
+                subspaces,
+                centroids,
+                codebook,
+                codes,
+                ..
+            } => {
+                let subspaces = *subspaces;
+                if codes.len() < (slot + 1) * subspaces {
+                    codes.resize((slot + 1) * subspaces, 0);
+                }
+                if vector.len() != *dimension {
+                    return;
+                }
+
+                let sub_dimension = *dimension / subspaces;
+                for subspace in 0..subspaces {
+                    let values = &vector[subspace * sub_dimension..(subspace + 1) * sub_dimension];
+                    let start = subspace * *centroids * sub_dimension;
+                    let centers = &codebook[start..start + *centroids * sub_dimension];
+                    codes[slot * subspaces + subspace] = nearest(centers, values) as u8;
+                }
+            }
+        }
+    }
+
+    /// Zero the codes of a slot
+    pub fn clear_row(&mut self, slot: usize) {
+        match self {
+            QuantizedVectors::Scalar { scales, .. } => {
+                if let Some(scale) = scales.get_mut(slot) {
+                    *scale = 0.0;
+                }
+            }
+            QuantizedVectors::Product {
+                subspaces, codes, ..
+            } => {
+                if let Some(row) = codes.get_mut(slot * *subspaces..(slot + 1) * *subspaces) {
+                    row.fill(0);
+                }
+            }
+        }
+    }
+
+    /// Prepare a normalized query for scoring
+    ///
+    /// Product quantization precomputes the similarity of the query with
+    /// every centroid, so scoring a slot only sums table entries.
+    pub fn prepare(&self, query: &[f32]) -> Vec<f32> {
+        match self {
+            QuantizedVectors::Scalar { .. } => query.to_vec(),
+            QuantizedVectors::Product {
+                dimension,
+                subspaces,
+                centroids,
+                codebook,
+                ..
+            } => {
+                if query.len() != *dimension {
+                    return vec![0.0; subspaces * centroids];
+                }
+
+                let sub_dimension = *dimension / *subspaces;
+                let mut table = Vec::with_capacity(subspaces * centroids);
+                for subspace in 0..*subspaces {
+                    let values = &query[subspace * sub_dimension..(subspace + 1) * sub_dimension];
+                    for centroid in 0..*centroids {
+                        let start = (subspace * *centroids + centroid) * sub_dimension;
+                        let center = &codebook[start..start + sub_dimension];
+                        table.push(values.iter().zip(center).map(|(a, b)| a * b).sum());
+                    }
+                }
+                table
+            }
+        }
+    }
+
+    /// Approximate the similarity of a prepared query with a slot
+    pub fn score(&self, prepared: &[f32], slot: usize) -> f32 {
+        match self {
+            QuantizedVectors::Scalar {
+                dimension,
+                scales,
+                codes,
+            } => {
+                let row = match codes.get(slot * dimension..(slot + 1) * dimension) {
+                    Some(row) if row.len() == prepared.len() => row,
+                    _ => return 0.0,
+                };
+
+                let dot: f32 = row
+                    .iter()
+                    .zip(prepared)
+                    .map(|(&code, value)| code as f32 * value)
+                    .sum();
+                dot * scales[slot]
+            }
+            QuantizedVectors::Product {
+                subspaces,
+                centroids,
+                codes,
+                ..
+            } => {
+                let row = match codes.get(slot * subspaces..(slot + 1) * subspaces) {
+                    Some(row) => row,
+                    None => return 0.0,
+                };
+
+                row.iter()
+                    .enumerate()
+                    .map(|(subspace, &code)| {
+                        prepared
+                            .get(subspace * centroids + code as usize)
+                            .copied()
+                            .unwrap_or(0.0)
+                    })
+                    .sum()
+            }
+        }
+    }
+
+    /// Get the memory used by the codes and codebook, in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            QuantizedVectors::Scalar { scales, codes, .. } => {
+                scales.len() * std::mem::size_of::<f32>() + codes.len()
+            }
+            QuantizedVectors::Product {
+                codebook, codes, ..
+            } => codebook.len() * std::mem::size_of::<f32>() + codes.len(),
+        }
+    }
+
+    /// Encode the quantized vectors
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            QuantizedVectors::Scalar {
+                dimension,
+                scales,
+                codes,
+            } => {
+                out.push(SCALAR_CODE);
+                put_u32(out, *dimension as u32);
+                put_u64(out, scales.len() as u64);
+                for scale in scales {
+                    put_f32(out, *scale);
+                }
+                out.extend(codes.iter().map(|&code| code as u8));
+            }
+            QuantizedVectors::Product {
+                dimension,
+                subspaces,
+                centroids,
+                codebook,
+                codes,
+                trained_on,
+            } => {
+                out.push(PRODUCT_CODE);
+                put_u32(out, *dimension as u32);
+                put_u32(out, *subspaces as u32);
+                put_u32(out, *centroids as u32);
+                put_u64(out, *trained_on as u64);
+                for value in codebook {
+                    put_f32(out, *value);
+                }
+                put_u64(out, (codes.len() / subspaces) as u64);
+                out.extend_from_slice(codes);
+            }
+        }
+    }
+
+    /// Decode quantized vectors written by [`QuantizedVectors::encode`]
+    pub fn decode(reader: &mut ByteReader) -> Result<Self> {
+        match reader.u8()? {
+            SCALAR_CODE => {
+                let dimension = reader.u32()? as usize;
+                let rows = reader.u64()? as usize;
+
+                let mut scales = Vec::with_capacity(rows);
+                for _ in 0..rows {
+                    scales.push(reader.f32()?);
+                }
+
+                let len = rows.checked_mul(dimension).ok_or_else(invalid)?;
+                let codes = reader.bytes(len)?.iter().map(|&code| code as i8).collect();
+
+                Ok(QuantizedVectors::Scalar {
+                    dimension,
+                    scales,
+                    codes,
+                })
+            }
+            PRODUCT_CODE => {
+                let dimension = reader.u32()? as usize;
+                let subspaces = reader.u32()? as usize;
+                let centroids = reader.u32()? as usize;
+                let trained_on = reader.u64()? as usize;
+
+                if subspaces == 0
+                    || dimension % subspaces != 0
+                    || centroids == 0
+                    || centroids > MAX_CENTROIDS
+                {
+                    return Err(invalid());
+                }
+
+                let mut codebook = Vec::with_capacity(centroids * dimension);
+                for _ in 0..centroids * dimension {
+                    codebook.push(reader.f32()?);
+                }
+
+                let rows = reader.u64()? as usize;
+                let len = rows.checked_mul(subspaces).ok_or_else(invalid)?;
+                let codes = reader.bytes(len)?.to_vec();
+                if codes.iter().any(|&code| code as usize >= centroids) {
+                    return Err(invalid());
+                }
+
+                Ok(QuantizedVectors::Product {
+                    dimension,
+                    subspaces,
+                    centroids,
+                    codebook,
+                    codes,
+                    trained_on,
+                })
+            }
+            code => Err(ZseiError::Indexing(format!(
+                "Unknown quantization code {}",
+                code
+            ))),
+        }
+    }
+}
+
+/// Get the number of product quantization subspaces used for a dimension:
+/// the largest divisor of the dimension not above the requested number
+pub fn product_subspaces(dimension: usize, requested: usize) -> usize {
+    (1..=requested.max(1))
+        .rev()
+        .find(|subspaces| dimension % subspaces == 0)
+        .unwrap_or(1)
+}
+
+/// Error for malformed quantized vectors
+fn invalid() -> ZseiError {
+    ZseiError::Indexing("Invalid quantized vectors".to_string())
+}
+
+/// Squared Euclidean distance of two vectors
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Index of the center nearest to some values
+///
+/// `centers` holds the centers one after the other.
+fn nearest(centers: &[f32], values: &[f32]) -> usize {
+    centers
+        .chunks_exact(values.len().max(1))
+        .enumerate()
+        .map(|(index, center)| (index, distance(center, values)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Cluster points with Lloyd's k-means
+///
+/// Centers start at evenly spaced points; a center that loses all its
+/// points keeps its position.
+fn kmeans(points: &[&[f32]], count: usize, dimension: usize) -> Vec<f32> {
+    let mut centers = vec![0.0; count * dimension];
+    if points.is_empty() || dimension == 0 {
+        return centers;
+    }
+
+    for center in 0..count {
+        let point = points[center * points.len() / count];
+        centers[center * dimension..(center + 1) * dimension].copy_from_slice(point);
+    }
+
+    let mut sums = vec![0.0f32; count * dimension];
+    let mut sizes = vec![0usize; count];
+    for _ in 0..TRAINING_ITERATIONS {
+        sums.fill(0.0);
+        sizes.fill(0);
+
+        for point in points {
+            let center = nearest(&centers, point);
+            sizes[center] += 1;
+            for (sum, value) in sums[center * dimension..(center + 1) * dimension]
+                .iter_mut()
+                .zip(point.iter())
+            {
+                *sum += value;
+            }
+        }
+
+        for (center, &size) in sizes.iter().enumerate() {
+            if size == 0 {
+                continue;
+            }
+            let range = center * dimension..(center + 1) * dimension;
+            for (value, sum) in centers[range.clone()].iter_mut().zip(&sums[range]) {
+                *value = sum / size as f32;
+            }
+        }
+    }
+
+    centers
+}
+
+/// Memory use and recall of a quantized vector store
+#[derive(Debug, Clone, Serialize)]
+pub struct QuantizationReport {
+    /// Quantization mode
+    pub mode: QuantizationMode,
+
+    /// Number of stored vectors
+    pub vectors: usize,
+
+    /// Memory the full-precision vectors would use if held in memory, in
+    /// bytes
+    pub full_bytes: usize,
+
+    /// Memory used by the quantized codes and codebook, in bytes
+    pub quantized_bytes: usize,
+
+    /// Memory actually held for the vectors, in bytes: the codes and
+    /// codebook, plus the full vectors kept for rescoring and saving
+    pub resident_bytes: usize,
+
+    /// Number of queries
+    pub queries: usize,
+
+    /// Number of results per query
+    pub k: usize,
+
+    /// Fraction of the exact top `k` found from the codes alone
+    pub recall: f32,
+
+    /// Fraction of the exact top `k` found after rescoring, if enabled
+    pub rescored_recall: Option<f32>,
+}
+
+impl QuantizationReport {
+    /// Get the memory saved by quantization, in bytes
+    pub fn saved_bytes(&self) -> usize {
+        self.full_bytes.saturating_sub(self.resident_bytes)
+    }
+}
+
+impl VectorStore {
+    /// Measure the memory saved by quantization and its impact on recall
+    ///
+    /// Up to `query_count` stored vectors, evenly spaced, are used as
+    /// held-out queries: each query's own vector is excluded from every
+    /// search, so it cannot trivially find itself. The top `k` found with
+    /// the codes, with and without rescoring, is compared with an exact
+    /// scan. Returns `None` when the store has no quantized codes.
+    pub fn quantization_report(&self, query_count: usize, k: usize) -> Option<QuantizationReport> {
+        let quantized = self.quantized.as_ref()?;
+
+        // One more result than needed, so the query's own vector can be
+        // dropped from each list
+        let params = VectorSearchParams {
+            max_results: k + 1,
+            min_score: f32::MIN,
+        };
+        let held_out = |results: Vec<VectorSearchResult>, source: &str| {
+            results
+                .into_iter()
+                .filter(|result| result.id != source)
+                .take(k)
+                .map(|result| result.id)
+                .collect::<HashSet<String>>()
+        };
+
+        let live: Vec<(usize, &str)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| Some((slot, entry.as_ref()?.id.as_str())))
+            .collect();
+        let step = (live.len() / query_count.max(1)).max(1);
+        let queries: Vec<(usize, &str)> = live
+            .iter()
+            .step_by(step)
+            .take(query_count)
+            .copied()
+            .collect();
+
+        let rescore_factor = self.quantization.rescore_factor;
+        let mut expected = 0;
+        let mut found = 0;
+        let mut found_rescored = 0;
+        for &(slot, source) in &queries {
+            let query = self.matrix.row(slot);
+
+            let exact = held_out(self.search_exact(query, params.clone()), source);
+            expected += exact.len();
+
+            found += held_out(
+                self.search_quantized(quantized, query, params.clone(), 0, &|_| true),
+                source,
+            )
+            .intersection(&exact)
+            .count();
+
+            if rescore_factor > 0 {
+                found_rescored += held_out(
+                    self.search_quantized(
+                        quantized,
+                        query,
+                        params.clone(),
+                        rescore_factor,
+                        &|_| true,
+                    ),
+                    source,
+                )
+                .intersection(&exact)
+                .count();
+            }
+        }
+
+        let fraction = |found: usize| {
+            if expected == 0 {
+                1.0
+            } else {
+                found as f32 / expected as f32
+            }
+        };
+
+        Some(QuantizationReport {
+            mode: quantized.mode(),
+            vectors: live.len(),
+            full_bytes: live.len() * self.dimension() * std::mem::size_of::<f32>(),
+            quantized_bytes: quantized.size_in_bytes(),
+            resident_bytes: quantized.size_in_bytes() + self.matrix.resident_bytes(),
+            queries: queries.len(),
+            k,
+            recall: fraction(found),
+            rescored_recall: (rescore_factor > 0).then(|| fraction(found_rescored)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{HnswConfig, QuantizationConfig, VectorStoreType};
+    use crate::embedding::{Embedding, EmbeddingMetadata, EmbeddingType};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Draw normalized vectors around a few cluster centers
+    fn clustered_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = seed;
+        let mut next = move || {
+            rng ^= rng << 13;
+            rng ^= rng >> 7;
+            rng ^= rng << 17;
+            (rng >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+        };
+
+        let centers: Vec<Vec<f32>> = (0..8)
+            .map(|_| (0..dimension).map(|_| next()).collect())
+            .collect();
+
+        (0..count)
+            .map(|i| {
+                let mut vector: Vec<f32> = centers[i % centers.len()]
+                    .iter()
+                    .map(|value| value + next() * 0.5)
+                    .collect();
+                crate::embedding::utils::normalize(&mut vector);
+                vector
+            })
+            .collect()
+    }
+
+    /// Dot product of two vectors
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    /// Flat store quantized with a mode, holding the given vectors
+    fn quantized_store(
+        mode: QuantizationMode,
+        rescore_factor: usize,
+        vectors: &[Vec<f32>],
+    ) -> VectorStore {
+        let mut store = VectorStore::with_backend(VectorStoreType::Flat, HnswConfig::default())
+            .with_quantization(QuantizationConfig {
+                mode,
+                pq_subspaces: 8,
+                rescore_factor,
+                ..QuantizationConfig::default()
+            });
+
+        for (index, vector) in vectors.iter().enumerate() {
+            store
+                .add_embedding(Embedding {
+                    vector: vector.clone(),
+                    embedding_type: EmbeddingType::Code,
+                    metadata: EmbeddingMetadata {
+                        source_path: PathBuf::from(format!("src/file_{}.rs", index)),
+                        content_hash: format!("{:x}", index),
+                        chunk: None,
+                        lines: None,
+                        language: None,
+                        timestamp: chrono::Utc::now(),
+                        attributes: HashMap::new(),
+                    },
+                })
+                .unwrap();
+        }
+        store.refresh_quantization();
+        store
+    }
+
+    #[test]
+    fn product_subspaces_divide_the_dimension() {
+        assert_eq!(product_subspaces(384, 16), 16);
+        assert_eq!(product_subspaces(30, 16), 15);
+        assert_eq!(product_subspaces(7, 4), 1);
+        assert_eq!(product_subspaces(10, 0), 1);
+    }
+
+    #[test]
+    fn scalar_scores_approximate_dot_product() {
+        let vectors = clustered_vectors(50, 32, 0x9e37_79b9_7f4a_7c15);
+        let mut quantized = QuantizedVectors::scalar(32);
+        for (slot, vector) in vectors.iter().enumerate() {
+            quantized.set_row(slot, vector);
+        }
+
+        let query = &vectors[7];
+        let prepared = quantized.prepare(query);
+        for (slot, vector) in vectors.iter().enumerate() {
+            let error = (quantized.score(&prepared, slot) - dot(query, vector)).abs();
+            assert!(error < 0.02, "slot {} off by {}", slot, error);
+        }
+
+        quantized.clear_row(3);
+        assert_eq!(quantized.score(&prepared, 3), 0.0);
+    }
+
+    #[test]
+    fn product_training_limits_centroids_to_the_sample() {
+        let vectors = clustered_vectors(20, 32, 0x2545_f491_4f6c_dd1d);
+        let rows: Vec<&[f32]> = vectors.iter().map(Vec::as_slice).collect();
+        let quantized = QuantizedVectors::train_product(32, 6, &rows);
+
+        assert_eq!(quantized.mode(), QuantizationMode::Product);
+        assert_eq!(quantized.subspaces(), Some(4));
+        assert_eq!(quantized.trained_on(), 20);
+        match &quantized {
+            QuantizedVectors::Product { centroids, .. } => assert_eq!(*centroids, 20),
+            QuantizedVectors::Scalar { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        let vectors = clustered_vectors(40, 16, 0x1234_5678_9abc_def1);
+        let rows: Vec<&[f32]> = vectors.iter().map(Vec::as_slice).collect();
+
+        let mut scalar = QuantizedVectors::scalar(16);
+        let mut product = QuantizedVectors::train_product(16, 4, &rows);
+        for (slot, vector) in vectors.iter().enumerate() {
+            scalar.set_row(slot, vector);
+            product.set_row(slot, vector);
+        }
+
+        for quantized in [scalar, product] {
+            let mut bytes = Vec::new();
+            quantized.encode(&mut bytes);
+            let decoded = QuantizedVectors::decode(&mut ByteReader::new(&bytes)).unwrap();
+
+            assert_eq!(decoded.mode(), quantized.mode());
+            assert_eq!(decoded.size_in_bytes(), quantized.size_in_bytes());
+
+            let prepared = quantized.prepare(&vectors[0]);
+            let decoded_prepared = decoded.prepare(&vectors[0]);
+            for slot in 0..vectors.len() {
+                assert_eq!(
+                    decoded.score(&decoded_prepared, slot),
+                    quantized.score(&prepared, slot)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decode_rejects_malformed_codes() {
+        assert!(QuantizedVectors::decode(&mut ByteReader::new(&[9])).is_err());
+
+        let vectors = clustered_vectors(4, 8, 42);
+        let rows: Vec<&[f32]> = vectors.iter().map(Vec::as_slice).collect();
+        let mut product = QuantizedVectors::train_product(8, 2, &rows);
+        product.set_row(0, &vectors[0]);
+
+        let mut bytes = Vec::new();
+        product.encode(&mut bytes);
+        let last = bytes.len() - 1;
+        bytes[last] = 200;
+        assert!(QuantizedVectors::decode(&mut ByteReader::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn rescoring_recovers_recall() {
+        let vectors = clustered_vectors(1000, 32, 0x3c6e_f372_fe94_f82b);
+
+        for mode in [QuantizationMode::Int8, QuantizationMode::Product] {
+            let store = quantized_store(mode, 4, &vectors);
+            let report = store.quantization_report(25, 10).unwrap();
+
+            assert_eq!(report.mode, mode);
+            assert_eq!(report.vectors, 1000);
+            assert_eq!(report.queries, 25);
+            let rescored = report.rescored_recall.unwrap();
+            assert!(rescored >= report.recall, "{:?}", report);
+            assert!(rescored >= 0.9, "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn rescored_search_matches_float_store() {
+        let vectors = clustered_vectors(1000, 32, 0x510e_527f_ade6_82d1);
+        let queries = clustered_vectors(20, 32, 0x9b05_688c_2b3e_6c1f);
+        let params = VectorSearchParams {
+            max_results: 10,
+            min_score: f32::MIN,
+        };
+        let top = |store: &VectorStore, query: &[f32]| -> Vec<String> {
+            store
+                .search(query, params.clone())
+                .unwrap()
+                .into_iter()
+                .map(|result| result.id)
+                .collect()
+        };
+
+        let float = quantized_store(QuantizationMode::None, 0, &vectors);
+        for mode in [QuantizationMode::Int8, QuantizationMode::Product] {
+            let store = quantized_store(mode, 20, &vectors);
+            for query in &queries {
+                assert_eq!(top(&store, query), top(&float, query), "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn report_counts_codes_and_full_vectors() {
+        let vectors = clustered_vectors(300, 32, 0x5eed);
+        let mut store = quantized_store(QuantizationMode::Int8, 4, &vectors);
+        let full_bytes = 300 * 32 * std::mem::size_of::<f32>();
+
+        let report = store.quantization_report(10, 5).unwrap();
+        assert_eq!(report.full_bytes, full_bytes);
+        assert!(report.quantized_bytes < report.full_bytes);
+        assert_eq!(report.resident_bytes, report.quantized_bytes + full_bytes);
+
+        // Saving reads the full vectors back into one buffer
+        let path =
+            std::env::temp_dir().join(format!("zsei-quantization-{}.bin", std::process::id()));
+        store.save(&path).unwrap();
+        assert!(store.is_file_backed());
+
+        let report = store.quantization_report(10, 5).unwrap();
+        assert_eq!(report.resident_bytes, report.quantized_bytes + full_bytes);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
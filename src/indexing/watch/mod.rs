@@ -0,0 +1,340 @@
+//! Filesystem watching for ZSEI
+//!
+//! This module keeps the index of a project fresh while its files change.
+//! Filesystem events are debounced into batches, and each batch runs
+//! incremental indexing over the touched files before saving the index. The
+//! index is loaded once and kept in memory between batches.
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::core::project::{IgnoreMatcher, Project};
+use crate::errors::{Result, ZseiError};
+use crate::indexing::{Indexer, PruneReport};
+
+/// Summary of one processed batch of changes
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchBatch {
+    /// Project files touched in the batch, empty for the initial catch-up
+    pub touched: Vec<PathBuf>,
+
+    /// Entries pruned from the index
+    pub pruned: PruneReport,
+
+    /// Time taken to process the batch, in milliseconds
+    pub elapsed_ms: u64,
+
+    /// Error that stopped the batch, if any
+    pub error: Option<String>,
+}
+
+/// Watches the project sources and re-indexes changed files
+pub struct IndexWatcher {
+    /// Indexer used for each batch
+    indexer: Arc<Indexer>,
+
+    /// Project whose sources are watched
+    project: Arc<Project>,
+
+    /// Directory the index is saved to after each batch
+    index_path: PathBuf,
+
+    /// Quiet period that ends a batch
+    debounce: Duration,
+}
+
+impl IndexWatcher {
+    /// Create a new watcher
+    pub fn new(
+        indexer: Arc<Indexer>,
+        project: Arc<Project>,
+        index_path: PathBuf,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            indexer,
+            project,
+            index_path,
+            debounce,
+        }
+    }
+
+    /// Watch until interrupted, calling `on_batch` after each batch
+    ///
+    /// The index is first brought up to date with the whole project. Errors
+    /// while processing a batch are reported in its summary and do not stop
+    /// the watcher.
+    pub async fn run<F>(&self, mut on_batch: F) -> Result<()>
+    where
+        F: FnMut(&WatchBatch),
+    {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut watcher = notify::recommended_watcher(move |event| {
+            // The receiver only goes away when watching stops
+            let _ = tx.send(event);
+        })
+        .map_err(|e| ZseiError::Indexing(format!("Failed to create file watcher: {}", e)))?;
+
+        for root in self.project.source_roots() {
+            if !root.exists() {
+                warn!("Not watching missing directory {}", root.display());
+                continue;
+            }
+
+            watcher
+                .watch(&root, RecursiveMode::Recursive)
+                .map_err(|e| {
+                    ZseiError::Indexing(format!("Failed to watch {}: {}", root.display(), e))
+                })?;
+            info!("Watching {}", root.display());
+        }
+
+        // Catch up with changes made while not watching
+        let mut loaded = false;
+        on_batch(&self.process_batch(Vec::new(), &mut loaded).await);
+
+        // Ignore files are parsed once and kept until one of them changes
+        let mut ignores = IgnoreMatcher::new();
+
+        loop {
+            let first = tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Stopping watch");
+                    break;
+                }
+            };
+
+            // Wait until no event arrives for the debounce period
+            let mut events = vec![first];
+            while let Ok(Some(event)) = tokio::time::timeout(self.debounce, rx.recv()).await {
+                events.push(event);
+            }
+
+            let touched = touched_files(&self.project, events, &mut ignores);
+            if touched.is_empty() {
+                continue;
+            }
+
+            debug!("Processing {} touched files", touched.len());
+            on_batch(&self.process_batch(touched, &mut loaded).await);
+        }
+
+        Ok(())
+    }
+
+    /// Index the touched files, or the whole project when none are given, and
+    /// save the index
+    ///
+    /// The saved index is loaded until a batch succeeds; later batches work
+    /// on the index held in memory, so only the touched files are processed.
+    async fn process_batch(&self, touched: Vec<PathBuf>, loaded: &mut bool) -> WatchBatch {
+        let start = Instant::now();
+
+        let result = async {
+            let pruned = if *loaded {
+                self.indexer.index_changes(&touched, None).await?
+            } else {
+                self.indexer.index_incremental(&touched, None).await?
+            };
+            self.indexer.save(&self.index_path).await?;
+            *loaded = true;
+            Ok::<_, ZseiError>(pruned)
+        }
+        .await;
+
+        let (pruned, error) = match result {
+            Ok(pruned) => (pruned, None),
+            Err(e) => (PruneReport::default(), Some(e.to_string())),
+        };
+
+        WatchBatch {
+            touched,
+            pruned,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+            error,
+        }
+    }
+}
+
+/// Get the project files touched by a batch of filesystem events
+///
+/// Access events and watch errors are dropped. Directories stand for the
+/// project files under them, and deleted directories for the indexed files
+/// that were under them. Paths that are ignored, excluded, or inside the
+/// `.zsei` or `.git` directories are left out. When an ignore file changed,
+/// `ignores` is reset so its new rules apply.
+pub fn touched_files(
+    project: &Project,
+    events: impl IntoIterator<Item = notify::Result<Event>>,
+    ignores: &mut IgnoreMatcher,
+) -> Vec<PathBuf> {
+    let mut touched = BTreeSet::new();
+    for event in events {
+        match event {
+            // Reads do not change the index
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+            Ok(event) => touched.extend(event.paths),
+            Err(e) => warn!("File watch error: {}", e),
+        }
+    }
+
+    if touched
+        .iter()
+        .any(|path| IgnoreMatcher::is_ignore_file(path))
+    {
+        debug!("Ignore files changed, reloading them");
+        *ignores = IgnoreMatcher::new();
+    }
+
+    let mut files = BTreeSet::new();
+    for path in touched {
+        match project.expand_touched_path(&path, ignores) {
+            Ok(paths) => files.extend(paths),
+            Err(e) => warn!("Skipping {}: {}", path.display(), e),
+        }
+    }
+
+    files.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::Config;
+    use notify::event::{AccessKind, CreateKind, ModifyKind, RemoveKind};
+    use std::fs;
+    use std::path::Path;
+
+    /// Empty scratch directory for a test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zsei-watch-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Project rooted at `root`
+    fn project(root: &Path) -> Project {
+        let mut config = Config::default();
+        config.set_project_root(root.to_path_buf());
+        Project::new(Arc::new(config)).unwrap()
+    }
+
+    /// Event of the given kind on one path
+    fn event(kind: EventKind, path: PathBuf) -> notify::Result<Event> {
+        Ok(Event::new(kind).add_path(path))
+    }
+
+    #[test]
+    fn events_map_to_project_files() {
+        let root = scratch_dir("files");
+        fs::write(root.join(".gitignore"), "generated.py\n").unwrap();
+        fs::write(root.join("app.py"), "x = 1\n").unwrap();
+        fs::write(root.join("generated.py"), "x = 2\n").unwrap();
+        fs::write(root.join("notes.txt"), "notes\n").unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join(".git/hooks.py"), "x = 3\n").unwrap();
+        fs::create_dir_all(root.join(".zsei/metadata")).unwrap();
+        fs::write(root.join(".zsei/metadata/cache.py"), "x = 4\n").unwrap();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(root.join("pkg/a.py"), "x = 5\n").unwrap();
+        fs::write(root.join("pkg/b.py"), "x = 6\n").unwrap();
+
+        let project = project(&root);
+        let mut ignores = IgnoreMatcher::new();
+        let modify = || EventKind::Modify(ModifyKind::Any);
+
+        let touched = touched_files(
+            &project,
+            vec![
+                event(modify(), root.join("app.py")),
+                event(modify(), root.join("generated.py")),
+                event(modify(), root.join("notes.txt")),
+                event(modify(), root.join(".git/hooks.py")),
+                event(modify(), root.join(".zsei/metadata/cache.py")),
+                event(EventKind::Create(CreateKind::Folder), root.join("pkg")),
+                Err(notify::Error::generic("watch failed")),
+            ],
+            &mut ignores,
+        );
+        assert_eq!(
+            touched,
+            vec![
+                root.join("app.py"),
+                root.join("pkg/a.py"),
+                root.join("pkg/b.py")
+            ]
+        );
+
+        // Reading a file does not touch it
+        let touched = touched_files(
+            &project,
+            vec![event(
+                EventKind::Access(AccessKind::Any),
+                root.join("app.py"),
+            )],
+            &mut ignores,
+        );
+        assert!(touched.is_empty());
+
+        // A changed ignore file applies to the same batch
+        fs::write(root.join(".gitignore"), "generated.py\napp.py\n").unwrap();
+        let touched = touched_files(
+            &project,
+            vec![
+                event(modify(), root.join(".gitignore")),
+                event(modify(), root.join("app.py")),
+            ],
+            &mut ignores,
+        );
+        assert!(touched.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn deleted_directories_expand_to_their_indexed_files() {
+        let root = scratch_dir("deleted");
+        fs::create_dir_all(root.join("pkg/sub")).unwrap();
+        let files = [
+            root.join("pkg/a.py"),
+            root.join("pkg/sub/b.py"),
+            root.join("other.py"),
+        ];
+        for path in &files {
+            fs::write(path, "x = 1\n").unwrap();
+        }
+
+        let project = project(&root);
+        project
+            .update_indexed_files(files.iter().map(|path| (path.as_path(), None, None)))
+            .unwrap();
+
+        fs::remove_dir_all(root.join("pkg")).unwrap();
+        let touched = touched_files(
+            &project,
+            vec![event(
+                EventKind::Remove(RemoveKind::Folder),
+                root.join("pkg"),
+            )],
+            &mut IgnoreMatcher::new(),
+        );
+        assert_eq!(
+            touched,
+            vec![root.join("pkg/a.py"), root.join("pkg/sub/b.py")]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
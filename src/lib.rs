@@ -81,6 +81,10 @@ pub mod errors {
         #[error("Configuration error: {0}")]
         Config(String),
 
+        /// Git repository errors
+        #[error("Git error: {0}")]
+        Git(String),
+
         /// Serialization errors
         #[error("Serialization error: {0}")]
         Serialization(#[from] serde_json::Error),
@@ -121,13 +125,21 @@ pub struct Zsei {
 impl Zsei {
     /// Create a new ZSEI instance
     pub async fn new(config: core::config::Config) -> Result<Self> {
+        config.validate()?;
         let config = Arc::new(config);
 
         // Initialize core components
         let (project, analyzer) = core::initialize(config.clone()).await?;
 
-        // Initialize LLMs
-        let default_llm = llm::LlmFactory::create_model(config.llm.clone()).await?;
+        // Initialize LLMs; lexical search and embeddings from other backends run without one
+        let default_llm = match llm::LlmFactory::create_model(config.llm.clone()).await {
+            Ok(model) => model,
+            Err(e) if config.runs_without_llm() => {
+                tracing::warn!("Running without an LLM: {}", e);
+                Arc::new(llm::UnavailableModel::new(e.to_string())) as Arc<dyn llm::Model>
+            }
+            Err(e) => return Err(e),
+        };
 
         // Initialize phase-specific LLMs if configured
         let phase1_llm = if config.phase1_llm.is_some() {
@@ -142,11 +154,19 @@ impl Zsei {
             default_llm.clone()
         };
 
+        // Load the sentence embedding model of the ONNX backend
+        let embedding_model = if config.embedding.backend == core::config::EmbeddingBackend::Onnx {
+            Some(embedding::model::create_model(&config.embedding)?)
+        } else {
+            None
+        };
+
         // Initialize components
         let indexer = Arc::new(indexing::Indexer::new(
             config.clone(),
             analyzer.clone(),
             default_llm.clone(),
+            embedding_model,
         ));
 
         let query_engine = Arc::new(query::QueryEngine::new(
@@ -147,6 +147,61 @@ pub trait Model: Send + Sync {
 
     /// Generate embeddings for text
     async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Check if the model can be used
+    ///
+    /// Work that can be done without a model, like lexical search, checks
+    /// this instead of failing.
+    fn is_available(&self) -> bool {
+        true
+    }
+}
+
+/// Stand-in for a model that could not be loaded
+///
+/// Used when indexing and search are configured not to need an LLM, so
+/// ZSEI runs without one. Every call fails with the reason the model is
+/// missing.
+pub struct UnavailableModel {
+    /// Why the model could not be loaded
+    reason: String,
+}
+
+impl UnavailableModel {
+    /// Create a stand-in with the reason the model could not be loaded
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Get the error returned by every call
+    fn error(&self) -> ZseiError {
+        ZseiError::Llm(format!("No LLM available: {}", self.reason))
+    }
+}
+
+#[async_trait]
+impl Model for UnavailableModel {
+    async fn generate(&self, _prompt: &str) -> Result<String> {
+        Err(self.error())
+    }
+
+    async fn generate_with_parameters(
+        &self,
+        _prompt: &str,
+        _parameters: ModelParameters,
+    ) -> Result<String> {
+        Err(self.error())
+    }
+
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Err(self.error())
+    }
+
+    fn is_available(&self) -> bool {
+        false
+    }
 }
 
 /// LLM factory
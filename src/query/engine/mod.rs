@@ -10,6 +10,7 @@ use tracing::{debug, info};
 use crate::core::config::Config;
 use crate::embedding::EmbeddingType;
 use crate::errors::{Result, ZseiError};
+use crate::indexing::search::SearchRequest;
 use crate::indexing::{Indexer, SearchResult};
 use crate::llm::{Model, prompt::PromptManager};
 use crate::query::{CodeSnippet, QueryContext, QueryResult, QueryType, Suggestion};
@@ -123,7 +124,12 @@ impl QueryEngineImpl {
         };
 
         // Search for relevant code
-        let search_results = self.indexer.search(&context.query, embedding_type, max_results).await?;
+        let request = SearchRequest {
+            embedding_type,
+            max_results,
+            ..SearchRequest::new(context.query.as_str())
+        };
+        let search_results = self.indexer.search(&request).await?;
 
         Ok(search_results)
     }
@@ -144,6 +150,12 @@ impl QueryEngineImpl {
                 }
             };
 
+            // Narrow the content to the matching chunk
+            let content = match result.lines {
+                Some(lines) => lines.extract(&content),
+                None => content,
+            };
+
             // Extract relationships
             let mut relationships = HashMap::new();
 
@@ -167,6 +179,7 @@ impl QueryEngineImpl {
                 path: result.path.clone(),
                 language: Some(result.metadata.language.clone()),
                 content,
+                lines: result.lines,
                 relationships,
                 score: result.score,
             };
@@ -241,7 +254,7 @@ impl QueryEngineImpl {
 
         for (i, snippet) in code_snippets.iter().enumerate() {
             prompt.push_str(&format!("Snippet {}:\n", i + 1));
-            prompt.push_str(&format!("File: {}\n", snippet.path.display()));
+            prompt.push_str(&format!("File: {}\n", snippet.location()));
             prompt.push_str(&format!("Language: {}\n", snippet.language.as_deref().unwrap_or("unknown")));
             prompt.push_str(&format!("Score: {:.2}\n\n", snippet.score));
             prompt.push_str(&format!("```\n{}\n```\n\n", snippet.content));
@@ -345,7 +358,7 @@ impl QueryEngineImpl {
 
         for (i, snippet) in code_snippets.iter().enumerate() {
             prompt.push_str(&format!("Snippet {}:\n", i + 1));
-            prompt.push_str(&format!("File: {}\n", snippet.path.display()));
+            prompt.push_str(&format!("File: {}\n", snippet.location()));
             prompt.push_str(&format!("Language: {}\n", snippet.language.as_deref().unwrap_or("unknown")));
             prompt.push_str(&format!("```\n{}\n```\n\n", snippet.content));
         }
@@ -15,8 +15,9 @@ pub mod prompt;
 
 use crate::analyzers::common::{CodeGraph, Dependency, DependencyType};
 use crate::core::config::Config;
-use crate::embedding::EmbeddingType;
+use crate::embedding::{EmbeddingType, LineRange};
 use crate::errors::{Result, ZseiError};
+use crate::indexing::search::SearchRequest;
 use crate::indexing::{Indexer, SearchResult};
 use crate::llm::Model;
 
@@ -121,7 +122,7 @@ impl QueryResult {
             markdown.push_str("# Relevant Code Snippets\n\n");
 
             for snippet in &self.code_snippets {
-                markdown.push_str(&format!("## {}\n\n", snippet.path.display()));
+                markdown.push_str(&format!("## {}\n\n", snippet.location()));
 
                 // Add language hint for syntax highlighting
                 let language = snippet.language.as_deref().unwrap_or("");
@@ -172,6 +173,10 @@ pub struct CodeSnippet {
     /// Code content
     pub content: String,
 
+    /// Lines of the file in the content, if not the whole file
+    #[serde(default)]
+    pub lines: Option<LineRange>,
+
     /// Relationships
     pub relationships: HashMap<String, Vec<String>>,
 
@@ -179,6 +184,17 @@ pub struct CodeSnippet {
     pub score: f32,
 }
 
+impl CodeSnippet {
+    /// Get the file path, followed by the lines when the snippet is part
+    /// of the file
+    pub fn location(&self) -> String {
+        match self.lines {
+            Some(lines) => format!("{}:{}", self.path.display(), lines),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
 /// Suggestion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Suggestion {
@@ -234,10 +250,27 @@ impl QueryEngine {
         max_results: usize,
         max_context_size: usize,
     ) -> Result<QueryResult> {
+        let request = SearchRequest {
+            max_results,
+            ..SearchRequest::new(query)
+        };
+
+        self.query_with_request(request, max_context_size).await
+    }
+
+    /// Execute a query with the limits and filters of a search request
+    ///
+    /// The embedding type of the request is chosen from the query type.
+    pub async fn query_with_request(
+        &self,
+        mut request: SearchRequest,
+        max_context_size: usize,
+    ) -> Result<QueryResult> {
+        let query = request.query.clone();
         info!("Executing query: {}", query);
 
         // Determine query type
-        let query_type = self.determine_query_type(query);
+        let query_type = self.determine_query_type(&query);
         debug!("Determined query type: {:?}", query_type);
 
         // Create query context
@@ -254,10 +287,8 @@ impl QueryEngine {
             QueryType::Text => EmbeddingType::Code, // Default to code for now
         };
 
-        let search_results = self
-            .indexer
-            .search(query, embedding_type, max_results)
-            .await?;
+        request.embedding_type = embedding_type;
+        let search_results = self.indexer.search(&request).await?;
         debug!("Found {} relevant files", search_results.len());
 
         // Extract code snippets
@@ -322,6 +353,12 @@ impl QueryEngine {
                 }
             };
 
+            // Narrow the content to the matching chunk
+            let content = match result.lines {
+                Some(lines) => lines.extract(&content),
+                None => content,
+            };
+
             // Extract relationships
             let relationships = self.extract_relationships(result);
 
@@ -330,6 +367,7 @@ impl QueryEngine {
                 path: result.path.clone(),
                 language: Some(result.metadata.language.clone()),
                 content,
+                lines: result.lines,
                 relationships,
                 score: result.score,
             };
@@ -390,6 +428,19 @@ impl QueryEngine {
         code_snippets: &[CodeSnippet],
         dependency_graph: &CodeGraph,
     ) -> Result<String> {
+        // Without an LLM, list the matches instead
+        if !self.llm.is_available() {
+            let mut response = format!("Found {} matching files:\n", code_snippets.len());
+            for snippet in code_snippets {
+                response.push_str(&format!(
+                    "- {} (score {:.2})\n",
+                    snippet.location(),
+                    snippet.score
+                ));
+            }
+            return Ok(response);
+        }
+
         // Create prompt for LLM
         let prompt = self.create_response_prompt(context, code_snippets, dependency_graph)?;
 
@@ -416,7 +467,7 @@ impl QueryEngine {
 
         for (i, snippet) in code_snippets.iter().enumerate() {
             prompt.push_str(&format!("Snippet {}:\n", i + 1));
-            prompt.push_str(&format!("File: {}\n", snippet.path.display()));
+            prompt.push_str(&format!("File: {}\n", snippet.location()));
             prompt.push_str(&format!(
                 "Language: {}\n",
                 snippet.language.as_deref().unwrap_or("unknown")
@@ -491,7 +542,9 @@ impl QueryEngine {
         dependency_graph: &CodeGraph,
     ) -> Result<Vec<Suggestion>> {
         // Only generate suggestions for error and refactor queries
-        if matches!(context.query_type, QueryType::Text | QueryType::Code) {
+        if matches!(context.query_type, QueryType::Text | QueryType::Code)
+            || !self.llm.is_available()
+        {
             return Ok(Vec::new());
         }
 
@@ -524,7 +577,7 @@ impl QueryEngine {
 
         for (i, snippet) in code_snippets.iter().enumerate() {
             prompt.push_str(&format!("Snippet {}:\n", i + 1));
-            prompt.push_str(&format!("File: {}\n", snippet.path.display()));
+            prompt.push_str(&format!("File: {}\n", snippet.location()));
             prompt.push_str(&format!(
                 "Language: {}\n",
                 snippet.language.as_deref().unwrap_or("unknown")
@@ -312,7 +312,7 @@ impl QueryPromptBuilder {
         // Replace code snippets (simplified, would use a proper template engine in production)
         let mut code_snippets_text = String::new();
         for snippet in &self.code_snippets {
-            code_snippets_text.push_str(&format!("File: {}\n", snippet.path.display()));
+            code_snippets_text.push_str(&format!("File: {}\n", snippet.location()));
             code_snippets_text.push_str(&format!("Language: {}\n", snippet.language.as_deref().unwrap_or("unknown")));
             code_snippets_text.push_str(&format!("Score: {:.2}\n\n", snippet.score));
             code_snippets_text.push_str("```\n");
@@ -416,7 +416,7 @@ impl SuggestionsPromptBuilder {
 
         for (i, snippet) in self.code_snippets.iter().enumerate() {
             prompt.push_str(&format!("Snippet {}:\n", i + 1));
-            prompt.push_str(&format!("File: {}\n", snippet.path.display()));
+            prompt.push_str(&format!("File: {}\n", snippet.location()));
             prompt.push_str(&format!("Language: {}\n", snippet.language.as_deref().unwrap_or("unknown")));
             prompt.push_str("```\n");
             prompt.push_str(&snippet.content);
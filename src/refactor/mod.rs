@@ -208,7 +208,7 @@ impl RefactoringEngine {
         prompt.push_str("Code Snippets:\n\n");
 
         for (i, snippet) in query_result.code_snippets.iter().enumerate() {
-            prompt.push_str(&format!("File {}: {}\n", i + 1, snippet.path.display()));
+            prompt.push_str(&format!("File {}: {}\n", i + 1, snippet.location()));
 
             if let Some(language) = &snippet.language {
                 prompt.push_str(&format!("Language: {}\n", language));
@@ -330,7 +330,7 @@ impl RefactoringEngine {
             let original = query_result
                 .code_snippets
                 .iter()
-                .find(|s| s.path == path)
+                .find(|s| s.path == path && s.lines.is_none())
                 .map(|s| s.content.clone())
                 .unwrap_or_else(|| {
                     // Try to read from file if not in snippets
@@ -159,3 +159,58 @@ pub fn file_name(path: &Path) -> Option<String> {
         .and_then(|name| name.to_str())
         .map(String::from)
 }
+
+/// Normalize a path lexically, resolving `.` and `..` components
+///
+/// Unlike `fs::canonicalize` this does not touch the filesystem or follow
+/// symlinks, so it also works for paths that do not exist.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // `..` at the root stays at the root
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                // Leading `..` cannot be resolved lexically
+                _ => normalized.push(component.as_os_str()),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_resolves_dot_components() {
+        let cases = [
+            ("a/./b/../c", "a/c"),
+            ("a/b/../../c", "c"),
+            ("../a", "../a"),
+            ("../../a", "../../a"),
+            ("a/../../b", "../b"),
+            ("./../a/b/..", "../a"),
+            ("/a/../../b", "/b"),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                normalize_path(Path::new(path)),
+                PathBuf::from(expected),
+                "normalizing {}",
+                path
+            );
+        }
+    }
+}